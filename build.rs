@@ -0,0 +1,100 @@
+//! Compiles puzzle data into the binary, when the `embed` feature is on.
+//!
+//! Every input file (`src/y{year}/d{day}/*.txt`) and parameter file
+//! (`assets/params/{group}/{year}/d{day}.toml`) is listed in a table which
+//! `src/embedded.rs` includes. Set `AOC_EMBED` to a comma-separated list of
+//! years (`2023`) and days (`2022-15`) to embed only those puzzles' files.
+//!
+//! Only the standard library is used, so that the script builds for any host.
+
+use std::{
+	env,
+	fmt::Write as _,
+	fs,
+	path::{
+		Path,
+		PathBuf,
+	},
+};
+
+fn main() {
+	println!("cargo:rerun-if-changed=build.rs");
+	println!("cargo:rerun-if-env-changed=AOC_EMBED");
+	if env::var_os("CARGO_FEATURE_EMBED").is_none() {
+		return;
+	}
+	let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+	let filters = env::var("AOC_EMBED").unwrap_or_default();
+	let filters = filters
+		.split(',')
+		.map(str::trim)
+		.filter(|f| !f.is_empty())
+		.collect::<Vec<_>>();
+
+	let mut files = Vec::new();
+	for (dir, ext) in [("src", "txt"), ("assets/params", "toml")] {
+		let dir = root.join(dir);
+		println!("cargo:rerun-if-changed={}", dir.display());
+		walk(&dir, ext, &mut files);
+	}
+	let mut files = files
+		.into_iter()
+		.filter_map(|path| {
+			let rel = path.strip_prefix(&root).ok()?;
+			let (year, day) = puzzle_of(rel)?;
+			let keep = filters.is_empty()
+				|| filters.iter().any(|&filter| {
+					filter == year.to_string()
+						|| filter == format!("{year}-{day:0>2}")
+				});
+			let key = rel
+				.components()
+				.map(|c| c.as_os_str().to_string_lossy())
+				.collect::<Vec<_>>()
+				.join("/");
+			keep.then_some((key, path))
+		})
+		.collect::<Vec<_>>();
+	files.sort();
+
+	let mut table = String::from("static FILES: &[(&str, &str)] = &[\n");
+	for (key, path) in &files {
+		writeln!(table, "\t({key:?}, include_str!({:?})),", path.display())
+			.unwrap();
+	}
+	table.push_str("];\n");
+	let out = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+	fs::write(out.join("embedded.rs"), table).unwrap();
+}
+
+/// Collects every file with an extension under a directory.
+fn walk(dir: &Path, ext: &str, out: &mut Vec<PathBuf>) {
+	let Ok(entries) = fs::read_dir(dir)
+	else {
+		return;
+	};
+	for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+		if path.is_dir() {
+			walk(&path, ext, out);
+		}
+		else if path.extension().is_some_and(|e| e == ext) {
+			out.push(path);
+		}
+	}
+}
+
+/// Finds the puzzle a data file belongs to, from where it sits in the tree.
+fn puzzle_of(rel: &Path) -> Option<(u16, u8)> {
+	let parts = rel
+		.iter()
+		.map(|part| part.to_str())
+		.collect::<Option<Vec<_>>>()?;
+	let (year, day) = match parts[..] {
+		["src", year, day, _] => (year.strip_prefix('y')?, day),
+		["assets", "params", _, year, file] => {
+			(year, file.strip_suffix(".toml")?)
+		},
+		_ => return None,
+	};
+	Some((year.parse().ok()?, day.strip_prefix('d')?.parse().ok()?))
+}
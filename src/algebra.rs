@@ -0,0 +1,301 @@
+//! Symbolic arithmetic over a single unknown.
+//!
+//! Some puzzles describe a tree of arithmetic operations, hide one leaf, and
+//! ask what value the leaf must hold for the tree to produce a known result.
+//! Rather than walking the tree backwards and inverting each operation by
+//! hand, this module folds the tree into the linear form `a·x + b` and solves
+//! that directly. Coefficients are kept as exact rationals, so division in the
+//! middle of the tree does not lose precision the way integer division would.
+//!
+//! Trees in which the unknown is multiplied by itself, or appears in a
+//! divisor, are not linear, and are rejected rather than approximated.
+
+use std::{
+	fmt,
+	ops::{
+		Add,
+		Div,
+		Mul,
+		Sub,
+	},
+};
+
+use num::Zero;
+
+use crate::ratio::Exact as _;
+pub use crate::ratio::Rational;
+
+/// One of the four arithmetic operations.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Op {
+	Add,
+	Sub,
+	Mul,
+	Div,
+}
+
+impl Op {
+	/// Applies the operation to two known values.
+	pub fn evaluate(
+		self,
+		lhs: Rational,
+		rhs: Rational,
+	) -> eyre::Result<Rational> {
+		Ok(match self {
+			Self::Add => lhs + rhs,
+			Self::Sub => lhs - rhs,
+			Self::Mul => lhs * rhs,
+			Self::Div if rhs.is_zero() => eyre::bail!("division by zero"),
+			Self::Div => lhs / rhs,
+		})
+	}
+}
+
+impl fmt::Display for Op {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.write_str(match self {
+			Self::Add => "+",
+			Self::Sub => "-",
+			Self::Mul => "*",
+			Self::Div => "/",
+		})
+	}
+}
+
+/// An arithmetic expression containing at most one unknown.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Expr {
+	/// A known value.
+	Const(Rational),
+	/// The value being solved for.
+	Unknown,
+	/// An operation on two sub-expressions.
+	Apply(Box<Expr>, Op, Box<Expr>),
+}
+
+impl Expr {
+	/// Creates a known integer value.
+	pub fn constant(value: i64) -> Self {
+		Self::Const(Rational::from_integer(value.into()))
+	}
+
+	/// Combines two expressions with an operation.
+	///
+	/// If both sides are already known, the operation is performed
+	/// immediately, so that trees with large known branches stay small.
+	pub fn apply(lhs: Self, op: Op, rhs: Self) -> Self {
+		match (&lhs, &rhs) {
+			(Self::Const(l), Self::Const(r)) => match op.evaluate(*l, *r) {
+				Ok(value) => Self::Const(value),
+				Err(_) => Self::Apply(Box::new(lhs), op, Box::new(rhs)),
+			},
+			_ => Self::Apply(Box::new(lhs), op, Box::new(rhs)),
+		}
+	}
+
+	/// Tests whether the unknown appears anywhere in the expression.
+	pub fn has_unknown(&self) -> bool {
+		match self {
+			Self::Const(_) => false,
+			Self::Unknown => true,
+			Self::Apply(lhs, _, rhs) => lhs.has_unknown() || rhs.has_unknown(),
+		}
+	}
+
+	/// Computes the value of the expression for a given value of the unknown.
+	pub fn evaluate(&self, unknown: Rational) -> eyre::Result<Rational> {
+		match self {
+			Self::Const(value) => Ok(*value),
+			Self::Unknown => Ok(unknown),
+			Self::Apply(lhs, op, rhs) => {
+				op.evaluate(lhs.evaluate(unknown)?, rhs.evaluate(unknown)?)
+			},
+		}
+	}
+
+	/// Folds the expression into the form `a·x + b`.
+	///
+	/// This fails if the expression is not linear in the unknown, or if it
+	/// divides by zero.
+	pub fn to_linear(&self) -> eyre::Result<Linear> {
+		match self {
+			Self::Const(value) => Ok(Linear::constant(*value)),
+			Self::Unknown => Ok(Linear::UNKNOWN),
+			Self::Apply(lhs, op, rhs) => {
+				let (lhs, rhs) = (lhs.to_linear()?, rhs.to_linear()?);
+				match op {
+					Op::Add => Ok(lhs + rhs),
+					Op::Sub => Ok(lhs - rhs),
+					Op::Mul if lhs.is_constant() => Ok(rhs * lhs.constant),
+					Op::Mul if rhs.is_constant() => Ok(lhs * rhs.constant),
+					Op::Mul => eyre::bail!(
+						"({lhs}) * ({rhs}) multiplies the unknown by itself"
+					),
+					Op::Div if !rhs.is_constant() => {
+						eyre::bail!("({lhs}) / ({rhs}) divides by the unknown")
+					},
+					Op::Div if rhs.constant.is_zero() => {
+						eyre::bail!("({lhs}) / ({rhs}) divides by zero")
+					},
+					Op::Div => Ok(lhs / rhs.constant),
+				}
+			},
+		}
+	}
+}
+
+impl fmt::Display for Expr {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Const(value) => write!(fmt, "{value}"),
+			Self::Unknown => fmt.write_str("x"),
+			Self::Apply(lhs, op, rhs) => write!(fmt, "({lhs} {op} {rhs})"),
+		}
+	}
+}
+
+/// An expression of the form `a·x + b`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Linear {
+	/// The coefficient of the unknown.
+	pub coefficient: Rational,
+	/// The constant term.
+	pub constant:    Rational,
+}
+
+impl Linear {
+	/// The bare unknown, `1·x + 0`.
+	pub const UNKNOWN: Self = Self {
+		coefficient: Rational::new_raw(1, 1),
+		constant:    Rational::new_raw(0, 1),
+	};
+
+	/// Creates a linear expression with no unknown component.
+	pub fn constant(value: Rational) -> Self {
+		Self {
+			coefficient: Rational::zero(),
+			constant:    value,
+		}
+	}
+
+	/// Tests whether the unknown has been cancelled out.
+	pub fn is_constant(&self) -> bool {
+		self.coefficient.is_zero()
+	}
+
+	/// Finds the value of the unknown which makes this expression equal to
+	/// another.
+	pub fn solve_eq(self, other: Self) -> eyre::Result<Rational> {
+		let Self {
+			coefficient,
+			constant,
+		} = self - other;
+		if coefficient.is_zero() {
+			if constant.is_zero() {
+				eyre::bail!("every value of the unknown satisfies the equation");
+			}
+			eyre::bail!("no value of the unknown satisfies the equation");
+		}
+		Ok(-constant / coefficient)
+	}
+}
+
+impl Add for Linear {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self {
+		Self {
+			coefficient: self.coefficient + rhs.coefficient,
+			constant:    self.constant + rhs.constant,
+		}
+	}
+}
+
+impl Sub for Linear {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		Self {
+			coefficient: self.coefficient - rhs.coefficient,
+			constant:    self.constant - rhs.constant,
+		}
+	}
+}
+
+impl Mul<Rational> for Linear {
+	type Output = Self;
+
+	fn mul(self, rhs: Rational) -> Self {
+		Self {
+			coefficient: self.coefficient * rhs,
+			constant:    self.constant * rhs,
+		}
+	}
+}
+
+impl Div<Rational> for Linear {
+	type Output = Self;
+
+	fn div(self, rhs: Rational) -> Self {
+		Self {
+			coefficient: self.coefficient / rhs,
+			constant:    self.constant / rhs,
+		}
+	}
+}
+
+impl fmt::Display for Linear {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}x + {}", self.coefficient, self.constant)
+	}
+}
+
+/// Solves `lhs = rhs` for the unknown, which may appear on either side.
+pub fn solve_eq(lhs: &Expr, rhs: &Expr) -> eyre::Result<Rational> {
+	lhs.to_linear()?.solve_eq(rhs.to_linear()?)
+}
+
+/// Converts a rational to an integer answer, failing if it has a fractional
+/// part or does not fit.
+pub fn to_integer(value: Rational) -> eyre::Result<i64> {
+	value.to_exact()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn solves_linear() -> eyre::Result<()> {
+		// (4 + 2 * (x - 3)) / 4 = 150
+		let lhs = Expr::apply(
+			Expr::apply(
+				Expr::constant(4),
+				Op::Add,
+				Expr::apply(
+					Expr::constant(2),
+					Op::Mul,
+					Expr::apply(Expr::Unknown, Op::Sub, Expr::constant(3)),
+				),
+			),
+			Op::Div,
+			Expr::constant(4),
+		);
+		let answer = solve_eq(&lhs, &Expr::constant(150))?;
+		assert_eq!(to_integer(answer)?, 301);
+		assert_eq!(lhs.evaluate(answer)?, Rational::from_integer(150));
+
+		// Known branches are folded as the tree is built.
+		let folded = Expr::apply(Expr::constant(7), Op::Div, Expr::constant(2));
+		assert_eq!(folded, Expr::Const(Rational::new(7, 2)));
+		assert!(to_integer(Rational::new(7, 2)).is_err());
+
+		let square = Expr::apply(Expr::Unknown, Op::Mul, Expr::Unknown);
+		assert!(square.to_linear().is_err());
+		let inverse = Expr::apply(Expr::constant(1), Op::Div, Expr::Unknown);
+		assert!(inverse.to_linear().is_err());
+		assert!(solve_eq(&Expr::Unknown, &Expr::Unknown).is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,188 @@
+//! Puzzle answers.
+//!
+//! Almost every answer fits in an `i64`, and so that is what
+//! [`Puzzle::part_1`] and [`Puzzle::part_2`] return. The few that do not (long
+//! products, least-common-multiples of many cycle lengths) can override
+//! [`Puzzle::answer_1`] or [`Puzzle::answer_2`] instead, and return the full
+//! 128-bit value as an [`Answer`].
+//!
+//! A few puzzles want something other than a number: a pair of coördinates
+//! written `x,y`, a code with its leading zeroes, or a value in hexadecimal.
+//! These are [`Answer::Formatted`], built with [`Answer::coords`],
+//! [`Answer::padded`], or [`Answer::hex`], and are displayed, compared with
+//! known answers, and submitted as exactly the text they hold.
+//!
+//! [`Puzzle::part_1`]: crate::Puzzle::part_1
+//! [`Puzzle::part_2`]: crate::Puzzle::part_2
+//! [`Puzzle::answer_1`]: crate::Puzzle::answer_1
+//! [`Puzzle::answer_2`]: crate::Puzzle::answer_2
+
+use std::{
+	fmt,
+	hash::{
+		Hash,
+		Hasher,
+	},
+};
+
+/// The solution to one part of a puzzle.
+///
+/// Numbers compare by value, whichever variant holds them, so that
+/// `Signed(5)` equals `Unsigned(5)`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Answer {
+	Signed(i128),
+	Unsigned(u128),
+	/// Text which must be submitted exactly as written.
+	Formatted(String),
+}
+
+impl Answer {
+	/// Formats a pair of coördinates as `x,y`.
+	pub fn coords(x: impl fmt::Display, y: impl fmt::Display) -> Self {
+		Self::Formatted(format!("{x},{y}"))
+	}
+
+	/// Formats a number with leading zeroes, out to at least `width` digits.
+	pub fn padded(val: impl fmt::Display, width: usize) -> Self {
+		Self::Formatted(format!("{val:0>width$}"))
+	}
+
+	/// Formats a number in lowercase hexadecimal, without a `0x` prefix.
+	pub fn hex(val: impl fmt::LowerHex) -> Self {
+		Self::Formatted(format!("{val:x}"))
+	}
+
+	/// Gets the answer as a signed number, if it is a number and it fits.
+	///
+	/// Formatted answers are never numbers, even when their text would parse
+	/// as one: `007` is not `7`.
+	pub fn to_i128(&self) -> Option<i128> {
+		match *self {
+			Self::Signed(val) => Some(val),
+			Self::Unsigned(val) => val.try_into().ok(),
+			Self::Formatted(_) => None,
+		}
+	}
+
+	/// Gets the answer as an `i64`, if it is a number and it fits.
+	pub fn to_i64(&self) -> Option<i64> {
+		self.to_i128()?.try_into().ok()
+	}
+
+	/// Puts numbers in one form, so that equal values from different variants
+	/// compare and hash alike.
+	fn normalized(&self) -> Normalized<'_> {
+		match *self {
+			Self::Signed(val) => match u128::try_from(val) {
+				Ok(val) => Normalized::Natural(val),
+				Err(_) => Normalized::Negative(val),
+			},
+			Self::Unsigned(val) => Normalized::Natural(val),
+			Self::Formatted(ref text) => Normalized::Text(text),
+		}
+	}
+}
+
+/// An [`Answer`] with each number held in exactly one way.
+#[derive(Eq, Hash, PartialEq)]
+enum Normalized<'a> {
+	Negative(i128),
+	Natural(u128),
+	Text(&'a str),
+}
+
+impl PartialEq for Answer {
+	fn eq(&self, other: &Self) -> bool {
+		self.normalized() == other.normalized()
+	}
+}
+
+impl Eq for Answer {
+}
+
+impl Hash for Answer {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.normalized().hash(state);
+	}
+}
+
+macro_rules! from_int {
+	($($variant:ident($wide:ty) <= $($t:ty),+);+ $(;)?) => { $( $(
+		impl From<$t> for Answer {
+			fn from(val: $t) -> Self {
+				Self::$variant(val as $wide)
+			}
+		}
+	)+ )+ };
+}
+
+from_int! {
+	Signed(i128) <= i8, i16, i32, i64, i128, isize;
+	Unsigned(u128) <= u8, u16, u32, u64, u128, usize;
+}
+
+impl From<String> for Answer {
+	fn from(text: String) -> Self {
+		Self::Formatted(text)
+	}
+}
+
+impl From<&str> for Answer {
+	fn from(text: &str) -> Self {
+		Self::Formatted(text.to_owned())
+	}
+}
+
+impl PartialEq<i64> for Answer {
+	fn eq(&self, other: &i64) -> bool {
+		self.to_i128() == Some(*other as i128)
+	}
+}
+
+impl fmt::Display for Answer {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Signed(val) => fmt::Display::fmt(val, fmt),
+			Self::Unsigned(val) => fmt::Display::fmt(val, fmt),
+			Self::Formatted(text) => fmt.write_str(text),
+		}
+	}
+}
+
+impl PartialEq<&str> for Answer {
+	fn eq(&self, other: &&str) -> bool {
+		format!("{self}") == *other
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn formatted_answers() {
+		assert_eq!(Answer::coords(6, -3), "6,-3");
+		assert_eq!(Answer::padded(42, 5), "00042");
+		assert_eq!(Answer::padded("abc", 2), "abc");
+		assert_eq!(Answer::hex(255u8), "ff");
+		assert_eq!(Answer::padded(7, 3).to_i64(), None);
+		assert_eq!(Answer::from(7), "7");
+	}
+
+	#[test]
+	fn numbers_compare_by_value() {
+		assert_eq!(Answer::Signed(5), Answer::Unsigned(5));
+		assert_ne!(Answer::Signed(-5), Answer::Unsigned(5));
+		assert_ne!(Answer::Unsigned(u128::MAX), Answer::Signed(-1));
+		assert_ne!(Answer::from(5), Answer::from("5"));
+
+		let hash = |answer: &Answer| {
+			let mut hasher = std::hash::DefaultHasher::new();
+			answer.hash(&mut hasher);
+			hasher.finish()
+		};
+		assert_eq!(hash(&Answer::from(5i64)), hash(&Answer::from(5u64)));
+	}
+}
@@ -0,0 +1,187 @@
+//! Intermediate products of a puzzle run.
+//!
+//! Some puzzles produce something worth looking at on the way to their answer:
+//! a rendered CRT screen, the final state of a grid, a graph to feed into
+//! another tool. Rather than printing these to stdout (where they interleave
+//! with the trace log), solvers hand them back through [`Puzzle::artifacts`],
+//! and the harness writes them to `target/aoc-artifacts/{year}/d{day}/`.
+//!
+//! [`Puzzle::artifacts`]: crate::Puzzle::artifacts
+
+use std::{
+	fs,
+	path::PathBuf,
+};
+
+use eyre::Context;
+
+use crate::Puzzle;
+
+/// A named piece of output produced by a solver.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Artifact {
+	/// Human-readable text, such as a rendered grid. Written as `{name}.txt`.
+	Text { name: String, text: String },
+	/// A greyscale raster image, stored row-major with one byte per pixel.
+	/// Written as a binary PGM file, `{name}.pgm`.
+	Image {
+		name:   String,
+		width:  usize,
+		height: usize,
+		pixels: Vec<u8>,
+	},
+	/// Arbitrary bytes. The name is used as the file name verbatim, so it
+	/// should carry its own extension.
+	Blob { name: String, bytes: Vec<u8> },
+}
+
+impl Artifact {
+	/// Creates a text artifact.
+	pub fn text(name: impl Into<String>, text: impl Into<String>) -> Self {
+		Self::Text {
+			name: name.into(),
+			text: text.into(),
+		}
+	}
+
+	/// Creates an image artifact from a grid of on/off pixels.
+	///
+	/// Every row should be the same length as the first; short rows are
+	/// padded with dark pixels.
+	pub fn bitmap<R: AsRef<[bool]>>(
+		name: impl Into<String>,
+		rows: impl IntoIterator<Item = R>,
+	) -> Self {
+		let rows = rows.into_iter().collect::<Vec<_>>();
+		let width = rows.first().map(|r| r.as_ref().len()).unwrap_or_default();
+		let pixels = rows
+			.iter()
+			.flat_map(|row| {
+				let row = row.as_ref();
+				(0 .. width).map(move |col| match row.get(col) {
+					Some(true) => 0xFF,
+					_ => 0x00,
+				})
+			})
+			.collect();
+		Self::Image {
+			name: name.into(),
+			width,
+			height: rows.len(),
+			pixels,
+		}
+	}
+
+	/// Creates an opaque binary artifact.
+	pub fn blob(name: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+		Self::Blob {
+			name:  name.into(),
+			bytes: bytes.into(),
+		}
+	}
+
+	/// Computes the file name the artifact will be written to.
+	pub fn file_name(&self) -> String {
+		match self {
+			Self::Text { name, .. } => format!("{name}.txt"),
+			Self::Image { name, .. } => format!("{name}.pgm"),
+			Self::Blob { name, .. } => name.clone(),
+		}
+	}
+
+	/// Renders the artifact into the bytes of its file.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		match self {
+			Self::Text { text, .. } => text.clone().into_bytes(),
+			Self::Image {
+				width,
+				height,
+				pixels,
+				..
+			} => {
+				let mut out =
+					format!("P5\n{width} {height}\n255\n").into_bytes();
+				out.extend_from_slice(pixels);
+				out
+			},
+			Self::Blob { bytes, .. } => bytes.clone(),
+		}
+	}
+}
+
+/// Computes the directory which holds a day's artifacts.
+pub fn artifact_dir(year: u16, day: u8) -> eyre::Result<PathBuf> {
+	let mut path = crate::config::asset_root()?;
+	path.push("target");
+	path.push("aoc-artifacts");
+	path.push(year.to_string());
+	path.push(format!("d{day:0>2}"));
+	Ok(path)
+}
+
+/// Writes a day's artifacts to disk, returning the paths of the written files.
+#[cfg_attr(feature = "trace", tracing::instrument(skip(artifacts)))]
+pub fn write_artifacts(
+	year: u16,
+	day: u8,
+	artifacts: &[Artifact],
+) -> eyre::Result<Vec<PathBuf>> {
+	if artifacts.is_empty() {
+		return Ok(Vec::new());
+	}
+	let dir = artifact_dir(year, day)?;
+	fs::create_dir_all(&dir)
+		.wrap_err_with(|| format!("could not create {}", dir.display()))?;
+	artifacts
+		.iter()
+		.map(|artifact| {
+			let path = dir.join(artifact.file_name());
+			fs::write(&path, artifact.to_bytes()).wrap_err_with(|| {
+				format!("could not write {}", path.display())
+			})?;
+			tracing::debug!(file=%path.display(), "wrote artifact");
+			Ok(path)
+		})
+		.collect()
+}
+
+/// Writes a solver's artifacts to its day's directory, along with an
+/// `index.txt` naming the puzzle and listing the files.
+///
+/// The solver must know which day it solves; every solver produced by the
+/// registry does.
+pub fn write_puzzle_artifacts(
+	puzzle: &dyn Puzzle,
+) -> eyre::Result<Vec<PathBuf>> {
+	let (year, day) = puzzle
+		.year_day()
+		.ok_or_else(|| eyre::eyre!("the solver does not know its puzzle"))?;
+	let mut artifacts = puzzle.artifacts();
+	if artifacts.is_empty() {
+		return Ok(Vec::new());
+	}
+	let mut index = format!("{year}-{day:0>2}");
+	if let Some(title) = puzzle.title() {
+		index.push_str(": ");
+		index.push_str(title);
+	}
+	index.push('\n');
+	for artifact in &artifacts {
+		index.push_str(&artifact.file_name());
+		index.push('\n');
+	}
+	artifacts.push(Artifact::text("index", index));
+	write_artifacts(year, day, &artifacts)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bitmap_to_pgm() {
+		let art = Artifact::bitmap("screen", [[true, false], [false, true]]);
+		assert_eq!(art.file_name(), "screen.pgm");
+		assert_eq!(art.to_bytes(), b"P5\n2 2\n255\n\xFF\x00\x00\xFF");
+	}
+}
@@ -0,0 +1,320 @@
+//! Repeated, statistically summarized timing of a solver.
+//!
+//! A single run's phase metrics are fine for noticing that a day takes a
+//! second rather than a millisecond, but comparing two attempts at making a
+//! day faster needs more care: the first run pays for cold caches and page
+//! faults, the scheduler occasionally parks the thread for a while, and the
+//! CPU may be moved between cores with different clocks. [`run`] deals with
+//! each of those by running the solver a few times without measuring it,
+//! measuring many more runs, discarding the outliers, and reporting a
+//! confidence interval rather than a single number. It can also pin the
+//! running thread to one core.
+//!
+//! Two attempts whose intervals overlap are not distinguishable; run more
+//! samples, or accept that the change did not matter.
+
+use std::{
+	fmt,
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+use eyre::WrapErr as _;
+
+use crate::{
+	cancel,
+	random,
+	Answer,
+	ParamMap,
+	Solver,
+};
+
+/// How to benchmark a solver.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+	/// Runs to make, and throw away, before measuring.
+	pub warmup:  usize,
+	/// Runs to measure.
+	pub samples: usize,
+	/// The core to pin the running thread to, if any.
+	pub core:    Option<usize>,
+	pub part_1:  bool,
+	pub part_2:  bool,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			warmup:  3,
+			samples: 30,
+			core:    None,
+			part_1:  true,
+			part_2:  true,
+		}
+	}
+}
+
+/// The summary of many timings of one phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+	/// The number of timings taken.
+	pub samples:  usize,
+	/// The number of timings discarded as outliers.
+	pub outliers: usize,
+	pub min:      Duration,
+	pub median:   Duration,
+	/// The mean of the timings which were kept.
+	pub mean:     Duration,
+	/// The sample standard deviation of the timings which were kept.
+	pub stddev:   Duration,
+	/// The half-width of the 95% confidence interval around the mean.
+	pub interval: Duration,
+}
+
+impl Stats {
+	/// Summarizes a set of timings.
+	///
+	/// Outliers are the timings more than one and a half interquartile ranges
+	/// outside the middle half, and are excluded from the mean, deviation, and
+	/// interval. This produces `None` when there are no timings at all.
+	pub fn new(timings: &[Duration]) -> Option<Self> {
+		let mut secs = timings
+			.iter()
+			.map(Duration::as_secs_f64)
+			.collect::<Vec<_>>();
+		secs.sort_by(f64::total_cmp);
+		let min = *secs.first()?;
+		let median = quantile(&secs, 0.5);
+		let (q1, q3) = (quantile(&secs, 0.25), quantile(&secs, 0.75));
+		let fence = 1.5 * (q3 - q1);
+		let kept = secs
+			.iter()
+			.copied()
+			.filter(|&s| q1 - fence <= s && s <= q3 + fence)
+			.collect::<Vec<_>>();
+
+		let n = kept.len() as f64;
+		let mean = kept.iter().sum::<f64>() / n;
+		let stddev = if kept.len() > 1 {
+			(kept.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0))
+				.sqrt()
+		}
+		else {
+			0.0
+		};
+		let interval =
+			t_critical(kept.len().saturating_sub(1)) * stddev / n.sqrt();
+		Some(Self {
+			samples:  secs.len(),
+			outliers: secs.len() - kept.len(),
+			min:      Duration::from_secs_f64(min),
+			median:   Duration::from_secs_f64(median),
+			mean:     Duration::from_secs_f64(mean),
+			stddev:   Duration::from_secs_f64(stddev),
+			interval: Duration::from_secs_f64(interval),
+		})
+	}
+}
+
+impl fmt::Display for Stats {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			fmt,
+			"mean {:?} ± {:?} (95%), median {:?}, min {:?}, σ {:?}",
+			self.mean, self.interval, self.median, self.min, self.stddev
+		)?;
+		if self.outliers > 0 {
+			write!(
+				fmt,
+				", {} of {} samples discarded",
+				self.outliers, self.samples
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Interpolates a quantile of sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+	let pos = q * (sorted.len() - 1) as f64;
+	let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+	sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+/// The two-sided 95% critical value of Student's t distribution.
+///
+/// Past thirty degrees of freedom, the normal distribution is close enough.
+fn t_critical(freedom: usize) -> f64 {
+	const TABLE: [f64; 30] = [
+		12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+		2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+		2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+	];
+	match freedom {
+		0 => 0.0,
+		n => TABLE.get(n - 1).copied().unwrap_or(1.96),
+	}
+}
+
+/// The summaries of each phase of a benchmarked solver.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+	/// Parsing, parameters, post-parse validation, and shared preparation.
+	pub parse:  Stats,
+	/// Preparing for and solving part 1, if it was run.
+	pub part_1: Option<Stats>,
+	/// Preparing for and solving part 2, if it was run.
+	pub part_2: Option<Stats>,
+}
+
+/// Benchmarks a solver on one data group.
+///
+/// Every run must produce the same answers as the first; a solver which does
+/// not is reported as an error, since its timings are not comparable either.
+pub fn run(
+	solver: Solver,
+	group: &str,
+	overrides: &ParamMap,
+	config: Config,
+) -> eyre::Result<Report> {
+	eyre::ensure!(config.samples > 0, "cannot benchmark without any samples");
+	if let Some(core) = config.core {
+		pin_to_core(core)?;
+	}
+	let text = solver.load_input(group)?;
+	let mut params = solver.load_params(group)?;
+	params.extend(overrides.iter());
+
+	let mut first = None;
+	let mut timings = [vec![], vec![], vec![]];
+	for iteration in 0 .. config.warmup + config.samples {
+		cancel::check()?;
+		let (times, answers) = run_once(solver, &text, &params, config)
+			.wrap_err_with(|| format!("run {} failed", iteration + 1))?;
+		match &first {
+			None => first = Some(answers),
+			Some(first) if *first != answers => eyre::bail!(
+				"run {} answered {answers:?}, but the first run answered \
+				 {first:?}",
+				iteration + 1
+			),
+			Some(_) => {},
+		}
+		if iteration >= config.warmup {
+			for (all, time) in timings.iter_mut().zip(times) {
+				all.extend(time);
+			}
+		}
+	}
+	let [parse, part_1, part_2] = timings.map(|t| Stats::new(&t));
+	Ok(Report {
+		parse: parse.unwrap_or_default(),
+		part_1,
+		part_2,
+	})
+}
+
+type Timings = [Option<Duration>; 3];
+
+/// Parses and solves once, timing each phase.
+fn run_once(
+	solver: Solver,
+	text: &str,
+	params: &ParamMap,
+	config: Config,
+) -> eyre::Result<(Timings, [Option<Answer>; 2])> {
+	let mut times = [None; 3];
+	let mut answers = [None, None];
+	let start = Instant::now();
+	let (_, mut puzzle) =
+		solver.parse(text).map_err(|err| eyre::eyre!("{err}"))?;
+	puzzle.set_params(params)?;
+	puzzle.set_rng(random::rng());
+	puzzle.after_parse()?;
+	puzzle.prepare_common()?;
+	times[0] = Some(start.elapsed());
+
+	if config.part_1 {
+		let start = Instant::now();
+		puzzle.prepare_1()?;
+		answers[0] = Some(puzzle.answer_1()?);
+		times[1] = Some(start.elapsed());
+	}
+	if config.part_2 && puzzle.has_part_2() {
+		let start = Instant::now();
+		puzzle.prepare_2()?;
+		answers[1] = Some(puzzle.answer_2()?);
+		times[2] = Some(start.elapsed());
+	}
+	Ok((times, answers))
+}
+
+/// Pins the calling thread to one CPU core.
+#[cfg(target_os = "linux")]
+pub fn pin_to_core(core: usize) -> eyre::Result<()> {
+	// `CPU_SET` indexes into the set without checking.
+	if core >= libc::CPU_SETSIZE as usize {
+		let last = libc::CPU_SETSIZE - 1;
+		eyre::bail!("cannot pin to core {core}: the last core is {last}");
+	}
+	// SAFETY: `cpu_set_t` is a plain bitmask, for which all zeroes is the
+	// empty set, and `sched_setaffinity` only reads it. `core` was checked to
+	// be inside the set. A pid of 0 is the calling thread.
+	let status = unsafe {
+		let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+		libc::CPU_SET(core, &mut set);
+		libc::sched_setaffinity(0, std::mem::size_of_val(&set), &set)
+	};
+	if status != 0 {
+		let err = std::io::Error::last_os_error();
+		let cores = std::thread::available_parallelism()
+			.map(|count| count.to_string())
+			.unwrap_or_else(|_| "unknown".to_owned());
+		return Err(err).wrap_err_with(|| {
+			format!(
+				"could not pin the solver to core {core} (available \
+				 parallelism: {cores})"
+			)
+		});
+	}
+	tracing::debug!(core, "pinned the solver thread");
+	Ok(())
+}
+
+/// Pins the calling thread to one CPU core.
+#[cfg(not(target_os = "linux"))]
+pub fn pin_to_core(core: usize) -> eyre::Result<()> {
+	eyre::bail!("cannot pin to core {core}: not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn discards_outliers() {
+		let mut timings = (0 .. 20)
+			.map(|n| Duration::from_micros(1000 + n % 5))
+			.collect::<Vec<_>>();
+		timings.push(Duration::from_millis(50));
+		let stats = Stats::new(&timings).unwrap();
+		assert_eq!(stats.samples, 21);
+		assert_eq!(stats.outliers, 1);
+		assert_eq!(stats.min, Duration::from_micros(1000));
+		assert_eq!(stats.median, Duration::from_micros(1002));
+		assert!(
+			stats.mean.abs_diff(Duration::from_micros(1002))
+				< Duration::from_nanos(10)
+		);
+		assert!(stats.interval < Duration::from_micros(1));
+
+		let single = Stats::new(&[Duration::from_millis(3)]).unwrap();
+		assert_eq!(single.interval, Duration::ZERO);
+		assert!(Stats::new(&[]).is_none());
+	}
+}
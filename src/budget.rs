@@ -0,0 +1,319 @@
+//! Time and memory budgets for solvers, checked by `cargo test`.
+//!
+//! A solver that used to finish in a few milliseconds can quietly get a
+//! hundred times slower after a refactor, and nothing notices until the next
+//! time I happen to run it. A day can declare how long each phase is allowed
+//! to take, and how much memory it may hold at once, with [`budget!`]:
+//!
+//! ```rust,ignore
+//! crate::budget!(2024, 6, parse_ms = 50, part1_ms = 100, part2_ms = 1000, peak_mb = 256);
+//! ```
+//!
+//! The `budgets` integration test runs every day which has a budget against
+//! its real input, and fails if any phase goes over. Real inputs are not
+//! committed, so days without one are skipped. The timings are only
+//! meaningful in an optimized build; in a debug build, every time limit is
+//! stretched by [`DEBUG_SLOWDOWN`] so that `cargo test` still catches the
+//! gross regressions. Run `cargo test --release --test budgets` for the real
+//! thing.
+//!
+//! Memory is measured by [`PeakAlloc`], which has to be installed as the
+//! global allocator of the program doing the checking. The integration test
+//! does this; elsewhere, memory limits are not checked.
+
+use std::{
+	alloc::{
+		GlobalAlloc,
+		Layout,
+		System,
+	},
+	fmt,
+	sync::atomic::{
+		AtomicBool,
+		AtomicUsize,
+		Ordering,
+	},
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+use eyre::WrapErr as _;
+
+use crate::{
+	random,
+	solutions,
+	AocError,
+	Puzzle,
+};
+
+/// How much longer a debug build is allowed to take than the budget says.
+pub const DEBUG_SLOWDOWN: u32 = 20;
+
+/// The limits declared for one day's solver.
+///
+/// Limits which are not set are not checked.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Budget {
+	pub year:   u16,
+	pub day:    u8,
+	/// The limit on parsing, including post-parse validation and shared
+	/// preparation.
+	pub parse:  Option<Duration>,
+	/// The limit on preparing for and solving part 1.
+	pub part_1: Option<Duration>,
+	/// The limit on preparing for and solving part 2.
+	pub part_2: Option<Duration>,
+	/// The limit on the most heap memory held at once, in bytes, across the
+	/// whole run.
+	pub peak:   Option<usize>,
+}
+
+impl Budget {
+	/// Creates an empty budget for a day.
+	pub const fn new(year: u16, day: u8) -> Self {
+		Self {
+			year,
+			day,
+			parse: None,
+			part_1: None,
+			part_2: None,
+			peak: None,
+		}
+	}
+
+	pub const fn parse_ms(mut self, ms: u64) -> Self {
+		self.parse = Some(Duration::from_millis(ms));
+		self
+	}
+
+	pub const fn part1_ms(mut self, ms: u64) -> Self {
+		self.part_1 = Some(Duration::from_millis(ms));
+		self
+	}
+
+	pub const fn part2_ms(mut self, ms: u64) -> Self {
+		self.part_2 = Some(Duration::from_millis(ms));
+		self
+	}
+
+	pub const fn peak_mb(mut self, mb: usize) -> Self {
+		self.peak = Some(mb << 20);
+		self
+	}
+
+	/// Runs the day's solver against its real input, and compares what it
+	/// used with the budget.
+	///
+	/// This produces `Ok(None)` if the day has no real input on disk, and an
+	/// error if the solver fails or is not registered.
+	pub fn check(&self) -> eyre::Result<Option<Usage>> {
+		let solver = solutions()
+			.get(&self.year)
+			.and_then(|days| days.get(&self.day))
+			.copied()
+			.ok_or_else(|| eyre::eyre!("no solver is registered"))?;
+		if !solver.has_group("input") {
+			return Ok(None);
+		}
+		let text = solver.load_input("input")?;
+		let params = solver.load_params("input")?;
+
+		PeakAlloc::reset_peak();
+		let mut usage = Usage::default();
+		let start = Instant::now();
+		let (rest, mut puzzle) = solver
+			.parse(&text)
+			.map_err(|err| AocError::InvalidInput(err.to_string()))?;
+		AocError::check_leftover(rest)?;
+		puzzle.set_params(&params)?;
+		puzzle.set_rng(random::rng());
+		puzzle.after_parse()?;
+		puzzle.prepare_common()?;
+		usage.parse = start.elapsed();
+
+		usage.part_1 = time(|| {
+			puzzle.prepare_1()?;
+			puzzle.answer_1().map(drop)
+		})
+		.wrap_err("part 1 failed")?;
+		usage.part_2 = time(|| {
+			if !puzzle.has_part_2() {
+				return Ok(());
+			}
+			puzzle.prepare_2()?;
+			puzzle.answer_2().map(drop)
+		})
+		.wrap_err("part 2 failed")?;
+		drop::<Box<dyn Puzzle + Send>>(puzzle);
+		usage.peak = PeakAlloc::peak();
+		Ok(Some(usage))
+	}
+
+	/// Lists every limit that a run went over.
+	pub fn overruns(&self, usage: &Usage) -> Vec<String> {
+		let slowdown = if cfg!(debug_assertions) {
+			DEBUG_SLOWDOWN
+		}
+		else {
+			1
+		};
+		let mut out = Vec::new();
+		for (phase, limit, spent) in [
+			("parse", self.parse, usage.parse),
+			("part 1", self.part_1, usage.part_1),
+			("part 2", self.part_2, usage.part_2),
+		] {
+			if let Some(limit) = limit.map(|limit| limit * slowdown) {
+				if spent > limit {
+					out.push(format!("{phase} took {spent:?}, over {limit:?}"));
+				}
+			}
+		}
+		if let (Some(limit), Some(peak)) = (self.peak, usage.peak) {
+			if peak > limit {
+				out.push(format!(
+					"peak memory was {} MiB, over {} MiB",
+					peak >> 20,
+					limit >> 20
+				));
+			}
+		}
+		out
+	}
+}
+
+/// Times a phase.
+fn time(phase: impl FnOnce() -> eyre::Result<()>) -> eyre::Result<Duration> {
+	let start = Instant::now();
+	phase()?;
+	Ok(start.elapsed())
+}
+
+/// What one run of a solver actually used.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Usage {
+	pub parse:  Duration,
+	pub part_1: Duration,
+	pub part_2: Duration,
+	/// The most heap memory held at once, if [`PeakAlloc`] is installed.
+	pub peak:   Option<usize>,
+}
+
+impl fmt::Display for Usage {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			fmt,
+			"parse {:?}, part 1 {:?}, part 2 {:?}",
+			self.parse, self.part_1, self.part_2
+		)?;
+		if let Some(peak) = self.peak {
+			write!(fmt, ", peak {} KiB", peak >> 10)?;
+		}
+		Ok(())
+	}
+}
+
+/// Every declared budget. Use [`budget!`] to add to it.
+#[linkme::distributed_slice]
+pub static BUDGETS: [Budget];
+
+/// Declares a day's budget.
+///
+/// The limits are given as `name = value` pairs, naming the [`Budget`]
+/// builder methods: `parse_ms`, `part1_ms`, `part2_ms`, and `peak_mb`.
+#[macro_export]
+macro_rules! budget {
+	($year:literal, $day:literal $(, $limit:ident = $value:expr)* $(,)?) => {
+		#[linkme::distributed_slice($crate::budget::BUDGETS)]
+		static BUDGET: $crate::budget::Budget =
+			$crate::budget::Budget::new($year, $day)$(.$limit($value))*;
+	};
+}
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// A global allocator which keeps track of the most memory held at once.
+///
+/// ```rust,ignore
+/// #[global_allocator]
+/// static ALLOC: wyz_aoc::budget::PeakAlloc = wyz_aoc::budget::PeakAlloc;
+/// ```
+///
+/// The count covers every thread in the process, so measurements are only
+/// accurate while nothing else is running.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PeakAlloc;
+
+impl PeakAlloc {
+	/// Gets the most memory held at once since the last reset, or `None` if
+	/// this is not the global allocator.
+	pub fn peak() -> Option<usize> {
+		INSTALLED
+			.load(Ordering::Relaxed)
+			.then(|| PEAK.load(Ordering::Relaxed))
+	}
+
+	/// Starts measuring a new peak from the memory currently held.
+	pub fn reset_peak() {
+		PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+	}
+
+	fn grew(size: usize) {
+		let now = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+		PEAK.fetch_max(now, Ordering::Relaxed);
+	}
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		INSTALLED.store(true, Ordering::Relaxed);
+		let ptr = System.alloc(layout);
+		if !ptr.is_null() {
+			Self::grew(layout.size());
+		}
+		ptr
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout);
+		CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+	}
+
+	unsafe fn realloc(
+		&self,
+		ptr: *mut u8,
+		layout: Layout,
+		new_size: usize,
+	) -> *mut u8 {
+		let out = System.realloc(ptr, layout, new_size);
+		if !out.is_null() {
+			CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+			Self::grew(new_size);
+		}
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reports_overruns() {
+		let budget = Budget::new(2024, 6).part1_ms(10).peak_mb(1);
+		let usage = Usage {
+			part_1: Duration::from_secs(60),
+			part_2: Duration::from_secs(60),
+			peak: Some(2 << 20),
+			..Usage::default()
+		};
+		let overruns = budget.overruns(&usage);
+		assert_eq!(overruns.len(), 2);
+		assert!(overruns[0].starts_with("part 1"));
+		assert!(budget.overruns(&Usage::default()).is_empty());
+	}
+}
@@ -0,0 +1,197 @@
+//! Answers remembered from earlier runs.
+//!
+//! Working on part 2 means running the day over and over, and every run solves
+//! part 1 again even though nothing about it has changed. The harness instead
+//! remembers each answer in `target/aoc-cache/{year}/d{day}.json`, keyed by a
+//! fingerprint of the input text and parameters it was computed from, and
+//! reuses it the next time the same input comes around. A run that wants both
+//! parts only reuses answers if both are remembered, since solving part 2 can
+//! depend on what preparing part 1 left behind.
+//!
+//! An answer is only as good as the code which produced it, so each one also
+//! records a fingerprint of the executable that computed it. Rebuilding the
+//! harness changes the fingerprint, and every answer computed by the old build
+//! is ignored, and then discarded when a new answer is stored.
+
+use std::{
+	collections::BTreeMap,
+	env,
+	fs,
+	path::PathBuf,
+	sync::OnceLock,
+	time::UNIX_EPOCH,
+};
+
+use chrono::{
+	DateTime,
+	Utc,
+};
+use eyre::Context;
+
+use crate::{
+	provenance,
+	Answer,
+	ParamMap,
+};
+
+/// One remembered answer.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedAnswer {
+	pub answer:   Answer,
+	/// The fingerprint of the build which computed the answer.
+	pub build:    String,
+	/// When the answer was computed.
+	pub computed: DateTime<Utc>,
+}
+
+/// The remembered answers for one day's puzzle.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnswerCache {
+	pub year:    u16,
+	pub day:     u8,
+	/// Answers by input fingerprint, then by part.
+	pub answers: BTreeMap<String, BTreeMap<u8, CachedAnswer>>,
+}
+
+impl AnswerCache {
+	/// Creates an empty cache for a day.
+	pub fn new(year: u16, day: u8) -> Self {
+		Self {
+			year,
+			day,
+			answers: BTreeMap::new(),
+		}
+	}
+
+	/// Computes the path of the cache file for a day.
+	pub fn path(year: u16, day: u8) -> eyre::Result<PathBuf> {
+		let mut path = crate::config::asset_root()?;
+		path.push("target");
+		path.push("aoc-cache");
+		path.push(year.to_string());
+		path.push(format!("d{day:0>2}.json"));
+		Ok(path)
+	}
+
+	/// Loads the cache for a day, or creates an empty one if none exists yet.
+	#[cfg_attr(feature = "trace", tracing::instrument)]
+	pub fn load(year: u16, day: u8) -> eyre::Result<Self> {
+		let path = Self::path(year, day)?;
+		if !path.exists() {
+			return Ok(Self::new(year, day));
+		}
+		let text = fs::read_to_string(&path).wrap_err_with(|| {
+			format!("could not read answer cache {}", path.display())
+		})?;
+		serde_json::from_str(&text).wrap_err_with(|| {
+			format!("could not parse answer cache {}", path.display())
+		})
+	}
+
+	/// Writes the cache back to disk.
+	#[cfg_attr(feature = "trace", tracing::instrument(skip(self), fields(year=%self.year, day=%self.day)))]
+	pub fn save(&self) -> eyre::Result<()> {
+		let path = Self::path(self.year, self.day)?;
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let text = serde_json::to_string_pretty(self)?;
+		provenance::write_atomic(&path, &text).wrap_err_with(|| {
+			format!("could not write answer cache {}", path.display())
+		})
+	}
+
+	/// Gets the answer a build computed for a part from an input.
+	pub fn get(&self, input: &str, part: u8, build: &str) -> Option<Answer> {
+		self.answers
+			.get(input)?
+			.get(&part)
+			.filter(|cached| cached.build == build)
+			.map(|cached| cached.answer.clone())
+	}
+
+	/// Remembers the answer a build computed for a part from an input.
+	///
+	/// Answers computed by any other build are discarded, since they will
+	/// never be used again.
+	pub fn insert(
+		&mut self,
+		input: &str,
+		part: u8,
+		answer: Answer,
+		build: &str,
+		now: DateTime<Utc>,
+	) {
+		for parts in self.answers.values_mut() {
+			parts.retain(|_, cached| cached.build == build);
+		}
+		self.answers.retain(|_, parts| !parts.is_empty());
+		self.answers.entry(input.to_owned()).or_default().insert(
+			part,
+			CachedAnswer {
+				answer,
+				build: build.to_owned(),
+				computed: now,
+			},
+		);
+	}
+}
+
+/// Fingerprints the input text and parameters that an answer depends on.
+pub fn input_key(text: &str, params: &ParamMap) -> String {
+	let mut keyed = text.to_owned();
+	for (key, value) in params.iter() {
+		keyed.push_str(&format!("\n{key}={value}"));
+	}
+	provenance::hash(&keyed)
+}
+
+/// Fingerprints the running executable.
+///
+/// This is computed once per process, from the executable's size and
+/// modification time, so any rebuild that relinks it changes the fingerprint
+/// without the whole file having to be read.
+pub fn build_fingerprint() -> eyre::Result<&'static str> {
+	static BUILD: OnceLock<String> = OnceLock::new();
+	if let Some(build) = BUILD.get() {
+		return Ok(build);
+	}
+	let exe = env::current_exe()?;
+	let meta = fs::metadata(&exe)
+		.wrap_err_with(|| format!("could not inspect {}", exe.display()))?;
+	let modified = meta
+		.modified()?
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_nanos();
+	Ok(BUILD.get_or_init(|| format!("{:x}-{modified:x}", meta.len())))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn answers_expire_with_their_build() {
+		let now = Utc::now();
+		let params = ParamMap::from_iter([("rounds", "20")]);
+		let input = input_key("1\n2\n", &params);
+		assert_ne!(input, input_key("1\n2\n", &ParamMap::new()));
+
+		let mut cache = AnswerCache::new(2022, 1);
+		cache.insert(&input, 1, 3.into(), "old", now);
+		cache.insert(&input, 2, 5.into(), "old", now);
+		assert_eq!(cache.get(&input, 1, "old"), Some(3.into()));
+		assert_eq!(cache.get(&input, 1, "new"), None);
+
+		cache.insert(&input, 1, 4.into(), "new", now);
+		assert_eq!(cache.get(&input, 1, "new"), Some(4.into()));
+		assert_eq!(
+			cache.get(&input, 2, "old"),
+			None,
+			"stale builds are dropped"
+		);
+	}
+}
@@ -0,0 +1,106 @@
+//! Cooperative cancellation of a run.
+//!
+//! The harness installs a Ctrl-C handler which raises a process-wide flag
+//! rather than killing the process outright, so that it can still report the
+//! phase timings it has collected. Solvers with long loops should call
+//! [`check`] every so often (once per step of a simulation, once per node of
+//! a search), and propagate its error; the harness also checks between
+//! phases. A solver which never checks can still be abandoned with a second
+//! Ctrl-C.
+
+use std::{
+	fmt,
+	sync::atomic::{
+		AtomicBool,
+		Ordering,
+	},
+};
+
+/// The token shared by the harness and every solver in the process.
+static GLOBAL: Token = Token::new();
+
+/// A flag which can be raised once, to ask a computation to stop.
+#[derive(Debug, Default)]
+pub struct Token {
+	cancelled: AtomicBool,
+}
+
+impl Token {
+	/// Creates a token which has not been cancelled.
+	pub const fn new() -> Self {
+		Self {
+			cancelled: AtomicBool::new(false),
+		}
+	}
+
+	/// Raises the flag.
+	///
+	/// Returns whether it had already been raised.
+	pub fn cancel(&self) -> bool {
+		self.cancelled.swap(true, Ordering::SeqCst)
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(Ordering::Relaxed)
+	}
+
+	/// Fails with [`Cancelled`] if the flag has been raised.
+	pub fn check(&self) -> Result<(), Cancelled> {
+		if self.is_cancelled() {
+			return Err(Cancelled);
+		}
+		Ok(())
+	}
+}
+
+/// The error produced when a run is cancelled.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.write_str("the run was cancelled")
+	}
+}
+
+impl std::error::Error for Cancelled {
+}
+
+/// Views the process-wide token.
+pub fn token() -> &'static Token {
+	&GLOBAL
+}
+
+/// Asks the current run to stop.
+///
+/// Returns whether cancellation had already been requested.
+pub fn cancel() -> bool {
+	GLOBAL.cancel()
+}
+
+/// Tests whether the current run has been asked to stop.
+pub fn is_cancelled() -> bool {
+	GLOBAL.is_cancelled()
+}
+
+/// Fails with [`Cancelled`] if the current run has been asked to stop.
+pub fn check() -> Result<(), Cancelled> {
+	GLOBAL.check()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cancels_once() {
+		// The process-wide token is shared with every other test, so this
+		// exercises a private one.
+		let token = Token::new();
+		assert!(token.check().is_ok());
+		assert!(!token.cancel());
+		assert!(token.cancel());
+		let err: eyre::Report = token.check().unwrap_err().into();
+		assert!(err.is::<Cancelled>());
+	}
+}
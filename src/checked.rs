@@ -0,0 +1,91 @@
+//! Overflow-checked arithmetic.
+//!
+//! Rust's `Sum` and `Product` panic on overflow in debug builds and wrap in
+//! release builds; neither is a useful way to learn that an answer needs a
+//! wider integer. These helpers fold with the `checked_*` methods instead, and
+//! turn overflow into an ordinary error that the harness can report.
+
+use std::{
+	any,
+	fmt,
+};
+
+use funty::Integral;
+
+use crate::AocError;
+
+/// Folds iterators of integers with overflow checking.
+pub trait CheckedIterator: Iterator + Sized
+where Self::Item: Integral
+{
+	/// Adds up all the items, failing if the total overflows.
+	fn checked_sum(self) -> eyre::Result<Self::Item> {
+		checked_fold(self, <Self::Item as Integral>::ZERO, "sum", |a, b| {
+			a.checked_add(b)
+		})
+	}
+
+	/// Multiplies all the items together, failing if the product overflows.
+	fn checked_product(self) -> eyre::Result<Self::Item> {
+		checked_fold(self, <Self::Item as Integral>::ONE, "product", |a, b| {
+			a.checked_mul(b)
+		})
+	}
+
+	/// Finds the least common multiple of all the items, failing if it
+	/// overflows.
+	///
+	/// The signs of the items are ignored. Any zero item makes the result
+	/// zero.
+	fn checked_lcm(self) -> eyre::Result<Self::Item> {
+		checked_fold(self, <Self::Item as Integral>::ONE, "lcm", |a, b| {
+			let zero = <Self::Item as Integral>::ZERO;
+			let b = if b < zero { zero.checked_sub(b)? } else { b };
+			if a == zero || b == zero {
+				return Some(zero);
+			}
+			let (mut x, mut y) = (a, b);
+			while y != zero {
+				(x, y) = (y, x % y);
+			}
+			(a / x).checked_mul(b)
+		})
+	}
+}
+
+impl<I: Iterator> CheckedIterator for I where I::Item: Integral
+{
+}
+
+fn checked_fold<T: Integral + fmt::Display>(
+	iter: impl Iterator<Item = T>,
+	init: T,
+	what: &str,
+	step: impl Fn(T, T) -> Option<T>,
+) -> eyre::Result<T> {
+	iter.enumerate().try_fold(init, |acc, (idx, item)| {
+		step(acc, item).ok_or_else(|| {
+			AocError::Overflow(format!(
+				"{what} overflowed {} at item {idx} ({acc} and {item})",
+				any::type_name::<T>()
+			))
+			.into()
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn checked_folds() {
+		assert_eq!([1i32, 2, 3].into_iter().checked_sum().unwrap(), 6);
+		assert_eq!([4u8, 8, 16].into_iter().checked_product().ok(), None);
+		let wide = [u64::MAX as u128, 2].into_iter().checked_product().unwrap();
+		assert_eq!(wide, u64::MAX as u128 * 2);
+		assert!([i64::MAX, 1].into_iter().checked_sum().is_err());
+		assert_eq!([4i128, -6, 10].into_iter().checked_lcm().unwrap(), 60);
+		assert!([255u8, 254].into_iter().checked_lcm().is_err());
+	}
+}
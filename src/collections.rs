@@ -0,0 +1,350 @@
+//! Compact collections for search states.
+//!
+//! Searches which track "which of these things have I done yet" (valves
+//! opened, keys collected, nodes visited) need that record to be cheap to copy,
+//! hash, and compare, since it is part of every state on the worklist. When
+//! the things come from a small universe, a single integer used as a bitset
+//! does all of this for free.
+//!
+//! The [`Fenwick`] and [`SegmentTree`] types serve puzzles which update and
+//! inspect long runs of an array at once, in logarithmic time per operation,
+//! and the [`IntervalTree`] finds which of many ranges contain a point.
+
+use std::{
+	fmt,
+	hash::Hash,
+	iter::FusedIterator,
+	sync::Arc,
+};
+
+use funty::Unsigned;
+
+pub use self::{
+	interval::IntervalTree,
+	range_tree::{
+		Fenwick,
+		SegmentTree,
+	},
+};
+use crate::dict::Dictionary;
+
+pub mod interval;
+mod range_tree;
+
+/// A set of small indices, stored as the bits of a single integer.
+///
+/// The set can hold any index below `B::BITS`: 0 through 63 for the default
+/// `u64`, or 0 through 127 for `u128`. Like the integer it wraps, the set is
+/// `Copy`, and its hash, equality, and ordering are all that of the integer.
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SmallSet<B: Unsigned = u64> {
+	bits: B,
+}
+
+impl<B: Unsigned> SmallSet<B> {
+	/// The number of distinct indices the set can hold.
+	pub const CAPACITY: usize = B::BITS as usize;
+
+	/// Creates an empty set.
+	pub fn new() -> Self {
+		Self { bits: B::ZERO }
+	}
+
+	/// Creates a set containing every index below `len`.
+	pub fn full(len: usize) -> Self {
+		assert!(len <= Self::CAPACITY, "{len} exceeds the set's capacity");
+		if len == Self::CAPACITY {
+			return Self { bits: !B::ZERO };
+		}
+		Self {
+			bits: (B::ONE << len) - B::ONE,
+		}
+	}
+
+	/// Wraps a raw bit-pattern.
+	pub fn from_bits(bits: B) -> Self {
+		Self { bits }
+	}
+
+	/// Gets the raw bit-pattern.
+	pub fn bits(self) -> B {
+		self.bits
+	}
+
+	/// Counts the members of the set.
+	pub fn len(self) -> usize {
+		self.bits.count_ones() as usize
+	}
+
+	pub fn is_empty(self) -> bool {
+		self.bits == B::ZERO
+	}
+
+	/// Tests whether an index is in the set.
+	pub fn contains(self, idx: usize) -> bool {
+		idx < Self::CAPACITY && self.bits & (B::ONE << idx) != B::ZERO
+	}
+
+	/// Adds an index to the set, returning `true` if it was not already
+	/// present.
+	///
+	/// ## Panics
+	///
+	/// This panics if the index is not below [`Self::CAPACITY`].
+	pub fn insert(&mut self, idx: usize) -> bool {
+		assert!(
+			idx < Self::CAPACITY,
+			"{idx} does not fit in a set of {} bits",
+			Self::CAPACITY
+		);
+		let had = self.contains(idx);
+		self.bits |= B::ONE << idx;
+		!had
+	}
+
+	/// Removes an index from the set, returning `true` if it was present.
+	pub fn remove(&mut self, idx: usize) -> bool {
+		let had = self.contains(idx);
+		if had {
+			self.bits &= !(B::ONE << idx);
+		}
+		had
+	}
+
+	/// Produces a copy of the set with an index added.
+	pub fn with(mut self, idx: usize) -> Self {
+		self.insert(idx);
+		self
+	}
+
+	/// Produces a copy of the set with an index removed.
+	pub fn without(mut self, idx: usize) -> Self {
+		self.remove(idx);
+		self
+	}
+
+	pub fn union(self, other: Self) -> Self {
+		Self::from_bits(self.bits | other.bits)
+	}
+
+	pub fn intersection(self, other: Self) -> Self {
+		Self::from_bits(self.bits & other.bits)
+	}
+
+	/// Produces the members of `self` which are not in `other`.
+	pub fn difference(self, other: Self) -> Self {
+		Self::from_bits(self.bits & !other.bits)
+	}
+
+	/// Tests whether every member of `self` is also in `other`.
+	pub fn is_subset(self, other: Self) -> bool {
+		self.difference(other).is_empty()
+	}
+
+	/// Tests whether every member of `other` is also in `self`.
+	pub fn is_superset(self, other: Self) -> bool {
+		other.is_subset(self)
+	}
+
+	/// Tests whether the two sets have no members in common.
+	pub fn is_disjoint(self, other: Self) -> bool {
+		self.intersection(other).is_empty()
+	}
+
+	/// Iterates over the members of the set, in ascending order.
+	pub fn iter(self) -> Iter<B> {
+		Iter { bits: self.bits }
+	}
+}
+
+impl<B: Unsigned> fmt::Debug for SmallSet<B> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_set().entries(self.iter()).finish()
+	}
+}
+
+impl<B: Unsigned> IntoIterator for SmallSet<B> {
+	type IntoIter = Iter<B>;
+	type Item = usize;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<B: Unsigned> FromIterator<usize> for SmallSet<B> {
+	fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+		let mut out = Self::new();
+		out.extend(iter);
+		out
+	}
+}
+
+impl<B: Unsigned> Extend<usize> for SmallSet<B> {
+	fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+		for idx in iter {
+			self.insert(idx);
+		}
+	}
+}
+
+/// Walks the members of a [`SmallSet`], lowest first.
+#[derive(Clone, Copy, Debug)]
+pub struct Iter<B: Unsigned> {
+	bits: B,
+}
+
+impl<B: Unsigned> Iterator for Iter<B> {
+	type Item = usize;
+
+	fn next(&mut self) -> Option<usize> {
+		if self.bits == B::ZERO {
+			return None;
+		}
+		let idx = self.bits.trailing_zeros() as usize;
+		// Clear the lowest set bit.
+		self.bits &= self.bits - B::ONE;
+		Some(idx)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.bits.count_ones() as usize;
+		(len, Some(len))
+	}
+}
+
+impl<B: Unsigned> ExactSizeIterator for Iter<B> {
+}
+
+impl<B: Unsigned> FusedIterator for Iter<B> {
+}
+
+/// Assigns labels to the indices of [`SmallSet`]s.
+///
+/// Labels are numbered in the order they are first seen, using a
+/// [`Dictionary`], so a universe built from the puzzle input before the search
+/// starts gives every label a stable index.
+pub struct Universe<T: ?Sized + Eq + Hash> {
+	labels: Dictionary<T>,
+}
+
+impl<T: ?Sized + Eq + Hash> Universe<T> {
+	/// Creates an empty universe.
+	pub fn new() -> Self {
+		Self {
+			labels: Dictionary::new(),
+		}
+	}
+
+	/// Counts the labels in the universe.
+	pub fn len(&self) -> usize {
+		self.labels.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.labels.is_empty()
+	}
+
+	/// Adds a label to the universe, returning its index.
+	///
+	/// Labels which are already known keep their existing index.
+	pub fn add(&mut self, label: impl AsRef<T> + Into<Arc<T>>) -> usize {
+		self.labels.insert(label).index()
+	}
+
+	/// Finds the index of a known label.
+	pub fn index_of(&self, label: impl AsRef<T>) -> Option<usize> {
+		self.labels.lookup_value(label).map(|ident| ident.index())
+	}
+
+	/// Finds the label at an index.
+	pub fn label(&self, idx: usize) -> Option<Arc<T>> {
+		self.labels.lookup(self.labels.identifier(idx)?)
+	}
+
+	/// Creates a set containing some known labels.
+	///
+	/// This fails if any label is not in the universe, or if its index does
+	/// not fit in the set.
+	pub fn set_of<B: Unsigned, L: AsRef<T>>(
+		&self,
+		labels: impl IntoIterator<Item = L>,
+	) -> eyre::Result<SmallSet<B>> {
+		let mut out = SmallSet::new();
+		for label in labels {
+			let idx = self
+				.index_of(label)
+				.ok_or_else(|| eyre::eyre!("label is not in the universe"))?;
+			if idx >= SmallSet::<B>::CAPACITY {
+				eyre::bail!(
+					"label {idx} does not fit in a set of {} bits",
+					SmallSet::<B>::CAPACITY
+				);
+			}
+			out.insert(idx);
+		}
+		Ok(out)
+	}
+
+	/// Iterates over the labels in a set.
+	pub fn labels_in<B: Unsigned>(
+		&self,
+		set: SmallSet<B>,
+	) -> impl '_ + Iterator<Item = Arc<T>> {
+		set.iter().filter_map(|idx| self.label(idx))
+	}
+}
+
+impl<T: ?Sized + Eq + Hash> Clone for Universe<T> {
+	fn clone(&self) -> Self {
+		Self {
+			labels: self.labels.clone(),
+		}
+	}
+}
+
+impl<T: ?Sized + Eq + Hash + fmt::Debug> fmt::Debug for Universe<T> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(&self.labels, fmt)
+	}
+}
+
+impl<T: ?Sized + Eq + Hash> Default for Universe<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn small_sets() -> eyre::Result<()> {
+		let evens = (0 .. 10).step_by(2).collect::<SmallSet>();
+		let low = SmallSet::<u64>::full(5);
+		assert_eq!(evens.len(), 5);
+		assert_eq!(low.iter().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+		assert_eq!(evens.intersection(low).iter().collect::<Vec<_>>(), [
+			0, 2, 4
+		]);
+		assert!(SmallSet::new().with(2).with(4).is_subset(evens));
+		assert!(!evens.is_subset(low));
+		assert!(evens.difference(low).is_disjoint(low));
+		assert_eq!(SmallSet::<u128>::full(128).len(), 128);
+		assert!(SmallSet::<u128>::new().with(127).contains(127));
+
+		let mut valves = Universe::<str>::new();
+		for name in ["AA", "BB", "CC", "DD"] {
+			valves.add(name);
+		}
+		assert_eq!(valves.add("BB"), 1);
+		let open = valves.set_of::<u64, _>(["CC", "AA"])?;
+		assert_eq!(format!("{open:?}"), "{0, 2}");
+		let names = valves.labels_in(open).collect::<Vec<_>>();
+		assert_eq!(names, [Arc::from("AA"), Arc::from("CC")]);
+		assert!(valves.set_of::<u64, _>(["ZZ"]).is_err());
+		Ok(())
+	}
+}
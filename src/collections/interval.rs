@@ -0,0 +1,203 @@
+//! An index of inclusive ranges, for finding the ranges which cover a point.
+//!
+//! Puzzles full of sensors, firewalls, or seed maps give many ranges up
+//! front and then ask which of them contain some value, over and over. Sorting
+//! the ranges by their start and recording, for each subtree of the sorted
+//! array, the furthest end within it, lets a query skip every subtree which
+//! ends too early or starts too late.
+
+use std::ops::RangeInclusive;
+
+/// One range in an [`IntervalTree`], with its value.
+pub type Entry<'a, I, T> = (&'a RangeInclusive<I>, &'a T);
+
+/// A static collection of inclusive ranges, each carrying a value, which
+/// answers stabbing and overlap queries in logarithmic time plus the size of
+/// the answer.
+///
+/// The tree is built all at once, from an iterator. Empty ranges are dropped.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalTree<I, T> {
+	/// The ranges, sorted by their start.
+	entries: Vec<(RangeInclusive<I>, T)>,
+	/// The greatest end in the implicit subtree rooted at each index.
+	///
+	/// The subtree over `lo .. hi` is rooted at its midpoint, as in a binary
+	/// search.
+	max_end: Vec<I>,
+}
+
+impl<I: Ord + Copy, T> IntervalTree<I, T> {
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Iterates over every range, in order of their start.
+	pub fn iter(&self) -> impl '_ + Iterator<Item = Entry<'_, I, T>> {
+		self.entries.iter().map(|(range, value)| (range, value))
+	}
+
+	/// Finds every range which contains a point.
+	pub fn stab(&self, point: I) -> Vec<Entry<'_, I, T>> {
+		self.overlapping(point ..= point)
+	}
+
+	/// Finds every range which shares at least one point with a query range,
+	/// in order of their start.
+	pub fn overlapping(&self, query: RangeInclusive<I>) -> Vec<Entry<'_, I, T>> {
+		let mut out = Vec::new();
+		if !query.is_empty() {
+			self.visit(0, self.entries.len(), &query, &mut out);
+		}
+		out
+	}
+
+	/// Finds every pair of ranges which overlap each other.
+	///
+	/// Each pair is reported once, with the range that starts first (or, for
+	/// ranges starting together, was sorted first) on the left.
+	pub fn overlapping_pairs(&self) -> Vec<(Entry<'_, I, T>, Entry<'_, I, T>)> {
+		let mut out = Vec::new();
+		let mut active: Vec<usize> = Vec::new();
+		for (idx, (range, value)) in self.entries.iter().enumerate() {
+			active.retain(|&prev| self.entries[prev].0.end() >= range.start());
+			for &prev in &active {
+				let (prev_range, prev_value) = &self.entries[prev];
+				out.push(((prev_range, prev_value), (range, value)));
+			}
+			active.push(idx);
+		}
+		out
+	}
+
+	/// Joins the ranges into the fewest ranges which cover exactly the same
+	/// points, in order.
+	///
+	/// Ranges which overlap are joined; ranges which merely touch are not.
+	pub fn union(&self) -> Vec<RangeInclusive<I>> {
+		let mut out = Vec::<RangeInclusive<I>>::new();
+		for (range, _) in &self.entries {
+			match out.last_mut() {
+				Some(last) if last.end() >= range.start() => {
+					*last = *last.start() ..= *last.end().max(range.end());
+				},
+				_ => out.push(range.clone()),
+			}
+		}
+		out
+	}
+
+	fn visit<'a>(
+		&'a self,
+		lo: usize,
+		hi: usize,
+		query: &RangeInclusive<I>,
+		out: &mut Vec<Entry<'a, I, T>>,
+	) {
+		if lo >= hi {
+			return;
+		}
+		let mid = lo + (hi - lo) / 2;
+		// Nothing in this subtree reaches the query.
+		if self.max_end[mid] < *query.start() {
+			return;
+		}
+		self.visit(lo, mid, query, out);
+		let (range, value) = &self.entries[mid];
+		// Nothing from here rightwards starts early enough.
+		if range.start() > query.end() {
+			return;
+		}
+		if range.end() >= query.start() {
+			out.push((range, value));
+		}
+		self.visit(mid + 1, hi, query, out);
+	}
+
+	/// Computes the furthest end in each subtree, returning that of the whole
+	/// `lo .. hi` subtree.
+	fn build(&mut self, lo: usize, hi: usize) -> Option<I> {
+		if lo >= hi {
+			return None;
+		}
+		let mid = lo + (hi - lo) / 2;
+		let mut end = *self.entries[mid].0.end();
+		for child in [self.build(lo, mid), self.build(mid + 1, hi)] {
+			end = end.max(child.unwrap_or(end));
+		}
+		self.max_end[mid] = end;
+		Some(end)
+	}
+}
+
+impl<I: Ord + Copy, T> FromIterator<(RangeInclusive<I>, T)>
+	for IntervalTree<I, T>
+{
+	fn from_iter<It: IntoIterator<Item = (RangeInclusive<I>, T)>>(
+		iter: It,
+	) -> Self {
+		let mut entries = iter
+			.into_iter()
+			.filter(|(range, _)| !range.is_empty())
+			.collect::<Vec<_>>();
+		entries.sort_by_key(|(range, _)| *range.start());
+		let max_end = entries.iter().map(|(range, _)| *range.end()).collect();
+		let mut out = Self { entries, max_end };
+		out.build(0, out.entries.len());
+		out
+	}
+}
+
+impl<I, T> Default for IntervalTree<I, T> {
+	fn default() -> Self {
+		Self {
+			entries: Vec::new(),
+			max_end: Vec::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stabbing_queries() {
+		let ranges = [(5, 10), (0, 2), (8, 20), (3, 3), (15, 16), (9, 4)];
+		let tree = ranges
+			.iter()
+			.enumerate()
+			.map(|(idx, &(lo, hi))| (lo ..= hi, idx))
+			.collect::<IntervalTree<i32, usize>>();
+		assert_eq!(tree.len(), 5, "the empty range is dropped");
+
+		let names = |found: Vec<Entry<i32, usize>>| {
+			found.into_iter().map(|(_, &idx)| idx).collect::<Vec<_>>()
+		};
+		for point in -1 ..= 21 {
+			let naive = ranges
+				.iter()
+				.enumerate()
+				.filter(|(_, &(lo, hi))| (lo ..= hi).contains(&point))
+				.map(|(idx, _)| idx)
+				.collect::<Vec<_>>();
+			let mut found = names(tree.stab(point));
+			found.sort();
+			assert_eq!(found, naive, "stabbing {point}");
+		}
+		assert_eq!(names(tree.overlapping(2 ..= 5)), [1, 3, 0]);
+
+		let pairs = tree
+			.overlapping_pairs()
+			.into_iter()
+			.map(|((_, &a), (_, &b))| (a, b))
+			.collect::<Vec<_>>();
+		assert_eq!(pairs, [(0, 2), (2, 4)]);
+		assert_eq!(tree.union(), [0 ..= 2, 3 ..= 3, 5 ..= 20]);
+	}
+}
@@ -0,0 +1,352 @@
+//! Trees over integer indices which answer questions about whole ranges.
+//!
+//! Instructions like "add 3 to every light from 500 through 90,000" are slow
+//! to apply one cell at a time, and slower still to sum back up afterwards.
+//! These trees apply an update to a range, and answer a query about a range, in
+//! time logarithmic in the length of the whole array.
+
+use std::ops::{
+	Bound,
+	Range,
+	RangeBounds,
+};
+
+/// Converts any kind of range into a half-open range over `0 .. len`.
+///
+/// ## Panics
+///
+/// This panics if the range does not fit in the array.
+fn span(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+	let start = match range.start_bound() {
+		Bound::Included(&n) => n,
+		Bound::Excluded(&n) => n + 1,
+		Bound::Unbounded => 0,
+	};
+	let end = match range.end_bound() {
+		Bound::Included(&n) => n + 1,
+		Bound::Excluded(&n) => n,
+		Bound::Unbounded => len,
+	};
+	assert!(
+		start <= end && end <= len,
+		"range {start} .. {end} is out of bounds for length {len}"
+	);
+	start .. end
+}
+
+/// A Fenwick tree (binary indexed tree) supporting range additions and range
+/// sums.
+///
+/// This keeps two trees: one of the additions themselves, and one correcting
+/// for where each addition started, so that the sum of any prefix is
+/// `added(i) * i - correction(i)`.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fenwick {
+	added:      Vec<i64>,
+	correction: Vec<i64>,
+}
+
+impl Fenwick {
+	/// Creates an array of zeros.
+	pub fn new(len: usize) -> Self {
+		Self {
+			added:      vec![0; len + 1],
+			correction: vec![0; len + 1],
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.added.len() - 1
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Adds to a single element.
+	pub fn add(&mut self, idx: usize, delta: i64) {
+		self.range_add(idx ..= idx, delta);
+	}
+
+	/// Adds to every element in a range.
+	pub fn range_add(&mut self, range: impl RangeBounds<usize>, delta: i64) {
+		let Range { start, end } = span(range, self.len());
+		if start == end {
+			return;
+		}
+		self.update(start, delta, delta * start as i64);
+		self.update(end, -delta, -delta * end as i64);
+	}
+
+	/// Gets a single element.
+	pub fn get(&self, idx: usize) -> i64 {
+		self.sum(idx ..= idx)
+	}
+
+	/// Sums the elements in a range.
+	pub fn sum(&self, range: impl RangeBounds<usize>) -> i64 {
+		let Range { start, end } = span(range, self.len());
+		self.prefix_sum(end) - self.prefix_sum(start)
+	}
+
+	/// Sums the first `end` elements.
+	pub fn prefix_sum(&self, end: usize) -> i64 {
+		let (mut added, mut correction) = (0, 0);
+		let mut node = end;
+		while node > 0 {
+			added += self.added[node];
+			correction += self.correction[node];
+			node &= node - 1;
+		}
+		added * end as i64 - correction
+	}
+
+	/// Records an addition which applies from `idx` onwards.
+	fn update(&mut self, idx: usize, added: i64, correction: i64) {
+		let mut node = idx + 1;
+		while node < self.added.len() {
+			self.added[node] += added;
+			self.correction[node] += correction;
+			node += node & node.wrapping_neg();
+		}
+	}
+}
+
+impl FromIterator<i64> for Fenwick {
+	fn from_iter<I: IntoIterator<Item = i64>>(iter: I) -> Self {
+		let values = iter.into_iter().collect::<Vec<_>>();
+		let mut out = Self::new(values.len());
+		for (idx, value) in values.into_iter().enumerate() {
+			out.add(idx, value);
+		}
+		out
+	}
+}
+
+/// A segment tree supporting range additions, and range sums, minima, and
+/// maxima.
+///
+/// Additions are applied lazily: an addition which covers a whole subtree is
+/// recorded at the subtree's root, and only pushed down to its children when a
+/// later operation needs to look inside it.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentTree {
+	len:   usize,
+	nodes: Vec<Node>,
+}
+
+/// The summary of one subtree.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Node {
+	sum:     i64,
+	min:     i64,
+	max:     i64,
+	/// An addition which has been applied to this node but not its children.
+	pending: i64,
+}
+
+impl SegmentTree {
+	/// Creates an array of zeros.
+	pub fn new(len: usize) -> Self {
+		Self::from_slice(&vec![0; len])
+	}
+
+	/// Creates a tree holding some values.
+	pub fn from_slice(values: &[i64]) -> Self {
+		let mut out = Self {
+			len:   values.len(),
+			nodes: vec![Node::default(); 4 * values.len().max(1)],
+		};
+		if !values.is_empty() {
+			out.build(1, 0 .. values.len(), values);
+		}
+		out
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Adds to every element in a range.
+	pub fn range_add(&mut self, range: impl RangeBounds<usize>, delta: i64) {
+		let range = span(range, self.len);
+		if !range.is_empty() {
+			self.add_in(1, 0 .. self.len, &range, delta);
+		}
+	}
+
+	/// Gets a single element.
+	pub fn get(&mut self, idx: usize) -> i64 {
+		self.sum(idx ..= idx)
+	}
+
+	/// Sums the elements in a range.
+	pub fn sum(&mut self, range: impl RangeBounds<usize>) -> i64 {
+		self.query(range).map(|node| node.sum).unwrap_or_default()
+	}
+
+	/// Finds the least element in a range, if it is not empty.
+	pub fn min(&mut self, range: impl RangeBounds<usize>) -> Option<i64> {
+		self.query(range).map(|node| node.min)
+	}
+
+	/// Finds the greatest element in a range, if it is not empty.
+	pub fn max(&mut self, range: impl RangeBounds<usize>) -> Option<i64> {
+		self.query(range).map(|node| node.max)
+	}
+
+	fn query(&mut self, range: impl RangeBounds<usize>) -> Option<Node> {
+		let range = span(range, self.len);
+		if range.is_empty() {
+			return None;
+		}
+		self.query_in(1, 0 .. self.len, &range)
+	}
+
+	fn build(&mut self, node: usize, span: Range<usize>, values: &[i64]) {
+		if span.len() == 1 {
+			let value = values[span.start];
+			self.nodes[node] = Node {
+				sum:     value,
+				min:     value,
+				max:     value,
+				pending: 0,
+			};
+			return;
+		}
+		let mid = span.start + span.len() / 2;
+		self.build(2 * node, span.start .. mid, values);
+		self.build(2 * node + 1, mid .. span.end, values);
+		self.pull(node);
+	}
+
+	fn add_in(
+		&mut self,
+		node: usize,
+		span: Range<usize>,
+		range: &Range<usize>,
+		delta: i64,
+	) {
+		if range.end <= span.start || span.end <= range.start {
+			return;
+		}
+		if range.start <= span.start && span.end <= range.end {
+			self.apply(node, span.len(), delta);
+			return;
+		}
+		self.push(node, &span);
+		let mid = span.start + span.len() / 2;
+		self.add_in(2 * node, span.start .. mid, range, delta);
+		self.add_in(2 * node + 1, mid .. span.end, range, delta);
+		self.pull(node);
+	}
+
+	fn query_in(
+		&mut self,
+		node: usize,
+		span: Range<usize>,
+		range: &Range<usize>,
+	) -> Option<Node> {
+		if range.end <= span.start || span.end <= range.start {
+			return None;
+		}
+		if range.start <= span.start && span.end <= range.end {
+			return Some(self.nodes[node]);
+		}
+		self.push(node, &span);
+		let mid = span.start + span.len() / 2;
+		let left = self.query_in(2 * node, span.start .. mid, range);
+		let right = self.query_in(2 * node + 1, mid .. span.end, range);
+		match (left, right) {
+			(Some(left), Some(right)) => Some(Node::merge(left, right)),
+			(one, None) | (None, one) => one,
+		}
+	}
+
+	/// Adds to every element in a subtree.
+	fn apply(&mut self, node: usize, len: usize, delta: i64) {
+		let node = &mut self.nodes[node];
+		node.sum += delta * len as i64;
+		node.min += delta;
+		node.max += delta;
+		node.pending += delta;
+	}
+
+	/// Hands a node's pending addition down to its children.
+	fn push(&mut self, node: usize, span: &Range<usize>) {
+		let pending = std::mem::take(&mut self.nodes[node].pending);
+		if pending != 0 {
+			let mid = span.start + span.len() / 2;
+			self.apply(2 * node, mid - span.start, pending);
+			self.apply(2 * node + 1, span.end - mid, pending);
+		}
+	}
+
+	/// Recomputes a node's summary from its children.
+	fn pull(&mut self, node: usize) {
+		self.nodes[node] =
+			Node::merge(self.nodes[2 * node], self.nodes[2 * node + 1]);
+	}
+}
+
+impl Node {
+	fn merge(left: Self, right: Self) -> Self {
+		Self {
+			sum:     left.sum + right.sum,
+			min:     left.min.min(right.min),
+			max:     left.max.max(right.max),
+			pending: 0,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn matches_naive_arrays() {
+		let mut naive = vec![0i64; 37];
+		let mut fenwick = Fenwick::new(naive.len());
+		let mut segments = SegmentTree::new(naive.len());
+		// A small linear congruential generator, so that the test is the same
+		// every run.
+		let mut seed = 12345u64;
+		let mut next = |below: usize| {
+			seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+			(seed >> 33) as usize % below
+		};
+		for _ in 0 .. 500 {
+			let (a, b) = (next(naive.len()), next(naive.len()));
+			let range = a.min(b) ..= a.max(b);
+			if next(2) == 0 {
+				let delta = next(21) as i64 - 10;
+				naive[range.clone()].iter_mut().for_each(|n| *n += delta);
+				fenwick.range_add(range.clone(), delta);
+				segments.range_add(range, delta);
+			}
+			else {
+				let expected = &naive[range.clone()];
+				let sum = expected.iter().sum::<i64>();
+				assert_eq!(fenwick.sum(range.clone()), sum);
+				assert_eq!(segments.sum(range.clone()), sum);
+				assert_eq!(
+					segments.min(range.clone()),
+					expected.iter().copied().min()
+				);
+				assert_eq!(segments.max(range), expected.iter().copied().max());
+			}
+		}
+		assert_eq!(segments.min(5 .. 5), None);
+		assert_eq!(fenwick.sum(..), naive.iter().sum::<i64>());
+		assert_eq!(Fenwick::from_iter([3, 1, 4, 1, 5]).sum(1 .. 4), 6);
+		assert_eq!(SegmentTree::from_slice(&[3, 1, 4, 1, 5]).max(..), Some(5));
+	}
+}
@@ -0,0 +1,57 @@
+//! Times many runs of a solver.
+
+use wyz_aoc::bench;
+
+use super::{
+	lookup,
+	Inputs,
+};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+pub struct BenchArgs {
+	/// The desired puzzle year.
+	pub year:    u16,
+	/// The desired puzzle day.
+	pub day:     u8,
+	#[command(flatten)]
+	pub inputs:  Inputs,
+	/// How many runs to measure.
+	#[arg(short = 'n', long, default_value_t = 50)]
+	pub samples: usize,
+	/// How many runs to throw away before measuring.
+	#[arg(long, value_name = "RUNS", default_value_t = 3)]
+	pub warmup:  usize,
+	/// Pin the solver to one CPU core.
+	#[arg(long, value_name = "CORE")]
+	pub pin:     Option<usize>,
+}
+
+impl BenchArgs {
+	/// Times many runs of the solver, after a warm-up, and reports statistics
+	/// about each phase.
+	pub fn execute(&self) -> eyre::Result<()> {
+		let entry = lookup(self.year, self.day)?;
+		let group = self.inputs.single_group("benchmark")?;
+		let (part_1, part_2) = self.inputs.parts();
+		let config = bench::Config {
+			warmup: self.warmup,
+			samples: self.samples,
+			core: self.pin,
+			part_1,
+			part_2,
+		};
+		tracing::info!(
+			warmup = config.warmup,
+			samples = config.samples,
+			"benchmarking"
+		);
+		let report = bench::run(entry, group, &self.inputs.overrides(), config)?;
+		tracing::info!("parse: {}", report.parse);
+		for (part, stats) in [(1, report.part_1), (2, report.part_2)] {
+			if let Some(stats) = stats {
+				tracing::info!("part {part}: {stats}");
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,109 @@
+//! Downloads a puzzle's real input.
+//!
+//! The site only serves input to a logged-in account, so this needs the
+//! account's session cookie. It is read from the `SESSION_ID` environment
+//! variable, then from the `session_file` named in `aoc.toml`, and failing
+//! those, from a `SESSION_ID=` line in `.env` at the project root, the same
+//! place `app/get.sh` looks. The download itself is done by `curl`.
+
+use std::{
+	env,
+	fs,
+	io::Write as _,
+	process::{
+		Command,
+		Stdio,
+	},
+};
+
+use chrono::Datelike as _;
+use eyre::WrapErr as _;
+use wyz_aoc::config::{
+	self,
+	Config,
+};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+pub struct FetchArgs {
+	/// The puzzle year. Defaults to the current year.
+	pub year:  Option<u16>,
+	/// The puzzle day. Defaults to the current day of the month.
+	pub day:   Option<u8>,
+	/// Replace the input file if it already exists.
+	#[arg(long)]
+	pub force: bool,
+}
+
+impl FetchArgs {
+	/// Downloads the input into the puzzle's directory, as its `input` group.
+	pub fn execute(&self) -> eyre::Result<()> {
+		let today = chrono::Local::now();
+		let year = self.year.unwrap_or(today.year() as u16);
+		let day = self.day.unwrap_or(today.day() as u8);
+		if !(1 ..= 25).contains(&day) {
+			eyre::bail!("there is no puzzle on day {day}");
+		}
+		let file = config::asset_root()?
+			.join(format!("src/y{year}/d{day:0>2}/input.txt"));
+		if file.exists() && !self.force {
+			eyre::bail!(
+				"{} already exists; pass `--force` to replace it",
+				file.display()
+			);
+		}
+		let session = session_id()?;
+		if let Some(dir) = file.parent() {
+			fs::create_dir_all(dir).wrap_err_with(|| {
+				format!("could not create {}", dir.display())
+			})?;
+		}
+
+		let url = format!("https://adventofcode.com/{year}/day/{day}/input");
+		tracing::info!(%url, file=%file.display(), "fetching");
+		// The cookie goes in on stdin, so that it does not show up in the
+		// process list.
+		let mut curl = Command::new("curl")
+			.args(["--fail", "--silent", "--show-error", "--header", "@-"])
+			.args(["--user-agent", "github.com/myrrlyn/aoc"])
+			.arg("--output")
+			.arg(&file)
+			.arg(&url)
+			.stdin(Stdio::piped())
+			.spawn()
+			.wrap_err("could not run `curl`")?;
+		if let Some(mut stdin) = curl.stdin.take() {
+			writeln!(stdin, "Cookie: session={session}")?;
+		}
+		let status = curl.wait()?;
+		if !status.success() {
+			eyre::bail!("`curl` could not fetch {url}: {status}");
+		}
+		tracing::info!(bytes = fs::metadata(&file)?.len(), "fetched");
+		Ok(())
+	}
+}
+
+/// Finds the session cookie.
+fn session_id() -> eyre::Result<String> {
+	if let Ok(id) = env::var("SESSION_ID") {
+		return Ok(id);
+	}
+	if let Some(path) = &Config::get().session_file {
+		let id = fs::read_to_string(path).wrap_err_with(|| {
+			format!("could not read the session file {}", path.display())
+		})?;
+		return Ok(id.trim().to_owned());
+	}
+	let dotenv = fs::read_to_string(".env").unwrap_or_default();
+	dotenv
+		.lines()
+		.filter_map(|line| line.trim().strip_prefix("SESSION_ID="))
+		.map(|id| id.trim_matches('"').to_owned())
+		.next()
+		.ok_or_else(|| {
+			eyre::eyre!(
+				"set `SESSION_ID` in the environment or in `.env`, or name a \
+				 `session_file` in `aoc.toml`"
+			)
+		})
+}
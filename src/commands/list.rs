@@ -0,0 +1,80 @@
+//! Lists every known puzzle.
+
+use clap::ValueEnum;
+use wyz_aoc::Difficulty;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+pub struct ListArgs {
+	/// How to print the list. This is separate from the global `--format`,
+	/// which styles trace messages.
+	#[arg(long, value_enum, value_name = "FORMAT", default_value_t)]
+	pub output: ListFormat,
+}
+
+#[derive(
+	Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum,
+)]
+pub enum ListFormat {
+	/// Aligned columns, for people.
+	#[default]
+	Table,
+	/// One JSON array, for scripts.
+	Json,
+}
+
+/// One row of the list.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct ListEntry {
+	year:       u16,
+	day:        u8,
+	title:      Option<String>,
+	has_sample: bool,
+	has_input:  bool,
+	difficulty: Difficulty,
+}
+
+impl ListArgs {
+	/// Prints every registered puzzle, with its title, available input files,
+	/// and estimated difficulty.
+	pub fn execute(&self) -> eyre::Result<()> {
+		let entries = wyz_aoc::solutions()
+			.values()
+			.flat_map(|days| days.values())
+			.map(|solver| ListEntry {
+				year:       solver.year,
+				day:        solver.day,
+				title:      solver.title(),
+				has_sample: solver.has_group("sample"),
+				has_input:  solver.has_group("input"),
+				difficulty: solver.difficulty(),
+			})
+			.collect::<Vec<_>>();
+		match self.output {
+			ListFormat::Table => {
+				let mark = |flag| if flag { "yes" } else { "no" };
+				println!("year day sample input     cost title");
+				for entry in &entries {
+					println!(
+						"{:>4} {:>3} {:>6} {:>5} {:>8} {}",
+						entry.year,
+						entry.day,
+						mark(entry.has_sample),
+						mark(entry.has_input),
+						entry.difficulty,
+						entry.title.as_deref().unwrap_or("-"),
+					);
+				}
+			},
+			#[cfg(feature = "serde")]
+			ListFormat::Json => {
+				println!("{}", serde_json::to_string_pretty(&entries)?);
+			},
+			#[cfg(not(feature = "serde"))]
+			ListFormat::Json => {
+				eyre::bail!("JSON listing requires the `serde` feature");
+			},
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,157 @@
+//! The harness' subcommands.
+//!
+//! Each workflow lives in its own module, with its own arguments and an
+//! `execute` method that the harness calls on the solver thread. Running a
+//! puzzle is the default: `wyz_aoc 2023 10` means `wyz_aoc run 2023 10`.
+
+use std::fmt::{
+	self,
+	Write as _,
+};
+
+use clap::ValueEnum;
+use eyre::WrapErr as _;
+use wyz_aoc::{
+	Answer,
+	ParamMap,
+	Solver,
+};
+
+pub mod bench;
+pub mod fetch;
+pub mod list;
+pub mod new;
+pub mod race;
+pub mod run;
+pub mod serve;
+pub mod verify;
+
+/// Which input, steps, and parameters to run a puzzle with. Shared by every
+/// subcommand that runs solvers.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+pub struct Inputs {
+	/// Whether to use the sample or real input data.
+	#[arg(short, long, value_enum, default_value_t)]
+	pub data:   Data,
+	/// Which step(s) to run.
+	#[arg(short, long, value_enum, default_value_t)]
+	pub step:   Step,
+	/// Override one of the solver's parameters, as `key=value`. Overrides
+	/// replace the values in the data group's parameter file, and may be
+	/// given more than once.
+	#[arg(
+		short = 'p',
+		long = "param",
+		value_name = "KEY=VALUE",
+		value_parser = parse_param,
+	)]
+	pub params: Vec<(String, String)>,
+}
+
+impl Inputs {
+	/// Whether part 1 and part 2, respectively, were requested.
+	pub fn parts(&self) -> (bool, bool) {
+		(self.step != Step::Two, self.step != Step::One)
+	}
+
+	/// Collects the `--param` overrides.
+	pub fn overrides(&self) -> ParamMap {
+		self.params.iter().cloned().collect()
+	}
+
+	/// Names the single input group to use, refusing `all-groups` for
+	/// workflows which cannot compare groups.
+	pub fn single_group(&self, workflow: &str) -> eyre::Result<&'static str> {
+		match self.data {
+			Data::Sample => Ok("sample"),
+			Data::Input => Ok("input"),
+			Data::AllGroups => {
+				eyre::bail!("cannot {workflow} across every input group")
+			},
+		}
+	}
+}
+
+#[derive(
+	Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum,
+)]
+pub enum Data {
+	#[default]
+	Sample,
+	Input,
+	/// Run every input file in the puzzle's directory and compare answers.
+	AllGroups,
+}
+
+impl fmt::Display for Data {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, fmt)
+	}
+}
+
+#[derive(
+	Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum,
+)]
+pub enum Step {
+	One,
+	Two,
+	#[default]
+	All,
+}
+
+impl fmt::Display for Step {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, fmt)
+	}
+}
+
+/// Looks up a puzzle's solver in the registry.
+pub fn lookup(year: u16, day: u8) -> eyre::Result<Solver> {
+	let span = tracing::error_span!("lookup");
+	let _span = span.enter();
+	let solution = wyz_aoc::solutions()
+		.get(&year)
+		.and_then(|y| y.get(&day))
+		.ok_or_else(|| eyre::eyre!("{}", render_known_puzzles()))
+		.wrap_err_with(|| {
+			eyre::eyre!("{year}-{day:0>2} has no registered solution")
+		})?;
+	tracing::trace!("found solver");
+	Ok(*solution)
+}
+
+/// Parses a `--param` argument.
+fn parse_param(text: &str) -> Result<(String, String), String> {
+	ParamMap::parse_assignment(text).map_err(|err| err.to_string())
+}
+
+/// Renders one cell of a summary table: the answer to a part, `n/a` when the
+/// part was requested but the puzzle has none, or `-` when it was not run.
+pub fn show(requested: bool, answer: Option<Answer>) -> String {
+	match answer {
+		Some(answer) => answer.to_string(),
+		None if requested => "n/a".to_owned(),
+		None => "-".to_owned(),
+	}
+}
+
+pub fn render_known_puzzles() -> String {
+	let mut show = String::new();
+	writeln!(&mut show, "Known solutions are:").ok();
+	for (year, days) in wyz_aoc::solutions() {
+		let mut days = days.keys();
+		if let Some(day) = days.next() {
+			write!(&mut show, "- y{year}: d{day:0>2}").ok();
+		}
+		for day in days {
+			write!(&mut show, ", d{day:0>2}").ok();
+		}
+		writeln!(&mut show).ok();
+	}
+	write!(
+		&mut show,
+		"Do not use the `y` or `d` prefixes when providing arguments."
+	)
+	.ok();
+	show
+}
@@ -0,0 +1,147 @@
+//! Starts a new puzzle module.
+//!
+//! This writes `src/y{year}/d{day}/mod.rs`, with a solver that parses its
+//! input into lines and solves nothing, and an empty `sample.txt` beside it,
+//! then declares the module in its year, and the year in the library if it is
+//! new. The harness has to be rebuilt before it can run the new puzzle.
+
+use std::{
+	fs,
+	path::Path,
+};
+
+use eyre::WrapErr as _;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+pub struct NewArgs {
+	/// The puzzle year.
+	pub year: u16,
+	/// The puzzle day.
+	pub day:  u8,
+	/// The name of the solver type.
+	#[arg(long, default_value = "Solution")]
+	pub name: String,
+}
+
+/// The new module's source. `YEAR`, `DAY`, and `NAME` are replaced.
+const TEMPLATE: &str = r#"use nom::{
+	character::complete::{
+		newline,
+		not_line_ending,
+	},
+	combinator::map,
+	multi::separated_list1,
+};
+
+use crate::prelude::*;
+
+crate::register_solver!(yYEAR, dDAY, NAME);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NAME {
+	lines: Vec<String>,
+}
+
+impl<'a> Parsed<&'a str> for NAME {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(separated_list1(newline, not_line_ending), |lines| Self {
+			lines: lines.into_iter().map(str::to_owned).collect(),
+		})(text)
+	}
+}
+
+impl Puzzle for NAME {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![("lines", self.lines.len().to_string())]
+	}
+}
+"#;
+
+impl NewArgs {
+	/// Writes the new module and declares it.
+	pub fn execute(&self) -> eyre::Result<()> {
+		let (year, day) = (self.year, self.day);
+		if !(1 ..= 25).contains(&day) {
+			eyre::bail!("there is no puzzle on day {day}");
+		}
+		if !is_type_name(&self.name) {
+			eyre::bail!("`{}` is not a type name", self.name);
+		}
+		let dir = format!("src/y{year}/d{day:0>2}");
+		let module = Path::new(&dir).join("mod.rs");
+		if module.exists() {
+			eyre::bail!("{} already exists", module.display());
+		}
+
+		fs::create_dir_all(&dir)
+			.wrap_err_with(|| format!("could not create {dir}"))?;
+		let year_module = format!("src/y{year}/mod.rs");
+		if !Path::new(&year_module).exists() {
+			fs::write(&year_module, "")
+				.wrap_err_with(|| format!("could not create {year_module}"))?;
+			tracing::info!(file = year_module, "created");
+			declare("src/lib.rs", &format!("pub mod y{year};"), |line| {
+				line.strip_prefix("pub mod y")
+					.and_then(|rest| rest.strip_suffix(';'))
+					.is_some_and(|y| y.len() == 4 && y.parse::<u16>().is_ok())
+			})?;
+		}
+		declare(&year_module, &format!("pub mod d{day:0>2};"), |line| {
+			line.starts_with("pub mod d")
+		})?;
+
+		let source = TEMPLATE
+			.replace("YEAR", &year.to_string())
+			.replace("DAY", &format!("{day:0>2}"))
+			.replace("NAME", &self.name);
+		fs::write(&module, source)
+			.wrap_err_with(|| format!("could not write {}", module.display()))?;
+		tracing::info!(file=%module.display(), "created");
+		let sample = Path::new(&dir).join("sample.txt");
+		if !sample.exists() {
+			fs::write(&sample, "")?;
+			tracing::info!(file=%sample.display(), "created");
+		}
+		Ok(())
+	}
+}
+
+/// Adds a module declaration to a file, keeping the run of similar
+/// declarations (those matching `similar`) in sorted order.
+fn declare(
+	file: &str,
+	decl: &str,
+	similar: impl Fn(&str) -> bool,
+) -> eyre::Result<()> {
+	let text = fs::read_to_string(file)
+		.wrap_err_with(|| format!("could not read {file}"))?;
+	let mut lines = text.lines().collect::<Vec<_>>();
+	if lines.contains(&decl) {
+		return Ok(());
+	}
+	let found = lines.iter().position(|line| similar(line));
+	let at = match found {
+		Some(first) => {
+			let mut at = first;
+			while at < lines.len() && similar(lines[at]) && lines[at] < decl {
+				at += 1;
+			}
+			at
+		},
+		None => lines.len(),
+	};
+	lines.insert(at, decl);
+	let mut text = lines.join("\n");
+	text.push('\n');
+	fs::write(file, text).wrap_err_with(|| format!("could not write {file}"))?;
+	tracing::info!(%file, %decl, "declared");
+	Ok(())
+}
+
+/// Tests whether a name can be used as a Rust type name.
+fn is_type_name(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars.next().is_some_and(|c| c.is_ascii_uppercase())
+		&& chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
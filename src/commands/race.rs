@@ -0,0 +1,119 @@
+//! Timing a run against the puzzle's unlock, as the leaderboard does.
+//!
+//! Each puzzle unlocks at midnight, US Eastern time (UTC-5, since December is
+//! outside daylight saving), on its day of December, and the leaderboard ranks
+//! solvers by how long after that they submitted. `--race` waits for the unlock
+//! if it has not happened yet, fetches the input if it is not on disk, and
+//! reports each answer's time on that clock.
+
+use std::{
+	io::{
+		self,
+		IsTerminal as _,
+		Write as _,
+	},
+	thread,
+	time::Duration,
+};
+
+use chrono::{
+	DateTime,
+	FixedOffset,
+	TimeZone as _,
+	Utc,
+};
+use wyz_aoc::{
+	cancel,
+	Solver,
+};
+
+use super::fetch::FetchArgs;
+
+/// The clock that a puzzle's leaderboard runs on.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Race {
+	unlock: DateTime<Utc>,
+}
+
+impl Race {
+	/// Finds when a puzzle unlocks.
+	pub fn new(year: u16, day: u8) -> eyre::Result<Self> {
+		let eastern =
+			FixedOffset::west_opt(5 * 3600).expect("UTC-05:00 is valid");
+		let unlock = eastern
+			.with_ymd_and_hms(year.into(), 12, day.into(), 0, 0, 0)
+			.single()
+			.ok_or_else(|| eyre::eyre!("{year}-12-{day:0>2} is not a date"))?;
+		Ok(Self {
+			unlock: unlock.with_timezone(&Utc),
+		})
+	}
+
+	/// Counts down to the unlock, if it is still to come, and then makes sure
+	/// the real input is on disk, if that is the `group` being run.
+	///
+	/// The countdown is drawn on one line of the terminal, when stderr is one,
+	/// and is cut short by Ctrl-C.
+	pub fn start(&self, entry: Solver, group: &str) -> eyre::Result<()> {
+		let wait = (self.unlock - Utc::now()).to_std().unwrap_or_default();
+		if !wait.is_zero() {
+			tracing::info!(
+				unlock = %self.unlock,
+				wait = %clock(wait),
+				"waiting for the puzzle to unlock",
+			);
+			let draw = io::stderr().is_terminal();
+			loop {
+				cancel::check()?;
+				let left =
+					(self.unlock - Utc::now()).to_std().unwrap_or_default();
+				if left.is_zero() {
+					break;
+				}
+				if draw {
+					eprint!("\r{} until the puzzle unlocks ", clock(left));
+					let _ = io::stderr().flush();
+				}
+				thread::sleep(left.min(Duration::from_secs(1)));
+			}
+			if draw {
+				eprintln!();
+			}
+		}
+		if group == "input" && !entry.has_group(group) {
+			FetchArgs {
+				year:  Some(entry.year),
+				day:   Some(entry.day),
+				force: false,
+			}
+			.execute()?;
+		}
+		Ok(())
+	}
+
+	/// Logs how long after the unlock a part's answer was ready.
+	pub fn report(&self, part: u8) {
+		match (Utc::now() - self.unlock).to_std() {
+			Ok(since) => {
+				tracing::info!(part, time = %clock(since), "leaderboard time")
+			},
+			Err(_) => {
+				tracing::warn!(part, "answered before the puzzle unlocked")
+			},
+		}
+	}
+}
+
+/// Formats a duration the way the leaderboard does, as `hh:mm:ss`, or with
+/// days in front once it passes a day.
+fn clock(time: Duration) -> String {
+	let secs = time.as_secs();
+	let (days, hours) = (secs / 86_400, secs / 3600 % 24);
+	let (mins, secs) = (secs / 60 % 60, secs % 60);
+	if days > 0 {
+		format!("{days}d {hours:0>2}:{mins:0>2}:{secs:0>2}")
+	}
+	else {
+		format!("{hours:0>2}:{mins:0>2}:{secs:0>2}")
+	}
+}
@@ -0,0 +1,836 @@
+//! Runs a puzzle, or one day's puzzles from every year.
+
+use std::{
+	fmt,
+	fs,
+	io::{
+		self,
+		Write as _,
+	},
+	mem,
+	path::PathBuf,
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+use clap::ValueEnum;
+use eyre::WrapErr as _;
+use wyz_aoc::{
+	cancel,
+	expected::{
+		Expected,
+		Mismatch,
+	},
+	explain::{
+		Phase,
+		Walkthrough,
+	},
+	interact::Streams,
+	metrics::{
+		self,
+		PhaseMetrics,
+	},
+	random,
+	sim::record::Frame,
+	Answer,
+	AocError,
+	ParamMap,
+	PartPhase,
+	Puzzle,
+	Solver,
+};
+
+use super::{
+	lookup,
+	race::Race,
+	render_known_puzzles,
+	show,
+	Data,
+	Inputs,
+};
+
+/// Runs one puzzle, or one day's puzzle from every year.
+///
+/// `--explore`, `--interactive`, `--parse-only`, and `--replay` each take over
+/// the run once the input is parsed, so at most one of them can be given.
+/// They form the `mode` group, which other flags conflict with as a whole.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+#[command(group(clap::ArgGroup::new("mode").multiple(false)))]
+pub struct RunArgs {
+	/// The desired puzzle year.
+	#[arg(required_unless_present = "day_across_years")]
+	pub year: Option<u16>,
+	/// The desired puzzle day.
+	#[arg(required_unless_present = "day_across_years")]
+	pub day: Option<u8>,
+	/// Run this day's solver from every year that has one, instead of a single
+	/// puzzle.
+	#[arg(
+		long,
+		value_name = "DAY",
+		conflicts_with_all = [
+			"year",
+			"day",
+			"mode",
+			"explain",
+			"check",
+			"race",
+			"dump_state",
+			"quiet",
+		],
+	)]
+	pub day_across_years: Option<u8>,
+	#[command(flatten)]
+	pub inputs: Inputs,
+	/// Open an interactive prompt after parsing, rather than running the
+	/// solver straight through.
+	#[arg(short, long, group = "mode")]
+	pub explore: bool,
+	/// Play the puzzle in the terminal after parsing, rather than solving it.
+	/// Only game-like solvers can be played; a script can be piped in
+	/// instead of typing.
+	#[arg(long, group = "mode")]
+	pub interactive: bool,
+	/// Stop after parsing, and report statistics about the parsed input
+	/// instead of solving.
+	#[arg(long, group = "mode")]
+	pub parse_only: bool,
+	/// Record the solver's steps while it runs, then step through them at an
+	/// interactive prompt. Only simulation-style solvers keep recordings.
+	#[arg(long, group = "mode")]
+	pub replay: bool,
+	/// Print the day's README, a section at a time, before each phase that it
+	/// explains.
+	#[arg(long, conflicts_with = "explore")]
+	pub explain: bool,
+	/// Solve every requested part, even those whose answers this build has
+	/// already computed from the same input and parameters.
+	#[arg(long)]
+	pub force: bool,
+	/// Compare each answer with the one known to be right, from
+	/// `assets/answers/` or the submission history, and fail if any differ.
+	#[arg(long, conflicts_with = "mode")]
+	pub check: bool,
+	/// Time the answers against the puzzle's unlock, as the leaderboard
+	/// does. If the puzzle has not unlocked yet, count down to it, and then
+	/// fetch the real input if it is missing.
+	#[arg(long, conflicts_with = "explore")]
+	pub race: bool,
+	/// Write the solver's state as JSON to PATH after PHASE, for analysis
+	/// elsewhere. This can be given more than once. Only solvers whose types
+	/// are serializable, under the `serde` feature, can be dumped.
+	#[arg(
+		long,
+		value_name = "PHASE=PATH",
+		value_parser = parse_dump,
+		conflicts_with = "explore",
+	)]
+	pub dump_state: Vec<(DumpPhase, PathBuf)>,
+	/// Print only the answers, one line per solved part, and no logs. Errors
+	/// are still printed, to stderr. With `--data all-groups`, each answer is
+	/// prefixed by its group's name.
+	#[arg(short, long, conflicts_with_all = ["mode", "explain"])]
+	pub quiet: bool,
+	/// Whether the global `--seed` was given. Cached answers came from
+	/// whatever seed their run had, so a replayed seed always solves afresh.
+	#[arg(skip)]
+	pub seeded: bool,
+}
+
+impl RunArgs {
+	/// Runs the requested puzzle, or the requested day from every year.
+	///
+	/// `metrics` is the harness' phase timer, for `--check` to report.
+	pub fn execute(&self, metrics: &PhaseMetrics) -> eyre::Result<()> {
+		if let Some(day) = self.day_across_years {
+			return self.run_across_years(day);
+		}
+		match (self.year, self.day) {
+			(Some(year), Some(day)) => self.run_one(year, day, metrics),
+			_ => eyre::bail!("did not provide a year and day"),
+		}
+	}
+
+	#[tracing::instrument(name = "run", skip(self, metrics))]
+	fn run_one(
+		&self,
+		year: u16,
+		day: u8,
+		metrics: &PhaseMetrics,
+	) -> eyre::Result<()> {
+		let entry = lookup(year, day)?;
+		let group = match self.inputs.data {
+			Data::Sample => "sample",
+			Data::Input => "input",
+			Data::AllGroups if self.check => {
+				eyre::bail!("can only check the answers of one input group")
+			},
+			Data::AllGroups => return self.compare_groups(entry),
+		};
+		let expected = self
+			.check
+			.then(|| Expected::load(group, year, day))
+			.transpose()?;
+		let mut dumps = self.dump_state.clone();
+		let race = self.race.then(|| Race::new(year, day)).transpose()?;
+		if let Some(race) = &race {
+			race.start(entry, group)?;
+			// Time the answers from the unlock, not from the wait before it.
+			metrics.restart_clock();
+		}
+		let source_text = entry.load_input(group)?;
+		entry.track_input(group, &source_text);
+		let mut params = entry.load_params(group)?;
+		params.extend(self.inputs.params.iter().cloned());
+
+		let walkthrough = self.explain.then(|| load_walkthrough(entry));
+		let explain = |phase| {
+			if let Some(text) =
+				walkthrough.as_ref().and_then(|w| w.section(phase))
+			{
+				println!("\n{text}\n");
+			}
+		};
+
+		explain(Phase::Parse);
+		tracing::info!("parsing");
+		// This error map is necessary because nom's default error holds views
+		// into the source data, but the error is returned out of this function
+		// after the source text is destroyed.
+		let (rest, mut solver) = entry
+			.parse(source_text.as_str())
+			.map_err(|err| AocError::InvalidInput(err.to_string()))?;
+		AocError::check_leftover(rest)?;
+		if let Some(title) = solver.title() {
+			tracing::info!(%title, "parsed");
+		}
+		for (key, value) in params.iter() {
+			tracing::debug!(%key, %value, "setting parameter");
+		}
+		solver
+			.set_params(&params)
+			.wrap_err("could not apply the data group's parameters")?;
+		solver.set_rng(random::rng());
+		dump_state(&mut dumps, DumpPhase::Parse, &*solver)?;
+		tracing::info!("processing");
+		tracing::error_span!(metrics::PROCESS)
+			.in_scope(|| solver.after_parse())
+			.wrap_err(
+				"input was successfully parsed, but was not valid for the \
+				 rules of the puzzle",
+			)?;
+		dump_state(&mut dumps, DumpPhase::Process, &*solver)?;
+
+		if self.parse_only {
+			describe_input(&source_text, rest, &*solver);
+			return Ok(());
+		}
+		if self.explore {
+			return explore(entry, &source_text, &params, solver);
+		}
+		if self.interactive {
+			tracing::info!("playing");
+			return solver.interact(&mut Streams::terminal());
+		}
+		if self.replay {
+			match solver.recorder() {
+				Some(recorder) => recorder.enable(),
+				None => tracing::warn!("this solver does not record its steps"),
+			}
+		}
+
+		let (one, two) = self.inputs.parts();
+		// Part 2 may build on what part 1 prepared, so a remembered answer is
+		// only reused when every requested part has one; otherwise, every
+		// requested part is solved afresh.
+		let wanted = [one, two && solver.has_part_2()];
+		let mut cached = [1, 2]
+			.map(|part| self.cached_answer(entry, &source_text, &params, part));
+		if wanted
+			.iter()
+			.zip(&cached)
+			.any(|(&want, answer)| want && answer.is_none())
+		{
+			cached = [None, None];
+		}
+		let from_cache = cached.iter().any(Option::is_some);
+		let [cached_1, cached_2] = cached;
+		let mut common_ready = false;
+		let mut wrong = Vec::new();
+		let parts = [
+			(1, one, cached_1, Phase::Part1),
+			(2, two, cached_2, Phase::Part2),
+		];
+		for (part, requested, cached, phase) in parts {
+			if !requested {
+				continue;
+			}
+			if part == 2 && !solver.has_part_2() {
+				tracing::info!(part = 2, "this puzzle has no part 2");
+				continue;
+			}
+			cancel::check()?;
+			explain(phase);
+			let answer = match cached {
+				Some(answer) => {
+					tracing::info!(part, "reusing a cached answer");
+					answer
+				},
+				None => {
+					prepare_common(&mut *solver, &mut common_ready)?;
+					let answer =
+						wyz_aoc::run_part(&mut *solver, part, |at, solver| {
+							dump_state(
+								&mut dumps,
+								DumpPhase::after(part, at),
+								solver,
+							)
+						})
+						.wrap_err_with(|| {
+							format!("{year}-{day:0>2}#{part} failed")
+						})?;
+					entry.cache_answer(
+						&source_text,
+						&params,
+						part,
+						answer.clone(),
+					);
+					answer
+				},
+			};
+			let lag = metrics.record_answer(group, part);
+			tracing::info!(part, %answer, ?lag, "solved!");
+			if let Some(race) = &race {
+				race.report(part);
+			}
+			if let Some(expected) = &expected {
+				wrong.extend(compare_expected(
+					expected,
+					&*solver,
+					metrics,
+					part,
+					answer.clone(),
+				));
+			}
+			if self.quiet {
+				println!("{answer}");
+			}
+			entry.record_answer(group, part, answer);
+		}
+
+		// A solver whose answers came from the cache never ran, so its
+		// artifacts would only show the unsolved input.
+		if !from_cache {
+			for path in wyz_aoc::artifacts::write_puzzle_artifacts(&*solver)? {
+				tracing::info!(file=%path.display(), "saved artifact");
+			}
+		}
+
+		if let Some(recorder) = solver.recorder().filter(|_| self.replay) {
+			if recorder.dropped() > 0 {
+				tracing::warn!(
+					dropped = recorder.dropped(),
+					"recording was too long; later frames were discarded",
+				);
+			}
+			replay(&recorder.take())?;
+		}
+
+		for (phase, path) in dumps {
+			tracing::warn!(
+				%phase,
+				file = %path.display(),
+				"the run did not reach this phase, so its state was not dumped",
+			);
+		}
+
+		if !wrong.is_empty() {
+			let wrong =
+				wrong.iter().map(ToString::to_string).collect::<Vec<_>>();
+			return Err(AocError::WrongAnswer(wrong.join("; ")).into());
+		}
+		Ok(())
+	}
+
+	/// Looks for an answer computed by an earlier run of this build.
+	///
+	/// Runs which step through the solver, rather than only wanting its
+	/// answers, always solve afresh, as do `--force` and `--seed`.
+	fn cached_answer(
+		&self,
+		entry: Solver,
+		text: &str,
+		params: &ParamMap,
+		part: u8,
+	) -> Option<Answer> {
+		if self.force
+			|| self.seeded
+			|| self.replay
+			|| self.explain
+			|| !self.dump_state.is_empty()
+		{
+			return None;
+		}
+		entry.cached_answer(text, params, part)
+	}
+
+	/// Runs one day's solvers from every year, and reports their answers and
+	/// run times next to each other.
+	///
+	/// A year which fails does not stop the others from running.
+	fn run_across_years(&self, day: u8) -> eyre::Result<()> {
+		let group = self.inputs.single_group("compare years")?;
+		let entries = wyz_aoc::solutions_for_day(day);
+		if entries.is_empty() {
+			return Err(eyre::eyre!("{}", render_known_puzzles()))
+				.wrap_err_with(|| {
+					format!("no year has a solution for day {day}")
+				});
+		}
+		let (one, two) = self.inputs.parts();
+		let overrides = self.inputs.overrides();
+		let mut failures = 0;
+		let mut total = Duration::ZERO;
+		for entry in &entries {
+			cancel::check()?;
+			let year = entry.year;
+			let start = Instant::now();
+			let outcome = entry.solve_with_params(group, one, two, &overrides);
+			let elapsed = start.elapsed();
+			total += elapsed;
+			match outcome {
+				Ok((part_1, part_2)) => {
+					tracing::info!(
+						"{year}-{day:0>2}: {:>20} {:>20} in {elapsed:?}",
+						show(one, part_1),
+						show(two, part_2),
+					);
+				},
+				Err(err) => {
+					failures += 1;
+					tracing::error!(
+						"{year}-{day:0>2}: {err:#} after {elapsed:?}"
+					);
+				},
+			}
+		}
+		tracing::info!(
+			"ran day {day} from {} years in {total:?}",
+			entries.len()
+		);
+		if failures > 0 {
+			eyre::bail!("{failures} of {} years failed", entries.len());
+		}
+		Ok(())
+	}
+
+	/// Runs the solver over every input group, and reports the answers from
+	/// each group next to each other.
+	///
+	/// A group which fails does not stop the others from running.
+	fn compare_groups(&self, entry: Solver) -> eyre::Result<()> {
+		if self.explore {
+			eyre::bail!("cannot explore more than one input group at a time");
+		}
+		if self.interactive {
+			eyre::bail!("cannot play more than one input group at a time");
+		}
+		let groups = entry.input_groups()?;
+		if groups.is_empty() {
+			eyre::bail!("{}-{:0>2} has no input files", entry.year, entry.day);
+		}
+		let (one, two) = self.inputs.parts();
+		let overrides = self.inputs.overrides();
+		let width = groups.iter().map(String::len).max().unwrap_or_default();
+		let mut failures = 0;
+		for group in &groups {
+			cancel::check()?;
+			match entry.solve_with_params(group, one, two, &overrides) {
+				Ok((part_1, part_2)) => {
+					if self.quiet {
+						for answer in [&part_1, &part_2].into_iter().flatten() {
+							println!("{group}: {answer}");
+						}
+					}
+					tracing::info!(
+						"{group:>width$}: {:>20} {:>20}",
+						show(one, part_1),
+						show(two, part_2),
+					);
+				},
+				Err(err) => {
+					failures += 1;
+					tracing::error!("{group:>width$}: {err:#}");
+				},
+			}
+		}
+		if failures > 0 {
+			eyre::bail!("{failures} of {} input groups failed", groups.len());
+		}
+		Ok(())
+	}
+}
+
+/// Loads the day's README as a walkthrough, saying so if there is nothing to
+/// show.
+fn load_walkthrough(entry: Solver) -> Walkthrough {
+	let walkthrough = entry
+		.readme()
+		.map(|readme| Walkthrough::parse(&readme))
+		.unwrap_or_default();
+	if walkthrough.is_empty() {
+		tracing::warn!("this puzzle has no README to explain it");
+	}
+	walkthrough
+}
+
+/// Reports statistics about the input and what the solver made of it.
+fn describe_input(source_text: &str, rest: &str, solver: &dyn Puzzle) {
+	tracing::info!(
+		bytes = source_text.len(),
+		lines = source_text.lines().count(),
+		unparsed = rest.trim().len(),
+		"input text"
+	);
+	let stats = solver.describe_input();
+	if stats.is_empty() {
+		tracing::info!("the solver does not describe its input");
+	}
+	for (name, value) in stats {
+		tracing::info!(%name, %value, "input statistic");
+	}
+}
+
+/// Runs a small command prompt over a parsed solver, so that its state can be
+/// inspected and its phases driven by hand.
+fn explore(
+	entry: Solver,
+	source_text: &str,
+	params: &ParamMap,
+	mut solver: Box<dyn Puzzle + Send>,
+) -> eyre::Result<()> {
+	const HELP: &str = "\
+commands:
+  debug                 print the solver state with `{:#?}`
+  describe              print the solver's statistics about its input
+  show [first [last]]   print the solver's rendering, optionally only some rows
+  prepare 1|2           run a preparation phase, after the shared one if it
+                        has not yet run
+  part 1|2              run a solution phase and print the answer
+  set <key> <value>     override a solver parameter
+  reset                 discard the solver state and parse the input again,
+                        restoring the data group's parameters
+  help                  print this message
+  quit                  leave the prompt";
+
+	let (year, day) = (entry.year, entry.day);
+	let mut common_ready = false;
+	let stdin = io::stdin();
+	let mut line = String::new();
+	println!("{HELP}");
+	loop {
+		print!("{year}-{day:0>2}> ");
+		io::stdout().flush()?;
+		line.clear();
+		if stdin.read_line(&mut line)? == 0 {
+			break;
+		}
+		let words = line.split_whitespace().collect::<Vec<_>>();
+		let outcome = match words.as_slice() {
+			[] => Ok(()),
+			["help" | "?"] => {
+				println!("{HELP}");
+				Ok(())
+			},
+			["quit" | "exit" | "q"] => break,
+			["debug"] => {
+				println!("{solver:#?}");
+				Ok(())
+			},
+			["describe"] => {
+				for (name, value) in solver.describe_input() {
+					println!("{name}: {value}");
+				}
+				Ok(())
+			},
+			["show", range @ ..] => show_rows(&*solver, range),
+			["prepare", "1"] => prepare_common(&mut *solver, &mut common_ready)
+				.and_then(|()| solver.prepare_1()),
+			["prepare", "2"] => prepare_common(&mut *solver, &mut common_ready)
+				.and_then(|()| solver.prepare_2()),
+			["part", "1"] => solver
+				.answer_1()
+				.and_then(|answer| check_answer(&*solver, 1, answer))
+				.map(|answer| println!("part 1: {answer}")),
+			["part", "2"] if !solver.has_part_2() => {
+				println!("part 2: n/a");
+				Ok(())
+			},
+			["part", "2"] => solver
+				.answer_2()
+				.and_then(|answer| check_answer(&*solver, 2, answer))
+				.map(|answer| println!("part 2: {answer}")),
+			["set", key, value] => solver.set_param(key, value),
+			["reset"] => entry
+				.parse(source_text)
+				.map_err(|err| eyre::eyre!("{err}"))
+				.and_then(|(_, fresh)| {
+					solver = fresh;
+					common_ready = false;
+					solver.set_params(params)?;
+					solver.set_rng(random::rng());
+					solver.after_parse()
+				}),
+			_ => Err(eyre::eyre!("unknown command; try `help`")),
+		};
+		if let Err(err) = outcome {
+			println!("error: {err:#}");
+		}
+	}
+	Ok(())
+}
+
+/// Steps through a solver's recorded frames at an interactive prompt.
+fn replay(frames: &[Frame]) -> eyre::Result<()> {
+	const HELP: &str = "\
+commands:
+  n [count]      step forward (an empty line also steps forward)
+  p [count]      step backward
+  first | last   jump to either end of the recording
+  goto <frame>   jump to a frame by number
+  find <text>    jump to the next frame whose label contains the text
+  help           print this message
+  quit           leave the prompt";
+
+	if frames.is_empty() {
+		println!("nothing was recorded");
+		return Ok(());
+	}
+	let last = frames.len() - 1;
+	let mut cursor = 0;
+	let stdin = io::stdin();
+	let mut line = String::new();
+	println!("{HELP}");
+	loop {
+		let Frame { label, state } = &frames[cursor];
+		println!("frame {cursor}/{last}: {label}\n{state}");
+		print!("replay> ");
+		io::stdout().flush()?;
+		line.clear();
+		if stdin.read_line(&mut line)? == 0 {
+			break;
+		}
+		let words = line.split_whitespace().collect::<Vec<_>>();
+		let count = |arg: &[&str]| -> eyre::Result<usize> {
+			arg.first().map_or(Ok(1), |n| n.parse().map_err(Into::into))
+		};
+		let outcome = match words.as_slice() {
+			[] => Ok(cursor + 1),
+			["help" | "?"] => {
+				println!("{HELP}");
+				Ok(cursor)
+			},
+			["quit" | "exit" | "q"] => break,
+			["n" | "next", rest @ ..] => count(rest).map(|n| cursor + n),
+			["p" | "prev", rest @ ..] => {
+				count(rest).map(|n| cursor.saturating_sub(n))
+			},
+			["first"] => Ok(0),
+			["last"] => Ok(last),
+			["goto", frame] => frame.parse().map_err(Into::into),
+			["find", text @ ..] => {
+				let text = text.join(" ");
+				frames[cursor + 1 ..]
+					.iter()
+					.position(|frame| frame.label.contains(&text))
+					.map(|idx| cursor + 1 + idx)
+					.ok_or_else(|| {
+						eyre::eyre!("no later frame matches `{text}`")
+					})
+			},
+			_ => Err(eyre::eyre!("unknown command; try `help`")),
+		};
+		match outcome {
+			Ok(next) => cursor = next.min(last),
+			Err(err) => println!("error: {err:#}"),
+		}
+	}
+	Ok(())
+}
+
+/// Prints a solver's rendering, optionally restricted to a range of lines.
+fn show_rows(solver: &dyn Puzzle, range: &[&str]) -> eyre::Result<()> {
+	let text = solver
+		.render_state()
+		.ok_or_else(|| eyre::eyre!("this solver has no rendering"))?;
+	let (first, last) = match range {
+		[] => (0, usize::MAX),
+		[first] => (first.parse()?, usize::MAX),
+		[first, last] => (first.parse()?, last.parse()?),
+		_ => eyre::bail!("usage: show [first [last]]"),
+	};
+	for line in text
+		.lines()
+		.skip(first)
+		.take(last.saturating_sub(first).saturating_add(1))
+	{
+		println!("{line}");
+	}
+	Ok(())
+}
+
+/// The points in a run after which `--dump-state` can write the solver out.
+#[derive(
+	Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum,
+)]
+pub enum DumpPhase {
+	/// Straight after parsing, once the parameters are applied.
+	Parse,
+	/// After the solver has checked and processed its input.
+	Process,
+	/// After preparing for part 1.
+	Prepare1,
+	/// After solving part 1.
+	Part1,
+	/// After preparing for part 2.
+	Prepare2,
+	/// After solving part 2.
+	Part2,
+}
+
+impl DumpPhase {
+	/// Names the point in part `part` which a [`PartPhase`] stands for.
+	fn after(part: u8, phase: PartPhase) -> Self {
+		match (part, phase) {
+			(1, PartPhase::Prepared) => Self::Prepare1,
+			(1, PartPhase::Solved) => Self::Part1,
+			(_, PartPhase::Prepared) => Self::Prepare2,
+			(_, PartPhase::Solved) => Self::Part2,
+		}
+	}
+}
+
+impl fmt::Display for DumpPhase {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, fmt)
+	}
+}
+
+/// Splits a `--dump-state` argument into its phase and path.
+fn parse_dump(text: &str) -> Result<(DumpPhase, PathBuf), String> {
+	let (phase, path) = text
+		.split_once('=')
+		.ok_or_else(|| format!("`{text}` is not `PHASE=PATH`"))?;
+	let phase = DumpPhase::from_str(phase, true)?;
+	if path.is_empty() {
+		return Err(format!("`{text}` does not name a file"));
+	}
+	Ok((phase, PathBuf::from(path)))
+}
+
+/// Writes the solver's state to every file requested for `phase`, and takes
+/// those requests off the list.
+fn dump_state(
+	dumps: &mut Vec<(DumpPhase, PathBuf)>,
+	phase: DumpPhase,
+	solver: &dyn Puzzle,
+) -> eyre::Result<()> {
+	let (now, later) = mem::take(dumps)
+		.into_iter()
+		.partition::<Vec<_>, _>(|&(at, _)| at == phase);
+	*dumps = later;
+	for (_, path) in now {
+		let Some(json) = solver.dump_state()
+		else {
+			eyre::bail!(
+				"this solver's state cannot be serialized{}",
+				if cfg!(feature = "serde") {
+					""
+				}
+				else {
+					" without the `serde` feature"
+				},
+			);
+		};
+		let json = json.wrap_err("could not serialize the solver's state")?;
+		fs::write(&path, json)
+			.wrap_err_with(|| format!("could not write {}", path.display()))?;
+		tracing::info!(%phase, file = %path.display(), "dumped the state");
+	}
+	Ok(())
+}
+
+/// Runs a solver's own plausibility checks on one of its answers.
+fn check_answer(
+	solver: &dyn Puzzle,
+	part: u8,
+	answer: Answer,
+) -> eyre::Result<Answer> {
+	solver.validate_answer(part, &answer).wrap_err_with(|| {
+		format!("part {part} answer {answer} is implausible")
+	})?;
+	Ok(answer)
+}
+
+/// Compares an answer with the known one, and reports the difference.
+///
+/// A wrong answer is reported alongside everything that might explain it: how
+/// far off it is, how long each phase took, and what the solver's state looks
+/// like, all under one `check` span.
+fn compare_expected(
+	expected: &Expected,
+	solver: &dyn Puzzle,
+	metrics: &PhaseMetrics,
+	part: u8,
+	answer: Answer,
+) -> Option<Mismatch> {
+	let span = tracing::error_span!("check", part);
+	let _span = span.enter();
+	let mismatch = match expected.check(part, answer) {
+		None => {
+			tracing::warn!("there is no known answer to check against");
+			return None;
+		},
+		Some(Ok(())) => {
+			tracing::info!("answer is correct");
+			return None;
+		},
+		Some(Err(mismatch)) => mismatch,
+	};
+	tracing::error!(
+		expected = mismatch.expected,
+		actual = %mismatch.actual,
+		"wrong answer"
+	);
+	if let Some(diff) = mismatch.difference() {
+		tracing::error!(diff, "actual - expected");
+	}
+	for timing in metrics.snapshot() {
+		tracing::error!(%timing, "phase metrics");
+	}
+	if let Some(state) = solver.render_state() {
+		tracing::error!("final state:\n{state}");
+	}
+	Some(mismatch)
+}
+
+/// Runs a solver's shared preparation, unless it has already run.
+///
+/// Both parts call this before their own preparation, so that the shared work
+/// happens once, and only if some part is actually solved. A preparation
+/// which fails is not marked done, so the next part tries it again.
+fn prepare_common(solver: &mut dyn Puzzle, done: &mut bool) -> eyre::Result<()> {
+	if *done {
+		return Ok(());
+	}
+	tracing::error_span!(metrics::PREPARE).in_scope(|| {
+		tracing::info!("preparing for both parts");
+		solver
+			.prepare_common()
+			.wrap_err("could not prepare for either part")
+	})?;
+	*done = true;
+	Ok(())
+}
@@ -0,0 +1,22 @@
+//! Answers puzzles over HTTP.
+
+use std::net::SocketAddr;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+pub struct ServeArgs {
+	/// The address to listen on, such as `127.0.0.1:8025`.
+	pub addr: SocketAddr,
+}
+
+impl ServeArgs {
+	/// Serves every solver over HTTP until the run is cancelled.
+	#[cfg(feature = "serve")]
+	pub fn execute(&self) -> eyre::Result<()> {
+		wyz_aoc::serve::serve(self.addr)
+	}
+
+	#[cfg(not(feature = "serve"))]
+	pub fn execute(&self) -> eyre::Result<()> {
+		eyre::bail!("serving solvers requires the `serve` feature")
+	}
+}
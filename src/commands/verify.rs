@@ -0,0 +1,127 @@
+//! Compares every puzzle's answers with those of an earlier build.
+
+use std::path::PathBuf;
+
+use super::Inputs;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Args)]
+pub struct VerifyArgs {
+	/// Compare each part's answer and run time with those saved in this
+	/// results file by an earlier build.
+	#[arg(long, value_name = "FILE", required_unless_present = "record")]
+	pub against: Option<PathBuf>,
+	/// Save each part's answer and run time in this results file, for a later
+	/// `verify --against` to compare with.
+	#[arg(long, value_name = "FILE")]
+	pub record: Option<PathBuf>,
+	/// How much slower or faster a part must become, as a percentage, to be
+	/// reported.
+	#[arg(
+		long,
+		value_name = "PERCENT",
+		default_value_t = 25,
+		requires = "against"
+	)]
+	pub timing_threshold: u32,
+	/// Run this many puzzles at once, starting with the slowest. Timings from
+	/// parallel runs are only comparable with runs on as many threads.
+	#[arg(short, long, value_name = "THREADS", default_value_t = 1)]
+	pub jobs: usize,
+	/// Leave out the puzzles whose solvers are estimated to be slow, here and
+	/// in the results compared against.
+	#[arg(long)]
+	pub skip_slow: bool,
+	#[command(flatten)]
+	pub inputs: Inputs,
+}
+
+impl VerifyArgs {
+	/// Runs every puzzle, then saves the results and compares them with those
+	/// of an earlier build, as requested.
+	///
+	/// Changed answers fail the run; changed timings are only reported.
+	#[cfg(feature = "serde")]
+	pub fn execute(&self) -> eyre::Result<()> {
+		use wyz_aoc::{
+			results::Results,
+			Difficulty,
+		};
+
+		let group = self.inputs.single_group("compare builds")?;
+		// Read the baseline first, so that a typo fails before the long run.
+		let before = self.against.as_deref().map(Results::load).transpose()?;
+		let (one, two) = self.inputs.parts();
+		tracing::info!(group, jobs = self.jobs, "running every puzzle");
+		let after = Results::collect(
+			group,
+			one,
+			two,
+			&self.inputs.overrides(),
+			self.jobs,
+			self.skip_slow,
+		)?;
+		tracing::info!(parts = after.parts.len(), "collected results");
+		for (class, summary) in after.by_difficulty() {
+			tracing::info!(
+				%class,
+				parts = summary.parts,
+				failed = summary.failed,
+				elapsed = ?summary.elapsed,
+				slowest = ?summary.slowest,
+				"by difficulty"
+			);
+		}
+		if let Some(path) = &self.record {
+			after.save(path)?;
+			tracing::info!(file=%path.display(), "saved results");
+		}
+		let Some(mut before) = before
+		else {
+			return Ok(());
+		};
+		if self.skip_slow {
+			// Those were not run this time, which is not a change.
+			before
+				.parts
+				.retain(|result| result.difficulty() != Difficulty::Slow);
+		}
+		if before.group != after.group {
+			tracing::warn!(
+				before = before.group,
+				after = after.group,
+				"comparing results from different input groups"
+			);
+		}
+		let changes =
+			after.diff(&before, f64::from(self.timing_threshold) / 100.0);
+		for change in &changes {
+			if change.is_answer() {
+				tracing::warn!(%change, "answer changed");
+			}
+			else if change.is_coverage() {
+				tracing::warn!(%change, "part run in only one build");
+			}
+			else {
+				tracing::info!(%change, "timing changed");
+			}
+		}
+		let changed = changes.iter().filter(|c| c.is_answer()).count();
+		let coverage = changes.iter().filter(|c| c.is_coverage()).count();
+		tracing::info!(
+			answers = changed,
+			coverage,
+			timings = changes.len() - changed - coverage,
+			"compared with results from {}",
+			before.recorded
+		);
+		if changed > 0 {
+			eyre::bail!("{changed} answers changed");
+		}
+		Ok(())
+	}
+
+	#[cfg(not(feature = "serde"))]
+	pub fn execute(&self) -> eyre::Result<()> {
+		eyre::bail!("comparing builds requires the `serde` feature")
+	}
+}
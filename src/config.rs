@@ -0,0 +1,204 @@
+//! Defaults for the harness, kept in `aoc.toml` at the project root.
+//!
+//! Most runs use the same handful of settings: the real input rather than the
+//! sample, a favorite trace format, the number of threads the machine has to
+//! spare. Rather than repeat those on every command line, they can be written
+//! once in `aoc.toml`, and the flags are left to cover the exceptions:
+//!
+//! ```toml
+//! # Where the session cookie for `fetch` is kept.
+//! session_file = "~/.config/aoc/session"
+//! # The trace format: compact, plain, pretty, or json.
+//! format = "compact"
+//! # The data group: sample, input, or all-groups.
+//! data = "input"
+//! # How many threads the solvers, and `verify`, may use.
+//! jobs = 8
+//! # The directory holding `assets/` and the puzzles' `src/` tree.
+//! asset_root = "../aoc"
+//! ```
+//!
+//! Every key is optional. Each may also be set in the environment, which wins
+//! over the file: `AOC_SESSION_FILE`, `AOC_FORMAT`, `AOC_DATA`, `AOC_JOBS`,
+//! and `AOC_ASSETS`. Relative paths are taken from the directory the harness
+//! is run in, and a leading `~/` from the home directory.
+//!
+//! The harness reads the configuration once, before it parses its arguments,
+//! and [installs](Config::install) it for the library to consult.
+
+use std::{
+	env,
+	fs,
+	path::{
+		Path,
+		PathBuf,
+	},
+	sync::OnceLock,
+};
+
+use eyre::WrapErr as _;
+
+/// The name of the configuration file.
+pub const FILE: &str = "aoc.toml";
+
+/// The configuration installed by the harness.
+static INSTALLED: OnceLock<Config> = OnceLock::new();
+
+/// The harness's defaults, from `aoc.toml` and the environment.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Config {
+	/// A file holding the session cookie, for downloading inputs.
+	pub session_file: Option<PathBuf>,
+	/// The name of the default trace format.
+	pub format:       Option<String>,
+	/// The name of the default data group.
+	pub data:         Option<String>,
+	/// How many threads to use.
+	pub jobs:         Option<usize>,
+	/// The directory holding `assets/` and the puzzles' `src/` tree.
+	pub asset_root:   Option<PathBuf>,
+}
+
+impl Config {
+	/// Parses a configuration file.
+	///
+	/// Unknown keys are rejected, so that a misspelled setting is not quietly
+	/// ignored.
+	pub fn parse(text: &str) -> eyre::Result<Self> {
+		let table = text.parse::<toml::Table>()?;
+		let mut this = Self::default();
+		for (key, value) in table {
+			let text = match value {
+				toml::Value::String(text) => text,
+				toml::Value::Integer(num) => num.to_string(),
+				other => eyre::bail!(
+					"setting `{key}` must be a string or a number, not a {}",
+					other.type_str()
+				),
+			};
+			this.set(&key, text)
+				.wrap_err_with(|| format!("invalid setting `{key}`"))?;
+		}
+		Ok(this)
+	}
+
+	/// Loads `aoc.toml` from the current directory, if it exists, and applies
+	/// the environment's overrides.
+	pub fn load() -> eyre::Result<Self> {
+		let path = Path::new(FILE);
+		let mut this = if path.exists() {
+			let text = fs::read_to_string(path)
+				.wrap_err_with(|| format!("could not read {FILE}"))?;
+			Self::parse(&text)
+				.wrap_err_with(|| format!("could not parse {FILE}"))?
+		}
+		else {
+			Self::default()
+		};
+		this.override_from(|var| env::var(var).ok())?;
+		Ok(this)
+	}
+
+	/// Replaces settings with those found by `lookup`, which is given the name
+	/// of each setting's environment variable.
+	pub fn override_from(
+		&mut self,
+		lookup: impl Fn(&str) -> Option<String>,
+	) -> eyre::Result<()> {
+		for (var, key) in [
+			("AOC_SESSION_FILE", "session_file"),
+			("AOC_FORMAT", "format"),
+			("AOC_DATA", "data"),
+			("AOC_JOBS", "jobs"),
+			("AOC_ASSETS", "asset_root"),
+		] {
+			if let Some(value) = lookup(var) {
+				self.set(key, value)
+					.wrap_err_with(|| format!("invalid `{var}`"))?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Makes this the configuration that the library consults.
+	///
+	/// Only the first configuration installed takes effect.
+	pub fn install(self) -> &'static Self {
+		INSTALLED.get_or_init(|| self)
+	}
+
+	/// Gets the installed configuration, or the defaults if none has been.
+	pub fn get() -> &'static Self {
+		INSTALLED.get_or_init(Self::default)
+	}
+
+	/// Gets the directory holding `assets/` and the puzzles' `src/` tree.
+	///
+	/// This is the configured asset root, or the current directory.
+	pub fn asset_root(&self) -> eyre::Result<PathBuf> {
+		match &self.asset_root {
+			Some(root) => Ok(root.clone()),
+			None => env::current_dir()
+				.wrap_err("could not find the current directory"),
+		}
+	}
+
+	fn set(&mut self, key: &str, value: String) -> eyre::Result<()> {
+		match key {
+			"session_file" => self.session_file = Some(expand(&value)),
+			"format" => self.format = Some(value),
+			"data" => self.data = Some(value),
+			"jobs" => match value.parse()? {
+				0 => eyre::bail!("cannot run on zero threads"),
+				jobs => self.jobs = Some(jobs),
+			},
+			"asset_root" => self.asset_root = Some(expand(&value)),
+			_ => eyre::bail!("no setting named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+/// Gets the directory holding `assets/` and the puzzles' `src/` tree, from the
+/// installed configuration.
+pub fn asset_root() -> eyre::Result<PathBuf> {
+	Config::get().asset_root()
+}
+
+/// Expands a leading `~/` to the home directory.
+fn expand(path: &str) -> PathBuf {
+	match (path.strip_prefix("~/"), env::var_os("HOME")) {
+		(Some(rest), Some(home)) => Path::new(&home).join(rest),
+		_ => PathBuf::from(path),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn file_then_environment() -> eyre::Result<()> {
+		let mut config = Config::parse(
+			"format = \"json\"\ndata = \"input\"\njobs = 4\nasset_root = \
+			 \"/srv/aoc\"\n",
+		)?;
+		assert_eq!(config.format.as_deref(), Some("json"));
+		assert_eq!(config.jobs, Some(4));
+		assert_eq!(config.asset_root()?, Path::new("/srv/aoc"));
+
+		config.override_from(|var| {
+			(var == "AOC_DATA").then(|| "sample".to_owned())
+		})?;
+		assert_eq!(config.data.as_deref(), Some("sample"));
+		assert_eq!(config.format.as_deref(), Some("json"));
+		assert!(config
+			.override_from(|var| (var == "AOC_JOBS").then(|| "0".to_owned()))
+			.is_err());
+
+		assert!(Config::parse("fromat = \"json\"").is_err());
+		assert!(Config::parse("jobs = [1]").is_err());
+		assert_eq!(Config::parse("")?, Config::default());
+		Ok(())
+	}
+}
@@ -1,19 +1,41 @@
 pub use funty::Signed;
 
 pub mod arithmetic;
+pub mod compress;
+pub mod lines;
+pub mod orders;
 pub mod points;
+pub mod polygon;
+pub mod scan;
 pub mod spaces;
+pub mod stateful;
+pub mod units;
 
 pub use self::{
+	compress::Compression,
+	lines::{
+		Intersection,
+		Ray,
+		Segment,
+	},
 	points::{
 		Cartesian2D as Cartesian2DPoint,
 		Cartesian3D as Cartesian3DPoint,
 	},
+	polygon::Polygon,
 	spaces::{
 		dense::Cartesian2D as Dense2DSpace,
+		matrix::Matrix,
 		sparse::{
 			Cartesian2D as Cartesian2DSpace,
 			Cartesian3D as Cartesian3DSpace,
 		},
 	},
+	stateful::StatefulCoord,
+	units::{
+		Col,
+		Distance,
+		Row,
+		Score,
+	},
 };
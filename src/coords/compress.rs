@@ -0,0 +1,215 @@
+//! Coordinate compression.
+//!
+//! Some puzzles scatter a handful of interesting points across a plane that is
+//! millions of cells wide, and then ask a question (usually an area) which
+//! would be a simple flood fill if only the plane fit in memory. Between any
+//! two adjacent interesting values, every row (or column) behaves identically,
+//! so the whole run can be collapsed into a single cell that remembers how
+//! wide it is.
+//!
+//! [`CompressedAxis`] performs this collapse along one dimension, and
+//! [`Compression`] pairs two of them to map a plane onto a small dense grid
+//! whose cells carry weights equal to the area they stand in for.
+
+use funty::Signed;
+
+use super::{
+	points::Cartesian2D as Point2D,
+	spaces::dense::Cartesian2D as Dense2D,
+};
+
+/// One dimension of a compressed space.
+///
+/// Every interesting value gets a cell of its own, one unit wide, and every
+/// gap between adjacent interesting values gets a single cell which is as wide
+/// as the gap. Values outside the first and last interesting values are not
+/// covered.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompressedAxis<I: Signed> {
+	/// The lowest value covered by each cell, in ascending order.
+	starts: Vec<I>,
+	/// How many values each cell covers.
+	spans:  Vec<i64>,
+}
+
+impl<I: Signed> CompressedAxis<I> {
+	/// Compresses an axis so that each of the given values lands in its own
+	/// cell.
+	pub fn new(values: impl IntoIterator<Item = I>) -> Self {
+		let mut values = values.into_iter().collect::<Vec<_>>();
+		values.sort_unstable();
+		values.dedup();
+		let mut starts = Vec::with_capacity(values.len() * 2);
+		let mut spans = Vec::with_capacity(values.len() * 2);
+		for pair in values.windows(2) {
+			let &[this, next] = pair
+			else {
+				unreachable!("windows always yields perfect subslices");
+			};
+			starts.push(this);
+			spans.push(1);
+			let gap = (next - this).as_usize() as i64 - 1;
+			if gap > 0 {
+				starts.push(this + I::ONE);
+				spans.push(gap);
+			}
+		}
+		if let Some(&last) = values.last() {
+			starts.push(last);
+			spans.push(1);
+		}
+		Self { starts, spans }
+	}
+
+	/// Counts the cells in the compressed axis.
+	pub fn len(&self) -> usize {
+		self.starts.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.starts.is_empty()
+	}
+
+	/// Finds the cell which covers a value, if any does.
+	pub fn index_of(&self, value: I) -> Option<usize> {
+		let idx = self.starts.partition_point(|&start| start <= value);
+		let idx = idx.checked_sub(1)?;
+		let covered = (value - self.starts[idx]).as_usize() as i64;
+		(covered < self.spans[idx]).then_some(idx)
+	}
+
+	/// Gets the lowest value covered by a cell.
+	pub fn start(&self, idx: usize) -> Option<I> {
+		self.starts.get(idx).copied()
+	}
+
+	/// Gets how many values a cell covers.
+	pub fn span(&self, idx: usize) -> Option<i64> {
+		self.spans.get(idx).copied()
+	}
+
+	/// Views the widths of all cells, in order.
+	pub fn spans(&self) -> &[i64] {
+		&self.spans
+	}
+}
+
+/// A plane compressed along both axes.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compression<I: Signed> {
+	pub x: CompressedAxis<I>,
+	pub y: CompressedAxis<I>,
+}
+
+impl<I: Signed> Compression<I> {
+	/// Compresses the plane so that every given point lands in a cell of its
+	/// own.
+	pub fn new(points: impl IntoIterator<Item = Point2D<I>>) -> Self {
+		let (xs, ys): (Vec<_>, Vec<_>) =
+			points.into_iter().map(|pt| (pt.x, pt.y)).unzip();
+		Self::from_axes(xs, ys)
+	}
+
+	/// Compresses the plane from separate sets of interesting columns and
+	/// rows.
+	pub fn from_axes(
+		xs: impl IntoIterator<Item = I>,
+		ys: impl IntoIterator<Item = I>,
+	) -> Self {
+		Self {
+			x: CompressedAxis::new(xs),
+			y: CompressedAxis::new(ys),
+		}
+	}
+
+	/// Maps a point in the original plane to the cell that covers it.
+	pub fn compress(&self, point: Point2D<I>) -> Option<Point2D<I>> {
+		let x = I::try_from(self.x.index_of(point.x)?).ok()?;
+		let y = I::try_from(self.y.index_of(point.y)?).ok()?;
+		Some(Point2D::new(x, y))
+	}
+
+	/// Maps a cell back to the lowest point it covers in the original plane.
+	pub fn expand(&self, cell: Point2D<I>) -> Option<Point2D<I>> {
+		Some(Point2D::new(
+			self.x.start(cell.x.as_usize())?,
+			self.y.start(cell.y.as_usize())?,
+		))
+	}
+
+	/// Computes how many points of the original plane a cell covers.
+	pub fn weight(&self, cell: Point2D<I>) -> i64 {
+		if cell.x < I::ZERO || cell.y < I::ZERO {
+			return 0;
+		}
+		let width = self.x.span(cell.x.as_usize()).unwrap_or_default();
+		let height = self.y.span(cell.y.as_usize()).unwrap_or_default();
+		width * height
+	}
+
+	/// Creates a dense grid with one element per compressed cell, with its
+	/// origin at zero.
+	pub fn dense<T: Clone>(&self, fill: T) -> Dense2D<I, T> {
+		let row = vec![fill; self.x.len()];
+		Dense2D::from_raw(Point2D::ZERO, vec![row; self.y.len()])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::VecDeque;
+
+	use super::*;
+	use crate::search::bfs_visit;
+
+	#[test]
+	fn compressed_area() {
+		let axis = CompressedAxis::new([10i32, 0, 3, 4]);
+		assert_eq!(axis.spans(), [1, 2, 1, 1, 5, 1]);
+		assert_eq!(axis.index_of(2), Some(1));
+		assert_eq!(axis.index_of(10), Some(5));
+		assert_eq!(axis.index_of(11), None);
+		assert_eq!(axis.index_of(-1), None);
+
+		// An L-shaped room with corners spread far apart.
+		let corners = [
+			(0, 0),
+			(1_000_000, 0),
+			(1_000_000, 10),
+			(20, 10),
+			(20, 500_000),
+			(0, 500_000),
+		]
+		.map(Point2D::<i64>::from);
+		// A one-unit margin keeps the outside connected around the shape.
+		let margin = [Point2D::new(-1, -1), Point2D::new(1_000_001, 500_001)];
+		let compression = Compression::new(corners.into_iter().chain(margin));
+
+		let mut walls = compression.dense(false);
+		for (&from, &to) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+			let from = compression.compress(from).unwrap();
+			let to = compression.compress(to).unwrap();
+			for cell in from.line_to(to).unwrap() {
+				walls[cell] = true;
+			}
+		}
+		let outside = bfs_visit(
+			[Point2D::ZERO],
+			|cell: Point2D<i64>, queue: &mut VecDeque<_>| {
+				queue.extend(
+					cell.direct_neighbors()
+						.into_iter()
+						.filter(|&n| walls.get(n).is_some_and(|&wall| !wall)),
+				);
+			},
+		);
+		let area = walls
+			.iter()
+			.filter(|&(cell, _)| !outside.contains(&cell))
+			.map(|(cell, _)| compression.weight(cell))
+			.sum::<i64>();
+		assert_eq!(area, 1_000_001 * 11 + 21 * (500_001 - 11));
+	}
+}
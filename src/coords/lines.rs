@@ -0,0 +1,405 @@
+//! Straight lines between lattice points.
+//!
+//! Puzzles about hailstones, lasers, or fence posts ask whether two straight
+//! paths cross, and where. The crossing of two lines through lattice points is
+//! rarely a lattice point itself, and computing it in `f64` rounds it, which
+//! matters when the coördinates are in the hundreds of trillions. Everything
+//! here is exact: the yes-or-no predicates never leave the integers, and
+//! crossing points are [`Rational`].
+//!
+//! Orientations are given in a y-up frame. Since this crate's grids put north
+//! at negative y, [`Orientation::CounterClockwise`] is clockwise on screen.
+//!
+//! The predicates work for any coördinates that fit in `i64`, by comparing the
+//! products in a cross product at double width rather than subtracting them.
+//! Crossing points need the cross products themselves, and report an overflow
+//! rather than panicking when one does not fit in `i128`.
+
+use std::{
+	cmp::Ordering,
+	fmt,
+};
+
+use funty::Signed;
+
+use super::points::Cartesian2D as Point2D;
+use crate::{
+	ratio::{
+		Exact as _,
+		Rational,
+	},
+	AocError,
+};
+
+/// The direction of the turn made at `b`, going from `a` through `b` to `c`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+	Clockwise,
+	Collinear,
+	CounterClockwise,
+}
+
+/// Finds which way the path `a → b → c` turns.
+pub fn orientation<I: Signed>(
+	a: Point2D<I>,
+	b: Point2D<I>,
+	c: Point2D<I>,
+) -> Orientation {
+	match cross_sign(delta(a, b), delta(a, c)) {
+		Ordering::Less => Orientation::Clockwise,
+		Ordering::Equal => Orientation::Collinear,
+		Ordering::Greater => Orientation::CounterClockwise,
+	}
+}
+
+/// Tests whether three points lie on one line.
+pub fn collinear<I: Signed>(
+	a: Point2D<I>,
+	b: Point2D<I>,
+	c: Point2D<I>,
+) -> bool {
+	orientation(a, b, c) == Orientation::Collinear
+}
+
+/// A point whose coördinates need not be whole numbers.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RationalPoint {
+	pub x: Rational,
+	pub y: Rational,
+}
+
+impl RationalPoint {
+	/// Converts back to a lattice point, if the point is on the lattice.
+	pub fn to_lattice(self) -> eyre::Result<Point2D<i64>> {
+		Ok(Point2D::new(self.x.to_exact()?, self.y.to_exact()?))
+	}
+}
+
+impl<I: Signed> From<Point2D<I>> for RationalPoint {
+	fn from(point: Point2D<I>) -> Self {
+		Self {
+			x: Rational::from_integer(point.x.as_i128()),
+			y: Rational::from_integer(point.y.as_i128()),
+		}
+	}
+}
+
+impl fmt::Display for RationalPoint {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "({}, {})", self.x, self.y)
+	}
+}
+
+/// Where two straight paths meet.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Intersection<I: Signed> {
+	/// The paths do not meet.
+	Disjoint,
+	/// The paths meet at one point.
+	Point(RationalPoint),
+	/// The paths lie along one line, and share a stretch of it. Both ends of
+	/// the shared stretch are ends of one path or the other, so they are
+	/// always lattice points.
+	Overlap(Segment<I>),
+}
+
+/// The straight path between two lattice points, including both ends.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment<I: Signed> {
+	pub start: Point2D<I>,
+	pub end:   Point2D<I>,
+}
+
+impl<I: Signed> Segment<I> {
+	pub const fn new(start: Point2D<I>, end: Point2D<I>) -> Self {
+		Self { start, end }
+	}
+
+	/// Tests whether a point lies on the segment, including its ends.
+	pub fn contains(&self, point: Point2D<I>) -> bool {
+		collinear(self.start, self.end, point)
+			&& within(self.start.x, self.end.x, point.x)
+			&& within(self.start.y, self.end.y, point.y)
+	}
+
+	/// Finds where two segments meet.
+	pub fn intersect(&self, other: &Self) -> eyre::Result<Intersection<I>> {
+		let (p, r) = (self.start, delta(self.start, self.end));
+		let (q, s) = (other.start, delta(other.start, other.end));
+		if cross_sign(r, s).is_eq() {
+			let shared = [self.start, self.end]
+				.into_iter()
+				.filter(|&point| other.contains(point))
+				.chain(
+					[other.start, other.end]
+						.into_iter()
+						.filter(|&point| self.contains(point)),
+				);
+			return Ok(overlap(shared));
+		}
+		let (t, u) = crossing(p, r, q, s)?;
+		if !in_unit(&t) || !in_unit(&u) {
+			return Ok(Intersection::Disjoint);
+		}
+		Ok(Intersection::Point(along(p, r, t)?))
+	}
+}
+
+impl<I: Signed> fmt::Display for Segment<I> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}–{}", self.start, self.end)
+	}
+}
+
+/// The straight path which leaves a lattice point in one direction, and never
+/// ends.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ray<I: Signed> {
+	pub origin:    Point2D<I>,
+	/// The displacement of one step along the ray. This must not be zero.
+	pub direction: Point2D<I>,
+}
+
+impl<I: Signed> Ray<I> {
+	pub const fn new(origin: Point2D<I>, direction: Point2D<I>) -> Self {
+		Self { origin, direction }
+	}
+
+	/// Tests whether a point lies on the ray, including its origin.
+	pub fn contains(&self, point: Point2D<I>) -> bool {
+		let (dx, dy) = delta(self.origin, point);
+		let (rx, ry) = (self.direction.x.as_i128(), self.direction.y.as_i128());
+		cross_sign((rx, ry), (dx, dy)).is_eq()
+			&& dx.signum() * rx.signum() >= 0
+			&& dy.signum() * ry.signum() >= 0
+	}
+
+	/// Finds where the ray meets a segment.
+	pub fn intersect_segment(
+		&self,
+		segment: &Segment<I>,
+	) -> eyre::Result<Intersection<I>> {
+		let p = self.origin;
+		let r = (self.direction.x.as_i128(), self.direction.y.as_i128());
+		let (q, s) = (segment.start, delta(segment.start, segment.end));
+		if cross_sign(r, s).is_eq() {
+			let shared = [segment.start, segment.end]
+				.into_iter()
+				.filter(|&point| self.contains(point))
+				.chain(Some(self.origin).filter(|&o| segment.contains(o)));
+			return Ok(overlap(shared));
+		}
+		let (t, u) = crossing(p, r, q, s)?;
+		if t < Rational::default() || !in_unit(&u) {
+			return Ok(Intersection::Disjoint);
+		}
+		Ok(Intersection::Point(along(p, r, t)?))
+	}
+}
+
+/// Computes the displacement from `a` to `b`.
+fn delta<I: Signed>(a: Point2D<I>, b: Point2D<I>) -> (i128, i128) {
+	(b.x.as_i128() - a.x.as_i128(), b.y.as_i128() - a.y.as_i128())
+}
+
+/// Tests whether `value` lies between `a` and `b`, in either order.
+fn within<I: Signed>(a: I, b: I, value: I) -> bool {
+	a.min(b) <= value && value <= a.max(b)
+}
+
+/// Tests whether a distance along a segment lies between its ends.
+fn in_unit(value: &Rational) -> bool {
+	Rational::default() <= *value && *value <= Rational::from_integer(1)
+}
+
+/// Finds the sign of the cross product `a × b`, without overflowing.
+fn cross_sign((ax, ay): (i128, i128), (bx, by): (i128, i128)) -> Ordering {
+	cmp_products(ax, by, ay, bx)
+}
+
+/// Compares `a·b` with `c·d` exactly, even when the products do not fit in
+/// `i128`.
+fn cmp_products(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+	if let (Some(ab), Some(cd)) = (a.checked_mul(b), c.checked_mul(d)) {
+		return ab.cmp(&cd);
+	}
+	let (sign_ab, sign_cd) = (a.signum() * b.signum(), c.signum() * d.signum());
+	if sign_ab != sign_cd {
+		return sign_ab.cmp(&sign_cd);
+	}
+	let ab = wide_mul(a.unsigned_abs(), b.unsigned_abs());
+	let cd = wide_mul(c.unsigned_abs(), d.unsigned_abs());
+	if sign_ab < 0 {
+		cd.cmp(&ab)
+	}
+	else {
+		ab.cmp(&cd)
+	}
+}
+
+/// Multiplies two integers into a double-width product, as `(high, low)`
+/// halves.
+fn wide_mul(a: u128, b: u128) -> (u128, u128) {
+	const HALF: u32 = 64;
+	const MASK: u128 = u64::MAX as u128;
+	let (a_hi, a_lo) = (a >> HALF, a & MASK);
+	let (b_hi, b_lo) = (b >> HALF, b & MASK);
+	let (mid, mid_carry) = (a_hi * b_lo).overflowing_add(a_lo * b_hi);
+	let (low, low_carry) = (a_lo * b_lo).overflowing_add(mid << HALF);
+	let high = a_hi * b_hi
+		+ (mid >> HALF)
+		+ (u128::from(mid_carry) << HALF)
+		+ u128::from(low_carry);
+	(high, low)
+}
+
+/// Finds where the lines `p + t·r` and `q + u·s` cross, as `(t, u)`.
+///
+/// The lines must not be parallel.
+fn crossing<I: Signed>(
+	p: Point2D<I>,
+	r: (i128, i128),
+	q: Point2D<I>,
+	s: (i128, i128),
+) -> eyre::Result<(Rational, Rational)> {
+	let cross = |(ax, ay): (i128, i128), (bx, by): (i128, i128)| {
+		ax.checked_mul(by)
+			.zip(ay.checked_mul(bx))
+			.and_then(|(lhs, rhs)| lhs.checked_sub(rhs))
+			.ok_or_else(|| {
+				AocError::Overflow(format!(
+					"the cross product of ({ax}, {ay}) and ({bx}, {by}) \
+					 overflowed i128"
+				))
+			})
+	};
+	let qp = delta(p, q);
+	let denom = cross(r, s)?;
+	Ok((
+		Rational::new(cross(qp, s)?, denom),
+		Rational::new(cross(qp, r)?, denom),
+	))
+}
+
+/// Finds the point `p + t·r`.
+fn along<I: Signed>(
+	p: Point2D<I>,
+	(rx, ry): (i128, i128),
+	t: Rational,
+) -> eyre::Result<RationalPoint> {
+	let p = RationalPoint::from(p);
+	Ok(RationalPoint {
+		x: p.x.try_add(&t.try_mul(&Rational::from_integer(rx))?)?,
+		y: p.y.try_add(&t.try_mul(&Rational::from_integer(ry))?)?,
+	})
+}
+
+/// Collects the lattice points shared by two collinear paths into the stretch
+/// they span.
+fn overlap<I: Signed>(
+	shared: impl Iterator<Item = Point2D<I>>,
+) -> Intersection<I> {
+	// Points on one line are ordered along it by `Point2D`'s ordering.
+	let (mut lo, mut hi) = (None::<Point2D<I>>, None::<Point2D<I>>);
+	for point in shared {
+		lo = Some(lo.map_or(point, |lo| lo.min(point)));
+		hi = Some(hi.map_or(point, |hi| hi.max(point)));
+	}
+	match lo.zip(hi) {
+		None => Intersection::Disjoint,
+		Some((lo, hi)) if lo == hi => Intersection::Point(lo.into()),
+		Some((lo, hi)) => Intersection::Overlap(Segment::new(lo, hi)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pt(x: i64, y: i64) -> Point2D<i64> {
+		Point2D::new(x, y)
+	}
+
+	fn seg(a: (i64, i64), b: (i64, i64)) -> Segment<i64> {
+		Segment::new(pt(a.0, a.1), pt(b.0, b.1))
+	}
+
+	#[test]
+	fn orientation_survives_overflow() {
+		assert_eq!(
+			orientation(pt(0, 0), pt(4, 0), pt(4, 4)),
+			Orientation::CounterClockwise
+		);
+		assert_eq!(
+			orientation(pt(0, 0), pt(4, 0), pt(4, -4)),
+			Orientation::Clockwise
+		);
+		assert!(collinear(pt(1, 1), pt(3, 5), pt(-1, -3)));
+
+		// Every difference here is near 2^64, and the products near 2^128.
+		let (lo, hi) = (i64::MIN, i64::MAX);
+		assert!(collinear(pt(lo, lo), pt(0, 0), pt(hi, hi)));
+		assert_eq!(
+			orientation(pt(lo, lo), pt(hi, hi), pt(hi - 1, hi)),
+			Orientation::CounterClockwise
+		);
+		assert_eq!(
+			orientation(pt(lo, lo), pt(hi, hi), pt(hi, hi - 1)),
+			Orientation::Clockwise
+		);
+		assert_eq!(wide_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+	}
+
+	#[test]
+	fn segments_and_rays() -> eyre::Result<()> {
+		let cross = seg((0, 0), (3, 3)).intersect(&seg((0, 3), (3, 1)))?;
+		assert_eq!(
+			cross,
+			Intersection::Point(RationalPoint {
+				x: Rational::new(9, 5),
+				y: Rational::new(9, 5),
+			})
+		);
+		assert_eq!(
+			seg((0, 0), (1, 1)).intersect(&seg((0, 3), (3, 1)))?,
+			Intersection::Disjoint
+		);
+		assert_eq!(
+			seg((0, 0), (4, 0)).intersect(&seg((2, 0), (9, 0)))?,
+			Intersection::Overlap(seg((2, 0), (4, 0)))
+		);
+		assert_eq!(
+			seg((0, 0), (2, 2)).intersect(&seg((2, 2), (5, 5)))?,
+			Intersection::Point(pt(2, 2).into())
+		);
+		assert_eq!(
+			seg((0, 0), (1, 0)).intersect(&seg((0, 1), (1, 1)))?,
+			Intersection::Disjoint
+		);
+
+		let ray = Ray::new(pt(0, 0), pt(1, 2));
+		assert_eq!(
+			ray.intersect_segment(&seg((4, 0), (0, 4)))?,
+			Intersection::Point(RationalPoint {
+				x: Rational::new(4, 3),
+				y: Rational::new(8, 3),
+			})
+		);
+		assert_eq!(
+			ray.intersect_segment(&seg((-4, 0), (0, -4)))?,
+			Intersection::Disjoint
+		);
+		assert_eq!(
+			ray.intersect_segment(&seg((-1, -2), (3, 6)))?,
+			Intersection::Overlap(seg((0, 0), (3, 6)))
+		);
+		assert_eq!(
+			ray.intersect_segment(&seg((-3, -6), (-1, -2)))?,
+			Intersection::Disjoint
+		);
+		Ok(())
+	}
+}
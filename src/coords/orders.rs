@@ -0,0 +1,150 @@
+//! Orders for visiting the points of a plane.
+//!
+//! Most grids are walked row by row, which `Axis` and the grid iterators
+//! already do. A few puzzles number their squares some other way: outwards in
+//! a spiral, along the diagonals, or back and forth like a plough. These are
+//! easy to describe and easy to get wrong by one, or by a sign, so they are
+//! written once here.
+//!
+//! All of these use the grid orientation of [`Direction2D`], where `y` grows
+//! downwards: north is `-y`.
+
+use std::{
+	iter,
+	ops::Neg,
+};
+
+use funty::Signed;
+
+use super::{
+	points::{
+		Cartesian2D as Point2D,
+		Direction2D,
+	},
+	spaces::dense::Axis,
+};
+
+/// Spirals outwards from `origin`, forever, in the order of the Ulam spiral.
+///
+/// The first point is `origin` itself, then the point east of it, and the
+/// spiral turns counter-clockwise from there: north, west twice, south twice,
+/// east three times, and so on. The `n`th point yielded is where square `n`
+/// sits, counting from 1 at the origin.
+///
+/// The spiral never ends on its own, and will overflow `I` if followed far
+/// enough; take only as many points as are needed.
+pub fn spiral<I>(origin: Point2D<I>) -> impl Iterator<Item = Point2D<I>>
+where I: Signed + Neg<Output = I> {
+	let mut point = origin;
+	let mut facing = Direction2D::East;
+	// The length of the current leg, and how much of it is left to walk.
+	let (mut leg, mut left) = (1, 1);
+	let mut turns = 0;
+	iter::once(origin).chain(iter::from_fn(move || {
+		point += facing.unit();
+		left -= 1;
+		if left == 0 {
+			facing = facing.turn_left();
+			turns += 1;
+			// Legs grow by one after every second turn.
+			if turns % 2 == 0 {
+				leg += 1;
+			}
+			left = leg;
+		}
+		Some(point)
+	}))
+}
+
+/// Visits every point in the rectangle between two corners, one diagonal at
+/// a time.
+///
+/// The diagonals are taken in order of their distance from the smaller
+/// corner, so the first point is that corner and the last is the opposite
+/// one. Each diagonal runs from its lowest `x` to its highest, which in grid
+/// orientation is from bottom-left to top-right.
+pub fn diagonals<I: Signed>(
+	corner: Point2D<I>,
+	opposite: Point2D<I>,
+) -> impl Iterator<Item = Point2D<I>> {
+	let (min, max) =
+		(corner.min_unifying(opposite), corner.max_unifying(opposite));
+	let (width, height) = (max.x - min.x, max.y - min.y);
+	Axis::new_inclusive(I::ZERO, width + height).flat_map(move |diag| {
+		let first = if diag > height {
+			diag - height
+		}
+		else {
+			I::ZERO
+		};
+		Axis::new_inclusive(first, diag.min(width))
+			.map(move |dx| Point2D::new(min.x + dx, min.y + diag - dx))
+	})
+}
+
+/// Visits every point in the rectangle between two corners, row by row, in
+/// alternating directions.
+///
+/// The first row, at the smaller `y`, runs from the smaller `x` to the larger;
+/// the next runs back again, and so on, so that each point is next to the one
+/// before it.
+pub fn boustrophedon<I: Signed>(
+	corner: Point2D<I>,
+	opposite: Point2D<I>,
+) -> impl Iterator<Item = Point2D<I>> {
+	let (min, max) =
+		(corner.min_unifying(opposite), corner.max_unifying(opposite));
+	let two = I::ONE + I::ONE;
+	Axis::new_inclusive(min.y, max.y).flat_map(move |y| {
+		let backwards = (y - min.y) % two != I::ZERO;
+		Axis::new_inclusive(min.x, max.x).map(move |x| {
+			let x = if backwards { max.x - (x - min.x) } else { x };
+			Point2D::new(x, y)
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn spirals_and_scans() {
+		let ulam = spiral(Point2D::<i32>::ZERO).take(10).collect::<Vec<_>>();
+		assert_eq!(ulam, [
+			Point2D::new(0, 0),
+			Point2D::new(1, 0),
+			Point2D::new(1, -1),
+			Point2D::new(0, -1),
+			Point2D::new(-1, -1),
+			Point2D::new(-1, 0),
+			Point2D::new(-1, 1),
+			Point2D::new(0, 1),
+			Point2D::new(1, 1),
+			Point2D::new(2, 1),
+		]);
+		// Square 1024 is 31 steps from the center, in 2017's spiral memory.
+		let far = spiral(Point2D::<i32>::ZERO).nth(1023).unwrap();
+		assert_eq!(far.abs_manhattan(), 31);
+
+		let diag = diagonals(Point2D::new(2, 1), Point2D::new(0, 0))
+			.map(|p| (p.x, p.y))
+			.collect::<Vec<_>>();
+		assert_eq!(diag, [(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)]);
+
+		let plough = boustrophedon(Point2D::new(-1, 5), Point2D::new(1, 7))
+			.map(|p| (p.x, p.y))
+			.collect::<Vec<_>>();
+		assert_eq!(plough, [
+			(-1, 5),
+			(0, 5),
+			(1, 5),
+			(1, 6),
+			(0, 6),
+			(-1, 6),
+			(-1, 7),
+			(0, 7),
+			(1, 7),
+		]);
+	}
+}
@@ -3,7 +3,10 @@ use std::{
 		self,
 		Write as _,
 	},
-	iter::FusedIterator,
+	iter::{
+		self,
+		FusedIterator,
+	},
 	ops::{
 		self,
 		BitOr,
@@ -18,8 +21,30 @@ use bitvec::{
 	BitArr,
 };
 use funty::Signed;
+use nom::{
+	character::complete::{
+		char,
+		digit1,
+		space0,
+	},
+	combinator::{
+		map,
+		map_res,
+		opt,
+		recognize,
+	},
+	sequence::{
+		pair,
+		tuple,
+	},
+};
 use tap::Tap;
 
+use crate::{
+	ParseResult,
+	Parsed,
+};
+
 /// An integral co-ordinate on a two-dimensional gridded plane.
 ///
 /// These are sorted by Y, then X.
@@ -100,6 +125,26 @@ impl<I: Signed> Cartesian2D<I> {
 			y: self.y.max(other.y),
 		}
 	}
+
+	/// Walks an axis-aligned line segment from this point to another, yielding
+	/// every point on it. Both endpoints are included.
+	///
+	/// Returns `None` if the two points do not share a row or column.
+	pub fn line_to(
+		self,
+		other: Self,
+	) -> Option<impl FusedIterator<Item = Self>> {
+		if self.x != other.x && self.y != other.y {
+			return None;
+		}
+		let step = Self {
+			x: (other.x - self.x).signum(),
+			y: (other.y - self.y).signum(),
+		};
+		Some(iter::successors(Some(self), move |&pt| {
+			(pt != other).then(|| pt + step)
+		}))
+	}
 }
 
 impl<I: Signed + Neg<Output = I>> Cartesian2D<I> {
@@ -115,6 +160,20 @@ impl<I: Signed + Neg<Output = I>> Cartesian2D<I> {
 		]
 	}
 
+	/// Computes all eight points in the square around this one, including the
+	/// diagonals.
+	///
+	/// They are returned with Y varying slowest and X fastest, from the
+	/// `(-1, -1)` corner to the `(+1, +1)` corner.
+	pub fn all_neighbors(self) -> [Self; 8] {
+		let step = [-I::ONE, I::ZERO, I::ONE];
+		std::array::from_fn(|idx| {
+			// Skip the center of the square, which is this point.
+			let idx = if idx < 4 { idx } else { idx + 1 };
+			self + Self::new(step[idx % 3], step[idx / 3])
+		})
+	}
+
 	pub fn abs_manhattan(self) -> I {
 		self.x.abs() + self.y.abs()
 	}
@@ -171,6 +230,36 @@ impl<I: Signed> Cartesian3D<I> {
 		out
 	}
 
+	/// Computes the six immediate neighbors of this point, which each differ
+	/// from it by one step along a single axis.
+	///
+	/// They are returned in `[-X, +X, -Y, +Y, -Z, +Z]` order.
+	pub fn direct_neighbors(self) -> [Self; 6] {
+		let Self { x, y, z } = self;
+		[
+			Self::new(x - I::ONE, y, z),
+			Self::new(x + I::ONE, y, z),
+			Self::new(x, y - I::ONE, z),
+			Self::new(x, y + I::ONE, z),
+			Self::new(x, y, z - I::ONE),
+			Self::new(x, y, z + I::ONE),
+		]
+	}
+
+	/// Computes all twenty-six points in the cube around this one, including
+	/// those which differ from it along more than one axis.
+	///
+	/// They are returned with Z varying slowest and X fastest, from the
+	/// `(-1, -1, -1)` corner to the `(+1, +1, +1)` corner.
+	pub fn all_neighbors(self) -> [Self; 26] {
+		let step = [I::ZERO - I::ONE, I::ZERO, I::ONE];
+		std::array::from_fn(|idx| {
+			// Skip the center of the cube, which is this point.
+			let idx = if idx < 13 { idx } else { idx + 1 };
+			self + Self::new(step[idx % 3], step[idx / 3 % 3], step[idx / 9])
+		})
+	}
+
 	pub fn make_2d(self) -> (I, Cartesian2D<I>) {
 		(self.z, Cartesian2D {
 			x: self.x,
@@ -193,6 +282,28 @@ impl<I: Signed> Cartesian3D<I> {
 			z: self.z.max(other.z),
 		}
 	}
+
+	/// Walks an axis-aligned line segment from this point to another, yielding
+	/// every point on it. Both endpoints are included.
+	///
+	/// Returns `None` if the two points differ in more than one axis.
+	pub fn line_to(
+		self,
+		other: Self,
+	) -> Option<impl FusedIterator<Item = Self>> {
+		let differs = [self.x != other.x, self.y != other.y, self.z != other.z];
+		if differs.into_iter().filter(|&d| d).count() > 1 {
+			return None;
+		}
+		let step = Self {
+			x: (other.x - self.x).signum(),
+			y: (other.y - self.y).signum(),
+			z: (other.z - self.z).signum(),
+		};
+		Some(iter::successors(Some(self), move |&pt| {
+			(pt != other).then(|| pt + step)
+		}))
+	}
 }
 
 impl<I: Signed> From<(I, I)> for Cartesian2D<I> {
@@ -207,6 +318,42 @@ impl<I: Signed> From<(I, I, I)> for Cartesian3D<I> {
 	}
 }
 
+/// Parses `x,y`, with optional spaces after the comma and optional
+/// surrounding parentheses: `3,4`, `3, 4`, and `(3,4)` are all accepted.
+impl<'a, I: Signed> Parsed<&'a str> for Cartesian2D<I> {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(ordinates::<I, 2>, |[x, y]| Self { x, y })(text)
+	}
+}
+
+/// Parses `x,y,z`, in the same forms as [`Cartesian2D`].
+impl<'a, I: Signed> Parsed<&'a str> for Cartesian3D<I> {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(ordinates::<I, 3>, |[x, y, z]| Self { x, y, z })(text)
+	}
+}
+
+/// Parses `N` comma-separated signed integers, optionally parenthesized.
+fn ordinates<I: Signed, const N: usize>(
+	text: &str,
+) -> ParseResult<&str, [I; N]> {
+	let mut ordinate =
+		map_res(recognize(pair(opt(char('-')), digit1)), I::from_str);
+	let mut comma = tuple((space0, char(','), space0));
+	let (mut text, open) = opt(pair(char('('), space0))(text)?;
+	let mut out = [I::ZERO; N];
+	for (idx, slot) in out.iter_mut().enumerate() {
+		if idx > 0 {
+			(text, _) = comma(text)?;
+		}
+		(text, *slot) = ordinate(text)?;
+	}
+	if open.is_some() {
+		(text, _) = pair(space0, char(')'))(text)?;
+	}
+	Ok((text, out))
+}
+
 impl<I: Signed> fmt::Display for Cartesian2D<I> {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		write!(fmt, "({}, {})", self.x, self.y)
@@ -301,6 +448,108 @@ impl<I: Signed> ops::SubAssign<Self> for Cartesian3D<I> {
 	}
 }
 
+/// Generates arithmetic on points which cannot panic.
+///
+/// The operators panic, in debug builds, when any coördinate overflows, and a
+/// step off the edge of a small grid (`i8` or `i16` coördinates) is an easy
+/// way to get there. These do the same arithmetic on every axis, and either
+/// report the overflow, clamp to the bounds of `I`, or wrap around.
+macro_rules! overflowing {
+	($($point:ident { $($axis:ident),+ });+ $(;)?) => { $(
+		impl<I: Signed> $point<I> {
+			/// Adds two points, or produces `None` if any axis overflows.
+			pub fn checked_add(self, rhs: Self) -> Option<Self> {
+				Some(Self { $($axis: self.$axis.checked_add(rhs.$axis)?),+ })
+			}
+
+			/// Subtracts two points, or produces `None` if any axis
+			/// overflows.
+			pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+				Some(Self { $($axis: self.$axis.checked_sub(rhs.$axis)?),+ })
+			}
+
+			/// Adds two points, clamping each axis to the bounds of `I`.
+			pub fn saturating_add(self, rhs: Self) -> Self {
+				Self { $($axis: self.$axis.saturating_add(rhs.$axis)),+ }
+			}
+
+			/// Subtracts two points, clamping each axis to the bounds of `I`.
+			pub fn saturating_sub(self, rhs: Self) -> Self {
+				Self { $($axis: self.$axis.saturating_sub(rhs.$axis)),+ }
+			}
+
+			/// Adds two points, wrapping each axis around the bounds of `I`.
+			pub fn wrapping_add(self, rhs: Self) -> Self {
+				Self { $($axis: self.$axis.wrapping_add(rhs.$axis)),+ }
+			}
+
+			/// Subtracts two points, wrapping each axis around the bounds of
+			/// `I`.
+			pub fn wrapping_sub(self, rhs: Self) -> Self {
+				Self { $($axis: self.$axis.wrapping_sub(rhs.$axis)),+ }
+			}
+		}
+	)+ };
+}
+
+overflowing! {
+	Cartesian2D { x, y };
+	Cartesian3D { x, y, z };
+}
+
+impl<I: Signed + Neg<Output = I>> Cartesian2D<I> {
+	/// Moves one unit in a direction, or produces `None` if that would
+	/// overflow.
+	///
+	/// Together with a grid's bounds check, this handles every edge the same
+	/// way: `pt.try_step(dir).filter(|&next| grid.in_bounds(next))`.
+	pub fn try_step(self, direction: Direction2D) -> Option<Self> {
+		self.checked_add(direction.unit())
+	}
+
+	/// Walks `distance` units in a straight line, yielding each point passed.
+	///
+	/// The starting point is not yielded, and the last point yielded is where
+	/// the walk ends.
+	pub fn walk(self, direction: Direction2D, distance: usize) -> Walk<I> {
+		Walk {
+			at:   self,
+			step: direction.unit(),
+			left: distance,
+		}
+	}
+}
+
+/// The points passed on a straight walk across the plane.
+///
+/// This is produced by [`Cartesian2D::walk`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Walk<I: Signed> {
+	at:   Cartesian2D<I>,
+	step: Cartesian2D<I>,
+	left: usize,
+}
+
+impl<I: Signed> Iterator for Walk<I> {
+	type Item = Cartesian2D<I>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.left = self.left.checked_sub(1)?;
+		self.at += self.step;
+		Some(self.at)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.left, Some(self.left))
+	}
+}
+
+impl<I: Signed> ExactSizeIterator for Walk<I> {
+}
+
+impl<I: Signed> FusedIterator for Walk<I> {
+}
+
 /// A direction in a 2-D plane.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -340,6 +589,69 @@ impl Direction2D {
 			Self::West => Self::North,
 		}
 	}
+
+	/// Turns the direction one step counter-clockwise.
+	pub fn turn_left(self) -> Self {
+		match self {
+			Self::North => Self::West,
+			Self::West => Self::South,
+			Self::South => Self::East,
+			Self::East => Self::North,
+		}
+	}
+
+	/// Turns the direction around.
+	pub fn reverse(self) -> Self {
+		match self {
+			Self::North => Self::South,
+			Self::South => Self::North,
+			Self::West => Self::East,
+			Self::East => Self::West,
+		}
+	}
+
+	/// Expands a move of some distance into that many single steps, as for
+	/// instructions like `R 4`.
+	pub fn steps(self, distance: usize) -> Steps {
+		Steps {
+			direction: self,
+			left:      distance,
+		}
+	}
+}
+
+/// The single steps that make up a longer move in one direction.
+///
+/// This is produced by [`Direction2D::steps`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Steps {
+	direction: Direction2D,
+	left:      usize,
+}
+
+impl Iterator for Steps {
+	type Item = Direction2D;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.left = self.left.checked_sub(1)?;
+		Some(self.direction)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.left, Some(self.left))
+	}
+}
+
+impl DoubleEndedIterator for Steps {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.next()
+	}
+}
+
+impl ExactSizeIterator for Steps {
+}
+
+impl FusedIterator for Steps {
 }
 
 impl fmt::Display for Direction2D {
@@ -421,3 +733,69 @@ impl BitOrAssign<Direction2D> for DirectionSet2D {
 		self.insert(rhs);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Parseable;
+
+	#[test]
+	fn parse_coords() -> eyre::Result<()> {
+		for text in ["3,-4", "3, -4", "(3,-4)", "( 3 , -4 )"] {
+			let (rest, pt) = text.parse_wyz::<Cartesian2D<i16>>()?;
+			assert_eq!((rest, pt), ("", Cartesian2D::new(3, -4)));
+		}
+		let (rest, pt) = "1,2,3 -> 4".parse_wyz::<Cartesian3D<i64>>()?;
+		assert_eq!((rest, pt), (" -> 4", Cartesian3D::new(1, 2, 3)));
+		assert!("(1,2".parse_wyz::<Cartesian2D<i8>>().is_err());
+		assert!("1,2".parse_wyz::<Cartesian3D<i8>>().is_err());
+		assert!("300,2".parse_wyz::<Cartesian2D<i8>>().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn overflow_without_panics() {
+		let corner = Cartesian2D::<i8>::new(127, -128);
+		assert_eq!(corner.try_step(Direction2D::East), None);
+		assert_eq!(corner.try_step(Direction2D::North), None);
+		assert_eq!(
+			corner.try_step(Direction2D::West),
+			Some(Cartesian2D::new(126, -128))
+		);
+		let step = Cartesian2D::new(1, -1);
+		assert_eq!(corner.checked_sub(step), Some(Cartesian2D::new(126, -127)));
+		assert_eq!(corner.checked_add(step), None);
+		assert_eq!(corner.saturating_add(step), corner);
+		assert_eq!(corner.wrapping_add(step), Cartesian2D::new(-128, 127));
+		assert_eq!(
+			Cartesian3D::<i8>::new(1, 2, 3)
+				.checked_sub(Cartesian3D::new(1, 1, 1)),
+			Some(Cartesian3D::new(0, 1, 2))
+		);
+		assert_eq!(
+			Cartesian3D::<i8>::new(1, 2, -100)
+				.wrapping_sub(Cartesian3D::new(0, 0, 100)),
+			Cartesian3D::new(1, 2, 56)
+		);
+	}
+
+	#[test]
+	fn walks_and_steps() {
+		let steps = Direction2D::West.steps(3);
+		assert_eq!(steps.len(), 3);
+		assert!(steps.eq([Direction2D::West; 3]));
+		assert_eq!(Direction2D::East.steps(0).next(), None);
+
+		let mut walk = Cartesian2D::<i32>::new(2, 5).walk(Direction2D::North, 3);
+		assert_eq!(walk.len(), 3);
+		assert_eq!(walk.next(), Some(Cartesian2D::new(2, 4)));
+		assert_eq!(walk.collect::<Vec<_>>(), [
+			Cartesian2D::new(2, 3),
+			Cartesian2D::new(2, 2)
+		]);
+		let mut done = Cartesian2D::<i32>::ZERO.walk(Direction2D::South, 1);
+		assert_eq!(done.nth(1), None);
+		assert_eq!(done.next(), None);
+		assert_eq!(done.len(), 0);
+	}
+}
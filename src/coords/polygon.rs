@@ -0,0 +1,130 @@
+//! Closed lattice polygons.
+//!
+//! Puzzles which have the reader dig a trench or build a fence around a region
+//! eventually ask how much area the region holds. Flood-filling the region
+//! works until the coördinates reach the millions; the shoelace formula and
+//! Pick's theorem together answer the question from the corners alone.
+
+use funty::Signed;
+
+use super::points::Cartesian2D as Point2D;
+
+/// A closed polygon whose corners lie on the integer lattice.
+///
+/// The vertices are stored in drawing order. The last vertex is implicitly
+/// joined back to the first, so a closed path should not repeat its starting
+/// point (though doing so is harmless).
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon<I: Signed> {
+	vertices: Vec<Point2D<I>>,
+}
+
+impl<I: Signed> Polygon<I> {
+	/// Creates a polygon from its corners, in drawing order.
+	pub fn new(vertices: impl IntoIterator<Item = Point2D<I>>) -> Self {
+		Self {
+			vertices: vertices.into_iter().collect(),
+		}
+	}
+
+	/// Views the corners of the polygon.
+	pub fn vertices(&self) -> &[Point2D<I>] {
+		&self.vertices
+	}
+
+	/// Iterates over each edge of the polygon, including the closing edge from
+	/// the last vertex back to the first.
+	pub fn edges(&self) -> impl '_ + Iterator<Item = (Point2D<I>, Point2D<I>)> {
+		self.vertices
+			.iter()
+			.copied()
+			.zip(self.vertices.iter().copied().cycle().skip(1))
+	}
+
+	/// Computes twice the signed area of the polygon, using the shoelace
+	/// formula.
+	///
+	/// The result is positive when the vertices run counter-clockwise in a
+	/// y-up frame. Since this crate's grids put north at negative y, that is
+	/// clockwise on screen.
+	pub fn area2(&self) -> i64 {
+		self.edges()
+			.map(|(a, b)| {
+				a.x.as_i64() * b.y.as_i64() - b.x.as_i64() * a.y.as_i64()
+			})
+			.sum()
+	}
+
+	/// Counts the lattice points that lie on the polygon's boundary.
+	///
+	/// For an axis-aligned polygon, this is the length of the path drawn
+	/// through its corners.
+	pub fn perimeter(&self) -> i64 {
+		self.edges()
+			.map(|(a, b)| {
+				let dx = (b.x.as_i64() - a.x.as_i64()).abs();
+				let dy = (b.y.as_i64() - a.y.as_i64()).abs();
+				num::integer::gcd(dx, dy)
+			})
+			.sum()
+	}
+
+	/// Counts the lattice points strictly inside the polygon, using Pick's
+	/// theorem: `A = i + b/2 - 1`.
+	///
+	/// The polygon must not cross itself.
+	pub fn interior_lattice_points(&self) -> i64 {
+		(self.area2().abs() - self.perimeter() + 2) / 2
+	}
+
+	/// Counts the lattice points either inside or on the polygon.
+	///
+	/// When the polygon traces the centers of grid cells, this is the number of
+	/// cells it covers, including those on its path.
+	pub fn enclosed_lattice_points(&self) -> i64 {
+		self.interior_lattice_points() + self.perimeter()
+	}
+}
+
+impl<I: Signed> FromIterator<Point2D<I>> for Polygon<I> {
+	fn from_iter<II: IntoIterator<Item = Point2D<I>>>(iter: II) -> Self {
+		Self::new(iter)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use tap::Pipe;
+
+	use super::*;
+
+	#[test]
+	fn shoelace_and_pick() {
+		let square = [(0, 0), (4, 0), (4, 4), (0, 4)]
+			.map(Point2D::<i32>::from)
+			.pipe(Polygon::new);
+		assert_eq!(square.area2(), 32);
+		assert_eq!(square.perimeter(), 16);
+		assert_eq!(square.interior_lattice_points(), 9);
+		assert_eq!(square.enclosed_lattice_points(), 25);
+
+		// Reversing the drawing order flips the sign, but nothing else.
+		let reversed = square
+			.vertices()
+			.iter()
+			.rev()
+			.copied()
+			.collect::<Polygon<_>>();
+		assert_eq!(reversed.area2(), -32);
+		assert_eq!(reversed.interior_lattice_points(), 9);
+
+		// The diagonal edge passes through (1, 2) and (2, 1) on its way.
+		let triangle = [(0, 0), (3, 0), (0, 3)]
+			.map(Point2D::<i32>::from)
+			.pipe(Polygon::new);
+		assert_eq!(triangle.area2(), 9);
+		assert_eq!(triangle.perimeter(), 9);
+		assert_eq!(triangle.interior_lattice_points(), 1);
+	}
+}
@@ -0,0 +1,79 @@
+//! Scanning the rows of a plane in parallel.
+//!
+//! Some puzzles are answered one row at a time, with each row worked out on
+//! its own: how much of the row a shape covers, or whether anything in the row
+//! is left uncovered. Rows are independent, so they can be spread across every
+//! core, but a scan over millions of rows also wants the progress logging and
+//! Ctrl-C handling that a hand-written loop would have. [`par_scan_rows`]
+//! provides both, and leaves the solver to write only what happens in one row.
+
+use std::sync::atomic::{
+	AtomicU64,
+	Ordering,
+};
+
+use rayon::prelude::*;
+
+use crate::cancel;
+
+/// Runs `scan` over every row in `min ..= max`, in parallel, producing each
+/// row's result.
+///
+/// The results arrive in no particular order, and are consumed with any of
+/// rayon's adapters: `.sum()` to total the rows, or `.find_map_any()` to stop
+/// every thread as soon as one row has an answer.
+///
+/// Progress is logged at the `DEBUG` level each time another percent of the
+/// rows is done. When the run is cancelled, the scan stops early, so callers
+/// must [`cancel::check`] afterwards before trusting a total.
+pub fn par_scan_rows<T, F>(
+	min: i64,
+	max: i64,
+	scan: F,
+) -> impl ParallelIterator<Item = T>
+where
+	T: Send,
+	F: Fn(i64) -> T + Send + Sync,
+{
+	let total = if max < min {
+		0
+	}
+	else {
+		u128::from(min.abs_diff(max)) + 1
+	};
+	let done = AtomicU64::new(0);
+	(min ..= max)
+		.into_par_iter()
+		.map(move |row| {
+			if cancel::is_cancelled() {
+				return None;
+			}
+			let out = scan(row);
+			let finished = u128::from(done.fetch_add(1, Ordering::Relaxed)) + 1;
+			let percent = finished * 100 / total;
+			if percent > (finished - 1) * 100 / total {
+				tracing::debug!(%percent, %finished, %total, "scanned rows");
+			}
+			Some(out)
+		})
+		.while_some()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn scans_every_row() {
+		let sum = par_scan_rows(-100, 100, |row| row * row).sum::<i64>();
+		assert_eq!(sum, 2 * (1 ..= 100).map(|n| n * n).sum::<i64>());
+		assert_eq!(par_scan_rows(5, 4, |row| row).count(), 0);
+
+		// Far too many rows to scan in a test, unless the scan stops early.
+		let root = par_scan_rows(0, i64::MAX, |row| {
+			(row.checked_mul(row) == Some(144)).then_some(row)
+		})
+		.find_map_any(|found| found);
+		assert_eq!(root, Some(12));
+	}
+}
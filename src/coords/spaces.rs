@@ -10,12 +10,20 @@ use std::{
 
 use funty::Signed;
 
+pub mod bits;
 pub mod dense;
+pub mod formats;
+pub mod matrix;
 pub mod sparse;
+pub mod tiling;
 
 pub use self::{
+	bits::BitGrid2D,
 	dense::Cartesian2D as Dense2D,
+	formats::RleCell,
+	matrix::Matrix,
 	sparse::Cartesian2D as Sparse2D,
+	tiling::TilingView,
 };
 pub use super::Cartesian2DPoint as Point2D;
 
@@ -51,6 +59,81 @@ pub struct Symbols {
 	pub quarter_3:  char,
 	/// A completely filled tile.
 	pub full:       char,
+	/// How grid cells map onto terminal cells.
+	pub mode:       RenderMode,
+	/// If set, grids wider than this many terminal cells are downscaled until
+	/// they fit.
+	pub max_width:  Option<usize>,
+}
+
+/// Strategies for packing grid cells into terminal cells.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RenderMode {
+	/// Each grid cell gets its own terminal cell, drawn by
+	/// [`DisplayGrid::print_cell`], with axis labels.
+	#[default]
+	Cells,
+	/// Each terminal cell shows a 1x2 column of grid cells using the Unicode
+	/// half-block characters.
+	HalfBlock,
+	/// Each terminal cell shows a 2x4 block of grid cells as a Braille glyph.
+	Braille,
+	/// Each terminal cell shows one grid cell's
+	/// [value](DisplayGrid::cell_value) on a gradient, from the smallest value
+	/// in the grid to the largest.
+	Heatmap(Gradient),
+}
+
+/// Scales which a heatmap can shade cells along.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gradient {
+	/// The four quarter-fill symbols, from [`Symbols::quarter_1`] to
+	/// [`Symbols::full`].
+	#[default]
+	Shades,
+	/// Background colors from the ANSI 256-color palette, running from blue
+	/// through green and yellow to red. This needs a terminal which
+	/// understands the escape codes.
+	Ansi256,
+}
+
+impl Gradient {
+	/// The 256-color palette entries used by [`Gradient::Ansi256`], coldest
+	/// first.
+	const ANSI: [u8; 16] = [
+		17, 19, 21, 27, 33, 39, 45, 51, 49, 47, 82, 154, 226, 214, 202, 196,
+	];
+}
+
+/// A cell value which can be placed on a heatmap's scale.
+pub trait HeatValue: Copy {
+	fn heat(self) -> f64;
+}
+
+macro_rules! heat_value {
+	($($t:ty),+ $(,)?) => { $(
+		impl HeatValue for $t {
+			fn heat(self) -> f64 {
+				self as f64
+			}
+		}
+	)+ };
+}
+
+heat_value!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl RenderMode {
+	/// The number of grid columns and rows that fit in one terminal cell.
+	pub const fn cell_size(self) -> (usize, usize) {
+		match self {
+			Self::Cells => (1, 1),
+			Self::HalfBlock => (1, 2),
+			Self::Braille => (2, 4),
+			Self::Heatmap(_) => (1, 1),
+		}
+	}
 }
 
 impl Symbols {
@@ -70,6 +153,8 @@ impl Symbols {
 		quarter_2:  'm',
 		quarter_3:  'M',
 		full:       '#',
+		mode:       RenderMode::Cells,
+		max_width:  None,
 	};
 	/// Symbols taken from the box-drawing and and geometric shapes Unicode
 	/// blocks.
@@ -88,7 +173,21 @@ impl Symbols {
 		quarter_2:  '▒',
 		quarter_3:  '▓',
 		full:       '█',
+		mode:       RenderMode::Cells,
+		max_width:  None,
 	};
+
+	/// Selects a packing mode for the renderer.
+	pub const fn with_mode(mut self, mode: RenderMode) -> Self {
+		self.mode = mode;
+		self
+	}
+
+	/// Sets the widest that a rendered grid may be, in terminal cells.
+	pub const fn with_max_width(mut self, max_width: usize) -> Self {
+		self.max_width = Some(max_width);
+		self
+	}
 }
 
 pub trait DisplayGrid<I: Signed, T> {
@@ -112,17 +211,62 @@ pub trait DisplayGrid<I: Signed, T> {
 		col_abs: usize,
 	) -> char;
 
+	/// Tests whether a cell has anything in it, for the renderers that can only
+	/// show cells as on or off.
+	///
+	/// By default, a cell is filled if its ASCII rendering is not blank.
+	fn is_filled(&self, row: I, col: I, row_abs: usize, col_abs: usize) -> bool {
+		self.print_cell(&Symbols::ASCII, row, col, row_abs, col_abs)
+			!= Symbols::ASCII.empty
+	}
+
+	/// Gives the number in a cell, for [heatmaps](RenderMode::Heatmap).
+	///
+	/// By default, no cell has a number, and heatmaps are blank. Grids which
+	/// store numbers directly can be wrapped in a [`Heatmap`] instead.
+	fn cell_value(
+		&self,
+		row: I,
+		col: I,
+		row_abs: usize,
+		col_abs: usize,
+	) -> Option<f64> {
+		let _ = (row, col, row_abs, col_abs);
+		None
+	}
+
+	/// Renders the grid with ASCII symbols, or with the fancy symbols under
+	/// `{:#}`.
 	fn render(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		let Some((min, max)) = self.bounds_inclusive()
-		else {
-			return Ok(());
-		};
 		let symbols = if fmt.alternate() {
 			Symbols::FANCY
 		}
 		else {
 			Symbols::ASCII
 		};
+		self.render_with(fmt, &symbols)
+	}
+
+	/// Renders the grid with a specific set of symbols and packing mode.
+	fn render_with(
+		&self,
+		fmt: &mut fmt::Formatter,
+		symbols: &Symbols,
+	) -> fmt::Result {
+		let Some((min, max)) = self.bounds_inclusive()
+		else {
+			return Ok(());
+		};
+		if let RenderMode::Heatmap(gradient) = symbols.mode {
+			return self.render_heatmap(fmt, symbols, gradient, min, max);
+		}
+		let width = (max.x - min.x).as_usize() + 1;
+		if symbols.mode != RenderMode::Cells
+			|| symbols.max_width.is_some_and(|w| width > w)
+		{
+			return self.render_packed(fmt, symbols, min, max);
+		}
+		let symbols = *symbols;
 		// We print from 0 to len, not from min to max, regardless of coördinate
 		// domain.
 		let (row_max_abs, col_max_abs) =
@@ -212,27 +356,328 @@ pub trait DisplayGrid<I: Signed, T> {
 		Ok(())
 	}
 
+	/// Renders the grid by packing several grid cells into each terminal cell,
+	/// and by downscaling if the result would still be too wide.
+	///
+	/// Axis labels are not drawn, since they would not line up with the
+	/// packed cells. When downscaling, each pixel shows whether any cell in
+	/// the block it covers is filled; in [`RenderMode::Cells`] the quarter-fill
+	/// symbols show roughly how much of the block is filled.
+	fn render_packed(
+		&self,
+		fmt: &mut fmt::Formatter,
+		symbols: &Symbols,
+		min: Point2D<I>,
+		max: Point2D<I>,
+	) -> fmt::Result {
+		let (height, width) = (
+			(max.y - min.y).as_usize() + 1,
+			(max.x - min.x).as_usize() + 1,
+		);
+		let mut filled = vec![vec![false; width]; height];
+		let mut row = min.y;
+		for (row_abs, line) in filled.iter_mut().enumerate() {
+			let mut col = min.x;
+			for (col_abs, cell) in line.iter_mut().enumerate() {
+				*cell = self.is_filled(row, col, row_abs, col_abs);
+				col += I::ONE;
+			}
+			row += I::ONE;
+		}
+
+		let (cell_w, cell_h) = symbols.mode.cell_size();
+		let scale = symbols
+			.max_width
+			.map(|max_w| width.div_ceil(max_w.max(1) * cell_w))
+			.unwrap_or(1)
+			.max(1);
+		// Counts the filled grid cells in the block covered by one pixel.
+		let density = |px: usize, py: usize| {
+			let rows = (py * scale) .. ((py + 1) * scale).min(height);
+			let cols = (px * scale) .. ((px + 1) * scale).min(width);
+			let total = rows.len() * cols.len();
+			let count = filled
+				.get(rows)
+				.unwrap_or_default()
+				.iter()
+				.map(|line| {
+					line.get(cols.clone())
+						.unwrap_or_default()
+						.iter()
+						.filter(|&&c| c)
+						.count()
+				})
+				.sum::<usize>();
+			(count, total)
+		};
+		let on = |px: usize, py: usize| density(px, py).0 > 0;
+
+		let (pix_w, pix_h) = (width.div_ceil(scale), height.div_ceil(scale));
+		for ty in 0 .. pix_h.div_ceil(cell_h) {
+			for tx in 0 .. pix_w.div_ceil(cell_w) {
+				let (px, py) = (tx * cell_w, ty * cell_h);
+				let glyph = match symbols.mode {
+					RenderMode::Cells | RenderMode::Heatmap(_) => {
+						match density(px, py) {
+							(0, _) => symbols.empty,
+							(n, d) if n == d => symbols.full,
+							(n, d) if n * 4 <= d => symbols.quarter_1,
+							(n, d) if n * 4 <= d * 2 => symbols.quarter_2,
+							_ => symbols.quarter_3,
+						}
+					},
+					RenderMode::HalfBlock => {
+						match (on(px, py), on(px, py + 1)) {
+							(true, true) => '█',
+							(true, false) => '▀',
+							(false, true) => '▄',
+							(false, false) => ' ',
+						}
+					},
+					RenderMode::Braille => {
+						const DOTS: [[u32; 2]; 4] =
+							[[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [
+								0x40, 0x80,
+							]];
+						let mut bits = 0;
+						for (dy, row) in DOTS.iter().enumerate() {
+							for (dx, bit) in row.iter().enumerate() {
+								if on(px + dx, py + dy) {
+									bits |= bit;
+								}
+							}
+						}
+						char::from_u32(0x2800 + bits).unwrap_or(symbols.full)
+					},
+				};
+				fmt.write_char(glyph)?;
+			}
+			writeln!(fmt)?;
+		}
+		Ok(())
+	}
+
+	/// Renders each cell's value as a shade along a gradient.
+	///
+	/// Values are scaled between the smallest and largest in the grid, so the
+	/// gradient is always used in full. Cells without a value are left
+	/// empty. Heatmaps are never packed or downscaled, and have no axis
+	/// labels.
+	fn render_heatmap(
+		&self,
+		fmt: &mut fmt::Formatter,
+		symbols: &Symbols,
+		gradient: Gradient,
+		min: Point2D<I>,
+		max: Point2D<I>,
+	) -> fmt::Result {
+		let (height, width) = (
+			(max.y - min.y).as_usize() + 1,
+			(max.x - min.x).as_usize() + 1,
+		);
+		// Visits every cell, with its ordinates and value.
+		let cells = || {
+			let mut row = min.y;
+			(0 .. height).flat_map(move |row_abs| {
+				let mut col = min.x - I::ONE;
+				let this_row = row;
+				row += I::ONE;
+				(0 .. width).map(move |col_abs| {
+					col += I::ONE;
+					let value = self.cell_value(this_row, col, row_abs, col_abs);
+					(col_abs, value)
+				})
+			})
+		};
+		let (lo, hi) = cells()
+			.filter_map(|(_, value)| value)
+			.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+				(lo.min(v), hi.max(v))
+			});
+		// Places a value on a gradient with some number of steps.
+		let level = |value: f64, steps: usize| {
+			if hi > lo {
+				((value - lo) / (hi - lo) * (steps - 1) as f64).round() as usize
+			}
+			else {
+				steps - 1
+			}
+		};
+		let shades = [
+			symbols.quarter_1,
+			symbols.quarter_2,
+			symbols.quarter_3,
+			symbols.full,
+		];
+		for (col_abs, value) in cells() {
+			match (gradient, value) {
+				(Gradient::Ansi256, None) => write!(fmt, "\x1b[0m ")?,
+				(_, None) => fmt.write_char(symbols.empty)?,
+				(Gradient::Shades, Some(value)) => {
+					fmt.write_char(shades[level(value, shades.len())])?
+				},
+				(Gradient::Ansi256, Some(value)) => {
+					let color =
+						Gradient::ANSI[level(value, Gradient::ANSI.len())];
+					write!(fmt, "\x1b[48;5;{color}m ")?
+				},
+			}
+			if col_abs + 1 == width {
+				if gradient == Gradient::Ansi256 {
+					fmt.write_str("\x1b[0m")?;
+				}
+				writeln!(fmt)?;
+			}
+		}
+		Ok(())
+	}
+
 	fn display<'a>(&'a self) -> GridPrinter<'a, Self, I, T> {
 		GridPrinter {
-			inner: self,
-			_grid: PhantomData,
+			inner:   self,
+			symbols: None,
+			_grid:   PhantomData,
+		}
+	}
+
+	/// Prepares to display the grid with a specific set of symbols and
+	/// packing mode, rather than choosing by the `{:#}` flag.
+	fn display_with<'a>(
+		&'a self,
+		symbols: Symbols,
+	) -> GridPrinter<'a, Self, I, T> {
+		GridPrinter {
+			inner:   self,
+			symbols: Some(symbols),
+			_grid:   PhantomData,
 		}
 	}
 }
 
 pub struct GridPrinter<'a, G: 'a + ?Sized + DisplayGrid<I, T>, I: Signed, T> {
-	inner: &'a G,
-	_grid: PhantomData<Dense2D<I, T>>,
+	inner:   &'a G,
+	symbols: Option<Symbols>,
+	_grid:   PhantomData<Dense2D<I, T>>,
 }
 
 impl<'a, G: 'a + DisplayGrid<I, T>, I: Signed, T> fmt::Display
 	for GridPrinter<'a, G, I, T>
 {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		self.inner.render(fmt)
+		match &self.symbols {
+			Some(symbols) => self.inner.render_with(fmt, symbols),
+			None => self.inner.render(fmt),
+		}
 	}
 }
 
+/// Shows a grid of numbers as a heatmap.
+///
+/// Dense and sparse grids of any primitive number can be wrapped in this to
+/// give them a [`cell_value`](DisplayGrid::cell_value). Printed with `{}` or
+/// `{:#}`, it draws a [`Gradient::Shades`] heatmap with the ASCII or fancy
+/// symbols; use [`display_with`](DisplayGrid::display_with) for the other
+/// gradients.
+#[derive(Clone, Copy, Debug)]
+pub struct Heatmap<'a, G: ?Sized> {
+	pub grid: &'a G,
+}
+
+impl<'a, G: ?Sized> Heatmap<'a, G> {
+	pub fn new(grid: &'a G) -> Self {
+		Self { grid }
+	}
+}
+
+impl<I: Signed, T: HeatValue + Default + PartialEq> DisplayGrid<I, T>
+	for Heatmap<'_, Dense2D<I, T>>
+{
+	fn bounds_inclusive(&self) -> Option<(Point2D<I>, Point2D<I>)> {
+		self.grid.bounds_inclusive()
+	}
+
+	fn print_cell(
+		&self,
+		symbols: &Symbols,
+		row: I,
+		col: I,
+		row_abs: usize,
+		col_abs: usize,
+	) -> char {
+		self.grid.print_cell(symbols, row, col, row_abs, col_abs)
+	}
+
+	fn cell_value(
+		&self,
+		row: I,
+		col: I,
+		_row_abs: usize,
+		_col_abs: usize,
+	) -> Option<f64> {
+		self.grid.get(Point2D::new(col, row)).map(|&v| v.heat())
+	}
+
+	fn render(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		self.render_with(fmt, &heatmap_symbols(fmt))
+	}
+}
+
+impl<I: Signed, T: HeatValue> DisplayGrid<I, T> for Heatmap<'_, Sparse2D<I, T>> {
+	fn bounds_inclusive(&self) -> Option<(Point2D<I>, Point2D<I>)> {
+		self.grid.bounds_inclusive()
+	}
+
+	fn print_cell(
+		&self,
+		symbols: &Symbols,
+		row: I,
+		col: I,
+		row_abs: usize,
+		col_abs: usize,
+	) -> char {
+		self.grid.print_cell(symbols, row, col, row_abs, col_abs)
+	}
+
+	fn cell_value(
+		&self,
+		row: I,
+		col: I,
+		_row_abs: usize,
+		_col_abs: usize,
+	) -> Option<f64> {
+		self.grid.get(Point2D::new(col, row)).map(|&v| v.heat())
+	}
+
+	fn render(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		self.render_with(fmt, &heatmap_symbols(fmt))
+	}
+}
+
+impl<I: Signed, T: HeatValue + Default + PartialEq> fmt::Display
+	for Heatmap<'_, Dense2D<I, T>>
+{
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		DisplayGrid::render(self, fmt)
+	}
+}
+
+impl<I: Signed, T: HeatValue> fmt::Display for Heatmap<'_, Sparse2D<I, T>> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		DisplayGrid::render(self, fmt)
+	}
+}
+
+/// Picks the shaded heatmap symbols for a formatter, fancy under `{:#}`.
+fn heatmap_symbols(fmt: &fmt::Formatter) -> Symbols {
+	let symbols = if fmt.alternate() {
+		Symbols::FANCY
+	}
+	else {
+		Symbols::ASCII
+	};
+	symbols.with_mode(RenderMode::Heatmap(Gradient::Shades))
+}
+
 #[cfg(test)]
 mod tests {
 	use tap::Pipe;
@@ -280,4 +725,135 @@ mod tests {
 		}
 		eprintln!("\n{data}");
 	}
+
+	#[test]
+	fn sparse_entries() {
+		let mut grid = Sparse2D::<i8, u8>::new();
+		let pt = Point2D::new(2, -1);
+		*grid.entry(pt).or_default() += 3;
+		grid.entry(pt).and_modify(|v| *v *= 2).or_insert(0);
+		grid.entry(Point2D::ZERO)
+			.and_modify(|v| *v *= 2)
+			.or_insert(7);
+		*grid.get_or_default(Point2D::new(-1, 4)) += 1;
+		grid[Point2D::ZERO] += 1;
+
+		assert_eq!(grid[pt], 6);
+		assert_eq!(grid[Point2D::ZERO], 8);
+		assert_eq!(grid[Point2D::new(-1, 4)], 1);
+		assert_eq!(
+			grid.dimensions(),
+			Some((Point2D::new(-1, -1), Point2D::new(2, 4)))
+		);
+		assert!(grid.encloses(Point2D::new(-1, -1)));
+	}
+
+	#[test]
+	fn sparse_volume() {
+		use crate::coords::{
+			Cartesian3DPoint as Point3D,
+			Cartesian3DSpace as Sparse3D,
+		};
+
+		// A 3x3x3 hollow cube, with one voxel missing from its top face.
+		let mut cube = Sparse3D::<i8, u8>::new();
+		for pt in Point3D::new(1, 1, 1).all_neighbors() {
+			cube.insert(pt, 1);
+		}
+		assert_eq!(cube.len(), 26);
+		cube.retain(|pt, _| pt != Point3D::new(1, 1, 2));
+		assert_eq!(cube.len(), 25);
+		assert_eq!(cube.neighbors(Point3D::new(1, 1, 1)).count(), 5);
+		assert_eq!(
+			cube.neighbors_with_diagonals(Point3D::new(1, 1, 1)).count(),
+			25
+		);
+		for (_, val) in cube.iter_mut() {
+			*val += 1;
+		}
+		assert!(cube.iter().all(|(_, &val)| val == 2));
+		assert_eq!(
+			cube.dimensions(),
+			Some((Point3D::new(0, 0, 0), Point3D::new(2, 2, 2)))
+		);
+
+		// The hollow middle can reach the open top face, and nothing else.
+		let air = cube.flood([Point3D::new(1, 1, 1)], |_, val| val.is_none());
+		assert_eq!(air.len(), 2);
+
+		let top = cube.slice(2).to_string();
+		assert_eq!(top.lines().filter(|line| line.contains('#')).count(), 3);
+		assert_eq!(cube.slices().count(), 3);
+	}
+
+	#[test]
+	fn draw_paths() {
+		let paths = [vec![(498, 4), (498, 6), (496, 6)], vec![
+			(503, 4),
+			(502, 4),
+			(502, 9),
+			(494, 9),
+		]];
+		let mut cave = Sparse2D::<i16, char>::new();
+		cave.draw_paths(
+			paths
+				.iter()
+				.map(|path| path.iter().copied().map(Point2D::from)),
+			'#',
+		)
+		.unwrap();
+		assert_eq!(cave.len(), 20);
+		assert!(cave.contains(Point2D::new(497, 6)));
+		assert!(cave.contains(Point2D::new(502, 7)));
+		assert!(!cave.contains(Point2D::new(499, 5)));
+
+		assert!(cave
+			.draw_paths([[Point2D::ZERO, Point2D::new(1, 1)]], '#')
+			.is_err());
+	}
+
+	#[test]
+	fn packed_rendering() {
+		let grid = [(0, 0), (1, 1), (0, 3), (3, 0)]
+			.into_iter()
+			.map(|pt| (Point2D::<i8>::from(pt), ()))
+			.collect::<Sparse2D<i8, ()>>();
+
+		let braille = Symbols::FANCY.with_mode(RenderMode::Braille);
+		assert_eq!(grid.display_with(braille).to_string(), "⡑⠈\n");
+
+		let halves = Symbols::FANCY.with_mode(RenderMode::HalfBlock);
+		assert_eq!(grid.display_with(halves).to_string(), "▀▄ ▀\n▄   \n");
+
+		let scaled = Symbols::ASCII.with_max_width(2);
+		assert_eq!(grid.display_with(scaled).to_string(), "m_\n_ \n");
+	}
+
+	#[test]
+	fn heatmap() {
+		let grid = Dense2D::<i8, u8>::from_raw(Point2D::ZERO, vec![
+			vec![0, 3, 6, 9],
+			vec![9, 6, 3, 0],
+		]);
+		let heat = Heatmap::new(&grid);
+		assert_eq!(heat.to_string(), "_mM#\n#Mm_\n");
+		assert_eq!(format!("{heat:#}"), "░▒▓█\n█▓▒░\n");
+
+		let ansi =
+			Symbols::FANCY.with_mode(RenderMode::Heatmap(Gradient::Ansi256));
+		let text = heat.display_with(ansi).to_string();
+		assert!(text.starts_with("\x1b[48;5;17m \x1b[48;5;39m "));
+		assert_eq!(text.lines().count(), 2);
+
+		// Cells without a number are left blank.
+		let sparse = [((0, 0), 5), ((2, 0), 10)]
+			.into_iter()
+			.map(|(pt, v)| (Point2D::<i8>::from(pt), v))
+			.collect::<Sparse2D<i8, i32>>();
+		assert_eq!(Heatmap::new(&sparse).to_string(), "_ #\n");
+		// Grids which have no numbers draw nothing but blanks.
+		let plain =
+			Symbols::ASCII.with_mode(RenderMode::Heatmap(Gradient::Shades));
+		assert_eq!(grid.display_with(plain).to_string(), "    \n    \n");
+	}
 }
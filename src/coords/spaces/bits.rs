@@ -0,0 +1,418 @@
+/*! Bit-packed 2-dimensional occupancy maps
+
+Plenty of puzzles only ask whether each cell in a region is lit, occupied, or
+visited. A `bool` per cell spends a byte where a bit would do, and a sparse map
+spends far more than that, so this module provides a fixed-size grid which
+stores one bit per cell, in row-major order, in a single `BitVec`. Filling and
+counting runs of a row then become word-at-a-time operations, which is what the
+"turn on 0,0 through 999,999" kind of puzzle spends all of its time doing.
+*/
+
+use std::{
+	fmt,
+	iter::FusedIterator,
+	ops::{
+		Index,
+		RangeInclusive,
+	},
+};
+
+use bitvec::prelude::*;
+use funty::Signed;
+
+use super::{
+	DisplayGrid,
+	Point2D,
+	Sparse2D,
+	Symbols,
+};
+
+/// A 2-dimensional Cartesian grid of fixed size, where each cell is either set
+/// or clear.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitGrid2D<I: Signed> {
+	origin: Point2D<I>,
+	width:  usize,
+	height: usize,
+	bits:   BitVec,
+}
+
+impl<I: Signed> BitGrid2D<I> {
+	/// Creates a clear grid covering every point from `min` to `max`,
+	/// inclusive.
+	///
+	/// An inverted range on either axis produces an empty grid.
+	pub fn new(min: Point2D<I>, max: Point2D<I>) -> Self {
+		let span = |lo: I, hi: I| {
+			if hi < lo {
+				0
+			}
+			else {
+				(hi.as_isize() - lo.as_isize()) as usize + 1
+			}
+		};
+		Self::with_size(min, span(min.x, max.x), span(min.y, max.y))
+	}
+
+	/// Creates a clear grid with its top-left corner at `origin`.
+	pub fn with_size(origin: Point2D<I>, width: usize, height: usize) -> Self {
+		Self {
+			origin,
+			width,
+			height,
+			bits: bitvec![0; width * height],
+		}
+	}
+
+	/// The number of columns in the grid.
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// The number of rows in the grid.
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// Views the underlying bits, in row-major order.
+	pub fn raw_data(&self) -> &BitSlice {
+		&self.bits
+	}
+
+	/// Clears every cell in the grid. The bounds do not change.
+	pub fn clear(&mut self) {
+		self.bits.fill(false);
+	}
+
+	/// Tests whether no cell in the grid is set.
+	pub fn is_empty(&self) -> bool {
+		self.bits.not_any()
+	}
+
+	/// Counts the set cells in the grid.
+	pub fn len(&self) -> usize {
+		self.bits.count_ones()
+	}
+
+	/// Produces the corners of the grid, if it has any cells at all.
+	///
+	/// Unlike the sparse grid, this is the full extent of the grid, not the
+	/// bounding box of its set cells.
+	pub fn dimensions(&self) -> Option<(Point2D<I>, Point2D<I>)> {
+		if self.bits.is_empty() {
+			return None;
+		}
+		let extent = Point2D::new(
+			I::try_from(self.width - 1).ok()?,
+			I::try_from(self.height - 1).ok()?,
+		);
+		Some((self.origin, self.origin + extent))
+	}
+
+	/// Tests whether a point lies inside the grid, whether or not it is set.
+	pub fn in_bounds(&self, point: Point2D<I>) -> bool {
+		self.offset(point).is_some()
+	}
+
+	/// Tests whether the cell at a point is set. Points outside the grid are
+	/// never set.
+	pub fn contains(&self, point: Point2D<I>) -> bool {
+		self.get(point).unwrap_or(false)
+	}
+
+	/// Gets the cell at a point, or `None` if the point is outside the grid.
+	pub fn get(&self, point: Point2D<I>) -> Option<bool> {
+		self.offset(point).map(|idx| self.bits[idx])
+	}
+
+	/// Sets the cell at a point, returning whether it was clear before.
+	///
+	/// # Panics
+	///
+	/// This panics if the point is outside the grid.
+	pub fn insert(&mut self, point: Point2D<I>) -> bool {
+		!self.replace(point, true)
+	}
+
+	/// Clears the cell at a point, returning whether it was set before.
+	/// Points outside the grid are never set.
+	pub fn remove(&mut self, point: Point2D<I>) -> bool {
+		self.in_bounds(point) && self.replace(point, false)
+	}
+
+	/// Writes a value into the cell at a point, returning its previous value.
+	///
+	/// # Panics
+	///
+	/// This panics if the point is outside the grid.
+	pub fn replace(&mut self, point: Point2D<I>, value: bool) -> bool {
+		let idx = self.expect_offset(point);
+		self.bits.replace(idx, value)
+	}
+
+	/// Flips the cell at a point, returning its new value.
+	///
+	/// # Panics
+	///
+	/// This panics if the point is outside the grid.
+	pub fn toggle(&mut self, point: Point2D<I>) -> bool {
+		let idx = self.expect_offset(point);
+		let value = !self.bits[idx];
+		self.bits.set(idx, value);
+		value
+	}
+
+	/// Views a run of cells in one row, clipped to the grid.
+	///
+	/// Rows outside the grid, and runs which miss it entirely, are empty.
+	pub fn row_range(&self, row: I, cols: RangeInclusive<I>) -> &BitSlice {
+		let span = self.span(row, cols);
+		&self.bits[span]
+	}
+
+	/// Mutably views a run of cells in one row, clipped to the grid.
+	pub fn row_range_mut(
+		&mut self,
+		row: I,
+		cols: RangeInclusive<I>,
+	) -> &mut BitSlice {
+		let span = self.span(row, cols);
+		&mut self.bits[span]
+	}
+
+	/// Sets or clears a run of cells in one row, clipped to the grid.
+	pub fn fill_row(&mut self, row: I, cols: RangeInclusive<I>, value: bool) {
+		self.row_range_mut(row, cols).fill(value);
+	}
+
+	/// Sets or clears every cell in the rectangle with corners `min` and
+	/// `max`, clipped to the grid.
+	pub fn fill_rect(&mut self, min: Point2D<I>, max: Point2D<I>, value: bool) {
+		for row in rows(min.y, max.y) {
+			self.fill_row(row, min.x ..= max.x, value);
+		}
+	}
+
+	/// Flips every cell in the rectangle with corners `min` and `max`, clipped
+	/// to the grid.
+	pub fn toggle_rect(&mut self, min: Point2D<I>, max: Point2D<I>) {
+		for row in rows(min.y, max.y) {
+			let cells = self.row_range_mut(row, min.x ..= max.x);
+			// `!` on a bit-slice inverts it a word at a time.
+			let _ = !cells;
+		}
+	}
+
+	/// Counts the set cells in a run of one row, clipped to the grid.
+	pub fn count_row(&self, row: I, cols: RangeInclusive<I>) -> usize {
+		self.row_range(row, cols).count_ones()
+	}
+
+	/// Counts the set cells in the rectangle with corners `min` and `max`,
+	/// clipped to the grid.
+	pub fn count_rect(&self, min: Point2D<I>, max: Point2D<I>) -> usize {
+		rows(min.y, max.y)
+			.map(|row| self.count_row(row, min.x ..= max.x))
+			.sum()
+	}
+
+	/// Iterates over the set cells, in row-major order.
+	pub fn iter(
+		&self,
+	) -> impl '_ + DoubleEndedIterator<Item = Point2D<I>> + FusedIterator {
+		self.bits.iter_ones().map(|idx| self.point_at(idx))
+	}
+
+	/// Finds the index of a point in the bit storage.
+	fn offset(&self, point: Point2D<I>) -> Option<usize> {
+		let col = usize::try_from(point.x.as_isize() - self.origin.x.as_isize())
+			.ok()
+			.filter(|&col| col < self.width)?;
+		let row = usize::try_from(point.y.as_isize() - self.origin.y.as_isize())
+			.ok()
+			.filter(|&row| row < self.height)?;
+		Some(row * self.width + col)
+	}
+
+	fn expect_offset(&self, point: Point2D<I>) -> usize {
+		self.offset(point).unwrap_or_else(|| {
+			panic!(
+				"{point} is outside the grid's {}x{}",
+				self.width, self.height
+			)
+		})
+	}
+
+	/// Finds the point at an index in the bit storage.
+	fn point_at(&self, idx: usize) -> Point2D<I> {
+		let shift = |base: I, by: usize| {
+			I::try_from(base.as_isize() + by as isize)
+				.unwrap_or_else(|_| unreachable!("grid cells fit in `I`"))
+		};
+		Point2D::new(
+			shift(self.origin.x, idx % self.width),
+			shift(self.origin.y, idx / self.width),
+		)
+	}
+
+	/// Finds the bit indices of a run of cells in a row, clipped to the grid.
+	fn span(&self, row: I, cols: RangeInclusive<I>) -> std::ops::Range<usize> {
+		let row = row.as_isize() - self.origin.y.as_isize();
+		if row < 0 || row as usize >= self.height {
+			return 0 .. 0;
+		}
+		// Work in `isize`, so that a range ending at `I::MAX` can still be made
+		// exclusive.
+		let offset =
+			|col: I| col.as_isize().saturating_sub(self.origin.x.as_isize());
+		let clip = |col: isize| col.clamp(0, self.width as isize) as usize;
+		let (lo, hi) = (
+			clip(offset(*cols.start())),
+			clip(offset(*cols.end()).saturating_add(1)),
+		);
+		let base = row as usize * self.width;
+		base + lo .. base + hi.max(lo)
+	}
+}
+
+/// Walks the rows from `lo` to `hi` inclusive, which may be empty.
+fn rows<I: Signed>(lo: I, hi: I) -> impl Iterator<Item = I> {
+	std::iter::successors((lo <= hi).then_some(lo), move |&row| {
+		(row < hi).then(|| row + I::ONE)
+	})
+}
+
+/// Indexing produces the value of a cell, and panics outside the grid.
+impl<I: Signed> Index<Point2D<I>> for BitGrid2D<I> {
+	type Output = bool;
+
+	fn index(&self, point: Point2D<I>) -> &bool {
+		&self.bits[self.expect_offset(point)]
+	}
+}
+
+/// Collects points into the smallest grid which holds all of them.
+impl<I: Signed> FromIterator<Point2D<I>> for BitGrid2D<I> {
+	fn from_iter<II: IntoIterator<Item = Point2D<I>>>(points: II) -> Self {
+		let points = points.into_iter().collect::<Vec<_>>();
+		let Some(&first) = points.first()
+		else {
+			return Self::with_size(Point2D::ZERO, 0, 0);
+		};
+		let (min, max) =
+			points.iter().fold((first, first), |(min, max), &pt| {
+				(pt.min_unifying(min), pt.max_unifying(max))
+			});
+		let mut this = Self::new(min, max);
+		for point in points {
+			this.insert(point);
+		}
+		this
+	}
+}
+
+/// Sets every point which holds a value in the sparse grid, within the sparse
+/// grid's bounding box.
+impl<I: Signed, T> From<&Sparse2D<I, T>> for BitGrid2D<I> {
+	fn from(sparse: &Sparse2D<I, T>) -> Self {
+		sparse.iter().map(|(point, _)| point).collect()
+	}
+}
+
+impl<I: Signed> DisplayGrid<I, bool> for BitGrid2D<I> {
+	fn bounds_inclusive(&self) -> Option<(Point2D<I>, Point2D<I>)> {
+		self.dimensions()
+	}
+
+	fn print_cell(
+		&self,
+		symbols: &Symbols,
+		_row: I,
+		_col: I,
+		row_abs: usize,
+		col_abs: usize,
+	) -> char {
+		if self.bits[row_abs * self.width + col_abs] {
+			symbols.full
+		}
+		else {
+			symbols.empty
+		}
+	}
+
+	fn is_filled(
+		&self,
+		_row: I,
+		_col: I,
+		row_abs: usize,
+		col_abs: usize,
+	) -> bool {
+		self.bits[row_abs * self.width + col_abs]
+	}
+}
+
+/// Renders the grid in Quadrant IV, with its origin at the top left.
+impl<I: Signed> fmt::Display for BitGrid2D<I> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		DisplayGrid::render(self, fmt)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fill_and_count() {
+		let mut grid =
+			BitGrid2D::<i16>::new(Point2D::new(-2, -1), Point2D::new(7, 3));
+		assert_eq!((grid.width(), grid.height()), (10, 5));
+		assert!(grid.is_empty());
+
+		grid.fill_rect(Point2D::new(0, 0), Point2D::new(3, 1), true);
+		assert_eq!(grid.len(), 8);
+		grid.toggle_rect(Point2D::new(2, 1), Point2D::new(20, 1));
+		assert_eq!(grid.count_row(1, -5 ..= 100), 2 + 4);
+		assert_eq!(grid.len(), 2 + 2 + 4 + 2);
+
+		grid.fill_row(-1, -100 ..= 100, true);
+		assert_eq!(grid.count_row(-1, 0 ..= 0), 1);
+		assert_eq!(
+			grid.count_rect(Point2D::new(-2, -1), Point2D::new(7, -1)),
+			10
+		);
+		grid.fill_row(9, 0 ..= 3, true);
+		assert_eq!(grid.count_row(1, i16::MIN ..= i16::MAX), 2 + 4);
+		let (lo, hi) = (5, 1);
+		grid.fill_row(2, lo ..= hi, true);
+		assert_eq!(grid.len(), 20);
+
+		assert!(grid.insert(Point2D::new(7, 3)));
+		assert!(!grid.insert(Point2D::new(7, 3)));
+		assert!(grid[Point2D::new(7, 3)]);
+		assert!(grid.remove(Point2D::new(7, 3)));
+		assert!(!grid.remove(Point2D::new(70, 3)));
+		assert!(!grid.toggle(Point2D::new(0, 0)));
+		assert_eq!(grid.get(Point2D::new(0, 0)), Some(false));
+		assert_eq!(grid.get(Point2D::new(-3, 0)), None);
+		assert_eq!(grid.iter().next(), Some(Point2D::new(-2, -1)));
+		assert_eq!(grid.iter().last(), Some(Point2D::new(7, 1)));
+	}
+
+	#[test]
+	fn matches_sparse() {
+		let points = [(0, 0), (1, 1), (0, 3), (3, 0)].map(Point2D::<i8>::from);
+		let sparse = points
+			.iter()
+			.map(|&pt| (pt, ()))
+			.collect::<Sparse2D<i8, ()>>();
+		let bits = BitGrid2D::from(&sparse);
+		assert_eq!(bits.len(), 4);
+		assert_eq!(bits.dimensions(), sparse.dimensions());
+		assert_eq!(bits.to_string(), sparse.to_string());
+		assert_eq!(bits.iter().collect::<Vec<_>>(), [
+			points[0], points[3], points[1], points[2]
+		]);
+	}
+}
@@ -8,6 +8,8 @@ switch to the sparse structure.
 */
 
 use std::{
+	cmp::Reverse,
+	collections::BTreeMap,
 	fmt,
 	iter::{
 		self,
@@ -16,6 +18,7 @@ use std::{
 	ops::{
 		Index,
 		IndexMut,
+		Neg,
 	},
 };
 
@@ -26,6 +29,13 @@ use super::{
 	Point2D,
 	Sparse2D,
 };
+use crate::coords::{
+	points::Direction2D,
+	units::{
+		Col,
+		Row,
+	},
+};
 
 /// A 2-dimensional Cartesian grid where all cells within the bounds are filled
 /// with some value.
@@ -59,6 +69,11 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 		&self.table
 	}
 
+	/// Unwraps the grid into its rows, dropping its origin.
+	pub fn into_raw(self) -> Vec<Vec<T>> {
+		self.table
+	}
+
 	pub fn clear(&mut self) {
 		*self = Self::new();
 	}
@@ -100,11 +115,46 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 	}
 
 	/// Gets an entire row from the grid.
-	pub fn get_row(&self, row: I) -> Option<&[T]> {
-		let r_abs = row - self.origin.y;
+	pub fn get_row(&self, row: impl Into<Row<I>>) -> Option<&[T]> {
+		let r_abs = row.into().get() - self.origin.y;
 		self.table.get(r_abs.as_usize()).map(Vec::as_slice)
 	}
 
+	/// Marches outward from a point in a straight line, yielding each tile
+	/// passed until the ray leaves the grid.
+	///
+	/// The starting point itself is not yielded.
+	pub fn ray(
+		&self,
+		from: Point2D<I>,
+		direction: Direction2D,
+	) -> impl '_ + Iterator<Item = (Point2D<I>, &T)>
+	where
+		I: Neg<Output = I>,
+	{
+		self.ray_stride(from, direction, I::ONE)
+	}
+
+	/// Like [`.ray()`](Self::ray), but advances `stride` tiles per step rather
+	/// than one.
+	///
+	/// A stride of zero never leaves the starting point, so it yields nothing.
+	/// A ray which would step past the edge of `I` ends there.
+	pub fn ray_stride(
+		&self,
+		from: Point2D<I>,
+		direction: Direction2D,
+		stride: I,
+	) -> impl '_ + Iterator<Item = (Point2D<I>, &T)>
+	where
+		I: Neg<Output = I>,
+	{
+		let step = direction.unit::<I>() * stride;
+		let first = from.checked_add(step).filter(|_| stride != I::ZERO);
+		iter::successors(first, move |&pt| pt.checked_add(step))
+			.map_while(move |pt| self.get(pt).map(|val| (pt, val)))
+	}
+
 	/// Iterates through each tile in the grid, in row-major order.
 	pub fn iter(
 		&self,
@@ -165,6 +215,126 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 	}
 }
 
+impl<I: Signed, T> Cartesian2D<I, T>
+where <I as TryFrom<isize>>::Error: fmt::Debug
+{
+	/// Iterates through the tiles of one row, with their coordinates.
+	///
+	/// Rows outside the grid are empty.
+	pub fn iter_row(
+		&self,
+		row: impl Into<Row<I>>,
+	) -> impl '_ + Iterator<Item = (Point2D<I>, &T)> {
+		let row = row.into().get();
+		let xs = self.table.first().map(Vec::len).unwrap_or_default();
+		self.get_row(row)
+			.into_iter()
+			.flat_map(move |cells| {
+				cells.iter().zip(Axis::new(self.origin.x, xs))
+			})
+			.map(move |(val, x)| (Point2D::new(x, row), val))
+	}
+
+	/// Iterates through the tiles of one column, with their coordinates.
+	///
+	/// Columns outside the grid are empty.
+	pub fn iter_column(
+		&self,
+		col: impl Into<Col<I>>,
+	) -> impl '_ + Iterator<Item = (Point2D<I>, &T)> {
+		let col = col.into().get();
+		let c_abs = (col - self.origin.x).as_usize();
+		self.table
+			.iter()
+			.zip(Axis::new(self.origin.y, self.table.len()))
+			.filter_map(move |(row, y)| {
+				row.get(c_abs).map(|val| (Point2D::new(col, y), val))
+			})
+	}
+}
+
+/// Statistics over grids of numbers, such as heightmaps and risk levels.
+///
+/// Where several tiles tie for a minimum or maximum, the first one in
+/// row-major order wins.
+impl<I: Signed, T: Ord + Copy> Cartesian2D<I, T>
+where <I as TryFrom<isize>>::Error: fmt::Debug
+{
+	/// Finds the smallest tile in the grid.
+	pub fn argmin(&self) -> Option<(Point2D<I>, T)> {
+		Self::least(self.iter())
+	}
+
+	/// Finds the largest tile in the grid.
+	pub fn argmax(&self) -> Option<(Point2D<I>, T)> {
+		Self::greatest(self.iter())
+	}
+
+	/// Finds the smallest tile in a row.
+	pub fn row_min(&self, row: impl Into<Row<I>>) -> Option<(Point2D<I>, T)> {
+		Self::least(self.iter_row(row))
+	}
+
+	/// Finds the largest tile in a row.
+	pub fn row_max(&self, row: impl Into<Row<I>>) -> Option<(Point2D<I>, T)> {
+		Self::greatest(self.iter_row(row))
+	}
+
+	/// Finds the smallest tile in a column.
+	pub fn column_min(&self, col: impl Into<Col<I>>) -> Option<(Point2D<I>, T)> {
+		Self::least(self.iter_column(col))
+	}
+
+	/// Finds the largest tile in a column.
+	pub fn column_max(&self, col: impl Into<Col<I>>) -> Option<(Point2D<I>, T)> {
+		Self::greatest(self.iter_column(col))
+	}
+
+	/// Adds up a row, producing `None` if it is outside the grid.
+	pub fn row_sum(&self, row: impl Into<Row<I>>) -> Option<T>
+	where T: iter::Sum {
+		self.get_row(row).map(|cells| cells.iter().copied().sum())
+	}
+
+	/// Adds up a column, producing `None` if it is outside the grid.
+	pub fn column_sum(&self, col: impl Into<Col<I>>) -> Option<T>
+	where T: iter::Sum {
+		let mut cells = self.iter_column(col).map(|(_, &val)| val).peekable();
+		cells.peek()?;
+		Some(cells.sum())
+	}
+
+	/// Counts how many tiles hold each value.
+	pub fn histogram(&self) -> BTreeMap<T, usize> {
+		let mut counts = BTreeMap::new();
+		for row in &self.table {
+			for &val in row {
+				*counts.entry(val).or_default() += 1;
+			}
+		}
+		counts
+	}
+
+	fn least<'a>(
+		cells: impl Iterator<Item = (Point2D<I>, &'a T)>,
+	) -> Option<(Point2D<I>, T)>
+	where T: 'a {
+		cells
+			.map(|(pt, &val)| (pt, val))
+			.min_by_key(|&(_, val)| val)
+	}
+
+	fn greatest<'a>(
+		cells: impl Iterator<Item = (Point2D<I>, &'a T)>,
+	) -> Option<(Point2D<I>, T)>
+	where T: 'a {
+		// `max_by_key` keeps the *last* of several equal maxima.
+		cells
+			.map(|(pt, &val)| (pt, val))
+			.min_by_key(|&(_, val)| Reverse(val))
+	}
+}
+
 impl<I: Signed, T: Default> From<Sparse2D<I, T>> for Cartesian2D<I, T> {
 	fn from(sparse: Sparse2D<I, T>) -> Self {
 		let Some((origin, extent)) = sparse.dimensions()
@@ -302,3 +472,54 @@ impl<I: Signed> ExactSizeIterator for Axis<I> {
 
 impl<I: Signed> FusedIterator for Axis<I> {
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn grid_statistics() {
+		let grid = Cartesian2D::<i16, u8>::from_raw(Point2D::new(-1, 10), vec![
+			vec![2, 1, 9],
+			vec![3, 9, 1],
+		]);
+		assert_eq!(grid.argmin(), Some((Point2D::new(0, 10), 1)));
+		assert_eq!(grid.argmax(), Some((Point2D::new(1, 10), 9)));
+		assert_eq!(grid.row_max(11), Some((Point2D::new(0, 11), 9)));
+		assert_eq!(grid.column_min(-1), Some((Point2D::new(-1, 10), 2)));
+		assert_eq!(grid.column_max(1), Some((Point2D::new(1, 10), 9)));
+		assert_eq!(grid.row_sum(10), Some(12));
+		assert_eq!(grid.column_sum(0), Some(10));
+		assert_eq!(grid.column_sum(2), None);
+		assert_eq!(grid.row_min(12), None);
+		assert_eq!(grid.histogram()[&9], 2);
+		assert_eq!(grid.histogram().len(), 4);
+	}
+
+	#[test]
+	fn rays() {
+		let grid = Cartesian2D::<i16, u8>::from_raw(Point2D::new(0, 0), vec![
+			vec![0, 1, 2, 3, 4],
+		]);
+		let origin = Point2D::new(0, 0);
+		let ray = |stride: i16| {
+			grid.ray_stride(origin, Direction2D::East, stride)
+				.map(|(_, &val)| val)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(ray(1), [1, 2, 3, 4]);
+		assert_eq!(ray(2), [2, 4]);
+		assert!(ray(-1).is_empty());
+		assert!(ray(0).is_empty());
+		assert_eq!(grid.ray(origin, Direction2D::South).count(), 0);
+
+		let edge = Cartesian2D::<i8, u8>::from_raw(Point2D::new(125, 0), vec![
+			vec![0, 1, 2],
+		]);
+		let vals = edge
+			.ray_stride(Point2D::new(125, 0), Direction2D::East, 2)
+			.map(|(_, &val)| val)
+			.collect::<Vec<_>>();
+		assert_eq!(vals, [2]);
+	}
+}
@@ -0,0 +1,364 @@
+/*! Storing dense grids as text
+
+Simulation puzzles often run over grids hundreds of cells on a side, and a
+snapshot test which compares against the full rendering needs a fixture of
+hundreds of kilobytes, nearly all of it runs of the same character. This module
+stores grids in two compact, widely understood text formats instead:
+
+- the run-length encoding used by Conway's Life tools, which
+  [`Cartesian2D::to_rle_string`] writes and [`Cartesian2D::from_rle_string`]
+  reads back, and
+- the plain Netpbm bitmap (`P1`) and greymap (`P2`) formats, which most image
+  viewers and converters accept.
+
+Neither format records the grid's origin: grids read back from RLE start at
+zero, and callers who need otherwise can move them with
+[`Cartesian2D::set_origin`]. Sparse grids can be stored by first converting
+them into dense ones.
+*/
+
+use std::fmt::Write as _;
+
+use funty::Signed;
+
+use super::{
+	dense::Cartesian2D,
+	Point2D,
+};
+
+/// The widest line written in RLE text, as recommended by the format.
+const RLE_WIDTH: usize = 70;
+
+/// The most cells that RLE text may describe. Blank rows and row ends cost no
+/// text, so a short header could otherwise ask for any amount of memory.
+const RLE_MAX_CELLS: usize = 1 << 28;
+
+/// A value which can be stored as one character of RLE text.
+pub trait RleCell: Sized + Copy + PartialEq {
+	/// The value of cells omitted from the end of a row.
+	const BLANK: Self;
+
+	/// Produces the character which stands for this value.
+	fn to_tag(self) -> char;
+
+	/// Reads a character back into a value.
+	fn from_tag(tag: char) -> Option<Self>;
+}
+
+/// Live cells are `o` and dead cells `b`, as in Conway's Life.
+impl RleCell for bool {
+	const BLANK: Self = false;
+
+	fn to_tag(self) -> char {
+		if self {
+			'o'
+		}
+		else {
+			'b'
+		}
+	}
+
+	fn from_tag(tag: char) -> Option<Self> {
+		match tag {
+			'o' => Some(true),
+			'b' => Some(false),
+			_ => None,
+		}
+	}
+}
+
+/// Characters store themselves, except for digits, whitespace, and the `$`
+/// and `!` markers, which RLE reserves. Blank cells are `.`, as in most
+/// puzzle maps.
+impl RleCell for char {
+	const BLANK: Self = '.';
+
+	fn to_tag(self) -> char {
+		self
+	}
+
+	fn from_tag(tag: char) -> Option<Self> {
+		(!tag.is_ascii_digit() && !tag.is_whitespace() && !"$!".contains(tag))
+			.then_some(tag)
+	}
+}
+
+impl<I: Signed, T: RleCell> Cartesian2D<I, T> {
+	/// Writes the grid in run-length encoded text.
+	///
+	/// The first line is a header, `x = {width}, y = {height}`. Then each row
+	/// is written as runs of `{count}{tag}`, with the count omitted when it is
+	/// one, and rows are ended by `$`. Blank cells at the end of a row, and
+	/// blank rows at the end of the grid, are omitted. The grid is finished by
+	/// `!`, and lines are wrapped at 70 columns.
+	///
+	/// # Panics
+	///
+	/// This panics if a cell's tag cannot be read back by
+	/// [`RleCell::from_tag`].
+	pub fn to_rle_string(&self) -> String {
+		let table = self.raw_data();
+		let width = table.first().map(Vec::len).unwrap_or_default();
+		let mut runs = Vec::<(usize, char)>::new();
+		let mut push = |count: usize, tag: char| match runs.last_mut() {
+			Some((prev, last)) if *last == tag => *prev += count,
+			_ => runs.push((count, tag)),
+		};
+		for row in table {
+			let used = row
+				.iter()
+				.rposition(|&cell| cell != T::BLANK)
+				.map_or(0, |last| last + 1);
+			for &cell in &row[.. used] {
+				let tag = cell.to_tag();
+				assert!(
+					T::from_tag(tag) == Some(cell),
+					"`{tag}` cannot be stored in RLE text"
+				);
+				push(1, tag);
+			}
+			push(1, '$');
+		}
+		// The last row is ended by `!` rather than `$`, and blank rows after
+		// it need no markers at all.
+		if let Some((_, '$')) = runs.last() {
+			runs.pop();
+		}
+		runs.push((1, '!'));
+
+		let mut out = format!("x = {width}, y = {}\n", table.len());
+		let mut line = String::new();
+		for (count, tag) in runs {
+			let mut run = String::new();
+			if count > 1 {
+				write!(run, "{count}").ok();
+			}
+			run.push(tag);
+			if line.len() + run.len() > RLE_WIDTH {
+				out.push_str(&line);
+				out.push('\n');
+				line.clear();
+			}
+			line.push_str(&run);
+		}
+		out.push_str(&line);
+		out.push('\n');
+		out
+	}
+
+	/// Reads a grid from run-length encoded text, as produced by
+	/// [`.to_rle_string()`](Self::to_rle_string).
+	///
+	/// Lines beginning with `#` before the header are comments, and any
+	/// fields in the header other than `x` and `y` (such as Life's `rule`) are
+	/// ignored. Omitted cells are filled with [`RleCell::BLANK`]. The grid's
+	/// origin is zero.
+	pub fn from_rle_string(text: &str) -> eyre::Result<Self> {
+		let mut lines = text
+			.lines()
+			.map(str::trim)
+			.skip_while(|line| line.is_empty() || line.starts_with('#'));
+		let header = lines
+			.next()
+			.ok_or_else(|| eyre::eyre!("RLE text has no header"))?;
+		let (mut width, mut height) = (None, None);
+		for field in header.split(',') {
+			let Some((key, value)) = field.split_once('=')
+			else {
+				eyre::bail!("malformed RLE header field `{field}`");
+			};
+			match key.trim() {
+				"x" => width = Some(value.trim().parse::<usize>()?),
+				"y" => height = Some(value.trim().parse::<usize>()?),
+				_ => {},
+			}
+		}
+		let (Some(width), Some(height)) = (width, height)
+		else {
+			eyre::bail!("RLE header `{header}` must set both `x` and `y`");
+		};
+		eyre::ensure!(
+			width
+				.checked_mul(height)
+				.is_some_and(|cells| cells <= RLE_MAX_CELLS),
+			"RLE header `{header}` describes a grid larger than \
+			 {RLE_MAX_CELLS} cells"
+		);
+		// The header is not trusted to size buffers: a row can hold no more
+		// cells than the text has characters to describe them.
+		let capacity = width.min(text.len());
+
+		let mut table = vec![Vec::with_capacity(capacity)];
+		let mut count = None::<usize>;
+		let mut finished = false;
+		for ch in lines.flat_map(str::chars).filter(|c| !c.is_whitespace()) {
+			if let Some(digit) = ch.to_digit(10) {
+				count = count
+					.unwrap_or(0)
+					.checked_mul(10)
+					.and_then(|count| count.checked_add(digit as usize))
+					.map(Some)
+					.ok_or_else(|| eyre::eyre!("RLE run count is too large"))?;
+				continue;
+			}
+			let run = count.take().unwrap_or(1);
+			match ch {
+				'!' => {
+					finished = true;
+					break;
+				},
+				'$' => {
+					eyre::ensure!(
+						table
+							.len()
+							.checked_add(run)
+							.is_some_and(|rows| rows <= height),
+						"RLE text has more rows than the {height} its header \
+						 says"
+					);
+					table
+						.extend((0 .. run).map(|_| Vec::with_capacity(capacity)))
+				},
+				tag => {
+					let cell = T::from_tag(tag).ok_or_else(|| {
+						eyre::eyre!("`{tag}` is not a valid RLE cell")
+					})?;
+					let idx = table.len() - 1;
+					let row = table.last_mut().expect("there is always a row");
+					eyre::ensure!(
+						run <= width - row.len(),
+						"RLE row {idx} has more cells than the {width} its \
+						 header says"
+					);
+					row.extend((0 .. run).map(|_| cell));
+				},
+			}
+		}
+		eyre::ensure!(finished, "RLE text is not terminated by `!`");
+		eyre::ensure!(
+			table.len() <= height,
+			"RLE text has {} rows, but its header says {height}",
+			table.len()
+		);
+		table.resize_with(height, Vec::new);
+		for row in &mut table {
+			row.resize(width, T::BLANK);
+		}
+		if width == 0 {
+			table.clear();
+		}
+		Ok(Self::from_raw(Point2D::ZERO, table))
+	}
+}
+
+impl<I: Signed> Cartesian2D<I, bool> {
+	/// Writes the grid as a plain PBM bitmap, in which set cells are black.
+	pub fn to_pbm(&self) -> String {
+		let table = self.raw_data();
+		let width = table.first().map(Vec::len).unwrap_or_default();
+		let mut out = format!("P1\n{width} {}\n", table.len());
+		for row in table {
+			let bits = row
+				.iter()
+				.map(|&cell| u8::from(cell).to_string())
+				.collect::<Vec<_>>();
+			out.push_str(&bits.join(" "));
+			out.push('\n');
+		}
+		out
+	}
+}
+
+impl<I: Signed, T: Copy + Into<u16>> Cartesian2D<I, T> {
+	/// Writes the grid as a plain PGM greymap, in which larger values are
+	/// brighter.
+	///
+	/// The brightest grey is the largest value in the grid, so that a map of
+	/// heights from zero to nine uses the whole range.
+	pub fn to_pgm(&self) -> String {
+		let table = self.raw_data();
+		let width = table.first().map(Vec::len).unwrap_or_default();
+		let max = table
+			.iter()
+			.flatten()
+			.map(|&cell| cell.into())
+			.max()
+			.unwrap_or_default()
+			.max(1);
+		let mut out = format!("P2\n{width} {}\n{max}\n", table.len());
+		for row in table {
+			let greys = row
+				.iter()
+				.map(|&cell| cell.into().to_string())
+				.collect::<Vec<_>>();
+			out.push_str(&greys.join(" "));
+			out.push('\n');
+		}
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rle_round_trip() -> eyre::Result<()> {
+		let glider = [".o...", "..o..", "ooo..", ".....", "....."]
+			.iter()
+			.map(|row| row.chars().map(|c| c == 'o').collect())
+			.collect::<Vec<Vec<bool>>>();
+		let grid = Cartesian2D::<i32, bool>::from_raw(Point2D::ZERO, glider);
+		let rle = grid.to_rle_string();
+		assert_eq!(rle, "x = 5, y = 5\nbo$2bo$3o!\n");
+		assert_eq!(Cartesian2D::<i32, bool>::from_rle_string(&rle)?, grid);
+
+		let life = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!";
+		let read = Cartesian2D::<i32, bool>::from_rle_string(life)?;
+		assert_eq!(read.get(Point2D::new(1, 0)), Some(&true));
+		assert_eq!(read.get(Point2D::new(2, 2)), Some(&true));
+		assert_eq!(read.get(Point2D::new(0, 1)), Some(&false));
+
+		let map = ["#..#", "....", "..~~"]
+			.iter()
+			.map(|row| row.chars().collect())
+			.collect::<Vec<Vec<char>>>();
+		let map = Cartesian2D::<i32, char>::from_raw(Point2D::ZERO, map);
+		let rle = map.to_rle_string();
+		assert_eq!(rle, "x = 4, y = 3\n#2.#2$2.2~!\n");
+		assert_eq!(Cartesian2D::<i32, char>::from_rle_string(&rle)?, map);
+
+		assert!(
+			Cartesian2D::<i32, bool>::from_rle_string("x = 1, y = 1\n2o!")
+				.is_err()
+		);
+		assert!(Cartesian2D::<i32, bool>::from_rle_string("x = 1, y = 1\no")
+			.is_err());
+		assert!(Cartesian2D::<i32, bool>::from_rle_string(
+			"x = 1, y = 1\n99999999999999999999999o!"
+		)
+		.is_err());
+		assert!(Cartesian2D::<i32, bool>::from_rle_string(
+			"x = 1, y = 2\n99999999999$o!"
+		)
+		.is_err());
+		assert!(Cartesian2D::<i32, bool>::from_rle_string(
+			"x = 100000000000, y = 100000000000\n!"
+		)
+		.is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn netpbm_export() {
+		let grid = Cartesian2D::<i32, bool>::from_raw(Point2D::ZERO, vec![
+			vec![true, false],
+			vec![false, true],
+		]);
+		assert_eq!(grid.to_pbm(), "P1\n2 2\n1 0\n0 1\n");
+
+		let heights =
+			Cartesian2D::<i32, u8>::from_raw(Point2D::ZERO, vec![vec![0, 5, 9]]);
+		assert_eq!(heights.to_pgm(), "P2\n3 1\n9\n0 5 9\n");
+	}
+}
@@ -0,0 +1,373 @@
+/*! Small rectangular matrices of cells
+
+Some puzzles hand over blocks of characters and ask about their shape rather
+than about any coördinate in them: where a block mirrors itself, what it looks
+like turned on its side, how it settles when tilted. These want rows and
+columns as whole lines, which can be compared against each other, more than
+they want points. [`Matrix`] keeps its cells in one row-major buffer, indexed
+by `(row, column)` from zero, so that a row is a slice and a column is a
+strided walk over the same buffer; neither copies anything.
+
+A matrix converts to and from a [`Dense2D`] grid, for puzzles which need both
+views of the same block.
+*/
+
+use std::{
+	fmt,
+	iter::{
+		FusedIterator,
+		StepBy,
+	},
+	ops::{
+		Index,
+		IndexMut,
+	},
+	slice,
+};
+
+use funty::Signed;
+
+use super::{
+	Dense2D,
+	Point2D,
+};
+
+/// A rectangle of cells, stored row by row.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix<T> {
+	rows:  usize,
+	cols:  usize,
+	cells: Vec<T>,
+}
+
+/// A view of one column of a [`Matrix`], from top to bottom.
+pub type Column<'a, T> = StepBy<slice::Iter<'a, T>>;
+
+impl<T> Matrix<T> {
+	/// Builds a matrix by calling `cell` with each `(row, column)` pair, in
+	/// row-major order.
+	pub fn from_fn(
+		rows: usize,
+		cols: usize,
+		mut cell: impl FnMut(usize, usize) -> T,
+	) -> Self {
+		let cells = (0 .. rows)
+			.flat_map(|row| (0 .. cols).map(move |col| (row, col)))
+			.map(|(row, col)| cell(row, col))
+			.collect();
+		Self { rows, cols, cells }
+	}
+
+	/// Builds a matrix from its rows.
+	///
+	/// Every row must be as long as the first.
+	pub fn from_rows<R>(
+		rows: impl IntoIterator<Item = R>,
+	) -> eyre::Result<Self>
+	where R: IntoIterator<Item = T> {
+		let mut this = Self {
+			rows:  0,
+			cols:  0,
+			cells: Vec::new(),
+		};
+		for row in rows {
+			let start = this.cells.len();
+			this.cells.extend(row);
+			let len = this.cells.len() - start;
+			if this.rows == 0 {
+				this.cols = len;
+			}
+			else if len != this.cols {
+				eyre::bail!(
+					"row {} has {len} cells, but the first row has {}",
+					this.rows,
+					this.cols
+				);
+			}
+			this.rows += 1;
+		}
+		Ok(this)
+	}
+
+	/// Counts the rows.
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	/// Counts the columns.
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	/// Tests whether the matrix has no cells.
+	pub fn is_empty(&self) -> bool {
+		self.cells.is_empty()
+	}
+
+	/// Gets a cell, if it is in the matrix.
+	pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+		self.offset(row, col).map(|idx| &self.cells[idx])
+	}
+
+	/// Mutably gets a cell, if it is in the matrix.
+	pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+		self.offset(row, col).map(|idx| &mut self.cells[idx])
+	}
+
+	/// Views one row.
+	///
+	/// # Panics
+	///
+	/// If the row is not in the matrix.
+	pub fn row(&self, row: usize) -> &[T] {
+		assert!(row < self.rows, "row {row} is not in the matrix");
+		&self.cells[row * self.cols ..][.. self.cols]
+	}
+
+	/// Mutably views one row.
+	///
+	/// # Panics
+	///
+	/// If the row is not in the matrix.
+	pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+		assert!(row < self.rows, "row {row} is not in the matrix");
+		&mut self.cells[row * self.cols ..][.. self.cols]
+	}
+
+	/// Views one column, from top to bottom.
+	///
+	/// # Panics
+	///
+	/// If the column is not in the matrix.
+	pub fn column(&self, col: usize) -> Column<'_, T> {
+		assert!(col < self.cols, "column {col} is not in the matrix");
+		self.cells[col ..].iter().step_by(self.cols)
+	}
+
+	/// Views each row, from top to bottom.
+	pub fn iter_rows(
+		&self,
+	) -> impl '_ + DoubleEndedIterator<Item = &[T]> + FusedIterator {
+		(0 .. self.rows).map(|row| self.row(row))
+	}
+
+	/// Views each column, from left to right.
+	pub fn iter_columns(
+		&self,
+	) -> impl '_ + DoubleEndedIterator<Item = Column<'_, T>> + FusedIterator {
+		(0 .. self.cols).map(|col| self.column(col))
+	}
+
+	/// Iterates through every cell, with its row and column, in row-major
+	/// order.
+	pub fn iter(&self) -> impl '_ + Iterator<Item = ((usize, usize), &T)> {
+		let cols = self.cols;
+		self.cells
+			.iter()
+			.enumerate()
+			.map(move |(idx, cell)| ((idx / cols, idx % cols), cell))
+	}
+
+	/// Finds the horizontal lines across which the matrix is its own mirror
+	/// image, allowing for exactly `smudges` cells which differ from their
+	/// reflections.
+	///
+	/// Each line is given as the number of rows above it, and lines are found
+	/// from top to bottom. Rows with no reflection, because the line is nearer
+	/// the other edge, are not compared.
+	pub fn mirror_rows(
+		&self,
+		smudges: usize,
+	) -> impl '_ + Iterator<Item = usize>
+	where
+		T: PartialEq,
+	{
+		mirrors(self.rows, smudges, |row| self.row(row).iter())
+	}
+
+	/// Finds the vertical lines across which the matrix is its own mirror
+	/// image, allowing for exactly `smudges` cells which differ from their
+	/// reflections.
+	///
+	/// Each line is given as the number of columns to its left, and lines are
+	/// found from left to right.
+	pub fn mirror_columns(
+		&self,
+		smudges: usize,
+	) -> impl '_ + Iterator<Item = usize>
+	where
+		T: PartialEq,
+	{
+		mirrors(self.cols, smudges, |col| self.column(col))
+	}
+
+	/// Converts the matrix into a dense grid, with its top-left cell at
+	/// `origin`.
+	pub fn into_dense<I: Signed>(self, origin: Point2D<I>) -> Dense2D<I, T> {
+		let mut cells = self.cells.into_iter();
+		let table = (0 .. self.rows)
+			.map(|_| cells.by_ref().take(self.cols).collect())
+			.collect();
+		Dense2D::from_raw(origin, table)
+	}
+
+	fn offset(&self, row: usize, col: usize) -> Option<usize> {
+		(row < self.rows && col < self.cols).then(|| row * self.cols + col)
+	}
+}
+
+impl<T: Clone> Matrix<T> {
+	/// Builds a matrix with every cell set to `fill`.
+	pub fn new(rows: usize, cols: usize, fill: T) -> Self {
+		Self {
+			rows,
+			cols,
+			cells: vec![fill; rows * cols],
+		}
+	}
+
+	/// Flips the matrix across its main diagonal, so that its rows become its
+	/// columns.
+	pub fn transpose(&self) -> Self {
+		Self::from_fn(self.cols, self.rows, |row, col| self[(col, row)].clone())
+	}
+
+	/// Turns the matrix a quarter-turn clockwise, so that its left column
+	/// becomes its top row.
+	pub fn rotate_clockwise(&self) -> Self {
+		Self::from_fn(self.cols, self.rows, |row, col| {
+			self[(self.rows - 1 - col, row)].clone()
+		})
+	}
+
+	/// Turns the matrix a quarter-turn counter-clockwise, so that its top row
+	/// becomes its left column.
+	pub fn rotate_counterclockwise(&self) -> Self {
+		Self::from_fn(self.cols, self.rows, |row, col| {
+			self[(col, self.cols - 1 - row)].clone()
+		})
+	}
+}
+
+/// Finds the lines of symmetry among `count` lines, each of which is walked
+/// by `line`.
+fn mirrors<'a, T, L>(
+	count: usize,
+	smudges: usize,
+	line: impl 'a + Fn(usize) -> L,
+) -> impl 'a + Iterator<Item = usize>
+where
+	T: 'a + PartialEq,
+	L: Iterator<Item = &'a T>,
+{
+	(1 .. count).filter(move |&split| {
+		let mut differ = 0;
+		// Pair off the lines outwards from the split, until either edge.
+		for (before, after) in (0 .. split).rev().zip(split .. count) {
+			differ += line(before)
+				.zip(line(after))
+				.filter(|(a, b)| a != b)
+				.count();
+			if differ > smudges {
+				return false;
+			}
+		}
+		differ == smudges
+	})
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+	type Output = T;
+
+	fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+		self.get(row, col).unwrap_or_else(|| {
+			panic!(
+				"({row}, {col}) is not in a {}×{} matrix",
+				self.rows, self.cols
+			)
+		})
+	}
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+	fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+		let (rows, cols) = (self.rows, self.cols);
+		self.get_mut(row, col).unwrap_or_else(|| {
+			panic!("({row}, {col}) is not in a {rows}×{cols} matrix")
+		})
+	}
+}
+
+/// The grid's origin is dropped; the matrix counts from its top-left cell.
+impl<I: Signed, T> From<Dense2D<I, T>> for Matrix<T> {
+	fn from(grid: Dense2D<I, T>) -> Self {
+		Self::from_rows(grid.into_raw()).expect("dense grids are never jagged")
+	}
+}
+
+impl<I: Signed, T> From<Matrix<T>> for Dense2D<I, T> {
+	fn from(matrix: Matrix<T>) -> Self {
+		matrix.into_dense(Point2D::new(I::ZERO, I::ZERO))
+	}
+}
+
+/// Writes each row on its own line, with no separators between cells.
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for (idx, row) in self.iter_rows().enumerate() {
+			if idx > 0 {
+				writeln!(fmt)?;
+			}
+			for cell in row {
+				fmt::Display::fmt(cell, fmt)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn chars(text: &str) -> Matrix<char> {
+		Matrix::from_rows(text.lines().map(str::chars)).unwrap()
+	}
+
+	#[test]
+	fn transforms_and_mirrors() {
+		let abc = chars("abc\ndef");
+		assert_eq!((abc.rows(), abc.cols()), (2, 3));
+		assert_eq!(abc.row(1), ['d', 'e', 'f']);
+		assert!(abc.column(2).eq(&['c', 'f']));
+		assert_eq!(abc.get(2, 0), None);
+		assert_eq!(abc.transpose().to_string(), "ad\nbe\ncf");
+		assert_eq!(abc.rotate_clockwise().to_string(), "da\neb\nfc");
+		assert_eq!(abc.rotate_counterclockwise().to_string(), "cf\nbe\nad");
+		assert_eq!(abc.rotate_clockwise().rotate_counterclockwise(), abc);
+		assert!(Matrix::from_rows(["ab".chars(), "c".chars()]).is_err());
+
+		let grid = Dense2D::<i8, char>::from(abc.clone());
+		assert_eq!(grid.get(Point2D::new(2, 1)), Some(&'f'));
+		assert_eq!(Matrix::from(grid), abc);
+
+		// The worked example of 2023's mirrors.
+		let pattern = Matrix::from_rows(
+			[
+				"#.##..##.",
+				"..#.##.#.",
+				"##......#",
+				"##......#",
+				"..#.##.#.",
+				"..##..##.",
+				"#.#.##.#.",
+			]
+			.map(str::chars),
+		)
+		.unwrap();
+		assert!(pattern.mirror_columns(0).eq([5]));
+		assert_eq!(pattern.mirror_rows(0).next(), None);
+		assert!(pattern.mirror_rows(1).eq([3]));
+	}
+}
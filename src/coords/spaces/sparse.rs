@@ -9,16 +9,28 @@ use std::{
 	fmt::{
 		self,
 	},
-	iter::FusedIterator,
-	ops::RangeInclusive,
+	iter::{
+		self,
+		FusedIterator,
+	},
+	ops::{
+		Index,
+		IndexMut,
+		Neg,
+		RangeInclusive,
+	},
 };
 
 use funty::Signed;
 use tap::Pipe;
 
-use crate::coords::{
-	Cartesian2DPoint,
-	Cartesian3DPoint,
+use crate::{
+	coords::{
+		points::Direction2D,
+		Cartesian2DPoint,
+		Cartesian3DPoint,
+	},
+	search,
 };
 
 /// A 2-dimensional planar grid, sparsely populated.
@@ -141,12 +153,7 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 	) where
 		T: Default,
 	{
-		self.rows
-			.entry(point.y)
-			.or_default()
-			.entry(point.x)
-			.or_default()
-			.pipe(func);
+		self.get_or_default(point).pipe(func);
 	}
 
 	/// Views a value stored at a give point. If the point is not currently
@@ -172,6 +179,55 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 		out
 	}
 
+	/// Views the value stored at a given point, inserting the default value
+	/// first if there is none.
+	pub fn get_or_default(&mut self, point: Cartesian2DPoint<I>) -> &mut T
+	where T: Default {
+		self.get_or_insert_with(point, T::default)
+	}
+
+	/// Gets the given point's entry in the graph for in-place manipulation.
+	///
+	/// This mirrors `BTreeMap::entry`.
+	pub fn entry(&mut self, point: Cartesian2DPoint<I>) -> Entry<'_, I, T> {
+		Entry { space: self, point }
+	}
+
+	/// Rasterizes a set of paths into the graph, storing a copy of `value` at
+	/// every point along them.
+	///
+	/// Each path is a chain of vertices, joined by axis-aligned line segments.
+	/// This is the usual shape of "walls drawn as polylines" puzzle input. A
+	/// path with a single vertex draws just that point. Points which already
+	/// hold a value keep it.
+	pub fn draw_paths<P: IntoIterator<Item = Cartesian2DPoint<I>>>(
+		&mut self,
+		paths: impl IntoIterator<Item = P>,
+		value: T,
+	) -> eyre::Result<()>
+	where
+		T: Clone,
+	{
+		for path in paths {
+			let mut vertices = path.into_iter();
+			let Some(mut prev) = vertices.next()
+			else {
+				continue;
+			};
+			self.insert(prev, value.clone());
+			for next in vertices {
+				let line = prev.line_to(next).ok_or_else(|| {
+					eyre::eyre!("segment {prev} -> {next} is not axis-aligned")
+				})?;
+				for pt in line.skip(1) {
+					self.insert(pt, value.clone());
+				}
+				prev = next;
+			}
+		}
+		Ok(())
+	}
+
 	/// Iterates over all points that have a live value.
 	pub fn iter(
 		&self,
@@ -188,6 +244,31 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 			.flatten()
 	}
 
+	/// Iterates over all points that have a live value, allowing the values to
+	/// be modified.
+	pub fn iter_mut(
+		&mut self,
+	) -> impl DoubleEndedIterator<Item = (Cartesian2DPoint<I>, &mut T)> + FusedIterator
+	{
+		self.rows.iter_mut().flat_map(|(&y, row)| {
+			row.iter_mut()
+				.map(move |(&x, val)| (Cartesian2DPoint::new(x, y), val))
+		})
+	}
+
+	/// Removes every value for which the predicate returns false.
+	///
+	/// The bounding box is not shrunk, as with [`.remove()`](Self::remove).
+	pub fn retain(
+		&mut self,
+		mut keep: impl FnMut(Cartesian2DPoint<I>, &mut T) -> bool,
+	) {
+		for (&y, row) in self.rows.iter_mut() {
+			row.retain(|&x, val| keep(Cartesian2DPoint::new(x, y), val));
+		}
+		self.rows.retain(|_, row| !row.is_empty());
+	}
+
 	/// Consumes the graph, iterating over its values in row-major order from
 	/// origin to the full extent.
 	pub fn into_iter(
@@ -204,6 +285,43 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 			.flatten()
 	}
 
+	/// Marches outward from a point in a straight line, yielding each point
+	/// passed (and its value, if one is stored there) until the ray leaves the
+	/// bounding box of the graph.
+	///
+	/// The starting point itself is not yielded.
+	pub fn ray(
+		&self,
+		from: Cartesian2DPoint<I>,
+		direction: Direction2D,
+	) -> impl '_ + Iterator<Item = (Cartesian2DPoint<I>, Option<&T>)>
+	where
+		I: Neg<Output = I>,
+	{
+		self.ray_stride(from, direction, I::ONE)
+	}
+
+	/// Like [`.ray()`](Self::ray), but advances `stride` cells per step rather
+	/// than one.
+	///
+	/// A stride of zero never leaves the starting point, so it yields nothing.
+	/// A ray which would step past the edge of `I` ends there.
+	pub fn ray_stride(
+		&self,
+		from: Cartesian2DPoint<I>,
+		direction: Direction2D,
+		stride: I,
+	) -> impl '_ + Iterator<Item = (Cartesian2DPoint<I>, Option<&T>)>
+	where
+		I: Neg<Output = I>,
+	{
+		let step = direction.unit::<I>() * stride;
+		let first = from.checked_add(step).filter(|_| stride != I::ZERO);
+		iter::successors(first, move |&pt| pt.checked_add(step))
+			.take_while(|&pt| self.encloses(pt))
+			.map(|pt| (pt, self.get(pt)))
+	}
+
 	/// Yields only the values which are placed in a particular row. They are
 	/// yielded in order of increasing column.
 	pub fn row<'a>(
@@ -242,6 +360,73 @@ impl<I: Signed, T> Cartesian2D<I, T> {
 	}
 }
 
+impl<I: Signed, T> Index<Cartesian2DPoint<I>> for Cartesian2D<I, T> {
+	type Output = T;
+
+	fn index(&self, point: Cartesian2DPoint<I>) -> &Self::Output {
+		self.get(point)
+			.unwrap_or_else(|| panic!("no value stored at {point}"))
+	}
+}
+
+impl<I: Signed, T> IndexMut<Cartesian2DPoint<I>> for Cartesian2D<I, T> {
+	fn index_mut(&mut self, point: Cartesian2DPoint<I>) -> &mut Self::Output {
+		self.get_mut(point)
+			.unwrap_or_else(|| panic!("no value stored at {point}"))
+	}
+}
+
+/// A view into a single point of a sparse 2-D graph, which may or may not hold
+/// a value.
+///
+/// This is constructed by [`Cartesian2D::entry`].
+pub struct Entry<'a, I: Signed, T> {
+	space: &'a mut Cartesian2D<I, T>,
+	point: Cartesian2DPoint<I>,
+}
+
+impl<'a, I: Signed, T> Entry<'a, I, T> {
+	/// Gets the point this entry refers to.
+	pub fn key(&self) -> Cartesian2DPoint<I> {
+		self.point
+	}
+
+	/// Modifies the stored value, if there is one.
+	pub fn and_modify(self, func: impl FnOnce(&mut T)) -> Self {
+		if let Some(value) = self.space.get_mut(self.point) {
+			func(value);
+		}
+		self
+	}
+
+	/// Ensures a value is stored by inserting `value` if the point is empty.
+	pub fn or_insert(self, value: T) -> &'a mut T {
+		self.space.get_or_insert_with(self.point, || value)
+	}
+
+	/// Ensures a value is stored by calling `fill` if the point is empty.
+	pub fn or_insert_with(self, fill: impl FnOnce() -> T) -> &'a mut T {
+		self.space.get_or_insert_with(self.point, fill)
+	}
+
+	/// Ensures a value is stored by calling `fill` with the point if it is
+	/// empty.
+	pub fn or_insert_with_key(
+		self,
+		fill: impl FnOnce(Cartesian2DPoint<I>) -> T,
+	) -> &'a mut T {
+		let point = self.point;
+		self.space.get_or_insert_with(point, || fill(point))
+	}
+
+	/// Ensures a value is stored by inserting the default if the point is
+	/// empty.
+	pub fn or_default(self) -> &'a mut T
+	where T: Default {
+		self.space.get_or_default(self.point)
+	}
+}
+
 impl<I: Signed, T> Default for Cartesian2D<I, T> {
 	fn default() -> Self {
 		Self {
@@ -292,6 +477,16 @@ impl<I: Signed, T> super::DisplayGrid<I, T> for Cartesian2D<I, T> {
 			symbols.empty
 		}
 	}
+
+	fn is_filled(
+		&self,
+		row: I,
+		col: I,
+		_row_abs: usize,
+		_col_abs: usize,
+	) -> bool {
+		self.contains(Cartesian2DPoint::new(col, row))
+	}
 }
 
 /// A 3-dimensional planar grid, sparsely populated.
@@ -315,6 +510,27 @@ impl<I: Signed, T> Cartesian3D<I, T> {
 		self.planes.values().map(Cartesian2D::len).sum::<usize>()
 	}
 
+	/// Checks if the volume is empty.
+	pub fn is_empty(&self) -> bool {
+		self.planes.values().all(Cartesian2D::is_empty)
+	}
+
+	/// If the volume is not empty, returns a pair of points describing the
+	/// bounding box of the values stored in it.
+	///
+	/// Unlike [`.bounds_inclusive()`](Self::bounds_inclusive), this is computed
+	/// from the values currently present, so it shrinks when they are
+	/// removed.
+	pub fn dimensions(
+		&self,
+	) -> Option<(Cartesian3DPoint<I>, Cartesian3DPoint<I>)> {
+		let mut points = self.iter().map(|(pt, _)| pt);
+		let first = points.next()?;
+		Some(points.fold((first, first), |(min, max), pt| {
+			(pt.min_unifying(min), pt.max_unifying(max))
+		}))
+	}
+
 	/// Tests if the graph stores a value at a given point.
 	pub fn contains(&self, point: Cartesian3DPoint<I>) -> bool {
 		let (z, xy) = point.make_2d();
@@ -352,6 +568,12 @@ impl<I: Signed, T> Cartesian3D<I, T> {
 		self.planes.get(&z).and_then(|plane| plane.get(xy))
 	}
 
+	/// Gets a mutable view to a particular value.
+	pub fn get_mut(&mut self, point: Cartesian3DPoint<I>) -> Option<&mut T> {
+		let (z, xy) = point.make_2d();
+		self.planes.get_mut(&z).and_then(|plane| plane.get_mut(xy))
+	}
+
 	/// Inserts a value into the graph at a given point.
 	pub fn insert(&mut self, point: Cartesian3DPoint<I>, value: T) {
 		self.get_or_insert_with(point, || value);
@@ -380,7 +602,75 @@ impl<I: Signed, T> Cartesian3D<I, T> {
 		out
 	}
 
+	/// Views the value stored at a given point, inserting the default value
+	/// first if there is none.
+	pub fn get_or_default(&mut self, point: Cartesian3DPoint<I>) -> &mut T
+	where T: Default {
+		self.get_or_insert_with(point, T::default)
+	}
+
+	/// Removes a value from the volume at a given point.
+	pub fn remove(&mut self, point: Cartesian3DPoint<I>) -> Option<T> {
+		let (z, xy) = point.make_2d();
+		let plane = self.planes.get_mut(&z)?;
+		let out = plane.remove(xy);
+		if plane.is_empty() {
+			self.planes.remove(&z);
+		}
+		out
+	}
+
+	/// Removes every value for which the predicate returns false.
+	///
+	/// The bounding volume is not shrunk, so that searches over it still cover
+	/// the same space.
+	pub fn retain(
+		&mut self,
+		mut keep: impl FnMut(Cartesian3DPoint<I>, &mut T) -> bool,
+	) {
+		for (&z, plane) in self.planes.iter_mut() {
+			plane.retain(|xy, val| keep(xy.make_3d(z), val));
+		}
+		self.planes.retain(|_, plane| !plane.is_empty());
+	}
+
+	/// Rasterizes a set of paths into the volume, storing a copy of `value` at
+	/// every voxel along them.
+	///
+	/// See [`Cartesian2D::draw_paths`].
+	pub fn draw_paths<P: IntoIterator<Item = Cartesian3DPoint<I>>>(
+		&mut self,
+		paths: impl IntoIterator<Item = P>,
+		value: T,
+	) -> eyre::Result<()>
+	where
+		T: Clone,
+	{
+		for path in paths {
+			let mut vertices = path.into_iter();
+			let Some(mut prev) = vertices.next()
+			else {
+				continue;
+			};
+			self.insert(prev, value.clone());
+			for next in vertices {
+				let line = prev.line_to(next).ok_or_else(|| {
+					eyre::eyre!("segment {prev} -> {next} is not axis-aligned")
+				})?;
+				for pt in line.skip(1) {
+					self.insert(pt, value.clone());
+				}
+				prev = next;
+			}
+		}
+		Ok(())
+	}
+
 	/// Performs a breadth-first search across a graph.
+	///
+	/// The search never leaves the bounding volume of the graph. This is a
+	/// thin wrapper over [`crate::search::bfs_visit`]; use that directly if the
+	/// search needs a different visited-set or state type.
 	pub fn search_bfs<CS: IntoIterator<Item = Cartesian3DPoint<I>>>(
 		&self,
 		initial_search: impl FnOnce(&Self) -> CS,
@@ -390,15 +680,72 @@ impl<I: Signed, T> Cartesian3D<I, T> {
 			&mut VecDeque<Cartesian3DPoint<I>>,
 		),
 	) {
-		let mut visited = BTreeSet::new();
-		let mut search_queue = VecDeque::new();
-		search_queue.extend(initial_search(self));
-		while let Some(pt) = search_queue.pop_front() {
-			if !self.encloses(pt) || !visited.insert(pt) {
-				continue;
+		search::bfs_visit(initial_search(self), |pt, queue| {
+			if self.encloses(pt) {
+				searcher(pt, self, queue);
 			}
-			searcher(pt, self, &mut search_queue);
-		}
+		});
+	}
+
+	/// Flood-fills the bounding volume from a set of seed points, moving only
+	/// between face-adjacent points that `open` accepts.
+	///
+	/// `open` is given each candidate point and the value stored there, if
+	/// any. Seeds are included only if they are themselves open. This is the
+	/// usual shape of "find the air outside the droplet" puzzles; pad the
+	/// bounds first if the flood must be able to go around the outside.
+	pub fn flood(
+		&self,
+		seeds: impl IntoIterator<Item = Cartesian3DPoint<I>>,
+		mut open: impl FnMut(Cartesian3DPoint<I>, Option<&T>) -> bool,
+	) -> BTreeSet<Cartesian3DPoint<I>> {
+		let mut open = |pt| self.encloses(pt) && open(pt, self.get(pt));
+		let seeds = seeds.into_iter().filter(|&pt| open(pt)).collect::<Vec<_>>();
+		search::bfs_visit(seeds, |pt, queue| {
+			queue.extend(pt.direct_neighbors().into_iter().filter(|&n| open(n)));
+		})
+	}
+
+	/// Yields the values stored at the six points which share a face with the
+	/// given point.
+	pub fn neighbors(
+		&self,
+		point: Cartesian3DPoint<I>,
+	) -> impl '_ + Iterator<Item = (Cartesian3DPoint<I>, &T)> {
+		point
+			.direct_neighbors()
+			.into_iter()
+			.filter_map(|pt| self.get(pt).map(|val| (pt, val)))
+	}
+
+	/// Yields the values stored at the twenty-six points which share a face,
+	/// an edge, or a corner with the given point.
+	pub fn neighbors_with_diagonals(
+		&self,
+		point: Cartesian3DPoint<I>,
+	) -> impl '_ + Iterator<Item = (Cartesian3DPoint<I>, &T)> {
+		point
+			.all_neighbors()
+			.into_iter()
+			.filter_map(|pt| self.get(pt).map(|val| (pt, val)))
+	}
+
+	/// Views one plane of constant Z, for rendering.
+	///
+	/// The slice is drawn over the X and Y extent of the whole volume, so that
+	/// every slice of the volume has the same size.
+	pub fn slice(&self, z: I) -> Slice<'_, I, T> {
+		Slice { space: self, z }
+	}
+
+	/// Views every plane of constant Z within the bounding volume, from the
+	/// lowest to the highest.
+	pub fn slices(&self) -> impl '_ + Iterator<Item = Slice<'_, I, T>>
+	where RangeInclusive<I>: IntoIterator<Item = I> {
+		self.bounds
+			.into_iter()
+			.flat_map(|(min, max)| min.z ..= max.z)
+			.map(|z| self.slice(z))
 	}
 
 	pub fn stream_volume(&self) -> impl Iterator<Item = Cartesian3DPoint<I>>
@@ -438,6 +785,33 @@ impl<I: Signed, T> Cartesian3D<I, T> {
 			xy.iter().map(move |(pt, val)| (pt.make_3d(z), val))
 		})
 	}
+
+	/// Iterates over all points that have a live value, allowing the values to
+	/// be modified.
+	pub fn iter_mut(
+		&mut self,
+	) -> impl DoubleEndedIterator<Item = (Cartesian3DPoint<I>, &mut T)> + FusedIterator
+	{
+		self.planes.iter_mut().flat_map(|(&z, xy)| {
+			xy.iter_mut().map(move |(pt, val)| (pt.make_3d(z), val))
+		})
+	}
+}
+
+impl<I: Signed, T> Index<Cartesian3DPoint<I>> for Cartesian3D<I, T> {
+	type Output = T;
+
+	fn index(&self, point: Cartesian3DPoint<I>) -> &Self::Output {
+		self.get(point)
+			.unwrap_or_else(|| panic!("no value stored at {point}"))
+	}
+}
+
+impl<I: Signed, T> IndexMut<Cartesian3DPoint<I>> for Cartesian3D<I, T> {
+	fn index_mut(&mut self, point: Cartesian3DPoint<I>) -> &mut Self::Output {
+		self.get_mut(point)
+			.unwrap_or_else(|| panic!("no value stored at {point}"))
+	}
 }
 
 impl<I: Signed, T> Default for Cartesian3D<I, T> {
@@ -448,3 +822,55 @@ impl<I: Signed, T> Default for Cartesian3D<I, T> {
 		}
 	}
 }
+
+/// A view of one plane of constant Z in a [`Cartesian3D`] volume.
+///
+/// This is constructed by [`Cartesian3D::slice`], and renders through
+/// [`DisplayGrid`](super::DisplayGrid) like a 2-D space does.
+pub struct Slice<'a, I: Signed, T> {
+	space: &'a Cartesian3D<I, T>,
+	z:     I,
+}
+
+impl<'a, I: Signed, T> Slice<'a, I, T> {
+	/// Gets the Z coordinate of the plane.
+	pub fn z(&self) -> I {
+		self.z
+	}
+
+	/// Views the values stored in the plane, if it has any.
+	pub fn plane(&self) -> Option<&'a Cartesian2D<I, T>> {
+		self.space.planes.get(&self.z)
+	}
+}
+
+impl<'a, I: Signed, T> super::DisplayGrid<I, T> for Slice<'a, I, T> {
+	fn bounds_inclusive(
+		&self,
+	) -> Option<(Cartesian2DPoint<I>, Cartesian2DPoint<I>)> {
+		let (min, max) = self.space.bounds?;
+		Some((min.make_2d().1, max.make_2d().1))
+	}
+
+	fn print_cell(
+		&self,
+		symbols: &super::Symbols,
+		row: I,
+		col: I,
+		_row_abs: usize,
+		_col_abs: usize,
+	) -> char {
+		if self.space.contains(Cartesian3DPoint::new(col, row, self.z)) {
+			symbols.full
+		}
+		else {
+			symbols.empty
+		}
+	}
+}
+
+impl<'a, I: Signed, T> fmt::Display for Slice<'a, I, T> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		super::DisplayGrid::render(self, fmt)
+	}
+}
@@ -0,0 +1,243 @@
+/*! Dense grids repeated across the plane
+
+Some puzzles hand over a small map and then declare that it repeats forever in
+every direction: gardens which tile outwards, blizzards which wrap around the
+valley, caves five times larger than the input with every copy a little more
+dangerous. Materializing those maps is either wasteful or impossible, so
+[`TilingView`] instead borrows the base grid and answers questions about any
+coördinate by reducing it modulo the base grid's dimensions.
+
+A view can be limited to a rectangle of tiles, in which case it behaves like a
+large but finite grid, and it can transform each value according to the tile in
+which it was found.
+*/
+
+use std::ops::Neg;
+
+use funty::Signed;
+
+use super::{
+	Dense2D,
+	Point2D,
+};
+
+/// An endless (or merely large) plane made of copies of a dense grid.
+///
+/// The copy of the base grid which holds a point is its *tile*. The tile which
+/// covers the base grid itself is at zero, its right-hand neighbor at `(1, 0)`,
+/// and so on. The transform receives a tile and a value from the base grid and
+/// produces the value seen in that tile.
+#[derive(Clone, Copy, Debug)]
+pub struct TilingView<'a, I: Signed, T, F> {
+	base:      &'a Dense2D<I, T>,
+	origin:    Point2D<I>,
+	size:      Point2D<I>,
+	/// The first and last tiles in the view, inclusive, if it is finite.
+	tiles:     Option<(Point2D<I>, Point2D<I>)>,
+	transform: F,
+}
+
+impl<'a, I: Signed, T: Clone> TilingView<'a, I, T, fn(Point2D<I>, &T) -> T> {
+	/// Tiles a grid across the whole plane, with every tile the same.
+	pub fn new(base: &'a Dense2D<I, T>) -> Self {
+		let (origin, max) = base.dimensions().unwrap_or_default();
+		Self {
+			base,
+			origin,
+			size: if base.is_empty() {
+				Point2D::ZERO
+			}
+			else {
+				max - origin + Point2D::new(I::ONE, I::ONE)
+			},
+			tiles: None,
+			transform: |_, value| value.clone(),
+		}
+	}
+}
+
+impl<'a, I: Signed, T, F: Fn(Point2D<I>, &T) -> T> TilingView<'a, I, T, F> {
+	/// Replaces the transform applied to the values of each tile.
+	pub fn with_transform<G: Fn(Point2D<I>, &T) -> T>(
+		self,
+		transform: G,
+	) -> TilingView<'a, I, T, G> {
+		TilingView {
+			base: self.base,
+			origin: self.origin,
+			size: self.size,
+			tiles: self.tiles,
+			transform,
+		}
+	}
+
+	/// Limits the view to the tiles from `min` to `max`, inclusive.
+	pub fn with_tiles(mut self, min: Point2D<I>, max: Point2D<I>) -> Self {
+		self.tiles = Some((min.min_unifying(max), min.max_unifying(max)));
+		self
+	}
+
+	/// Views the grid being tiled.
+	pub fn base(&self) -> &'a Dense2D<I, T> {
+		self.base
+	}
+
+	/// The width and height of each tile.
+	pub fn tile_size(&self) -> Point2D<I> {
+		self.size
+	}
+
+	/// If the view is limited to some tiles, produces the first and last
+	/// points that it covers.
+	pub fn dimensions(&self) -> Option<(Point2D<I>, Point2D<I>)> {
+		let (min, max) = self.tiles?;
+		let one = Point2D::new(I::ONE, I::ONE);
+		Some((
+			self.origin + self.scale(min),
+			self.origin + self.scale(max + one) - one,
+		))
+	}
+
+	/// Splits a point into the tile that holds it, and the point in the base
+	/// grid that it copies.
+	///
+	/// # Panics
+	///
+	/// This panics if the base grid is empty.
+	pub fn locate(&self, point: Point2D<I>) -> (Point2D<I>, Point2D<I>) {
+		let shifted = point - self.origin;
+		let tile = Point2D::new(
+			shifted.x.div_euclid(self.size.x),
+			shifted.y.div_euclid(self.size.y),
+		);
+		let inner = Point2D::new(
+			shifted.x.rem_euclid(self.size.x),
+			shifted.y.rem_euclid(self.size.y),
+		);
+		(tile, inner + self.origin)
+	}
+
+	/// Tests whether a point is covered by the view.
+	pub fn in_bounds(&self, point: Point2D<I>) -> bool {
+		if self.base.is_empty() {
+			return false;
+		}
+		match self.tiles {
+			None => true,
+			Some((min, max)) => {
+				let (tile, _) = self.locate(point);
+				(min.x ..= max.x).contains(&tile.x)
+					&& (min.y ..= max.y).contains(&tile.y)
+			},
+		}
+	}
+
+	/// Views the value in the base grid which a point copies, without the
+	/// transform.
+	pub fn get_base(&self, point: Point2D<I>) -> Option<&'a T> {
+		if !self.in_bounds(point) {
+			return None;
+		}
+		let (_, inner) = self.locate(point);
+		self.base.get(inner)
+	}
+
+	/// Produces the value at a point, as transformed for its tile.
+	pub fn get(&self, point: Point2D<I>) -> Option<T> {
+		if !self.in_bounds(point) {
+			return None;
+		}
+		let (tile, inner) = self.locate(point);
+		self.base
+			.get(inner)
+			.map(|value| (self.transform)(tile, value))
+	}
+
+	/// Finds the orthogonal neighbors of a point which are covered by the
+	/// view, with their values.
+	pub fn direct_neighbors(
+		&self,
+		point: Point2D<I>,
+	) -> impl '_ + Iterator<Item = (Point2D<I>, T)>
+	where
+		I: Neg<Output = I>,
+	{
+		point
+			.direct_neighbors()
+			.into_iter()
+			.filter_map(|next| self.get(next).map(|value| (next, value)))
+	}
+
+	/// Multiplies a tile index by the tile size.
+	fn scale(&self, tile: Point2D<I>) -> Point2D<I> {
+		Point2D::new(tile.x * self.size.x, tile.y * self.size.y)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeSet;
+
+	use super::*;
+	use crate::search;
+
+	fn garden() -> Dense2D<i32, char> {
+		let rows = ["...", ".#.", "..."]
+			.iter()
+			.map(|row| row.chars().collect())
+			.collect::<Vec<Vec<char>>>();
+		Dense2D::from_raw(Point2D::new(10, 20), rows)
+	}
+
+	#[test]
+	fn endless_tiles() {
+		let base = garden();
+		let view = TilingView::new(&base);
+		assert_eq!(view.tile_size(), Point2D::new(3, 3));
+		assert_eq!(view.dimensions(), None);
+		assert_eq!(
+			view.locate(Point2D::new(9, 23)),
+			(Point2D::new(-1, 1), Point2D::new(12, 20))
+		);
+		assert_eq!(view.get(Point2D::new(11, 21)), Some('#'));
+		assert_eq!(
+			view.get(Point2D::new(-1000 * 3 + 11, 3 * 77 + 21)),
+			Some('#')
+		);
+
+		// Walking exactly two steps from a corner reaches every point at an
+		// even distance except the one behind the rock.
+		let mut frontier = BTreeSet::from([Point2D::new(10, 20)]);
+		for _ in 0 .. 2 {
+			frontier = frontier
+				.iter()
+				.flat_map(|&pt| view.direct_neighbors(pt))
+				.filter(|&(_, cell)| cell == '.')
+				.map(|(pt, _)| pt)
+				.collect();
+		}
+		assert_eq!(frontier.len(), 8);
+	}
+
+	#[test]
+	fn bounded_transformed_tiles() {
+		let base = Dense2D::<i16, u8>::from_raw(Point2D::ZERO, vec![vec![8]]);
+		let view = TilingView::new(&base)
+			.with_tiles(Point2D::ZERO, Point2D::new(4, 4))
+			.with_transform(|tile, &risk| {
+				(risk + (tile.x + tile.y) as u8 - 1) % 9 + 1
+			});
+		assert_eq!(view.dimensions(), Some((Point2D::ZERO, Point2D::new(4, 4))));
+		let row = (0 .. 5)
+			.filter_map(|x| view.get(Point2D::new(x, 0)))
+			.collect::<Vec<_>>();
+		assert_eq!(row, [8, 9, 1, 2, 3]);
+		assert_eq!(view.get(Point2D::new(5, 0)), None);
+		assert_eq!(view.get_base(Point2D::new(4, 4)), Some(&8));
+
+		let reached = search::bfs_visit([Point2D::ZERO], |pt, queue| {
+			queue.extend(view.direct_neighbors(pt).map(|(next, _)| next));
+		});
+		assert_eq!(reached.len(), 25);
+	}
+}
@@ -0,0 +1,180 @@
+//! Search states which carry more than a position.
+//!
+//! Plenty of pathfinding puzzles restrict how the walker may move based on how
+//! it arrived where it is: it must keep going straight for a while, it cannot
+//! go straight for too long, it cannot turn around. A bare coördinate is then
+//! not enough to be a search state, since two arrivals at the same point can
+//! have different futures. [`StatefulCoord`] pairs a position with that extra
+//! state, and [`TurnRules`] describes the usual "heading and run length"
+//! restriction, so that such a puzzle only needs to say what each step costs.
+
+use std::ops::{
+	Add,
+	Neg,
+};
+
+use funty::Signed;
+
+use super::points::{
+	Cartesian2D,
+	Direction2D,
+};
+use crate::search;
+
+/// A position in the plane, plus whatever else a search needs to know about
+/// how it got there.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatefulCoord<I: Signed, S> {
+	pub pos:   Cartesian2D<I>,
+	pub state: S,
+}
+
+impl<I: Signed, S> StatefulCoord<I, S> {
+	pub fn new(pos: Cartesian2D<I>, state: S) -> Self {
+		Self { pos, state }
+	}
+
+	/// Replaces the extra state, keeping the position.
+	pub fn with_state<T>(self, state: T) -> StatefulCoord<I, T> {
+		StatefulCoord {
+			pos: self.pos,
+			state,
+		}
+	}
+}
+
+/// The direction a walker is facing, and how many steps it has taken in that
+/// direction since it last turned.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heading {
+	pub direction: Direction2D,
+	/// This is only zero before the walker has taken its first step, at which
+	/// point it may set off in any direction.
+	pub run:       u8,
+}
+
+/// How a walker which has to mind its heading is allowed to move.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurnRules {
+	/// The fewest steps the walker must take in a straight line before it can
+	/// turn, or stop.
+	pub min_run: u8,
+	/// The most steps the walker can take in a straight line.
+	pub max_run: u8,
+	/// Whether the walker can turn around, rather than only left or right.
+	pub reverse: bool,
+}
+
+impl TurnRules {
+	/// Rules which permit every move.
+	pub const FREE: Self = Self {
+		min_run: 0,
+		max_run: u8::MAX,
+		reverse: true,
+	};
+
+	/// Creates the state of a walker which has not yet moved.
+	pub fn start<I: Signed>(
+		&self,
+		pos: Cartesian2D<I>,
+	) -> StatefulCoord<I, Heading> {
+		StatefulCoord::new(pos, Heading {
+			direction: Direction2D::North,
+			run:       0,
+		})
+	}
+
+	/// Tests whether the walker is allowed to stop in its current state.
+	pub fn can_stop(&self, heading: Heading) -> bool {
+		heading.run >= self.min_run
+	}
+
+	/// Produces every state the walker can step into next.
+	pub fn successors<I: Signed + Neg<Output = I>>(
+		&self,
+		from: StatefulCoord<I, Heading>,
+	) -> impl Iterator<Item = StatefulCoord<I, Heading>> {
+		let Heading { direction, run } = from.state;
+		let can_turn = run >= self.min_run;
+		let ahead = (direction, run < self.max_run);
+		let left = (direction.turn_left(), can_turn);
+		let right = (direction.turn_right(), can_turn);
+		let back = (direction.reverse(), can_turn && self.reverse);
+		[ahead, left, right, back]
+			.into_iter()
+			.filter(move |&(_, allowed)| allowed || run == 0)
+			.map(move |(dir, _)| {
+				let run = if dir == direction { run + 1 } else { 1 };
+				StatefulCoord::new(from.pos + dir.unit(), Heading {
+					direction: dir,
+					run,
+				})
+			})
+	}
+
+	/// Finds the cost of the cheapest walk from one point to another.
+	///
+	/// `cost` gives the price of stepping onto a point, or `None` if the point
+	/// cannot be entered (because it is a wall, or off the map). The starting
+	/// point is free.
+	pub fn cheapest_path<I, C>(
+		&self,
+		start: Cartesian2D<I>,
+		goal: Cartesian2D<I>,
+		mut cost: impl FnMut(Cartesian2D<I>) -> Option<C>,
+	) -> Option<C>
+	where
+		I: Signed + Neg<Output = I>,
+		C: Ord + Copy + Default + Add<Output = C>,
+	{
+		search::dijkstra(
+			[self.start(start)],
+			|state| state.pos == goal && self.can_stop(state.state),
+			|&state, next| {
+				next.extend(self.successors(state).filter_map(|succ| {
+					cost(succ.pos).map(|price| (succ, price))
+				}));
+			},
+		)
+		.map(|(_, total)| total)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn turning_rules() {
+		let rules = TurnRules {
+			min_run: 2,
+			max_run: 3,
+			reverse: false,
+		};
+		let start = rules.start(Cartesian2D::<i8>::ZERO);
+		assert_eq!(rules.successors(start).count(), 4);
+
+		let heading = |run| {
+			StatefulCoord::new(Cartesian2D::<i8>::ZERO, Heading {
+				direction: Direction2D::East,
+				run,
+			})
+		};
+		let dirs = |run| {
+			rules
+				.successors(heading(run))
+				.map(|s| (s.state.direction, s.state.run))
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(dirs(1), [(Direction2D::East, 2)]);
+		assert_eq!(dirs(2), [
+			(Direction2D::East, 3),
+			(Direction2D::North, 1),
+			(Direction2D::South, 1),
+		]);
+		assert_eq!(dirs(3), [(Direction2D::North, 1), (Direction2D::South, 1)]);
+	}
+}
@@ -0,0 +1,268 @@
+//! Typed quantities, so that the compiler can tell them apart.
+//!
+//! Grids are indexed by `(x, y)` in some places and by `(row, col)` in others,
+//! and a bare integer does not say which it is. Swapping the two compiles,
+//! runs, and produces a wrong answer for any grid that is not square. [`Row`]
+//! and [`Col`] wrap a coördinate on one axis, and only combine with their own
+//! kind: a row plus an offset is a row, and the difference of two rows is an
+//! offset, but a row and a column cannot be added, compared, or passed for one
+//! another.
+//!
+//! [`Distance`] and [`Score`] do the same for the two kinds of total that
+//! puzzles most often keep, so that a path length cannot be added to a score.
+//! Both convert into answers.
+//!
+//! None of this is required. The grid methods which take a row or a column
+//! accept either the newtype or a bare integer, and
+//! [`Cartesian2D`](super::Cartesian2DPoint) keeps its `x` and `y` fields next
+//! to the typed accessors.
+
+use std::{
+	fmt,
+	iter::Sum,
+	ops::{
+		Add,
+		AddAssign,
+		Mul,
+		Sub,
+		SubAssign,
+	},
+};
+
+use funty::Signed;
+
+use super::points::Cartesian2D as Point2D;
+use crate::Answer;
+
+macro_rules! axis {
+	($($(#[$doc:meta])* $name:ident => $field:ident);+ $(;)?) => { $(
+		$(#[$doc])*
+		#[derive(
+			Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd,
+		)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize),
+			serde(transparent)
+		)]
+		pub struct $name<I: Signed>(pub I);
+
+		impl<I: Signed> $name<I> {
+			/// Unwraps the coördinate.
+			pub fn get(self) -> I {
+				self.0
+			}
+		}
+
+		impl<I: Signed> From<I> for $name<I> {
+			fn from(val: I) -> Self {
+				Self(val)
+			}
+		}
+
+		impl<I: Signed> Add<I> for $name<I> {
+			type Output = Self;
+
+			fn add(self, offset: I) -> Self {
+				Self(self.0 + offset)
+			}
+		}
+
+		impl<I: Signed> AddAssign<I> for $name<I> {
+			fn add_assign(&mut self, offset: I) {
+				self.0 += offset;
+			}
+		}
+
+		impl<I: Signed> Sub<I> for $name<I> {
+			type Output = Self;
+
+			fn sub(self, offset: I) -> Self {
+				Self(self.0 - offset)
+			}
+		}
+
+		impl<I: Signed> SubAssign<I> for $name<I> {
+			fn sub_assign(&mut self, offset: I) {
+				self.0 -= offset;
+			}
+		}
+
+		/// The offset from one coördinate to another on the same axis.
+		impl<I: Signed> Sub for $name<I> {
+			type Output = I;
+
+			fn sub(self, other: Self) -> I {
+				self.0 - other.0
+			}
+		}
+
+		impl<I: Signed> fmt::Display for $name<I> {
+			fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+				write!(fmt, "{}={}", stringify!($field), self.0)
+			}
+		}
+	)+ };
+}
+
+axis! {
+	/// A coördinate on the vertical axis: a `y` value.
+	Row => row;
+	/// A coördinate on the horizontal axis: an `x` value.
+	Col => col;
+}
+
+macro_rules! quantity {
+	($($(#[$doc:meta])* $name:ident);+ $(;)?) => { $(
+		$(#[$doc])*
+		#[derive(
+			Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd,
+		)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize),
+			serde(transparent)
+		)]
+		pub struct $name(pub i64);
+
+		impl $name {
+			pub const ZERO: Self = Self(0);
+
+			/// Unwraps the quantity.
+			pub fn get(self) -> i64 {
+				self.0
+			}
+		}
+
+		impl From<i64> for $name {
+			fn from(val: i64) -> Self {
+				Self(val)
+			}
+		}
+
+		impl From<$name> for i64 {
+			fn from(val: $name) -> Self {
+				val.0
+			}
+		}
+
+		impl From<$name> for Answer {
+			fn from(val: $name) -> Self {
+				val.0.into()
+			}
+		}
+
+		impl Add for $name {
+			type Output = Self;
+
+			fn add(self, other: Self) -> Self {
+				Self(self.0 + other.0)
+			}
+		}
+
+		impl AddAssign for $name {
+			fn add_assign(&mut self, other: Self) {
+				self.0 += other.0;
+			}
+		}
+
+		impl Sub for $name {
+			type Output = Self;
+
+			fn sub(self, other: Self) -> Self {
+				Self(self.0 - other.0)
+			}
+		}
+
+		impl SubAssign for $name {
+			fn sub_assign(&mut self, other: Self) {
+				self.0 -= other.0;
+			}
+		}
+
+		/// Scales the quantity, as when a step is repeated.
+		impl Mul<i64> for $name {
+			type Output = Self;
+
+			fn mul(self, factor: i64) -> Self {
+				Self(self.0 * factor)
+			}
+		}
+
+		impl Sum for $name {
+			fn sum<II: Iterator<Item = Self>>(iter: II) -> Self {
+				Self(iter.map(|val| val.0).sum())
+			}
+		}
+
+		impl fmt::Display for $name {
+			fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+				fmt::Display::fmt(&self.0, fmt)
+			}
+		}
+	)+ };
+}
+
+quantity! {
+	/// How far apart two things are, or how long a path is.
+	Distance;
+	/// Points awarded by a puzzle's rules.
+	Score;
+}
+
+impl<I: Signed> Point2D<I> {
+	/// Builds a point from its row and column, which cannot be swapped by
+	/// mistake.
+	pub fn at(row: Row<I>, col: Col<I>) -> Self {
+		Self::new(col.0, row.0)
+	}
+
+	/// Gets the point's row, which is its `y` coördinate.
+	pub fn row(self) -> Row<I> {
+		Row(self.y)
+	}
+
+	/// Gets the point's column, which is its `x` coördinate.
+	pub fn col(self) -> Col<I> {
+		Col(self.x)
+	}
+}
+
+/// Either order of row and column builds the same point.
+impl<I: Signed> From<(Row<I>, Col<I>)> for Point2D<I> {
+	fn from((row, col): (Row<I>, Col<I>)) -> Self {
+		Self::at(row, col)
+	}
+}
+
+impl<I: Signed> From<(Col<I>, Row<I>)> for Point2D<I> {
+	fn from((col, row): (Col<I>, Row<I>)) -> Self {
+		Self::at(row, col)
+	}
+}
+
+impl<I: Signed> From<Point2D<I>> for (Row<I>, Col<I>) {
+	fn from(point: Point2D<I>) -> Self {
+		(point.row(), point.col())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn axes_do_not_mix() {
+		let point = Point2D::at(Row(3), Col(5));
+		assert_eq!(point, Point2D::new(5, 3));
+		assert_eq!(Point2D::from((Col(5), Row(3))), point);
+		assert_eq!(<(Row<i32>, Col<i32>)>::from(point), (Row(3), Col(5)));
+		assert_eq!(point.row() + 2, Row(5));
+		assert_eq!(Row(7) - point.row(), 4);
+		assert_eq!(point.col().to_string(), "col=5");
+
+		let path = [3, 4, 5].into_iter().map(Distance).sum::<Distance>();
+		assert_eq!(path * 2, Distance(24));
+		assert_eq!(Answer::from(Score(10) - Score(4)), 6);
+	}
+}
@@ -0,0 +1,146 @@
+//! Identity for solvers which do not state their own.
+//!
+//! Most solvers have no idea which puzzle they solve: the registry knows the
+//! year and day, and the title lives in the README next to the module. The
+//! registry wraps every solver it produces in [`Described`], which answers
+//! [`Puzzle::title`] and [`Puzzle::year_day`] from the registration and
+//! forwards everything else untouched.
+
+use std::{
+	any::Any,
+	fmt,
+	sync::OnceLock,
+};
+
+use crate::{
+	artifacts::Artifact,
+	interact::InteractIo,
+	random,
+	sim,
+	Answer,
+	ParamMap,
+	Puzzle,
+	Solver,
+};
+
+/// A solver, along with the registry entry that produced it.
+///
+/// Every [`Puzzle`] method must be forwarded here, or wrapped solvers will
+/// silently lose their overrides of it.
+pub(crate) struct Described {
+	inner: Box<dyn Puzzle + Send>,
+	entry: Solver,
+	/// The README title, read on first request rather than on every parse.
+	title: OnceLock<Option<String>>,
+}
+
+impl Described {
+	pub(crate) fn new(inner: Box<dyn Puzzle + Send>, entry: Solver) -> Self {
+		Self {
+			inner,
+			entry,
+			title: OnceLock::new(),
+		}
+	}
+}
+
+impl fmt::Debug for Described {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(&self.inner, fmt)
+	}
+}
+
+impl Puzzle for Described {
+	fn title(&self) -> Option<&str> {
+		self.inner
+			.title()
+			.or_else(|| self.title.get_or_init(|| self.entry.title()).as_deref())
+	}
+
+	fn year_day(&self) -> Option<(u16, u8)> {
+		self.inner
+			.year_day()
+			.or(Some((self.entry.year, self.entry.day)))
+	}
+
+	fn after_parse(&mut self) -> eyre::Result<()> {
+		self.inner.after_parse()
+	}
+
+	fn prepare_common(&mut self) -> eyre::Result<()> {
+		self.inner.prepare_common()
+	}
+
+	fn prepare_1(&mut self) -> eyre::Result<()> {
+		self.inner.prepare_1()
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		self.inner.part_1()
+	}
+
+	fn has_part_2(&self) -> bool {
+		self.inner.has_part_2()
+	}
+
+	fn prepare_2(&mut self) -> eyre::Result<()> {
+		self.inner.prepare_2()
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		self.inner.part_2()
+	}
+
+	fn answer_1(&mut self) -> eyre::Result<Answer> {
+		self.inner.answer_1()
+	}
+
+	fn answer_2(&mut self) -> eyre::Result<Answer> {
+		self.inner.answer_2()
+	}
+
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		self.inner.describe_input()
+	}
+
+	fn validate_answer(&self, part: u8, answer: &Answer) -> eyre::Result<()> {
+		self.inner.validate_answer(part, answer)
+	}
+
+	fn render_input(&self) -> Option<String> {
+		self.inner.render_input()
+	}
+
+	fn render_state(&self) -> Option<String> {
+		self.inner.render_state()
+	}
+
+	fn dump_state(&self) -> Option<eyre::Result<String>> {
+		let inner: &dyn Any = &*self.inner;
+		self.inner.dump_state().or_else(|| (self.entry.dump)(inner))
+	}
+
+	fn artifacts(&self) -> Vec<Artifact> {
+		self.inner.artifacts()
+	}
+
+	fn recorder(&mut self) -> Option<&mut sim::Recorder> {
+		self.inner.recorder()
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		self.inner.set_param(key, value)
+	}
+
+	fn set_params(&mut self, params: &ParamMap) -> eyre::Result<()> {
+		self.inner.set_params(params)
+	}
+
+	fn set_rng(&mut self, rng: random::StdRng) {
+		self.inner.set_rng(rng)
+	}
+
+	fn interact(&mut self, io: &mut dyn InteractIo) -> eyre::Result<()> {
+		self.inner.interact(io)
+	}
+}
@@ -1,11 +1,16 @@
 use std::{
+	cmp,
 	collections::{
 		BTreeMap,
 		HashMap,
 	},
 	fmt,
-	hash::Hash,
+	hash::{
+		Hash,
+		Hasher,
+	},
 	iter::FusedIterator,
+	marker::PhantomData,
 	ops::Index,
 	sync::Arc,
 };
@@ -19,17 +24,39 @@ use std::{
 /// Items are stored inside `Arc`s, since the dictionary needs to maintain two
 /// links to the same object. This means that users can choose between holding
 /// the dictionary's `Identifier`s *or* other `Arc` handles.
-pub struct Dictionary<T: ?Sized + Eq + Hash> {
-	cached: HashMap<Arc<T>, usize>,
-	idents: BTreeMap<usize, Arc<T>>,
+///
+/// The `N` parameter is a namespace marker. It is carried by every
+/// `Identifier` the dictionary produces, so that when a puzzle interns several
+/// kinds of names, an identifier from one dictionary cannot be used to look
+/// something up in another. Any type can be a namespace; an empty `enum` is
+/// cheapest. Dictionaries which do not need to be told apart can leave it as
+/// `()`.
+///
+/// ```rust,compile_fail
+/// use wyz_aoc::dict::Dictionary;
+///
+/// enum Rooms {}
+/// enum Items {}
+///
+/// let mut rooms = Dictionary::<str, Rooms>::new();
+/// let mut items = Dictionary::<str, Items>::new();
+/// let lamp = items.insert("lamp");
+/// rooms.insert("hall");
+/// let _ = rooms.lookup(lamp);
+/// ```
+pub struct Dictionary<T: ?Sized + Eq + Hash, N = ()> {
+	cached:    HashMap<Arc<T>, usize>,
+	idents:    BTreeMap<usize, Arc<T>>,
+	namespace: PhantomData<fn() -> N>,
 }
 
-impl<T: ?Sized + Eq + Hash> Dictionary<T> {
+impl<T: ?Sized + Eq + Hash, N> Dictionary<T, N> {
 	/// Creates an empty dictionary.
 	pub fn new() -> Self {
 		Self {
-			cached: HashMap::new(),
-			idents: BTreeMap::new(),
+			cached:    HashMap::new(),
+			idents:    BTreeMap::new(),
+			namespace: PhantomData,
 		}
 	}
 
@@ -46,7 +73,10 @@ impl<T: ?Sized + Eq + Hash> Dictionary<T> {
 	/// Inserts an item into the dictionary, returning an opaque identifier that
 	/// can be used to retrieve it later. If the item is already stored in the
 	/// dictionary, then the existing identifier is returned.
-	pub fn insert(&mut self, value: impl AsRef<T> + Into<Arc<T>>) -> Identifier {
+	pub fn insert(
+		&mut self,
+		value: impl AsRef<T> + Into<Arc<T>>,
+	) -> Identifier<N> {
 		let next_ident = self.len();
 		if let Some(&out) = self.cached.get(value.as_ref()) {
 			return Identifier::new(out);
@@ -59,17 +89,16 @@ impl<T: ?Sized + Eq + Hash> Dictionary<T> {
 
 	/// Attempts to get a value out of the dictionary.
 	///
-	/// While `Identifier` can only be produced by inserting values into the
-	/// dictionary, I currently have no way to tie identifiers to the dictionary
-	/// that produced them, so an identifier from one dictionary might not work
-	/// in another.
-	pub fn lookup(&self, ident: Identifier) -> Option<Arc<T>> {
+	/// Identifiers are tied to their dictionary's namespace, not to the
+	/// dictionary itself, so an identifier from another dictionary in the same
+	/// namespace might not work here.
+	pub fn lookup(&self, ident: Identifier<N>) -> Option<Arc<T>> {
 		self.idents.get(&ident.ident).cloned()
 	}
 
 	/// Attempts to get the identifier for a value if it is present in the
 	/// dictionary.
-	pub fn lookup_value(&self, value: impl AsRef<T>) -> Option<Identifier> {
+	pub fn lookup_value(&self, value: impl AsRef<T>) -> Option<Identifier<N>> {
 		self.cached
 			.get(value.as_ref())
 			.map(|&ident| Identifier::new(ident))
@@ -81,63 +110,133 @@ impl<T: ?Sized + Eq + Hash> Dictionary<T> {
 	}
 
 	/// Tests if a key is stored in the dictionary.
-	pub fn contains_key(&self, ident: Identifier) -> bool {
+	pub fn contains_key(&self, ident: Identifier<N>) -> bool {
 		self.idents.contains_key(&ident.ident)
 	}
 
+	/// Gets the identifier at a given index, if the dictionary has assigned
+	/// it.
+	pub fn identifier(&self, index: usize) -> Option<Identifier<N>> {
+		self.idents
+			.contains_key(&index)
+			.then(|| Identifier::new(index))
+	}
+
+	/// Iterates over every identifier the dictionary has assigned, in order.
 	pub fn identifiers<'a>(
 		&'a self,
 	) -> impl 'a
-	       + Iterator<Item = Identifier>
-	       + DoubleEndedIterator
+	       + DoubleEndedIterator<Item = Identifier<N>>
 	       + ExactSizeIterator
 	       + FusedIterator {
 		self.idents.keys().map(|k| Identifier::new(*k))
 	}
 }
 
-impl<T: ?Sized + Eq + Hash> Index<Identifier> for Dictionary<T> {
+impl<T: ?Sized + Eq + Hash, N> Index<Identifier<N>> for Dictionary<T, N> {
 	type Output = T;
 
-	fn index(&self, ident: Identifier) -> &Self::Output {
+	fn index(&self, ident: Identifier<N>) -> &Self::Output {
 		&self.idents[&ident.ident]
 	}
 }
 
-impl<T: ?Sized + Eq + Hash> Clone for Dictionary<T> {
+impl<T: ?Sized + Eq + Hash, N> Clone for Dictionary<T, N> {
 	fn clone(&self) -> Self {
 		Self {
-			cached: self.cached.clone(),
-			idents: self.idents.clone(),
+			cached:    self.cached.clone(),
+			idents:    self.idents.clone(),
+			namespace: PhantomData,
 		}
 	}
 }
 
-impl<T: ?Sized + Eq + Hash + fmt::Debug> fmt::Debug for Dictionary<T> {
+impl<T: ?Sized + Eq + Hash + fmt::Debug, N> fmt::Debug for Dictionary<T, N> {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		fmt.debug_map().entries(self.idents.iter()).finish()
 	}
 }
 
-impl<T: ?Sized + Eq + Hash> Default for Dictionary<T> {
+impl<T: ?Sized + Eq + Hash, N> Default for Dictionary<T, N> {
 	fn default() -> Self {
 		Self::new()
 	}
 }
 
 /// An opaque identifier produced by a dictionary.
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Identifier {
-	ident: usize,
+///
+/// The identifier carries the namespace of the dictionary which produced it,
+/// and can only be given back to dictionaries in that namespace.
+pub struct Identifier<N = ()> {
+	ident:     usize,
+	namespace: PhantomData<fn() -> N>,
 }
 
-impl Identifier {
+impl<N> Identifier<N> {
 	fn new(ident: usize) -> Self {
-		Self { ident }
+		Self {
+			ident,
+			namespace: PhantomData,
+		}
+	}
+
+	/// Gets the numeric value of the identifier.
+	///
+	/// Dictionaries assign identifiers densely, counting up from zero in the
+	/// order that items are first inserted.
+	pub fn index(self) -> usize {
+		self.ident
 	}
 }
 
-impl fmt::Display for Identifier {
+// These are written out by hand, because deriving them would require the
+// namespace marker to implement them too.
+
+impl<N> Clone for Identifier<N> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<N> Copy for Identifier<N> {
+}
+
+impl<N> PartialEq for Identifier<N> {
+	fn eq(&self, other: &Self) -> bool {
+		self.ident == other.ident
+	}
+}
+
+impl<N> Eq for Identifier<N> {
+}
+
+impl<N> PartialOrd for Identifier<N> {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<N> Ord for Identifier<N> {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		self.ident.cmp(&other.ident)
+	}
+}
+
+impl<N> Hash for Identifier<N> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.ident.hash(state);
+	}
+}
+
+impl<N> fmt::Debug for Identifier<N> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("Identifier")
+			.field("ident", &self.ident)
+			.finish()
+	}
+}
+
+impl<N> fmt::Display for Identifier<N> {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		fmt::Display::fmt(&self.ident, fmt)
 	}
@@ -159,4 +258,22 @@ mod tests {
 
 		assert_eq!(&dict[id2], "world");
 	}
+
+	#[test]
+	fn namespaces() {
+		enum Rooms {}
+		enum Items {}
+
+		let mut rooms = Dictionary::<str, Rooms>::new();
+		let mut items = Dictionary::<str, Items>::new();
+		let hall = rooms.insert("hall");
+		let lamp = items.insert("lamp");
+
+		// Both are the first entry in their dictionary, but only their own
+		// dictionary will accept them.
+		assert_eq!(hall.index(), lamp.index());
+		assert_eq!(&rooms[hall], "hall");
+		assert_eq!(&items[lamp], "lamp");
+		assert_eq!(rooms.identifiers().collect::<Vec<_>>(), [hall]);
+	}
 }
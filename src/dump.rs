@@ -0,0 +1,90 @@
+//! Writing a solver's state out as JSON, for analysis outside the harness.
+//!
+//! Most puzzle structs derive `serde::Serialize` under the `serde` feature, but
+//! the harness only ever holds them as `dyn Puzzle`, which cannot be
+//! serialized. [`register_solver!`](crate::register_solver) knows the concrete
+//! type, and uses a [`Probe`] to store a function which recovers it and
+//! serializes it, when it can be serialized. The harness reaches that function
+//! through [`Puzzle::dump_state`](crate::Puzzle::dump_state).
+//!
+//! The probe chooses between the two traits below by method resolution: a
+//! call on `&Probe<T>` finds [`ViaSerialize`] first when `T` implements it,
+//! and otherwise falls back to [`ViaNothing`], which is implemented one
+//! reference further out.
+
+use std::{
+	any::Any,
+	marker::PhantomData,
+};
+
+/// Stands in for a puzzle type while choosing how to dump it.
+pub struct Probe<T>(PhantomData<T>);
+
+impl<T> Probe<T> {
+	pub const fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T> Default for Probe<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Dumps puzzles which can be serialized.
+pub trait ViaSerialize {
+	fn dump(&self, puzzle: &dyn Any) -> Option<eyre::Result<String>>;
+}
+
+#[cfg(feature = "serde")]
+impl<T: 'static + serde::Serialize> ViaSerialize for Probe<T> {
+	fn dump(&self, puzzle: &dyn Any) -> Option<eyre::Result<String>> {
+		let puzzle = puzzle.downcast_ref::<T>()?;
+		Some(serde_json::to_string_pretty(puzzle).map_err(Into::into))
+	}
+}
+
+/// Declines to dump puzzles which cannot be serialized.
+pub trait ViaNothing {
+	fn dump(&self, puzzle: &dyn Any) -> Option<eyre::Result<String>>;
+}
+
+impl<T> ViaNothing for &Probe<T> {
+	fn dump(&self, _: &dyn Any) -> Option<eyre::Result<String>> {
+		None
+	}
+}
+
+/// The dumper for solvers registered without one.
+pub fn nothing(_: &dyn Any) -> Option<eyre::Result<String>> {
+	None
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+	use super::*;
+
+	#[derive(serde::Serialize)]
+	struct Plain {
+		cells: Vec<u8>,
+	}
+
+	struct Opaque;
+
+	// The borrows are what `register_solver!` writes, and are needed for the
+	// fallback to be found.
+	#[test]
+	#[allow(clippy::needless_borrow)]
+	fn probes_for_serialize() -> eyre::Result<()> {
+		let plain = Plain { cells: vec![1, 2] };
+		let text = (&Probe::<Plain>::new()).dump(&plain).transpose()?;
+		assert_eq!(
+			text.map(|text| text.split_whitespace().collect::<String>()),
+			Some(r#"{"cells":[1,2]}"#.to_owned())
+		);
+		assert!((&Probe::<Plain>::new()).dump(&Opaque).is_none());
+		assert!((&Probe::<Opaque>::new()).dump(&Opaque).is_none());
+		Ok(())
+	}
+}
@@ -0,0 +1,50 @@
+//! Puzzle data compiled into the binary.
+//!
+//! Building with the `embed` feature copies the input and parameter files into
+//! the executable, so that it can be handed to someone without the repository
+//! (say, cross-compiled for a demo machine) and still solve them. `AOC_EMBED`
+//! narrows which puzzles' files are copied; see `build.rs`.
+//!
+//! Files on disk always win. The harness only looks here when the file it
+//! wants does not exist, so a checkout can still edit its inputs without
+//! rebuilding.
+
+use std::path::Path;
+
+// Generated by `build.rs`: `(path, contents)` pairs, sorted by path. Paths are
+// relative to the repository root, and always use `/`.
+include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+/// Looks up an embedded file by its path relative to the repository root.
+pub fn file(path: impl AsRef<Path>) -> Option<&'static str> {
+	let key = path
+		.as_ref()
+		.iter()
+		.map(|part| part.to_string_lossy())
+		.collect::<Vec<_>>()
+		.join("/");
+	FILES
+		.binary_search_by(|&(name, _)| name.cmp(&key))
+		.ok()
+		.map(|idx| FILES[idx].1)
+}
+
+/// Looks up the embedded input text for a puzzle's data group.
+pub fn input(year: u16, day: u8, group: &str) -> Option<&'static str> {
+	file(format!("src/y{year}/d{day:0>2}/{group}.txt"))
+}
+
+/// Looks up the embedded parameter file for a puzzle's data group.
+pub fn params(group: &str, year: u16, day: u8) -> Option<&'static str> {
+	file(format!("assets/params/{group}/{year}/d{day:0>2}.toml"))
+}
+
+/// Lists the data groups embedded for a puzzle, in name order.
+pub fn groups(year: u16, day: u8) -> Vec<String> {
+	let dir = format!("src/y{year}/d{day:0>2}/");
+	FILES
+		.iter()
+		.filter_map(|(name, _)| name.strip_prefix(&dir)?.strip_suffix(".txt"))
+		.map(str::to_owned)
+		.collect()
+}
@@ -0,0 +1,151 @@
+//! Classes of solver failure.
+//!
+//! Solvers report failure through `eyre`, like the rest of the crate. Most
+//! errors are prose meant for whoever is reading the log. When the *kind* of
+//! failure matters to the harness, such as a part not written yet or input
+//! that breaks the puzzle's rules, return an [`AocError`] instead. `?` turns
+//! it into an `eyre::Report`, and it survives any `.wrap_err()` context added
+//! on the way out.
+//!
+//! The harness looks for one with [`AocError::find`], and uses it to pick the
+//! process exit status and how the failure is logged:
+//!
+//! | Class             | Status |
+//! |:------------------|-------:|
+//! | (unclassified)    |      1 |
+//! | `NotImplemented`  |      3 |
+//! | `ParseIncomplete` |      4 |
+//! | `InvalidInput`    |      5 |
+//! | `Overflow`        |      6 |
+//! | `Timeout`         |      7 |
+//! | `Internal`        |      8 |
+//! | `WrongAnswer`     |      9 |
+//!
+//! A run stopped by Ctrl-C exits with 130 no matter what error it produced.
+
+use std::fmt;
+
+/// A classified solver failure.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum AocError {
+	/// The solver does not answer this part yet.
+	NotImplemented { part: u8 },
+	/// The parser stopped before the end of the input. `rest` holds the first
+	/// line it did not consume.
+	ParseIncomplete { rest: String },
+	/// The input parsed, but breaks the rules of the puzzle.
+	InvalidInput(String),
+	/// An answer, or a value on the way to one, does not fit in its integer.
+	Overflow(String),
+	/// The solver gave up after running past a time or iteration limit.
+	Timeout(String),
+	/// The solver reached a state which should be impossible.
+	Internal(String),
+	/// The solver finished, but its answer is not the one known to be right.
+	WrongAnswer(String),
+}
+
+impl AocError {
+	/// Checks the text that a parser did not consume. Trailing whitespace is
+	/// fine; anything else means that the parser stopped early.
+	pub fn check_leftover(rest: &str) -> Result<(), Self> {
+		match rest.trim_start().lines().next() {
+			Some(line) if !line.trim().is_empty() => {
+				Err(Self::ParseIncomplete {
+					rest: line.to_owned(),
+				})
+			},
+			_ => Ok(()),
+		}
+	}
+
+	/// Finds the classified error at the root of a report, if there is one.
+	///
+	/// Context wrapped around it does not hide it.
+	pub fn find(report: &eyre::Report) -> Option<&Self> {
+		report.chain().find_map(|err| err.downcast_ref::<Self>())
+	}
+
+	/// Names the class of the failure, for logs and reports.
+	pub fn class(&self) -> &'static str {
+		match self {
+			Self::NotImplemented { .. } => "not implemented",
+			Self::ParseIncomplete { .. } => "parse incomplete",
+			Self::InvalidInput(_) => "invalid input",
+			Self::Overflow(_) => "overflow",
+			Self::Timeout(_) => "timeout",
+			Self::Internal(_) => "internal",
+			Self::WrongAnswer(_) => "wrong answer",
+		}
+	}
+
+	/// The exit status of a run which fails with this error.
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Self::NotImplemented { .. } => 3,
+			Self::ParseIncomplete { .. } => 4,
+			Self::InvalidInput(_) => 5,
+			Self::Overflow(_) => 6,
+			Self::Timeout(_) => 7,
+			Self::Internal(_) => 8,
+			Self::WrongAnswer(_) => 9,
+		}
+	}
+
+	/// Tests whether the failure is the solver's fault rather than the
+	/// input's.
+	pub fn is_bug(&self) -> bool {
+		matches!(self, Self::Overflow(_) | Self::Internal(_))
+	}
+}
+
+impl fmt::Display for AocError {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::NotImplemented { part } => {
+				write!(fmt, "have not yet solved part {part}")
+			},
+			Self::ParseIncomplete { rest } => {
+				write!(fmt, "could not parse the input from `{rest}` onward")
+			},
+			Self::InvalidInput(msg)
+			| Self::Overflow(msg)
+			| Self::Timeout(msg)
+			| Self::Internal(msg)
+			| Self::WrongAnswer(msg) => fmt.write_str(msg),
+		}
+	}
+}
+
+impl std::error::Error for AocError {
+}
+
+#[cfg(test)]
+mod tests {
+	use eyre::WrapErr;
+
+	use super::*;
+
+	#[test]
+	fn survives_context() {
+		let report = Err::<(), _>(AocError::Overflow("too big".into()))
+			.wrap_err("could not solve part 1")
+			.wrap_err("2015-01")
+			.unwrap_err();
+		let found = AocError::find(&report).unwrap();
+		assert_eq!(found.exit_code(), 6);
+		assert!(found.is_bug());
+		assert!(AocError::find(&eyre::eyre!("plain")).is_none());
+	}
+
+	#[test]
+	fn leftover_input() {
+		assert_eq!(AocError::check_leftover("\n  \n"), Ok(()));
+		assert_eq!(
+			AocError::check_leftover("\nfold along x=5\nfold along y=7\n"),
+			Err(AocError::ParseIncomplete {
+				rest: "fold along x=5".to_owned(),
+			}),
+		);
+	}
+}
@@ -0,0 +1,191 @@
+//! Known answers, to check runs against.
+//!
+//! A wrong answer looks just like a right one in the log, unless I happen to
+//! remember the right one. The answers a data group should produce can be
+//! written down in `assets/answers/{group}/{year}/d{day}.toml`:
+//!
+//! ```toml
+//! part_1 = 21
+//! part_2 = 525152
+//! ```
+//!
+//! and `run --check` compares each part's answer with them. For the `input`
+//! group, a part with no written answer falls back on the answer the site
+//! accepted, from the [submission history](crate::history).
+
+use std::{
+	collections::BTreeMap,
+	fmt,
+	fs,
+	path::PathBuf,
+};
+
+use eyre::WrapErr as _;
+
+use crate::{
+	Answer,
+	ParamMap,
+};
+
+/// The known answers for one data group of one puzzle.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Expected {
+	answers: BTreeMap<u8, String>,
+}
+
+impl Expected {
+	/// Computes the path of the answer file for a data group.
+	pub fn path(group: &str, year: u16, day: u8) -> eyre::Result<PathBuf> {
+		let mut path = crate::config::asset_root()?;
+		path.push("assets");
+		path.push("answers");
+		path.push(group);
+		path.push(year.to_string());
+		path.push(format!("d{day:0>2}.toml"));
+		Ok(path)
+	}
+
+	/// Parses an answer file, which has a `part_1` and a `part_2` key, either
+	/// of which may be missing.
+	pub fn parse(text: &str) -> eyre::Result<Self> {
+		let table = ParamMap::parse(text)?;
+		let mut answers = BTreeMap::new();
+		for (key, value) in table.iter() {
+			let part = match key {
+				"part_1" => 1,
+				"part_2" => 2,
+				_ => eyre::bail!("`{key}` is not `part_1` or `part_2`"),
+			};
+			answers.insert(part, value.to_owned());
+		}
+		Ok(Self { answers })
+	}
+
+	/// Loads the known answers for a data group, which may be none.
+	#[cfg_attr(feature = "trace", tracing::instrument)]
+	pub fn load(group: &str, year: u16, day: u8) -> eyre::Result<Self> {
+		let path = Self::path(group, year, day)?;
+		let this = if path.exists() {
+			let text = fs::read_to_string(&path).wrap_err_with(|| {
+				format!("could not read answers {}", path.display())
+			})?;
+			Self::parse(&text).wrap_err_with(|| {
+				format!("could not parse answers {}", path.display())
+			})?
+		}
+		else {
+			tracing::trace!(file=%path.display(), "no answer file");
+			Self::default()
+		};
+		#[cfg(feature = "serde")]
+		let this = if group == "input" {
+			this.fill_from_history(year, day)?
+		}
+		else {
+			this
+		};
+		Ok(this)
+	}
+
+	/// Fills in the answers the submission history knows to be right, where
+	/// the answer file has none.
+	#[cfg(feature = "serde")]
+	fn fill_from_history(mut self, year: u16, day: u8) -> eyre::Result<Self> {
+		let history = crate::history::History::load(year, day)?;
+		for part in [1, 2] {
+			if let Some(answer) = history.correct_answer(part) {
+				self.answers
+					.entry(part)
+					.or_insert_with(|| answer.to_owned());
+			}
+		}
+		Ok(self)
+	}
+
+	/// Gets the known answer to a part.
+	pub fn get(&self, part: u8) -> Option<&str> {
+		self.answers.get(&part).map(String::as_str)
+	}
+
+	/// Compares an answer with the known one.
+	///
+	/// This produces `None` if the part has no known answer, so there is
+	/// nothing to compare with.
+	pub fn check(
+		&self,
+		part: u8,
+		actual: Answer,
+	) -> Option<Result<(), Mismatch>> {
+		let expected = self.get(part)?;
+		if actual == expected {
+			return Some(Ok(()));
+		}
+		Some(Err(Mismatch {
+			part,
+			expected: expected.to_owned(),
+			actual,
+		}))
+	}
+}
+
+/// An answer which is not the known one.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Mismatch {
+	pub part:     u8,
+	pub expected: String,
+	pub actual:   Answer,
+}
+
+impl Mismatch {
+	/// How far the answer is from the known one, when both are numbers.
+	///
+	/// Being off by one, or by a factor, says a lot about where the bug is.
+	pub fn difference(&self) -> Option<i128> {
+		let expected = self.expected.parse::<i128>().ok()?;
+		self.actual.to_i128()?.checked_sub(expected)
+	}
+}
+
+impl fmt::Display for Mismatch {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let Self {
+			part,
+			expected,
+			actual,
+		} = self;
+		write!(
+			fmt,
+			"part {part} answered {actual}, but expected {expected}"
+		)?;
+		match self.difference() {
+			Some(diff) if diff > 0 => write!(fmt, " ({diff} too high)"),
+			Some(diff) if diff < 0 => write!(fmt, " ({} too low)", -diff),
+			_ => Ok(()),
+		}
+	}
+}
+
+impl std::error::Error for Mismatch {
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn compares_answers() -> eyre::Result<()> {
+		let expected = Expected::parse("part_1 = 21\npart_2 = \"525152\"")?;
+		assert_eq!(expected.check(1, Answer::from(21)), Some(Ok(())));
+		assert_eq!(expected.check(2, Answer::from(525152u64)), Some(Ok(())));
+		let wrong = expected.check(1, Answer::from(20)).unwrap().unwrap_err();
+		assert_eq!(wrong.difference(), Some(-1));
+		assert_eq!(
+			wrong.to_string(),
+			"part 1 answered 20, but expected 21 (1 too low)"
+		);
+		assert_eq!(Expected::default().check(1, Answer::from(0)), None);
+		assert!(Expected::parse("part_3 = 1").is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,149 @@
+//! Walkthroughs of a day's approach, pulled from its README.
+//!
+//! Days which took some thought keep a `README.md` explaining it, which is
+//! also the module's documentation. When run with `--explain`, the harness
+//! prints the part of that README which describes each phase just before the
+//! phase runs, so that the run reads as a walkthrough.
+//!
+//! A README divides itself into phases with HTML comments, which neither
+//! rustdoc nor a Markdown viewer shows:
+//!
+//! ```markdown
+//! <!-- explain: parse -->
+//! How the input is read ...
+//!
+//! <!-- explain: part1 part2 -->
+//! How both parts are solved ...
+//!
+//! <!-- explain: end -->
+//! Text which belongs to no phase.
+//! ```
+//!
+//! Each marker starts a section which runs until the next marker, and may name
+//! more than one phase. A README without markers is divided by its headings
+//! instead: the text before the first `##` heading explains parsing, and
+//! sections titled "Part 1" or "Part 2" (or "Part One", "Part Two") explain
+//! their part.
+
+use std::collections::BTreeMap;
+
+/// The phases of a run which can be explained.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Phase {
+	Parse,
+	Part1,
+	Part2,
+}
+
+impl Phase {
+	/// Finds the phase named by a marker.
+	pub fn from_marker(name: &str) -> Option<Self> {
+		match name {
+			"parse" => Some(Self::Parse),
+			"part1" => Some(Self::Part1),
+			"part2" => Some(Self::Part2),
+			_ => None,
+		}
+	}
+
+	/// Finds the phase named by a heading, if it is a part heading.
+	fn from_heading(heading: &str) -> Option<Self> {
+		match heading.trim().to_lowercase().as_str() {
+			"part 1" | "part one" => Some(Self::Part1),
+			"part 2" | "part two" => Some(Self::Part2),
+			_ => None,
+		}
+	}
+}
+
+/// A README, divided into the sections which explain each phase.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Walkthrough {
+	sections: BTreeMap<Phase, String>,
+}
+
+impl Walkthrough {
+	/// Divides a README into sections.
+	pub fn parse(markdown: &str) -> Self {
+		let has_markers = markdown.lines().any(|line| marker(line).is_some());
+		let mut out = Self::default();
+		let mut current = if has_markers {
+			vec![]
+		}
+		else {
+			vec![Phase::Parse]
+		};
+		for line in markdown.lines() {
+			if has_markers {
+				if let Some(names) = marker(line) {
+					current = names
+						.split_whitespace()
+						.filter_map(Phase::from_marker)
+						.collect();
+					continue;
+				}
+			}
+			else if let Some(heading) = line.strip_prefix("## ") {
+				current = Phase::from_heading(heading).into_iter().collect();
+				continue;
+			}
+			else if line.starts_with("# ") {
+				// The title is shown by the harness already.
+				continue;
+			}
+			for &phase in &current {
+				let text = out.sections.entry(phase).or_default();
+				text.push_str(line);
+				text.push('\n');
+			}
+		}
+		out.sections.retain(|_, text| {
+			*text = text.trim().to_owned();
+			!text.is_empty()
+		});
+		out
+	}
+
+	/// Gets the explanation of a phase, if the README has one.
+	pub fn section(&self, phase: Phase) -> Option<&str> {
+		self.sections.get(&phase).map(String::as_str)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.sections.is_empty()
+	}
+}
+
+/// Gets the phase list out of a marker comment.
+fn marker(line: &str) -> Option<&str> {
+	line.trim()
+		.strip_prefix("<!--")?
+		.strip_suffix("-->")?
+		.trim()
+		.strip_prefix("explain:")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn divides_by_markers() {
+		let walk = Walkthrough::parse(
+			"# Title\n\nintro\n<!-- explain: parse -->\nreading\n<!-- explain: \
+			 part1 part2 -->\n## Solving\nboth\n<!-- explain: end \
+			 -->\nfootnote\n",
+		);
+		assert_eq!(walk.section(Phase::Parse), Some("reading"));
+		assert_eq!(walk.section(Phase::Part1), Some("## Solving\nboth"));
+		assert_eq!(walk.section(Phase::Part2), walk.section(Phase::Part1));
+	}
+
+	#[test]
+	fn divides_by_headings() {
+		let walk = Walkthrough::parse(include_str!("y2023/d20/README.md"));
+		assert!(walk.section(Phase::Parse).unwrap().starts_with("This is a"));
+		assert!(walk.section(Phase::Part1).unwrap().starts_with("This is a"));
+		assert!(walk.section(Phase::Part2).unwrap().starts_with("This ..."));
+	}
+}
@@ -0,0 +1,463 @@
+//! General-purpose directed graphs.
+//!
+//! Where [`web`](crate::web) is a specialized routing table, this module is
+//! the plain adjacency list that most graph puzzles want: nodes carry labels,
+//! edges carry weights, and the usual algorithms (layered traversal,
+//! dependency ordering, component discovery, and flow) work on any of them.
+//!
+//! Nodes are addressed by [`NodeId`]s, which are dense indices assigned in the
+//! order the labels are first seen. Undirected graphs are stored as pairs of
+//! opposing directed edges.
+
+use std::{
+	collections::{
+		BTreeSet,
+		HashMap,
+		VecDeque,
+	},
+	hash::Hash,
+};
+
+/// The index of a node within its graph.
+pub type NodeId = usize;
+
+/// A directed edge.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edge<E> {
+	pub from:   NodeId,
+	pub to:     NodeId,
+	pub weight: E,
+}
+
+/// An adjacency-list graph with labeled nodes and weighted edges.
+#[derive(Clone, Debug)]
+pub struct Graph<N: Eq + Hash, E = ()> {
+	/// The label of each node, by ID.
+	nodes:    Vec<N>,
+	/// Finds the ID of a node by its label.
+	ids:      HashMap<N, NodeId>,
+	/// Every edge in the graph, in insertion order.
+	edges:    Vec<Edge<E>>,
+	/// The indices in `edges` of each node's outbound edges.
+	outbound: Vec<Vec<usize>>,
+}
+
+impl<N: Eq + Hash + Clone, E> Graph<N, E> {
+	/// Creates an empty graph.
+	pub fn new() -> Self {
+		Self {
+			nodes:    Vec::new(),
+			ids:      HashMap::new(),
+			edges:    Vec::new(),
+			outbound: Vec::new(),
+		}
+	}
+
+	/// Builds a directed graph from `(from, to, weight)` triples, adding nodes
+	/// as they are named.
+	pub fn from_edges(edges: impl IntoIterator<Item = (N, N, E)>) -> Self {
+		let mut out = Self::new();
+		for (from, to, weight) in edges {
+			let (from, to) = (out.add_node(from), out.add_node(to));
+			out.add_edge(from, to, weight);
+		}
+		out
+	}
+
+	/// Builds an undirected graph from `(one, two, weight)` triples.
+	pub fn from_undirected_edges(
+		edges: impl IntoIterator<Item = (N, N, E)>,
+	) -> Self
+	where E: Clone {
+		let mut out = Self::new();
+		for (one, two, weight) in edges {
+			let (one, two) = (out.add_node(one), out.add_node(two));
+			out.add_undirected_edge(one, two, weight);
+		}
+		out
+	}
+
+	/// Adds a node to the graph, returning its ID. If a node with this label is
+	/// already present, its existing ID is returned instead.
+	pub fn add_node(&mut self, label: N) -> NodeId {
+		if let Some(&id) = self.ids.get(&label) {
+			return id;
+		}
+		let id = self.nodes.len();
+		self.nodes.push(label.clone());
+		self.ids.insert(label, id);
+		self.outbound.push(Vec::new());
+		id
+	}
+
+	/// Adds a directed edge between two existing nodes.
+	///
+	/// ## Panics
+	///
+	/// This panics if either node is not in the graph.
+	pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: E) {
+		assert!(to < self.nodes.len(), "node {to} is not in the graph");
+		self.outbound[from].push(self.edges.len());
+		self.edges.push(Edge { from, to, weight });
+	}
+
+	/// Adds a pair of opposing edges between two existing nodes.
+	pub fn add_undirected_edge(&mut self, one: NodeId, two: NodeId, weight: E)
+	where E: Clone {
+		self.add_edge(one, two, weight.clone());
+		self.add_edge(two, one, weight);
+	}
+}
+
+impl<N: Eq + Hash, E> Graph<N, E> {
+	pub fn node_count(&self) -> usize {
+		self.nodes.len()
+	}
+
+	pub fn edge_count(&self) -> usize {
+		self.edges.len()
+	}
+
+	/// Gets the label of a node.
+	pub fn node(&self, id: NodeId) -> Option<&N> {
+		self.nodes.get(id)
+	}
+
+	/// Finds a node by its label.
+	pub fn id_of(&self, label: &N) -> Option<NodeId> {
+		self.ids.get(label).copied()
+	}
+
+	/// Views every edge in the graph.
+	pub fn edges(&self) -> &[Edge<E>] {
+		&self.edges
+	}
+
+	/// Iterates over the edges leaving a node.
+	pub fn outbound(&self, id: NodeId) -> impl '_ + Iterator<Item = &Edge<E>> {
+		self.outbound
+			.get(id)
+			.into_iter()
+			.flatten()
+			.map(|&idx| &self.edges[idx])
+	}
+
+	/// Iterates over the nodes directly reachable from a node.
+	pub fn neighbors(&self, id: NodeId) -> impl '_ + Iterator<Item = NodeId> {
+		self.outbound(id).map(|edge| edge.to)
+	}
+
+	/// Groups the nodes reachable from `start` by their distance from it, in
+	/// edges. The first layer holds only `start`.
+	pub fn bfs_layers(&self, start: NodeId) -> Vec<Vec<NodeId>> {
+		let mut seen = vec![false; self.node_count()];
+		let mut layers = Vec::new();
+		let mut layer = vec![start];
+		seen[start] = true;
+		while !layer.is_empty() {
+			let mut next = Vec::new();
+			for &node in &layer {
+				for neighbor in self.neighbors(node) {
+					if !seen[neighbor] {
+						seen[neighbor] = true;
+						next.push(neighbor);
+					}
+				}
+			}
+			layers.push(layer);
+			layer = next;
+		}
+		layers
+	}
+
+	/// Lists the nodes reachable from `start` in depth-first preorder.
+	pub fn dfs_order(&self, start: NodeId) -> Vec<NodeId> {
+		let mut seen = vec![false; self.node_count()];
+		let mut order = Vec::new();
+		let mut stack = vec![start];
+		while let Some(node) = stack.pop() {
+			if seen[node] {
+				continue;
+			}
+			seen[node] = true;
+			order.push(node);
+			// Push in reverse so that the first-added edge is explored first.
+			let neighbors = self.neighbors(node).collect::<Vec<_>>();
+			stack.extend(neighbors.into_iter().rev().filter(|&n| !seen[n]));
+		}
+		order
+	}
+
+	/// Orders the nodes so that every edge points from an earlier node to a
+	/// later one.
+	///
+	/// Among nodes which are free to go next, the lowest ID goes first, so the
+	/// result is deterministic. This fails if the graph has a cycle.
+	pub fn topological_sort(&self) -> eyre::Result<Vec<NodeId>> {
+		let mut indegree = vec![0usize; self.node_count()];
+		for edge in &self.edges {
+			indegree[edge.to] += 1;
+		}
+		let mut ready = BTreeSet::new();
+		ready.extend((0 .. self.node_count()).filter(|&n| indegree[n] == 0));
+		let mut order = Vec::with_capacity(self.node_count());
+		while let Some(node) = ready.pop_first() {
+			order.push(node);
+			for next in self.neighbors(node) {
+				indegree[next] -= 1;
+				if indegree[next] == 0 {
+					ready.insert(next);
+				}
+			}
+		}
+		if order.len() != self.node_count() {
+			eyre::bail!(
+				"graph has a cycle; {} nodes could not be ordered",
+				self.node_count() - order.len()
+			);
+		}
+		Ok(order)
+	}
+
+	/// Partitions the nodes into strongly connected components: maximal sets
+	/// in which every node can reach every other.
+	///
+	/// Components are listed in topological order of the condensed graph, so
+	/// no edge runs from a later component to an earlier one.
+	pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+		// Kosaraju's algorithm: order the nodes by when a depth-first search
+		// finishes with them, then collect components by searching the
+		// transposed graph in reverse finishing order.
+		let count = self.node_count();
+		let mut seen = vec![false; count];
+		let mut finished = Vec::with_capacity(count);
+		for root in 0 .. count {
+			if seen[root] {
+				continue;
+			}
+			seen[root] = true;
+			let mut stack =
+				vec![(root, self.neighbors(root).collect::<Vec<_>>())];
+			while let Some((node, pending)) = stack.last_mut() {
+				match pending.pop() {
+					Some(next) if !seen[next] => {
+						seen[next] = true;
+						let further = self.neighbors(next).collect();
+						stack.push((next, further));
+					},
+					Some(_) => {},
+					None => {
+						finished.push(*node);
+						stack.pop();
+					},
+				}
+			}
+		}
+
+		let mut inbound = vec![Vec::new(); count];
+		for edge in &self.edges {
+			inbound[edge.to].push(edge.from);
+		}
+		let mut assigned = vec![false; count];
+		let mut components = Vec::new();
+		for &root in finished.iter().rev() {
+			if assigned[root] {
+				continue;
+			}
+			assigned[root] = true;
+			let mut component = Vec::new();
+			let mut stack = vec![root];
+			while let Some(node) = stack.pop() {
+				component.push(node);
+				for &prev in &inbound[node] {
+					if !assigned[prev] {
+						assigned[prev] = true;
+						stack.push(prev);
+					}
+				}
+			}
+			component.sort_unstable();
+			components.push(component);
+		}
+		components
+	}
+
+	/// Partitions the nodes into groups which are connected when edge
+	/// direction is ignored.
+	pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+		let mut undirected = vec![Vec::new(); self.node_count()];
+		for edge in &self.edges {
+			undirected[edge.from].push(edge.to);
+			undirected[edge.to].push(edge.from);
+		}
+		let mut seen = vec![false; self.node_count()];
+		let mut components = Vec::new();
+		for root in 0 .. self.node_count() {
+			if seen[root] {
+				continue;
+			}
+			seen[root] = true;
+			let mut component = vec![root];
+			let mut cursor = 0;
+			while let Some(&node) = component.get(cursor) {
+				cursor += 1;
+				for &next in &undirected[node] {
+					if !seen[next] {
+						seen[next] = true;
+						component.push(next);
+					}
+				}
+			}
+			component.sort_unstable();
+			components.push(component);
+		}
+		components
+	}
+
+	/// Computes the maximum flow from `source` to `sink`, and the minimum cut
+	/// which limits it.
+	///
+	/// Each edge's capacity is taken from its weight by `capacity`. This uses
+	/// the Edmonds-Karp algorithm, which augments along shortest paths and so
+	/// runs in time polynomial in the graph size regardless of capacities.
+	pub fn max_flow(
+		&self,
+		source: NodeId,
+		sink: NodeId,
+		capacity: impl Fn(&E) -> i64,
+	) -> MinCut {
+		// The residual network stores each edge next to its reverse, so that
+		// `arc ^ 1` is always the arc running the other way.
+		let mut heads = Vec::with_capacity(self.edges.len() * 2);
+		let mut residual = Vec::with_capacity(self.edges.len() * 2);
+		let mut arcs = vec![Vec::new(); self.node_count()];
+		for edge in &self.edges {
+			arcs[edge.from].push(heads.len());
+			heads.push(edge.to);
+			residual.push(capacity(&edge.weight));
+			arcs[edge.to].push(heads.len());
+			heads.push(edge.from);
+			residual.push(0);
+		}
+
+		let mut flow = 0;
+		loop {
+			// Find the shortest augmenting path, remembering the arc used to
+			// reach each node.
+			let mut via = vec![None; self.node_count()];
+			let mut reached = vec![false; self.node_count()];
+			reached[source] = true;
+			let mut queue = VecDeque::from([source]);
+			while let Some(node) = queue.pop_front() {
+				if node == sink {
+					break;
+				}
+				for &arc in &arcs[node] {
+					let next = heads[arc];
+					if residual[arc] > 0 && !reached[next] {
+						reached[next] = true;
+						via[next] = Some(arc);
+						queue.push_back(next);
+					}
+				}
+			}
+			if !reached[sink] {
+				let source_side =
+					(0 .. self.node_count()).filter(|&n| reached[n]).collect();
+				return MinCut { flow, source_side };
+			}
+
+			let mut path = Vec::new();
+			let mut node = sink;
+			while let Some(arc) = via[node] {
+				path.push(arc);
+				node = heads[arc ^ 1];
+			}
+			let push = path.iter().map(|&arc| residual[arc]).min().unwrap_or(0);
+			for arc in path {
+				residual[arc] -= push;
+				residual[arc ^ 1] += push;
+			}
+			flow += push;
+		}
+	}
+}
+
+impl<N: Eq + Hash + Clone, E> Default for Graph<N, E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// The result of a maximum-flow computation.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MinCut {
+	/// The total flow from source to sink, which is also the total capacity of
+	/// the edges crossing the cut.
+	pub flow:        i64,
+	/// The nodes still reachable from the source once the cut edges are
+	/// saturated.
+	pub source_side: Vec<NodeId>,
+}
+
+impl MinCut {
+	/// Tests whether a node is on the source's side of the cut.
+	pub fn is_source_side(&self, node: NodeId) -> bool {
+		self.source_side.binary_search(&node).is_ok()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn graph_algorithms() -> eyre::Result<()> {
+		// Two cycles, a -> b -> c -> a and d -> e -> d, joined by c -> d, with
+		// a tail e -> f.
+		let graph = Graph::from_edges(
+			[
+				("a", "b"),
+				("b", "c"),
+				("c", "a"),
+				("c", "d"),
+				("d", "e"),
+				("e", "d"),
+				("e", "f"),
+			]
+			.map(|(from, to)| (from, to, 1)),
+		);
+		let id = |name| graph.id_of(&name).unwrap();
+
+		let layers = graph.bfs_layers(id("a"));
+		assert_eq!(layers, [vec![0], vec![1], vec![2], vec![3], vec![4], vec![
+			5
+		]]);
+		assert_eq!(graph.dfs_order(id("c")), [2, 0, 1, 3, 4, 5]);
+		assert!(graph.topological_sort().is_err());
+
+		let sccs = graph.strongly_connected_components();
+		assert_eq!(sccs, [vec![0, 1, 2], vec![3, 4], vec![5]]);
+		assert_eq!(graph.connected_components().len(), 1);
+
+		let cut = graph.max_flow(id("a"), id("f"), |&w| w);
+		assert_eq!(cut.flow, 1);
+		assert!(cut.is_source_side(id("a")));
+		assert!(!cut.is_source_side(id("f")));
+
+		let dag = Graph::from_edges([
+			("shirt", "tie", ()),
+			("tie", "jacket", ()),
+			("pants", "shoes", ()),
+		]);
+		let order = dag
+			.topological_sort()?
+			.into_iter()
+			.map(|id| *dag.node(id).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(order, ["shirt", "tie", "jacket", "pants", "shoes"]);
+		assert_eq!(dag.connected_components().len(), 2);
+		Ok(())
+	}
+}
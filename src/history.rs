@@ -0,0 +1,243 @@
+//! Records of answers submitted to the Advent of Code site.
+//!
+//! The site punishes wrong answers with a cool-down before it will accept
+//! another attempt, and it is embarrassingly easy to resubmit the same wrong
+//! number after a refactor that didn't actually change anything. Every
+//! submission is therefore logged, with its verdict, in
+//! `assets/history/{year}/d{day}.json`, and the submission client asks the log
+//! for permission before sending anything.
+//!
+//! The client is expected to call [`History::check`] before submitting and
+//! [`History::record`] (followed by [`History::save`]) once the site responds.
+
+use std::{
+	fs,
+	path::PathBuf,
+};
+
+use chrono::{
+	DateTime,
+	Duration,
+	Utc,
+};
+use eyre::Context;
+
+/// The cool-down the site imposes after a wrong answer, if it didn't say.
+const DEFAULT_WAIT_SECS: i64 = 60;
+
+/// The site's response to a submitted answer.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Verdict {
+	/// The answer was accepted.
+	Correct,
+	/// The answer was wrong, and the site said it was too high.
+	TooHigh,
+	/// The answer was wrong, and the site said it was too low.
+	TooLow,
+	/// The answer was wrong, with no further hint.
+	Wrong,
+	/// The site refused to judge the answer because a cool-down was active.
+	RateLimited,
+}
+
+impl Verdict {
+	/// Tests if the site judged the answer to be incorrect.
+	pub fn is_wrong(self) -> bool {
+		matches!(self, Self::TooHigh | Self::TooLow | Self::Wrong)
+	}
+}
+
+/// A single answer sent to the site.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Submission {
+	/// Which part of the puzzle the answer was for.
+	pub part:    u8,
+	/// The answer text, exactly as submitted.
+	pub answer:  String,
+	/// When the answer was submitted.
+	pub at:      DateTime<Utc>,
+	/// How the site judged the answer.
+	pub verdict: Verdict,
+	/// How long the site asked us to wait before trying again, in seconds.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub wait:    Option<i64>,
+}
+
+/// The full submission log for one day's puzzle.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct History {
+	pub year:        u16,
+	pub day:         u8,
+	pub submissions: Vec<Submission>,
+}
+
+impl History {
+	/// Creates an empty log for a day.
+	pub fn new(year: u16, day: u8) -> Self {
+		Self {
+			year,
+			day,
+			submissions: Vec::new(),
+		}
+	}
+
+	/// Computes the path of the log file for a day.
+	pub fn path(year: u16, day: u8) -> eyre::Result<PathBuf> {
+		let mut path = crate::config::asset_root()?;
+		path.push("assets");
+		path.push("history");
+		path.push(year.to_string());
+		path.push(format!("d{day:0>2}.json"));
+		Ok(path)
+	}
+
+	/// Loads the log for a day, or creates an empty one if none exists yet.
+	#[cfg_attr(feature = "trace", tracing::instrument)]
+	pub fn load(year: u16, day: u8) -> eyre::Result<Self> {
+		let path = Self::path(year, day)?;
+		if !path.exists() {
+			tracing::debug!(file=%path.display(), "no submission history");
+			return Ok(Self::new(year, day));
+		}
+		let text = fs::read_to_string(&path).wrap_err_with(|| {
+			format!("could not read history {}", path.display())
+		})?;
+		serde_json::from_str(&text).wrap_err_with(|| {
+			format!("could not parse history {}", path.display())
+		})
+	}
+
+	/// Writes the log back to disk.
+	#[cfg_attr(feature = "trace", tracing::instrument(skip(self), fields(year=%self.year, day=%self.day)))]
+	pub fn save(&self) -> eyre::Result<()> {
+		let path = Self::path(self.year, self.day)?;
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let text = serde_json::to_string_pretty(self)?;
+		fs::write(&path, text).wrap_err_with(|| {
+			format!("could not write history {}", path.display())
+		})
+	}
+
+	/// Appends a judged submission to the log.
+	pub fn record(
+		&mut self,
+		part: u8,
+		answer: impl ToString,
+		verdict: Verdict,
+		wait: Option<Duration>,
+	) {
+		self.submissions.push(Submission {
+			part,
+			answer: answer.to_string(),
+			at: Utc::now(),
+			verdict,
+			wait: wait.map(|w| w.num_seconds()),
+		});
+	}
+
+	/// Finds the accepted answer for a part, if there is one.
+	pub fn correct_answer(&self, part: u8) -> Option<&str> {
+		self.submissions
+			.iter()
+			.find(|s| s.part == part && s.verdict == Verdict::Correct)
+			.map(|s| s.answer.as_str())
+	}
+
+	/// Decides whether an answer may be submitted right now.
+	///
+	/// This refuses the submission if:
+	///
+	/// - the part has already been solved,
+	/// - the same answer has already been judged wrong,
+	/// - the answer is outside the bounds established by earlier "too high" or
+	///   "too low" verdicts,
+	/// - or the site's cool-down from the most recent wrong answer is still
+	///   running.
+	pub fn check(
+		&self,
+		part: u8,
+		answer: impl ToString,
+		now: DateTime<Utc>,
+	) -> eyre::Result<()> {
+		let answer = answer.to_string();
+		if let Some(correct) = self.correct_answer(part) {
+			eyre::bail!("part {part} was already solved with {correct}");
+		}
+		let attempts = self.submissions.iter().filter(|s| s.part == part);
+		if let Some(prior) = attempts
+			.clone()
+			.find(|s| s.answer == answer && s.verdict.is_wrong())
+		{
+			eyre::bail!(
+				"{answer} was already submitted at {} and judged {:?}",
+				prior.at,
+				prior.verdict
+			);
+		}
+		if let Ok(value) = answer.parse::<i128>() {
+			for prior in attempts.clone() {
+				let Ok(bound) = prior.answer.parse::<i128>()
+				else {
+					continue;
+				};
+				match prior.verdict {
+					Verdict::TooHigh if value >= bound => eyre::bail!(
+						"{answer} cannot be right: {bound} was already too high"
+					),
+					Verdict::TooLow if value <= bound => eyre::bail!(
+						"{answer} cannot be right: {bound} was already too low"
+					),
+					_ => {},
+				}
+			}
+		}
+		// The cool-down applies across both parts.
+		if let Some(last) = self
+			.submissions
+			.iter()
+			.filter(|s| {
+				s.verdict.is_wrong() || s.verdict == Verdict::RateLimited
+			})
+			.max_by_key(|s| s.at)
+		{
+			let wait = Duration::seconds(last.wait.unwrap_or(DEFAULT_WAIT_SECS));
+			let open_at = last.at + wait;
+			if now < open_at {
+				eyre::bail!(
+					"the site is still cooling down; wait {}s",
+					(open_at - now).num_seconds()
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn guards_submissions() {
+		let mut log = History::new(2023, 1);
+		log.record(1, 100, Verdict::TooLow, None);
+		log.record(1, 500, Verdict::TooHigh, Some(Duration::seconds(300)));
+		let last = log.submissions[1].at;
+
+		assert!(log.check(1, 300, last + Duration::seconds(10)).is_err());
+		let later = last + Duration::seconds(301);
+		assert!(log.check(1, 500, later).is_err());
+		assert!(log.check(1, 600, later).is_err());
+		assert!(log.check(1, 50, later).is_err());
+		assert!(log.check(1, 300, later).is_ok());
+
+		log.record(1, 300, Verdict::Correct, None);
+		assert!(log.check(1, 301, later).is_err());
+		assert!(log.check(2, 301, later).is_ok());
+	}
+}
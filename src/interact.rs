@@ -0,0 +1,144 @@
+//! Playing a puzzle interactively.
+//!
+//! Most solvers read their input once and answer. A few puzzles are games, or
+//! machines with a console, and are more fun (or easier to debug) when a
+//! person can take the controls. Such solvers implement
+//! [`Puzzle::interact`](crate::Puzzle::interact), which talks through an
+//! [`InteractIo`] rather than straight to the terminal. `run --interactive`
+//! connects it to stdin and stdout, so it can be played by hand or fed a
+//! script through a pipe; tests connect it to a [`Script`] instead.
+//!
+//! The exchange is line-based: the solver writes whatever it likes, then reads
+//! one line at a time. The end of the input ends the session.
+
+use std::{
+	collections::VecDeque,
+	io::{
+		self,
+		BufRead,
+		Write,
+	},
+};
+
+/// The solver's side of an interactive session.
+pub trait InteractIo {
+	/// Reads the next line, without its line ending, or `None` once the input
+	/// has ended.
+	fn read_line(&mut self) -> eyre::Result<Option<String>>;
+
+	/// Writes some text, as it is.
+	fn write(&mut self, text: &str) -> eyre::Result<()>;
+
+	/// Writes some text, followed by a line ending.
+	fn write_line(&mut self, text: &str) -> eyre::Result<()> {
+		self.write(text)?;
+		self.write("\n")
+	}
+
+	/// Writes a prompt, without a line ending, and reads the reply.
+	fn prompt(&mut self, prompt: &str) -> eyre::Result<Option<String>> {
+		self.write(prompt)?;
+		self.read_line()
+	}
+}
+
+/// A session over a pair of byte streams, such as stdin and stdout.
+#[derive(Debug)]
+pub struct Streams<R, W> {
+	reader: R,
+	writer: W,
+}
+
+impl<R: BufRead, W: Write> Streams<R, W> {
+	/// Runs a session over a reader and a writer.
+	pub fn new(reader: R, writer: W) -> Self {
+		Self { reader, writer }
+	}
+
+	/// Takes back the reader and writer.
+	pub fn into_inner(self) -> (R, W) {
+		(self.reader, self.writer)
+	}
+}
+
+impl Streams<io::StdinLock<'static>, io::Stdout> {
+	/// Runs a session in the terminal.
+	pub fn terminal() -> Self {
+		Self::new(io::stdin().lock(), io::stdout())
+	}
+}
+
+impl<R: BufRead, W: Write> InteractIo for Streams<R, W> {
+	fn read_line(&mut self) -> eyre::Result<Option<String>> {
+		let mut line = String::new();
+		if self.reader.read_line(&mut line)? == 0 {
+			return Ok(None);
+		}
+		let len = line.trim_end_matches(['\r', '\n']).len();
+		line.truncate(len);
+		Ok(Some(line))
+	}
+
+	fn write(&mut self, text: &str) -> eyre::Result<()> {
+		self.writer.write_all(text.as_bytes())?;
+		// Prompts have no line ending, and must be seen before the reply is
+		// typed.
+		self.writer.flush()?;
+		Ok(())
+	}
+}
+
+/// A session with its input written in advance, which keeps everything the
+/// solver writes.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Script {
+	input:  VecDeque<String>,
+	output: String,
+}
+
+impl Script {
+	/// Prepares a session which reads these lines, in order.
+	pub fn new<S: Into<String>>(lines: impl IntoIterator<Item = S>) -> Self {
+		Self {
+			input:  lines.into_iter().map(Into::into).collect(),
+			output: String::new(),
+		}
+	}
+
+	/// Views everything the solver has written so far.
+	pub fn output(&self) -> &str {
+		&self.output
+	}
+}
+
+impl InteractIo for Script {
+	fn read_line(&mut self) -> eyre::Result<Option<String>> {
+		Ok(self.input.pop_front())
+	}
+
+	fn write(&mut self, text: &str) -> eyre::Result<()> {
+		self.output.push_str(text);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sessions_read_lines() -> eyre::Result<()> {
+		let mut streams = Streams::new("up 3\r\ndown\n".as_bytes(), Vec::new());
+		assert_eq!(streams.prompt("> ")?.as_deref(), Some("up 3"));
+		assert_eq!(streams.prompt("> ")?.as_deref(), Some("down"));
+		assert_eq!(streams.prompt("> ")?, None);
+		streams.write_line("bye")?;
+		assert_eq!(streams.into_inner().1, b"> > > bye\n");
+
+		let mut script = Script::new(["left"]);
+		assert_eq!(script.prompt("? ")?.as_deref(), Some("left"));
+		assert_eq!(script.read_line()?, None);
+		assert_eq!(script.output(), "? ");
+		Ok(())
+	}
+}
@@ -1,12 +1,25 @@
 #![doc = include_str!("rust.md")]
 
 use std::{
+	any::Any,
+	borrow::Cow,
+	cmp,
 	collections::BTreeMap,
-	env,
 	fmt,
 	fs,
+	hash::{
+		Hash,
+		Hasher,
+	},
+	iter,
 	ops::RangeInclusive,
-	path::PathBuf,
+	panic::Location,
+	path::{
+		self,
+		Path,
+		PathBuf,
+	},
+	str::FromStr,
 	sync::OnceLock,
 };
 
@@ -16,19 +29,83 @@ pub use funty::{
 	Signed,
 };
 use nom::{
-	branch::alt,
-	bytes::complete::tag,
+	bytes::complete::take_till1,
 	character::complete::digit1,
-	combinator::{
-		map_res,
-		value,
+	combinator::map_res,
+	error::{
+		Error as NomError,
+		ErrorKind,
 	},
 	IResult,
 };
 use tap::Tap;
 
+// Without the `trace` feature, `tracing::` paths lead to the no-op macros in
+// `tracing_shim` rather than to the `tracing` crate.
+#[cfg(not(feature = "trace"))]
+extern crate self as tracing;
+#[cfg(not(feature = "trace"))]
+use tracing_shim::{
+	debug,
+	debug_span,
+	error,
+	error_span,
+	info,
+	trace,
+	warn,
+};
+
+pub mod algebra;
+pub mod answer;
+pub mod artifacts;
+pub mod bench;
+pub mod budget;
+#[cfg(feature = "serde")]
+pub mod cache;
+pub mod cancel;
+pub mod checked;
+pub mod collections;
+pub mod config;
 pub mod coords;
+mod described;
 pub mod dict;
+pub mod dump;
+#[cfg(feature = "embed")]
+pub mod embedded;
+pub mod error;
+pub mod expected;
+pub mod explain;
+pub mod graph;
+#[cfg(feature = "serde")]
+pub mod history;
+pub mod interact;
+pub mod memo;
+pub mod metrics;
+pub mod modular;
+pub mod normalize;
+pub mod numerals;
+pub mod ocr;
+pub mod optimize;
+pub mod params;
+pub mod propagate;
+#[cfg(feature = "serde")]
+pub mod provenance;
+pub mod random;
+pub mod ratio;
+#[cfg(feature = "serde")]
+pub mod results;
+pub mod roundtrip;
+pub mod schedule;
+pub mod search;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod sim;
+pub mod snapshot;
+pub mod standalone;
+pub mod text;
+#[cfg(not(feature = "trace"))]
+#[doc(hidden)]
+pub mod tracing_shim;
 pub mod web;
 pub mod y2015;
 pub mod y2016;
@@ -43,6 +120,10 @@ pub mod y2024;
 
 pub mod prelude {
 	pub use crate::{
+		answer::Answer,
+		artifacts::Artifact,
+		checked::CheckedIterator,
+		error::AocError,
 		ParseResult,
 		Parseable,
 		Parsed,
@@ -52,11 +133,25 @@ pub mod prelude {
 	};
 }
 
-pub use crate::coords::{
-	Cartesian2DPoint as Coord2D,
-	Cartesian2DSpace as Grid2D,
-	Cartesian3DPoint as Coord3D,
-	Cartesian3DSpace as Grid3D,
+use crate::described::Described;
+pub use crate::{
+	answer::Answer,
+	artifacts::Artifact,
+	coords::{
+		Cartesian2DPoint as Coord2D,
+		Cartesian2DSpace as Grid2D,
+		Cartesian3DPoint as Coord3D,
+		Cartesian3DSpace as Grid3D,
+	},
+	error::AocError,
+	normalize::Normalization,
+	numerals::{
+		written_digit,
+		written_number,
+		written_ordinal,
+	},
+	params::ParamMap,
+	schedule::Difficulty,
 };
 
 /// The output of the main data parsers.
@@ -66,120 +161,817 @@ pub type ParseResult<I, T> = IResult<I, T>;
 /// solver. This allows the execution harness to dispatch into any given day's
 /// solver without having concrete knowledge of the puzzle logic.
 pub type DynParser =
-	for<'a> fn(&'a str) -> ParseResult<&'a str, Box<dyn Puzzle>>;
+	for<'a> fn(&'a str) -> ParseResult<&'a str, Box<dyn Puzzle + Send>>;
 
 /// Represents an entry in the puzzle set.
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+///
+/// Entries are compared, ordered, and hashed by their year and day alone. The
+/// function pointers they carry have no meaningful identity, and the registry
+/// never holds two entries for the same puzzle.
+#[derive(Clone, Copy, Debug)]
 pub struct Solver {
 	/// The year half of the puzzle's identifier.
 	pub year: u16,
 	/// The day half of the puzzle's identifier.
-	pub day:  u8,
+	pub day: u8,
 	/// The puzzle entry point. This function parses input text and produces an
 	/// engine capable of solving the puzzle.
 	pub func: DynParser,
+	/// Where the solver was registered, for reporting conflicts.
+	pub site: &'static Location<'static>,
+	/// Chooses how the input text is cleaned up before it is parsed. This is
+	/// the puzzle's [`Puzzle::input_normalization`].
+	pub normalization: fn() -> Normalization,
+	/// Estimates how expensive the solver is, for scheduling batches. This is
+	/// the puzzle's [`Puzzle::estimated_difficulty`].
+	pub difficulty: fn() -> Difficulty,
+	/// Serializes the puzzle's state, when its type allows. This is filled in
+	/// by [`register_solver!`]; see [`dump`].
+	pub dump: fn(&dyn Any) -> Option<eyre::Result<String>>,
+}
+
+impl PartialEq for Solver {
+	fn eq(&self, other: &Self) -> bool {
+		(self.year, self.day) == (other.year, other.day)
+	}
+}
+
+impl Eq for Solver {
+}
+
+impl PartialOrd for Solver {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Solver {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		(self.year, self.day).cmp(&(other.year, other.day))
+	}
+}
+
+impl Hash for Solver {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		(self.year, self.day).hash(state);
+	}
 }
 
 impl Solver {
 	/// Creates a new solver object from its components.
+	///
+	/// The caller's source location is recorded as the registration site.
+	#[track_caller]
 	pub const fn new(year: u16, day: u8, func: DynParser) -> Self {
-		Self { year, day, func }
+		Self {
+			year,
+			day,
+			func,
+			site: Location::caller(),
+			normalization: Normalization::default,
+			difficulty: Difficulty::default,
+			dump: dump::nothing,
+		}
 	}
 
-	/// Executes the solver
-	#[tracing::instrument(name = "solve", skip(self, group), fields(year=%self.year, day=%self.day))]
+	/// Sets how the solver's input is normalized before it is parsed.
+	pub const fn with_normalization(
+		mut self,
+		normalization: fn() -> Normalization,
+	) -> Self {
+		self.normalization = normalization;
+		self
+	}
+
+	/// Sets how expensive the solver claims to be.
+	pub const fn with_difficulty(
+		mut self,
+		difficulty: fn() -> Difficulty,
+	) -> Self {
+		self.difficulty = difficulty;
+		self
+	}
+
+	/// Sets how the solver's state is serialized for `--dump-state`.
+	pub const fn with_dump(
+		mut self,
+		dump: fn(&dyn Any) -> Option<eyre::Result<String>>,
+	) -> Self {
+		self.dump = dump;
+		self
+	}
+
+	/// Estimates how expensive the solver is.
+	pub fn difficulty(&self) -> Difficulty {
+		(self.difficulty)()
+	}
+
+	/// Cleans up input text as the puzzle asks, before it is parsed.
+	pub fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+		(self.normalization)().apply(text)
+	}
+
+	/// Executes the solver.
+	///
+	/// Each phase of the run is wrapped in the span named for it in
+	/// [`metrics`], so that a [`metrics::PhaseMetrics`] layer can time it.
 	pub fn solve(
 		&self,
 		group: &str,
 		part_1: bool,
 		part_2: bool,
-	) -> eyre::Result<(Option<i64>, Option<i64>)> {
+	) -> eyre::Result<(Option<Answer>, Option<Answer>)> {
+		self.solve_with_params(group, part_1, part_2, &ParamMap::new())
+	}
+
+	/// Executes the solver, with some parameters overriding those in the data
+	/// group's parameter file.
+	#[cfg_attr(feature = "trace", tracing::instrument(name = "run", skip(self, group, overrides), fields(year=%self.year, day=%self.day)))]
+	pub fn solve_with_params(
+		&self,
+		group: &str,
+		part_1: bool,
+		part_2: bool,
+		overrides: &ParamMap,
+	) -> eyre::Result<(Option<Answer>, Option<Answer>)> {
 		let text = self.load_input(group)?;
 		tracing::trace!("loaded input");
+		self.track_input(group, &text);
+		let mut params = self.load_params(group)?;
+		params.extend(overrides.iter());
+		self.run_text(&text, &params, part_1, part_2, |part, answer| {
+			self.record_answer(group, part, answer)
+		})
+	}
+
+	/// Executes the solver on input text supplied by the caller.
+	///
+	/// Unlike [`.solve()`](Self::solve), this never touches the filesystem:
+	/// no data group is read, no parameter file is applied, and no answer is
+	/// recorded, so it can be used wherever the input comes from something
+	/// other than `assets/`. The text is still [normalized](Self::normalize),
+	/// as text pasted into a form is even more likely to need it.
+	#[cfg_attr(feature = "trace", tracing::instrument(name = "run", skip(self, text, params), fields(year=%self.year, day=%self.day)))]
+	pub fn solve_with_input(
+		&self,
+		text: &str,
+		params: &ParamMap,
+		part_1: bool,
+		part_2: bool,
+	) -> eyre::Result<(Option<Answer>, Option<Answer>)> {
+		let text = self.normalize(text);
+		self.run_text(&text, params, part_1, part_2, |_, _| {})
+	}
+
+	/// Parses and solves input text, handing each answer to `record` as soon
+	/// as it has been checked.
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	fn run_text(
+		&self,
+		text: &str,
+		params: &ParamMap,
+		part_1: bool,
+		part_2: bool,
+		mut record: impl FnMut(u8, Answer),
+	) -> eyre::Result<(Option<Answer>, Option<Answer>)> {
 		for line in text.lines().take(3) {
 			tracing::trace!(%line, "input data");
 		}
 
-		let (rest, mut solver) = (self.func)(&text)
-			.map_err(|err| eyre::eyre!("{err}"))
+		let (rest, mut solver) = self
+			.parse(text)
+			.map_err(|err| AocError::InvalidInput(err.to_string()))
 			.wrap_err("failed to parse input")?;
-		if !rest.trim().is_empty() {
-			let rest = rest.lines().next().ok_or_else(|| {
-				eyre::eyre!("failed to render unparsed input for error message")
-			})?;
-			let rest = format!("{rest}...");
-			tracing::warn!(%rest, "unparsed input remaining");
-		}
-		let mut one = None;
-		if part_1 {
-			solver
-				.prepare_1()
-				.wrap_err("could not prepare for part 1")?;
-			one = Some(solver.part_1().wrap_err("could not solve part 1")?);
+		AocError::check_leftover(rest).wrap_err("failed to parse input")?;
+		solver
+			.set_params(params)
+			.wrap_err("could not apply the parameters")?;
+		solver.set_rng(random::rng());
+		tracing::error_span!(metrics::PROCESS)
+			.in_scope(|| solver.after_parse())
+			.wrap_err("input was not valid for the rules of the puzzle")?;
+		if part_1 || part_2 {
+			tracing::error_span!(metrics::PREPARE)
+				.in_scope(|| solver.prepare_common())
+				.wrap_err("could not prepare for either part")?;
 		}
 
-		let mut two = None;
-		if part_2 {
-			solver
-				.prepare_2()
-				.wrap_err("could not prepare for part 2")?;
-			two = Some(solver.part_2().wrap_err("could not solve part 2")?);
+		let mut answers = [None, None];
+		for (part, wanted) in [(1, part_1), (2, part_2)] {
+			if !wanted {
+				continue;
+			}
+			if part == 2 && !solver.has_part_2() {
+				tracing::info!(part = 2, "this puzzle has no part 2");
+				continue;
+			}
+			cancel::check()?;
+			let answer = run_part(&mut *solver, part, |_, _| Ok(()))?;
+			record(part, answer.clone());
+			answers[usize::from(part - 1)] = Some(answer);
 		}
 
+		let [one, two] = answers;
 		Ok((one, two))
 	}
 
-	#[tracing::instrument(name = "gather", skip(self))]
+	/// Reads the input text for a data group.
+	///
+	/// This only reads; runs which go on to record their answers should pass
+	/// the text to [`.track_input()`](Self::track_input) as well.
+	///
+	/// When the file does not exist, builds with the `embed` feature use the
+	/// copy compiled into the binary instead, if there is one. Otherwise, the
+	/// error names the path that was tried, and the [asset root](config) it
+	/// was built from.
+	///
+	/// Either way, the text is [normalized](Self::normalize) before it is
+	/// returned.
+	#[cfg_attr(
+		feature = "trace",
+		tracing::instrument(name = "gather", skip(self))
+	)]
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	pub fn load_input(&self, group: &str) -> eyre::Result<String> {
-		let mut path = PathBuf::from("src");
-		path.push(format!("y{}", self.year));
-		path.push(format!("d{:0>2}", self.day));
-		path.push(format!("{group}.txt"));
-		tracing::trace!(file=%path.display(), "generated input path");
-		let mut file = env::current_dir()?;
-		file.extend(path.components());
-		fs::read_to_string(&file)
-			.wrap_err_with(|| eyre::eyre!("could not read {}", file.display()))
+		let file = self.input_path(group)?;
+		tracing::trace!(file=%file.display(), "generated input path");
+		#[cfg(feature = "embed")]
+		if !file.exists() {
+			if let Some(text) = embedded::input(self.year, self.day, group) {
+				tracing::debug!("using the embedded input");
+				return Ok(self.normalize(text).into_owned());
+			}
+		}
+		if !file.exists() {
+			let root = config::asset_root()?;
+			eyre::bail!(
+				"there is no {group} input for {} day {}: {} does not exist. \
+				 Inputs are found under the asset root, {}; run from the \
+				 project root, or set it with `--asset-root` or `AOC_ASSETS`",
+				self.year,
+				self.day,
+				absolute(&file).display(),
+				absolute(&root).display(),
+			);
+		}
+		let text = fs::read_to_string(&file).wrap_err_with(|| {
+			eyre::eyre!("could not read {}", file.display())
+		})?;
+		Ok(match self.normalize(&text) {
+			Cow::Borrowed(_) => text,
+			Cow::Owned(normal) => {
+				tracing::debug!("normalized the input text");
+				normal
+			},
+		})
+	}
+
+	/// Fingerprints a data group's input text and compares it with the
+	/// [`provenance`] record of the last input seen for the group, then makes
+	/// the record describe this text.
+	///
+	/// A mismatch is logged as a warning, but does not stop the run, and
+	/// failing to read or write the record is only logged.
+	#[cfg_attr(
+		not(all(feature = "serde", feature = "trace")),
+		allow(unused_variables)
+	)]
+	pub fn track_input(&self, group: &str, text: &str) {
+		#[cfg(feature = "serde")]
+		if let Err(err) = provenance::with_day_lock(self.year, self.day, || {
+			self.update_provenance(group, text)
+		}) {
+			tracing::warn!(%err, "could not check the input's provenance");
+		}
+	}
+
+	/// Records an answer against the input it was computed from.
+	///
+	/// Failing to write the record is logged, not returned, as it has no
+	/// bearing on whether the answer is right.
+	#[cfg_attr(
+		not(all(feature = "serde", feature = "trace")),
+		allow(unused_variables)
+	)]
+	pub fn record_answer(&self, group: &str, part: u8, answer: Answer) {
+		#[cfg(feature = "serde")]
+		if let Err(err) = provenance::with_day_lock(self.year, self.day, || {
+			let mut records = provenance::Provenance::load(self.year, self.day)?;
+			records.record_answer(group, part, answer);
+			records.save()
+		}) {
+			tracing::warn!(%err, "could not record the answer's provenance");
+		}
+	}
+
+	/// Looks up the answer that this build computed for a part the last time
+	/// it saw the same input text and parameters.
+	///
+	/// See [`cache`] for how answers are remembered. Failing to read the cache
+	/// is logged, and treated as a miss.
+	#[cfg_attr(
+		not(all(feature = "serde", feature = "trace")),
+		allow(unused_variables)
+	)]
+	pub fn cached_answer(
+		&self,
+		text: &str,
+		params: &ParamMap,
+		part: u8,
+	) -> Option<Answer> {
+		#[cfg(feature = "serde")]
+		match cache::build_fingerprint().and_then(|build| {
+			cache::AnswerCache::load(self.year, self.day).map(|cache| {
+				cache.get(&cache::input_key(text, params), part, build)
+			})
+		}) {
+			Ok(answer) => return answer,
+			Err(err) => tracing::warn!(%err, "could not read the answer cache"),
+		}
+		None
+	}
+
+	/// Remembers an answer for [`.cached_answer()`](Self::cached_answer) to
+	/// find on a later run.
+	///
+	/// Failing to write the cache is logged, not returned.
+	#[cfg_attr(
+		not(all(feature = "serde", feature = "trace")),
+		allow(unused_variables)
+	)]
+	pub fn cache_answer(
+		&self,
+		text: &str,
+		params: &ParamMap,
+		part: u8,
+		answer: Answer,
+	) {
+		#[cfg(feature = "serde")]
+		if let Err(err) = cache::build_fingerprint().and_then(|build| {
+			provenance::with_day_lock(self.year, self.day, || {
+				let mut cache = cache::AnswerCache::load(self.year, self.day)?;
+				let input = cache::input_key(text, params);
+				cache.insert(&input, part, answer, build, chrono::Utc::now());
+				cache.save()
+			})
+		}) {
+			tracing::warn!(%err, "could not remember the answer");
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	fn update_provenance(&self, group: &str, text: &str) -> eyre::Result<()> {
+		use provenance::{
+			Check,
+			Provenance,
+		};
+
+		let mut records = Provenance::load(self.year, self.day)?;
+		match records.check(group, text, chrono::Utc::now()) {
+			Check::Unchanged => return Ok(()),
+			Check::New => tracing::debug!(%group, "recording new input"),
+			Check::Changed(old) => {
+				tracing::warn!(
+					%group,
+					old = %old.hash,
+					since = %old.seen,
+					"input has changed since it was last seen",
+				);
+				for (part, answer) in &old.answers {
+					tracing::warn!(
+						%part,
+						%answer,
+						"answer was computed from the old input",
+					);
+				}
+				if group == "input" {
+					let history = history::History::load(self.year, self.day)?;
+					if !history.submissions.is_empty() {
+						tracing::warn!(
+							submissions = history.submissions.len(),
+							"submission history may describe the old input",
+						);
+					}
+				}
+			},
+		}
+		records.save()
+	}
+
+	/// Loads the parameter overrides for a data group.
+	///
+	/// See [`params`] for where these live.
+	pub fn load_params(&self, group: &str) -> eyre::Result<ParamMap> {
+		ParamMap::load(group, self.year, self.day)
+	}
+
+	/// Lists the names of every input group available for this puzzle.
+	///
+	/// A group is any `.txt` file in the puzzle's directory; the name is the
+	/// file stem, suitable for passing to [`Self::load_input`]. Builds with
+	/// the `embed` feature list the embedded groups when the directory does
+	/// not exist.
+	pub fn input_groups(&self) -> eyre::Result<Vec<String>> {
+		let dir = self.input_dir()?;
+		#[cfg(feature = "embed")]
+		if !dir.exists() {
+			return Ok(embedded::groups(self.year, self.day));
+		}
+		let mut groups = fs::read_dir(&dir)
+			.wrap_err_with(|| eyre::eyre!("could not list {}", dir.display()))?
+			.filter_map(Result::ok)
+			.map(|entry| entry.path())
+			.filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+			.filter_map(|path| {
+				path.file_stem().and_then(|s| s.to_str()).map(str::to_owned)
+			})
+			.collect::<Vec<_>>();
+		groups.sort();
+		Ok(groups)
+	}
+
+	/// Computes the path of the input file for a data group.
+	pub fn input_path(&self, group: &str) -> eyre::Result<PathBuf> {
+		Ok(self.input_dir()?.join(format!("{group}.txt")))
+	}
+
+	/// Tests whether the puzzle's directory has an input file for a group, or
+	/// the binary has one embedded.
+	pub fn has_group(&self, group: &str) -> bool {
+		#[cfg(feature = "embed")]
+		if embedded::input(self.year, self.day, group).is_some() {
+			return true;
+		}
+		self.input_path(group).is_ok_and(|path| path.is_file())
+	}
+
+	/// Reads the `README.md` in the puzzle's directory, if it has one.
+	pub fn readme(&self) -> Option<String> {
+		fs::read_to_string(self.input_dir().ok()?.join("README.md")).ok()
+	}
+
+	/// Reads the puzzle's title from the first heading of the `README.md` in
+	/// its directory, if it has one.
+	pub fn title(&self) -> Option<String> {
+		self.readme()?
+			.lines()
+			.find_map(|line| line.strip_prefix("# "))
+			.map(|title| title.trim().to_owned())
+	}
+
+	/// Computes the directory holding this puzzle's input files.
+	fn input_dir(&self) -> eyre::Result<PathBuf> {
+		let mut dir = config::asset_root()?;
+		dir.push("src");
+		dir.push(format!("y{}", self.year));
+		dir.push(format!("d{:0>2}", self.day));
+		Ok(dir)
 	}
 
 	/// Parses the input into a solver engine.
-	#[tracing::instrument(name = "parse", skip(self, text))]
+	///
+	/// The engine knows which puzzle it is: its [`Puzzle::year_day`] comes
+	/// from this registration, and its [`Puzzle::title`] from the README.
+	#[cfg_attr(
+		feature = "trace",
+		tracing::instrument(name = "parse", skip(self, text))
+	)]
 	pub fn parse<'a>(
 		self,
 		text: &'a str,
-	) -> ParseResult<&'a str, Box<dyn Puzzle>> {
-		(self.func)(text)
+	) -> ParseResult<&'a str, Box<dyn Puzzle + Send>> {
+		(self.func)(text).map(|(rest, puzzle)| {
+			(
+				rest,
+				Box::new(Described::new(puzzle, self)) as Box<dyn Puzzle + Send>,
+			)
+		})
 	}
 }
 
-/// A collection of virtualized puzzle constructors, indexed by year and then
-/// day.
-pub type Registry = BTreeMap<u16, BTreeMap<u8, DynParser>>;
+/// A collection of registered solvers, indexed by year and then day.
+///
+/// Both levels are ordered, so iterating the registry always visits years in
+/// ascending order, and the days of each year in ascending order, regardless
+/// of the order in which the linker happened to lay out [`SOLVERS`].
+pub type Registry = BTreeMap<u16, BTreeMap<u8, Solver>>;
 
 /// An unsorted collection of daily puzzle solvers.
+///
+/// The order of this slice is up to the linker and must not be relied upon.
+/// Because of that, two entries for the same day cannot be tie-broken in any
+/// meaningful way, and are rejected when the registry is built.
 #[linkme::distributed_slice]
 pub static SOLVERS: [Solver];
 
+/// Registers a day's solver.
+///
+/// The year and day are given as the names of the modules the call is in,
+/// and the call fails to compile if it is anywhere else, so that a module
+/// copied from another day cannot quietly register itself as that day.
+///
+/// ```rust,ignore
+/// // in src/y2023/d19/mod.rs
+/// crate::register_solver!(y2023, d19, QualityControl);
+/// ```
+#[macro_export]
+macro_rules! register_solver {
+	($year:ident, $day:ident, $puzzle:ty) => {
+		const _: () = assert!(
+			$crate::module_is_day(
+				module_path!(),
+				stringify!($year),
+				stringify!($day),
+			),
+			concat!(
+				"register_solver!(",
+				stringify!($year),
+				", ",
+				stringify!($day),
+				", ..) must be called in the module `",
+				stringify!($year),
+				"::",
+				stringify!($day),
+				"`",
+			),
+		);
+		#[linkme::distributed_slice($crate::SOLVERS)]
+		static ITEM: $crate::Solver = $crate::Solver::new(
+			$crate::ident_number(stringify!($year), b'y'),
+			$crate::ident_number(stringify!($day), b'd') as u8,
+			|t| $crate::Parseable::parse_dyn_puzzle::<$puzzle>(t),
+		)
+		.with_normalization(<$puzzle as $crate::Puzzle>::input_normalization)
+		.with_difficulty(<$puzzle as $crate::Puzzle>::estimated_difficulty)
+		.with_dump(|puzzle| {
+			#[allow(unused_imports)]
+			use $crate::dump::{
+				ViaNothing as _,
+				ViaSerialize as _,
+			};
+			(&$crate::dump::Probe::<$puzzle>::new()).dump(puzzle)
+		});
+	};
+}
+
+/// Reads the number out of a module name like `y2023` or `d07`.
+///
+/// This is only public for [`register_solver!`], and fails compilation when
+/// called in a constant with a malformed name.
+#[doc(hidden)]
+pub const fn ident_number(ident: &str, prefix: u8) -> u16 {
+	let bytes = ident.as_bytes();
+	assert!(
+		bytes.len() > 1 && bytes[0] == prefix,
+		"module name has the wrong prefix"
+	);
+	let mut out = 0u16;
+	let mut idx = 1;
+	while idx < bytes.len() {
+		assert!(bytes[idx].is_ascii_digit(), "module name is not a number");
+		out = out * 10 + (bytes[idx] - b'0') as u16;
+		idx += 1;
+	}
+	out
+}
+
+/// Tests whether a module path ends in `::{year}::{day}`.
+#[doc(hidden)]
+pub const fn module_is_day(path: &str, year: &str, day: &str) -> bool {
+	let path = path.as_bytes();
+	let mut end = path.len();
+	let mut part = 0;
+	while part < 2 {
+		let name = if part == 0 { day } else { year }.as_bytes();
+		if end < name.len() + 2 {
+			return false;
+		}
+		let mut idx = 0;
+		while idx < name.len() {
+			if path[end - name.len() + idx] != name[idx] {
+				return false;
+			}
+			idx += 1;
+		}
+		end -= name.len();
+		if path[end - 1] != b':' || path[end - 2] != b':' {
+			return false;
+		}
+		end -= 2;
+		part += 1;
+	}
+	true
+}
+
+/// Two solvers registered for the same day.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DuplicateSolver {
+	pub year:   u16,
+	pub day:    u8,
+	/// The registration site of the solver seen first.
+	pub first:  &'static Location<'static>,
+	/// The registration site of the solver seen second.
+	pub second: &'static Location<'static>,
+}
+
+impl fmt::Display for DuplicateSolver {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			fmt,
+			"{}-{:0>2} is registered twice, at {} and at {}",
+			self.year, self.day, self.first, self.second
+		)
+	}
+}
+
+impl std::error::Error for DuplicateSolver {
+}
+
+/// Builds a registry out of a list of solvers, rejecting duplicated days.
+pub fn build_registry(solvers: &[Solver]) -> Result<Registry, DuplicateSolver> {
+	let mut registry = Registry::new();
+	for &solver in solvers {
+		let Solver { year, day, .. } = solver;
+		if let Some(prior) =
+			registry.entry(year).or_default().insert(day, solver)
+		{
+			// Report the sites in source order, so that the message does not
+			// change with the link order.
+			let (first, second) = if prior.site <= solver.site {
+				(prior.site, solver.site)
+			}
+			else {
+				(solver.site, prior.site)
+			};
+			return Err(DuplicateSolver {
+				year,
+				day,
+				first,
+				second,
+			});
+		}
+	}
+	Ok(registry)
+}
+
+/// Gets a structured view of all registered days, or the first conflict among
+/// them.
+pub fn try_solutions() -> Result<&'static Registry, DuplicateSolver> {
+	static REGISTRY: OnceLock<Result<Registry, DuplicateSolver>> =
+		OnceLock::new();
+	REGISTRY
+		.get_or_init(|| build_registry(&SOLVERS))
+		.as_ref()
+		.map_err(|&err| err)
+}
+
 /// Gets a structured view of all registered days.
+///
+/// ## Panics
+///
+/// This panics if two solvers are registered for the same day. Use
+/// [`try_solutions`] to handle that case instead.
 pub fn solutions() -> &'static Registry {
-	static REGISTRY: OnceLock<Registry> = OnceLock::new();
-	REGISTRY.get_or_init(|| {
-		SOLVERS.iter().fold(
-			Registry::new(),
-			|mut accum, &Solver { year, day, func }| {
-				accum.entry(year).or_default().insert(day, func);
-				accum
-			},
-		)
-	})
+	try_solutions().unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Gets every registered solver for a given day of the calendar, across all
+/// years, in year order.
+pub fn solutions_for_day(day: u8) -> Vec<Solver> {
+	solutions()
+		.values()
+		.filter_map(|days| days.get(&day).copied())
+		.collect()
+}
+
+/// Gets the most recent answer recorded for a puzzle part, computed from a data
+/// group's current input.
+///
+/// Answers are recorded in the input [`provenance`] files each time a solver
+/// runs, and discarded when the input they came from changes. This lets one
+/// run pick up a value produced by another (a previous day, or the other
+/// part) without recomputing it. The answer is returned as the text it was
+/// recorded as, for the caller to parse.
+#[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+pub fn prior_answer(
+	year: u16,
+	day: u8,
+	group: &str,
+	part: u8,
+) -> eyre::Result<Option<String>> {
+	#[cfg(feature = "serde")]
+	return provenance::Provenance::load(year, day)
+		.map(|records| records.answer(group, part).map(str::to_owned));
+	#[cfg(not(feature = "serde"))]
+	eyre::bail!("recorded answers require the `serde` feature");
+}
+
+/// The points in one part's run at which [`run_part`] hands the solver back
+/// to its caller.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PartPhase {
+	/// After `.prepare_1()` or `.prepare_2()`.
+	Prepared,
+	/// After `.answer_1()` or `.answer_2()`.
+	Solved,
+}
+
+/// Prepares for and solves one part of a puzzle, and checks that the answer
+/// is plausible.
+///
+/// `checkpoint` is called after each phase, so that the caller can look at or
+/// save the solver's state between them. The shared preparation is the
+/// caller's business, since it must only run once for both parts.
+pub fn run_part(
+	solver: &mut dyn Puzzle,
+	part: u8,
+	mut checkpoint: impl FnMut(PartPhase, &dyn Puzzle) -> eyre::Result<()>,
+) -> eyre::Result<Answer> {
+	tracing::error_span!(metrics::PREPARE, part)
+		.in_scope(|| {
+			tracing::info!("preparing");
+			match part {
+				1 => solver.prepare_1(),
+				_ => solver.prepare_2(),
+			}
+		})
+		.wrap_err_with(|| format!("could not prepare for part {part}"))?;
+	checkpoint(PartPhase::Prepared, solver)?;
+	let answer = tracing::error_span!(metrics::SOLVE, part)
+		.in_scope(|| {
+			tracing::info!("running");
+			match part {
+				1 => solver.answer_1(),
+				_ => solver.answer_2(),
+			}
+		})
+		.wrap_err_with(|| format!("could not solve part {part}"))?;
+	checkpoint(PartPhase::Solved, solver)?;
+	solver.validate_answer(part, &answer).wrap_err_with(|| {
+		format!("part {part} answer {answer} is implausible")
+	})?;
+	Ok(answer)
 }
 
 /// A solver for the day's pair of puzzles.
 ///
 /// Each day's module implements this trait and registers some
-/// `(&str) -> Result<Box<dyn Puzzle>>` with the above collection in order for
-/// the execution harness to find and run it.
-pub trait Puzzle {
+/// `(&str) -> Result<Box<dyn Puzzle + Send>>` with the above collection in
+/// order for the execution harness to find and run it.
+///
+/// Solvers must be `Debug` so that the harness can dump their state while
+/// exploring a run, and `Send` so that it can move them onto another thread
+/// to run them in parallel or under a time limit. Registration fails to compile
+/// for solvers which are not `Send`; keep shared ownership in `Arc` rather
+/// than `Rc`. Interior mutability through `Cell` and `RefCell` is fine, since
+/// those are `Send` and only a solver's own thread ever touches it.
+///
+/// Every method fails with an `eyre::Report`. Failures which the harness should
+/// be able to tell apart, such as bad input or an unfinished part, carry an
+/// [`AocError`] inside it.
+pub trait Puzzle: fmt::Debug + Any {
+	/// Names the puzzle, for logs and reports.
+	///
+	/// Solvers produced by the registry answer this from the first heading
+	/// of the `README.md` next to their module, so there is rarely a reason to
+	/// override it. The default has no title.
+	fn title(&self) -> Option<&str> {
+		None
+	}
+
+	/// Identifies the puzzle by year and day.
+	///
+	/// Solvers produced by the registry answer this from their registration.
+	/// The default does not know.
+	fn year_day(&self) -> Option<(u16, u8)> {
+		None
+	}
+
+	/// Chooses how input text is cleaned up before it is parsed.
+	///
+	/// This is asked before there is a solver to ask, so it cannot look at
+	/// `self`. The default normalizes line endings and the end of the text;
+	/// solvers whose parsers would rather not see trailing spaces either can
+	/// add [`Normalization::TRAILING_WHITESPACE`], and solvers which need the
+	/// text exactly as written can return [`Normalization::empty()`].
+	fn input_normalization() -> Normalization
+	where Self: Sized {
+		Normalization::default()
+	}
+
+	/// Estimates how long the solver takes on its real input.
+	///
+	/// Batch runs start the most expensive solvers first, so that they do not
+	/// hold up the end of the batch, and can leave out the slow ones entirely.
+	/// Like [`.input_normalization()`](Self::input_normalization), this is
+	/// asked before any input is parsed. The default is
+	/// [`Difficulty::Quick`].
+	fn estimated_difficulty() -> Difficulty
+	where Self: Sized {
+		Difficulty::default()
+	}
+
 	/// Additional processing after the text input has been parsed.
 	///
 	/// This is always called, no matter which stages are being run.
@@ -187,6 +979,18 @@ pub trait Puzzle {
 		Ok(())
 	}
 
+	/// Does work that both parts depend on, such as building a distance
+	/// matrix or a graph.
+	///
+	/// The harness calls this exactly once per parsed solver, after
+	/// `.after_parse()` and before the first `.prepare_1()` or `.prepare_2()`,
+	/// whichever parts are being run. Expensive shared setup belongs here
+	/// rather than in `.prepare_1()`, since part 2 may be run on its own and
+	/// `.prepare_2()` may be called after part 1 has already run.
+	fn prepare_common(&mut self) -> eyre::Result<()> {
+		Ok(())
+	}
+
 	/// Prepares a solver to execute part 1.
 	fn prepare_1(&mut self) -> eyre::Result<()> {
 		Ok(())
@@ -198,7 +1002,16 @@ pub trait Puzzle {
 	/// solvers may wish to skip Part 1 if the computation is expensive and not
 	/// relevant to Part 2's work.
 	fn part_1(&mut self) -> eyre::Result<i64> {
-		eyre::bail!("have not yet solved part 1");
+		Err(AocError::NotImplemented { part: 1 }.into())
+	}
+
+	/// Tests whether the puzzle has a second part at all.
+	///
+	/// Day 25 only has one. Solvers which return `false` never have their
+	/// part-2 methods called; the harness reports the part as `n/a`, and does
+	/// not count it as a failure.
+	fn has_part_2(&self) -> bool {
+		true
 	}
 
 	/// Prepares a solver to execute part 2.
@@ -217,7 +1030,145 @@ pub trait Puzzle {
 	/// been called, and must tolerate the part-1 methods being either run *or*
 	/// not run.
 	fn part_2(&mut self) -> eyre::Result<i64> {
-		eyre::bail!("have not yet solved part 2");
+		Err(AocError::NotImplemented { part: 2 }.into())
+	}
+
+	/// Produces the answer to part 1, as the harness sees it.
+	///
+	/// This defaults to widening the result of `.part_1()`. Solvers whose
+	/// answer does not fit in an `i64` should override this instead.
+	fn answer_1(&mut self) -> eyre::Result<Answer> {
+		self.part_1().map(Answer::from)
+	}
+
+	/// Produces the answer to part 2, as the harness sees it.
+	///
+	/// This defaults to widening the result of `.part_2()`. Solvers whose
+	/// answer does not fit in an `i64` should override this instead.
+	fn answer_2(&mut self) -> eyre::Result<Answer> {
+		self.part_2().map(Answer::from)
+	}
+
+	/// Summarizes the parsed input, as a list of named statistics.
+	///
+	/// The harness reports these when run with `--parse-only`, which is useful
+	/// for checking that a new parser understood the input before any solving
+	/// logic exists. Good candidates are record counts, grid dimensions, and
+	/// value ranges. The default has nothing to report.
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		Vec::new()
+	}
+
+	/// Checks an answer against whatever the solver knows about the shape of a
+	/// correct one.
+	///
+	/// The harness calls this on every answer before reporting it, so that a
+	/// result which cannot possibly be right (negative where the puzzle counts
+	/// things, larger than a bound established by the input, and so on) fails
+	/// the run instead of being reported as a solution. The default accepts
+	/// everything.
+	fn validate_answer(&self, part: u8, answer: &Answer) -> eyre::Result<()> {
+		let _ = (part, answer);
+		Ok(())
+	}
+
+	/// Writes the parsed input back out in the puzzle's own text format.
+	///
+	/// Solvers which can do this take part in the parser round-trip check in
+	/// [`roundtrip`], which catches parsers that drop or shift part of their
+	/// input. It is only called straight after parsing. The default cannot
+	/// render anything.
+	fn render_input(&self) -> Option<String> {
+		None
+	}
+
+	/// Renders the solver's current state for a human to look at.
+	///
+	/// Solvers whose state has a useful picture (usually a grid) should
+	/// return it here. The default has nothing to show.
+	fn render_state(&self) -> Option<String> {
+		None
+	}
+
+	/// Serializes the solver's current state as JSON, for analysis outside
+	/// the harness.
+	///
+	/// Solvers registered with [`register_solver!`] whose types implement
+	/// `serde::Serialize` get this for free; solvers can override it to write
+	/// something more useful than their whole struct. The default has nothing
+	/// to write.
+	fn dump_state(&self) -> Option<eyre::Result<String>> {
+		None
+	}
+
+	/// Collects any intermediate products of the run which are worth keeping,
+	/// such as a rendered screen or a final grid drawing.
+	///
+	/// The harness calls this after the requested parts have run, and writes
+	/// the results to disk. Solvers should use this instead of printing
+	/// multi-line output directly.
+	fn artifacts(&self) -> Vec<Artifact> {
+		Vec::new()
+	}
+
+	/// Gives the harness access to the solver's step recorder, if it keeps
+	/// one.
+	///
+	/// Simulation-style solvers which record their steps in a
+	/// [`sim::Recorder`] should return it here, so that the harness can
+	/// switch it on before the run and collect the frames afterwards. The
+	/// default keeps no recording.
+	fn recorder(&mut self) -> Option<&mut sim::Recorder> {
+		None
+	}
+
+	/// Overrides one of the solver's scalar parameters by name.
+	///
+	/// This allows the harness to re-run a solver with different constants
+	/// without recompiling. The value is passed as text, and the solver is
+	/// responsible for parsing it.
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		eyre::bail!("no parameter named `{key}` (tried to set it to `{value}`)");
+	}
+
+	/// Applies a whole set of parameters, such as those kept for a data group.
+	///
+	/// The harness calls this right after parsing, before
+	/// [`.after_parse()`](Self::after_parse). By default, this passes each
+	/// parameter to [`.set_param()`](Self::set_param) in key order, and fails
+	/// on the first one the solver rejects.
+	fn set_params(&mut self, params: &ParamMap) -> eyre::Result<()> {
+		for (key, value) in params.iter() {
+			self.set_param(key, value)?;
+		}
+		Ok(())
+	}
+
+	/// Hands the solver a random number generator.
+	///
+	/// The harness calls this right after applying parameters, with a
+	/// generator seeded from the run's [seed](random). Solvers which use
+	/// randomized algorithms should keep it and draw from it, rather than from
+	/// an unseeded source, so that runs can be replayed. The default discards
+	/// it.
+	fn set_rng(&mut self, rng: random::StdRng) {
+		let _ = rng;
+	}
+
+	/// Plays the puzzle interactively, reading commands from `io` and writing
+	/// the solver's side of the exchange back to it.
+	///
+	/// The harness calls this from `run --interactive`, after parsing and
+	/// processing the input, with `io` connected to the terminal. Solvers for
+	/// game-like puzzles can implement it so that they can be driven by hand,
+	/// or by a script piped in, and should return once the input ends. The
+	/// default cannot be played.
+	fn interact(
+		&mut self,
+		io: &mut dyn interact::InteractIo,
+	) -> eyre::Result<()> {
+		let _ = io;
+		eyre::bail!("this puzzle cannot be played interactively");
 	}
 }
 
@@ -231,10 +1182,12 @@ pub trait Parsed<Input>: Sized {
 	}
 
 	/// Parses the input into a virtualized instance of `Self`.
-	fn parse_dyn_puzzle(src: Input) -> ParseResult<Input, Box<dyn Puzzle>>
-	where Self: 'static + Puzzle {
+	fn parse_dyn_puzzle(
+		src: Input,
+	) -> ParseResult<Input, Box<dyn Puzzle + Send>>
+	where Self: 'static + Puzzle + Send {
 		Self::parse_wyz_boxed(src)
-			.map(|(rest, this)| (rest, this as Box<dyn Puzzle>))
+			.map(|(rest, this)| (rest, this as Box<dyn Puzzle + Send>))
 	}
 }
 
@@ -246,9 +1199,9 @@ pub trait Parseable: Sized {
 	}
 
 	/// Parses a data stream into a virtualized puzzle object.
-	fn parse_dyn_puzzle<P: 'static + Parsed<Self> + Puzzle>(
+	fn parse_dyn_puzzle<P: 'static + Parsed<Self> + Puzzle + Send>(
 		self,
-	) -> ParseResult<Self, Box<dyn Puzzle>> {
+	) -> ParseResult<Self, Box<dyn Puzzle + Send>> {
 		P::parse_dyn_puzzle(self)
 	}
 }
@@ -262,25 +1215,57 @@ impl Parseable for &[u8] {
 }
 
 /// Unifies a series of inclusive ranges by joining any that overlap.
+///
+/// This allocates a new vector for every call. Loops which unify a fresh set
+/// of ranges on each pass should use [`unify_ranges_into`] instead.
 pub fn unify_ranges_inclusive<I: Integral>(
 	ranges: impl Iterator<Item = RangeInclusive<I>>,
 ) -> Vec<RangeInclusive<I>> {
-	ranges
-		.collect::<Vec<_>>()
-		.tap_mut(|v| v.sort_by_key(|r| *r.start()))
-		.into_iter()
-		.fold(Vec::<RangeInclusive<I>>::new(), |mut acc, next| {
-			if let Some(prev) = acc.last_mut() {
-				let (a1, a2) = (*prev.start(), *prev.end());
-				let (b1, b2) = (*next.start(), *next.end());
-				if a2 >= b1 {
-					*prev = a1.min(b1) ..= a2.max(b2);
-					return acc;
-				}
-			}
-			acc.push(next);
-			acc
-		})
+	Vec::new().tap_mut(|out| unify_ranges_into(ranges, out))
+}
+
+/// Unifies a series of inclusive ranges by joining any that overlap, writing
+/// them into `buf`.
+///
+/// The buffer is cleared first, and then sorted and merged in place, so a
+/// buffer reused across calls stops allocating once it has grown to fit.
+pub fn unify_ranges_into<I: Integral>(
+	ranges: impl IntoIterator<Item = RangeInclusive<I>>,
+	buf: &mut Vec<RangeInclusive<I>>,
+) {
+	buf.clear();
+	buf.extend(ranges);
+	buf.sort_unstable_by_key(|r| *r.start());
+	let mut len = 0;
+	for idx in 0 .. buf.len() {
+		let (start, end) = (*buf[idx].start(), *buf[idx].end());
+		if len > 0 && *buf[len - 1].end() >= start {
+			let prev = &mut buf[len - 1];
+			*prev = *prev.start() ..= end.max(*prev.end());
+		}
+		else {
+			buf[len] = start ..= end;
+			len += 1;
+		}
+	}
+	buf.truncate(len);
+}
+
+/// Joins overlapping ranges from a sequence which is already sorted by the
+/// start of each range, without collecting them.
+///
+/// Ranges which arrive out of order are not joined to the ranges before them.
+pub fn merge_sorted_ranges<I: Integral>(
+	ranges: impl IntoIterator<Item = RangeInclusive<I>>,
+) -> impl Iterator<Item = RangeInclusive<I>> {
+	let mut ranges = ranges.into_iter().peekable();
+	iter::from_fn(move || {
+		let (start, mut end) = ranges.next()?.into_inner();
+		while let Some(next) = ranges.next_if(|next| *next.start() <= end) {
+			end = end.max(*next.end());
+		}
+		Some(start ..= end)
+	})
 }
 
 /// Parses a sequence of decimal digits into a given numeric primitive.
@@ -289,26 +1274,279 @@ where <T as TryFrom<i8>>::Error: fmt::Debug {
 	map_res(digit1, T::from_str)(text)
 }
 
-pub fn written_number<T: Integral>(text: &str) -> IResult<&str, T>
-where <T as TryFrom<i8>>::Error: fmt::Debug {
-	alt((
-		value(T::try_from(0i8).expect("infallible"), tag("zero")),
-		value(T::try_from(1i8).expect("infallible"), tag("one")),
-		value(T::try_from(2i8).expect("infallible"), tag("two")),
-		value(T::try_from(3i8).expect("infallible"), tag("three")),
-		value(T::try_from(4i8).expect("infallible"), tag("four")),
-		value(T::try_from(5i8).expect("infallible"), tag("five")),
-		value(T::try_from(6i8).expect("infallible"), tag("six")),
-		value(T::try_from(7i8).expect("infallible"), tag("seven")),
-		value(T::try_from(8i8).expect("infallible"), tag("eight")),
-		value(T::try_from(9i8).expect("infallible"), tag("nine")),
-	))(text)
+/// Lifts a [`FromStr`] implementation into a parser of one word: the run of
+/// text up to the next whitespace.
+///
+/// Small types (instructions, identifiers, settings like `divide:3`) often
+/// already parse themselves with `FromStr`, and this lets them sit inside a
+/// larger `nom` grammar without being written twice. Use [`from_str_with`]
+/// for values which can contain spaces.
+pub fn from_str_parser<'a, T>() -> impl FnMut(&'a str) -> IResult<&'a str, T>
+where
+	T: FromStr,
+	T::Err: fmt::Display,
+{
+	from_str_with(take_till1(char::is_whitespace))
+}
+
+/// Lifts a [`FromStr`] implementation into a parser of whatever text `span`
+/// recognizes.
+///
+/// `T` must accept the whole span. `nom` errors cannot carry a message, so
+/// when `T` rejects the span, its error is logged, and the parser fails
+/// (recoverably, so that an `alt` can try something else) at the start of the
+/// span.
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+pub fn from_str_with<'a, T, P>(
+	mut span: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, T>
+where
+	T: FromStr,
+	T::Err: fmt::Display,
+	P: FnMut(&'a str) -> IResult<&'a str, &'a str>,
+{
+	move |text: &'a str| {
+		let (rest, found) = span(text)?;
+		match found.parse::<T>() {
+			Ok(val) => Ok((rest, val)),
+			Err(err) => {
+				tracing::debug!(
+					text = found,
+					%err,
+					"could not parse as {}",
+					std::any::type_name::<T>(),
+				);
+				Err(nom::Err::Error(NomError::new(text, ErrorKind::MapRes)))
+			},
+		}
+	}
+}
+
+/// Parses text made of blocks separated by blank lines, such as lists of
+/// inventories or the maps of an almanac, by running `parser` over each block
+/// and collecting the results.
+///
+/// A line holding only whitespace counts as blank, and any number of blank
+/// lines separate two blocks, or lead or trail the text. Each block runs from
+/// the start of its first line to the end of its last one, without the line
+/// ending, so `parser` does not need to expect a newline after its final line,
+/// and indentation on the first line is kept. Line endings inside a block are
+/// left alone: parsers which should accept CRLF text can split lines with
+/// `line_ending` rather than `newline`.
+///
+/// `parser` must consume its whole block, apart from trailing whitespace, and
+/// there must be at least one block. This consumes all of the text.
+pub fn blocks<'a, P, C, T>(
+	mut parser: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, C>
+where
+	P: FnMut(&'a str) -> IResult<&'a str, T>,
+	C: FromIterator<T>,
+{
+	move |text: &'a str| {
+		let spans = block_spans(text);
+		if spans.is_empty() {
+			return Err(nom::Err::Error(NomError::new(text, ErrorKind::Many1)));
+		}
+		let collected = spans
+			.into_iter()
+			.map(|block| match parser(block)? {
+				(rest, item) if rest.trim().is_empty() => Ok(item),
+				(rest, _) => {
+					Err(nom::Err::Error(NomError::new(rest, ErrorKind::Eof)))
+				},
+			})
+			.collect::<Result<C, _>>()?;
+		Ok((&text[text.len() ..], collected))
+	}
+}
+
+/// Finds the blocks of non-blank lines in some text, for [`blocks`].
+fn block_spans(text: &str) -> Vec<&str> {
+	let mut spans = Vec::new();
+	let mut start = None;
+	let mut end = 0;
+	let mut at = 0;
+	for line in text.split_inclusive('\n') {
+		if line.trim().is_empty() {
+			if let Some(start) = start.take() {
+				spans.push(&text[start .. end]);
+			}
+		}
+		else {
+			start.get_or_insert(at);
+			end = at + line.trim_end_matches(['\r', '\n']).len();
+		}
+		at += line.len();
+	}
+	spans.extend(start.map(|start| &text[start .. end]));
+	spans
+}
+
+/// Makes a path absolute, for error messages, leaving it as it is if the
+/// current directory cannot be found.
+fn absolute(path: &Path) -> PathBuf {
+	path::absolute(path).unwrap_or_else(|_| path.to_owned())
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
 
+	#[test]
+	fn registry_is_unique() {
+		let registry =
+			build_registry(&SOLVERS).unwrap_or_else(|err| panic!("{err}"));
+		assert_eq!(
+			registry.values().map(BTreeMap::len).sum::<usize>(),
+			SOLVERS.len()
+		);
+		let keys = registry
+			.iter()
+			.flat_map(|(&year, days)| days.keys().map(move |&day| (year, day)))
+			.collect::<Vec<_>>();
+		assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+		for (&year, days) in &registry {
+			for (&day, solver) in days {
+				assert_eq!((solver.year, solver.day), (year, day));
+				assert!((1 ..= 25).contains(&day), "{year}-{day} is not a day");
+			}
+		}
+
+		let solver = registry.values().flat_map(BTreeMap::values).next();
+		let Some(&solver) = solver
+		else {
+			return;
+		};
+		let copy = Solver::new(solver.year, solver.day, solver.func);
+		let err = build_registry(&[solver, copy]).unwrap_err();
+		assert_eq!((err.year, err.day), (solver.year, solver.day));
+		assert_ne!(err.first, err.second);
+	}
+
+	#[test]
+	fn registered_solvers_know_their_puzzle() -> eyre::Result<()> {
+		let solver = solutions()[&2023][&18];
+		let (_, puzzle) = solver
+			.parse(include_str!("y2023/d18/sample.txt"))
+			.map_err(|err| eyre::eyre!("{err}"))?;
+		assert_eq!(puzzle.year_day(), Some((2023, 18)));
+		assert_eq!(puzzle.title(), Some("Area Inside a Curve"));
+		Ok(())
+	}
+
+	/// Registration already refuses solvers which are not `Send`; this checks
+	/// that the registry's own wrapper keeps them so.
+	#[test]
+	fn registered_solvers_are_send() -> eyre::Result<()> {
+		const fn assert_send<T: ?Sized + Send>() {
+		}
+		const _: () = assert_send::<Described>();
+		const _: () = assert_send::<DynParser>();
+
+		let solver = solutions()[&2023][&18];
+		let (_, mut puzzle) = solver
+			.parse(include_str!("y2023/d18/sample.txt"))
+			.map_err(|err| eyre::eyre!("{err}"))?;
+		let answer = std::thread::spawn(move || {
+			puzzle.prepare_1()?;
+			puzzle.part_1()
+		})
+		.join()
+		.map_err(|_| eyre::eyre!("solver thread panicked"))??;
+		assert_eq!(answer, 62);
+		Ok(())
+	}
+
+	#[test]
+	fn solve_without_files() -> eyre::Result<()> {
+		let solver = solutions()[&2023][&18];
+		let text = include_str!("y2023/d18/sample.txt");
+		let (one, two) =
+			solver.solve_with_input(text, &ParamMap::new(), true, true)?;
+		assert_eq!(one, Some(Answer::from(62)));
+		assert_eq!(two, Some(Answer::from(952408144115_i64)));
+		let (one, two) =
+			solver.solve_with_input(text, &ParamMap::new(), false, true)?;
+		assert_eq!((one, two.is_some()), (None, true));
+		Ok(())
+	}
+
+	#[test]
+	fn registration_names() {
+		assert_eq!(ident_number("y2023", b'y'), 2023);
+		assert_eq!(ident_number("d07", b'd'), 7);
+		assert!(module_is_day("wyz_aoc::y2023::d19", "y2023", "d19"));
+		assert!(!module_is_day("wyz_aoc::y2023::d19", "y2023", "d18"));
+		assert!(!module_is_day("wyz_aoc::y2022::d19", "y2023", "d19"));
+		assert!(!module_is_day("y2023::d19", "y2023", "d19"));
+		assert!(!module_is_day("wyz_aoc::xy2023::d19", "y2023", "d19"));
+	}
+
+	#[test]
+	fn unify_ranges() {
+		let ranges = [5 ..= 8, 0 ..= 2, 7 ..= 12, 2 ..= 3, 14 ..= 14];
+		let unified = unify_ranges_inclusive(ranges.iter().cloned());
+		assert_eq!(unified, [0 ..= 3, 5 ..= 12, 14 ..= 14]);
+
+		let mut buf = vec![100 ..= 200];
+		unify_ranges_into(ranges.iter().cloned(), &mut buf);
+		assert_eq!(buf, unified);
+		unify_ranges_into([3 ..= 4, 1 ..= 9], &mut buf);
+		assert_eq!(buf, [1 ..= 9]);
+
+		let mut sorted = ranges.to_vec();
+		sorted.sort_by_key(|r| *r.start());
+		let merged = merge_sorted_ranges(sorted).collect::<Vec<_>>();
+		assert_eq!(merged, unified);
+	}
+
+	#[test]
+	fn parse_blocks() -> eyre::Result<()> {
+		use nom::{
+			character::complete::line_ending,
+			multi::separated_list1,
+		};
+
+		let sums = |text| {
+			blocks::<_, Vec<_>, _>(nom::combinator::map(
+				separated_list1(line_ending, parse_number::<u32>),
+				|nums| nums.into_iter().sum::<u32>(),
+			))(text)
+		};
+		let (rest, totals) = sums("1\n2\n\n3\n\n\n4\n5 \n")?;
+		assert!(rest.is_empty());
+		assert_eq!(totals, vec![3, 3, 9]);
+		let (_, crlf) = sums("\r\n1\r\n2\r\n  \r\n3\r\n\r\n")?;
+		assert_eq!(crlf, totals[.. 2]);
+		assert!(sums("1\n2x\n\n3").is_err());
+		assert!(sums(" \n\n").is_err());
+
+		let (_, first) =
+			blocks::<_, Vec<_>, _>(nom::combinator::rest)("  a\nb\n\nc")?;
+		assert_eq!(first, ["  a\nb", "c"]);
+		Ok(())
+	}
+
+	#[test]
+	fn from_str_bridge() -> eyre::Result<()> {
+		use nom::{
+			bytes::complete::is_not,
+			character::complete::space1,
+			multi::separated_list1,
+		};
+
+		let (rest, nums) =
+			separated_list1(space1, from_str_parser::<i64>())("3 -4 5\nnext")?;
+		assert_eq!((rest, nums), ("\nnext", vec![3, -4, 5]));
+		assert!(from_str_parser::<u8>()("300").is_err());
+		assert!(from_str_parser::<u8>()(" 3").is_err());
+
+		let (rest, text) = from_str_with::<String, _>(is_not("\n"))("a b\nc")?;
+		assert_eq!((rest, text.as_str()), ("\nc", "a b"));
+		Ok(())
+	}
+
 	#[test]
 	fn written_numbers() -> eyre::Result<()> {
 		let text = "onethreefive";
@@ -1,26 +1,55 @@
-use std::fmt::{
-	self,
-	Write as _,
+use std::{
+	env,
+	ffi::OsString,
+	fmt,
+	io,
+	path::PathBuf,
 };
 
 use clap::{
 	error::ErrorKind,
+	Command,
+	CommandFactory,
+	FromArgMatches as _,
 	Parser,
 	ValueEnum,
 };
+use clap_complete::Shell;
 use eyre::WrapErr as _;
-use tap::Tap;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::prelude::*;
-use wyz_aoc::Solver;
+use wyz_aoc::{
+	cancel,
+	config::Config,
+	metrics::PhaseMetrics,
+	random,
+	AocError,
+};
+
+mod commands;
+
+use crate::commands::{
+	bench::BenchArgs,
+	fetch::FetchArgs,
+	list::ListArgs,
+	new::NewArgs,
+	render_known_puzzles,
+	run::RunArgs,
+	serve::ServeArgs,
+	verify::VerifyArgs,
+	Data,
+};
 
 /** Runs an Advent of Code solution.
 
 This harness expects to load puzzle data from the well-known filesystem tree in
 `assets/`, and expects to be run from the project root, **not** the Rust harness
-root.
+root. Defaults for the flags can be kept in `aoc.toml` there.
 
-It is capable of selecting either, or both, of a day's puzzles.
+It is capable of selecting either, or both, of a day's puzzles. Running a
+puzzle is the default subcommand, so `wyz_aoc 2023 10` is `wyz_aoc run 2023 10`;
+the others are `list`, `fetch`, `new`, `bench`, `verify`, `serve`, and
+`completions`.
 
 Days become selectable when the module `y{year}::d{day}` registers a parser with
 the harness' dispatch calendar. That parser is responsible for consuming puzzle
@@ -32,124 +61,79 @@ Solutions are delivered at the INFO level. ERROR and WARNING should only appear
 when a puzzle is not correctly built for the input. Enable DEBUG or TRACE to
 observe the solvers in action.
  */
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Parser)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Parser)]
 #[command(author, version, about)]
 pub struct Args {
-	/// The desired puzzle year.
-	year:   u16,
-	/// The desired puzzle day.
-	day:    u8,
-	/// Whether to use the sample or real input data.
-	#[arg(short, long, value_enum, default_value_t)]
-	data:   Data,
-	/// Which step(s) to run.
-	#[arg(short, long, value_enum, default_value_t)]
-	step:   Step,
+	/// Running a puzzle is the default, so `run` may be left out.
+	#[command(subcommand)]
+	task:       Task,
 	/// How to render trace messages
-	#[arg(short, long, value_enum, default_value_t)]
-	format: TraceFormat,
+	#[arg(short, long, value_enum, default_value_t, global = true)]
+	format:     TraceFormat,
+	/// Seed the random number generator handed to solvers, to replay a run
+	/// of a randomized solver. Without this, a fresh seed is chosen and
+	/// logged.
+	#[arg(long, value_name = "SEED", global = true)]
+	seed:       Option<u64>,
+	/// The directory holding `assets/` and the puzzles' `src/` tree, for
+	/// running away from the project root. Overrides `AOC_ASSETS` and
+	/// `aoc.toml`.
+	#[arg(long, value_name = "DIR", global = true)]
+	asset_root: Option<PathBuf>,
 }
 
-impl Args {
-	#[tracing::instrument(name = "run", skip(self), fields(year=%self.year, day=%self.day))]
-	fn execute_program(&self) -> eyre::Result<()> {
-		let span = tracing::error_span!("lookup");
-		let span = span.enter();
-		// Look up the requested solver in the registry
-		let (year, day) = (self.year, self.day);
-		let solution = wyz_aoc::solutions()
-			.get(&year)
-			.and_then(|y| y.get(&day))
-			.ok_or_else(|| eyre::eyre!("{}", render_known_puzzles()))
-			.wrap_err_with(|| {
-				eyre::eyre!("{year}-{day:0>2} has no registered solution")
-			})?;
-		let solver = Solver::new(self.year, self.day, *solution);
-		tracing::trace!("found solver");
-		drop(span);
-
-		let source_text = solver.load_input(match self.data {
-			Data::Sample => "sample",
-			Data::Input => "input",
-		})?;
-
-		let span = tracing::error_span!("solve");
-		let _span = span.enter();
-		tracing::info!("parsing");
-		// This error map is necessary because nom's default error holds views
-		// into the source data, but the error is returned out of this function
-		// after the source text is destroyed.
-		let (rest, mut solver) = solver
-			.parse(source_text.as_str())
-			.map_err(|err| eyre::eyre!("{err}"))?;
-		if !rest.trim().is_empty() {
-			tracing::warn!(?rest, "unparsed input remaining");
-		}
-		tracing::info!("processing");
-		solver.after_parse().wrap_err(
-			"input was successfully parsed, but was not valid for the rules of \
-			 the puzzle",
-		)?;
-
-		if self.step != Step::Two {
-			let span = tracing::error_span!("", part = 1);
-			let _span = span.enter();
-			tracing::info!("preparing");
-			solver.prepare_1().wrap_err_with(|| {
-				format!("error preparing {year}-{day:0>2}#1")
-			})?;
-			tracing::info!("running");
-			solver
-				.part_1()
-				.wrap_err_with(|| format!("failure running {year}-{day:0>2}#1"))?
-				.tap(|answer| tracing::info!(?answer, "solved!"));
-		}
-		if self.step != Step::One {
-			let span = tracing::error_span!("", part = 2);
-			let _span = span.enter();
-			tracing::info!("preparing");
-			solver.prepare_2().wrap_err_with(|| {
-				format!("error preparing {year}-{day:0>2}#2")
-			})?;
-			tracing::info!("running");
-			solver
-				.part_2()
-				.wrap_err_with(|| format!("failure running {year}-{day:0>2}#2"))?
-				.tap(|answer| tracing::info!(?answer, "solved!"));
-		}
-
-		Ok(())
-	}
+#[derive(Clone, Debug, Eq, Hash, PartialEq, clap::Subcommand)]
+enum Task {
+	/// Run a puzzle, or one day's puzzles from every year. This is the
+	/// default.
+	Run(RunArgs),
+	/// List every known puzzle and which input files it has.
+	List(ListArgs),
+	/// Download a puzzle's real input.
+	Fetch(FetchArgs),
+	/// Start a module for a new puzzle.
+	New(NewArgs),
+	/// Time many runs of a solver, after a warm-up, and report statistics
+	/// about them instead of the answers.
+	Bench(BenchArgs),
+	/// Run every puzzle, and compare each part's answer and run time with
+	/// those saved by an earlier build.
+	Verify(VerifyArgs),
+	/// Answer puzzles over HTTP, until interrupted: `POST
+	/// /{year}/{day}?part=1` with the input as the body returns the answer as
+	/// JSON.
+	Serve(ServeArgs),
+	/// Print a completion script for a shell.
+	Completions { shell: Shell },
 }
 
-#[derive(
-	Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum,
-)]
-pub enum Data {
-	#[default]
-	Sample,
-	Input,
-}
-
-impl fmt::Display for Data {
-	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		fmt::Debug::fmt(self, fmt)
+impl Task {
+	fn execute(&self, metrics: &PhaseMetrics) -> eyre::Result<()> {
+		match self {
+			Self::Run(args) => args.execute(metrics),
+			Self::List(args) => args.execute(),
+			Self::Fetch(args) => args.execute(),
+			Self::New(args) => args.execute(),
+			Self::Bench(args) => args.execute(),
+			Self::Verify(args) => args.execute(),
+			Self::Serve(args) => args.execute(),
+			Self::Completions { shell } => {
+				let mut cmd = Args::command();
+				let name = cmd.get_name().to_owned();
+				clap_complete::generate(
+					*shell,
+					&mut cmd,
+					name,
+					&mut io::stdout(),
+				);
+				Ok(())
+			},
+		}
 	}
-}
 
-#[derive(
-	Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, ValueEnum,
-)]
-pub enum Step {
-	One,
-	Two,
-	#[default]
-	All,
-}
-
-impl fmt::Display for Step {
-	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		fmt::Debug::fmt(self, fmt)
+	/// Whether only answers should be printed.
+	fn is_quiet(&self) -> bool {
+		matches!(self, Self::Run(args) if args.quiet)
 	}
 }
 
@@ -170,11 +154,28 @@ impl fmt::Display for TraceFormat {
 	}
 }
 
+/// The exit status of a run stopped by Ctrl-C, following the shell convention
+/// of 128 plus the signal number.
+const INTERRUPTED: i32 = 130;
+
 fn main() -> eyre::Result<()> {
+	// Answer lags are measured from here.
+	let metrics = PhaseMetrics::new();
 	color_eyre::install()?;
+	// Refuse to run at all if two modules claim the same day.
+	wyz_aoc::try_solutions()?;
+
+	// Read the defaults before the CLI args, so that the flags override them.
+	let mut config = Config::load()?;
+	if let Some(jobs) = config.jobs {
+		rayon::ThreadPoolBuilder::new()
+			.num_threads(jobs)
+			.build_global()
+			.wrap_err("could not start the thread pool")?;
+	}
 
 	// Get the CLI args
-	let args = match Args::try_parse() {
+	let mut args = match parse_args(&config)? {
 		Ok(args) => args,
 		Err(err) => match err.kind() {
 			// These are not a failed run
@@ -195,10 +196,263 @@ fn main() -> eyre::Result<()> {
 		},
 	};
 
-	// Install the tracing sinks
+	if let Some(root) = args.asset_root.take() {
+		config.asset_root = Some(root);
+	}
+	config.install();
+
+	let (format, seed) = (args.format, args.seed);
+	let mut task = args.task;
+	if let Task::Run(run) = &mut task {
+		run.seeded = seed.is_some();
+	}
+	let quiet = task.is_quiet();
+
+	// These only print to stdout, so they don't need the tracing sinks.
+	if let Task::List(_) | Task::Completions { .. } = task {
+		return task.execute(&metrics);
+	}
+
+	// Install the tracing sinks. Quiet runs install none, so that every event
+	// is discarded and only the answers reach the terminal.
+	if !quiet {
+		install_tracing(format, &metrics)?;
+	}
+
+	let seed = seed.unwrap_or_else(random::fresh_seed);
+	random::set_seed(seed);
+	tracing::info!(seed, "seeded the random number generator");
+
+	// The first Ctrl-C asks the solver to stop at its next check, so that the
+	// timings collected so far can still be reported. Solvers which never
+	// check would otherwise hold the process hostage, so a second Ctrl-C
+	// reports what it can and leaves immediately.
+	let interrupted = metrics.clone();
+	ctrlc::set_handler(move || {
+		if !cancel::cancel() {
+			tracing::warn!("interrupted; press Ctrl-C again to stop at once");
+			return;
+		}
+		report_metrics(&interrupted, format);
+		std::process::exit(INTERRUPTED);
+	})
+	.wrap_err("failed to install the Ctrl-C handler")?;
+
+	// Dispatch to the solvers! *Off* the main thread, just in case I ever
+	// figure out how to do window drawings.
+	let timer = metrics.clone();
+	let handle = std::thread::spawn(move || task.execute(&timer));
+	let outcome = handle.join().unwrap_or_else(|_| {
+		Err(AocError::Internal("solver thread panicked".into()).into())
+	});
+	report_metrics(&metrics, format);
+	if cancel::is_cancelled() {
+		// Whatever the solver was doing when it noticed, its error is only a
+		// consequence of the interruption.
+		if let Err(err) = &outcome {
+			tracing::debug!("{err:#}");
+		}
+		tracing::warn!("run was interrupted");
+		std::process::exit(INTERRUPTED);
+	}
+	let Err(err) = outcome
+	else {
+		return Ok(());
+	};
+	// Unclassified errors go to `color_eyre`, and exit with status 1.
+	let Some(class) = AocError::find(&err)
+	else {
+		return Err(err);
+	};
+	if quiet {
+		eprintln!("{err:#}");
+	}
+	else {
+		report_failure(&err, class);
+	}
+	std::process::exit(class.exit_code());
+}
+
+/// Parses the CLI args, with the configured defaults in place of the built-in
+/// ones.
+///
+/// The outer error is a bad configuration; the inner one is a bad command
+/// line, or a request for help.
+fn parse_args(config: &Config) -> eyre::Result<Result<Args, clap::Error>> {
+	let mut defaults = Vec::new();
+	if let Some(format) = &config.format {
+		let format = TraceFormat::from_str(format, true)
+			.map_err(|err| eyre::eyre!("invalid trace format: {err}"))?;
+		defaults.push(("format", value_name(format)));
+	}
+	if let Some(data) = &config.data {
+		let data = Data::from_str(data, true)
+			.map_err(|err| eyre::eyre!("invalid data group: {err}"))?;
+		defaults.push(("data", value_name(data)));
+	}
+	if let Some(jobs) = config.jobs {
+		defaults.push(("jobs", jobs.to_string()));
+	}
+	// Clap keeps default values for the life of the program, and so does the
+	// parsed command.
+	let defaults = defaults
+		.into_iter()
+		.map(|(id, value)| (id, &*value.leak()))
+		.collect::<Vec<_>>();
+	let cmd = with_defaults(Args::command(), &defaults);
+	let subcommands = cmd
+		.get_subcommands()
+		.map(|sub| sub.get_name().to_owned())
+		.collect::<Vec<_>>();
+	let args = default_task(legacy_args(env::args_os()), &subcommands);
+	Ok(cmd
+		.try_get_matches_from(args)
+		.and_then(|matches| Args::from_arg_matches(&matches)))
+}
+
+/// The global flags which take a value, and so are stepped over, along with
+/// their values, when looking for the subcommand.
+const GLOBAL_VALUE_FLAGS: &[&str] =
+	&["-f", "--format", "--seed", "--asset-root"];
+
+/// Inserts the `run` subcommand into a command line which names none, so that
+/// `--seed 3 2023 10` means `--seed 3 run 2023 10`.
+///
+/// The subcommand is expected after any global flags. Requests for help or the
+/// version are left alone.
+fn default_task(
+	mut args: Vec<OsString>,
+	subcommands: &[String],
+) -> Vec<OsString> {
+	let mut idx = 1;
+	while let Some(arg) = args.get(idx) {
+		let text = arg.to_string_lossy();
+		if GLOBAL_VALUE_FLAGS.contains(&&*text) {
+			idx += 2;
+		}
+		else if text
+			.split_once('=')
+			.is_some_and(|(flag, _)| GLOBAL_VALUE_FLAGS.contains(&flag))
+		{
+			idx += 1;
+		}
+		else {
+			break;
+		}
+	}
+	let named = args.get(idx).is_some_and(|arg| {
+		let text = arg.to_string_lossy();
+		matches!(&*text, "help" | "-h" | "--help" | "-V" | "--version")
+			|| subcommands.iter().any(|sub| *sub == text)
+	});
+	if !named {
+		args.insert(idx.min(args.len()), OsString::from("run"));
+	}
+	args
+}
+
+/// The flags which chose a workflow before the workflows were subcommands:
+/// each names the subcommand that replaced it, and the flag that its value
+/// moved to, if it is not the subcommand's positional argument.
+const LEGACY_FLAGS: &[(&str, &str, Option<&str>)] = &[
+	("--list", "list", Some("--output")),
+	("--completions", "completions", None),
+	("--serve", "serve", None),
+	("--bench", "bench", Some("--samples")),
+	("--diff-answers", "verify", Some("--against")),
+	("--record-results", "verify", Some("--record")),
+];
+
+/// Rewrites a command line which uses the old workflow flags into the
+/// subcommand which replaced them, so that `--bench 50 2023 10` still means
+/// `bench --samples 50 2023 10`.
+///
+/// Command lines which do not use them are returned unchanged.
+fn legacy_args(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+	let mut args = args.into_iter().peekable();
+	let program = args.next();
+	let mut rest = Vec::new();
+	let mut task = None;
+	while let Some(arg) = args.next() {
+		// Everything after `--` is a value, even if it looks like a flag.
+		if arg == "--" {
+			rest.push(arg);
+			rest.extend(args.by_ref());
+			break;
+		}
+		let text = arg.to_string_lossy();
+		let (flag, mut value) = match text.split_once('=') {
+			Some((flag, value)) => (flag, Some(OsString::from(value))),
+			None => (&*text, None),
+		};
+		let Some(&(old, sub, new)) =
+			LEGACY_FLAGS.iter().find(|(old, ..)| *old == flag)
+		else {
+			rest.push(arg);
+			continue;
+		};
+		eprintln!("`{old}` is now written as the `{sub}` subcommand");
+		task.get_or_insert(sub);
+		// `--list` may be given without its format.
+		let bare = old == "--list"
+			&& value.is_none()
+			&& args
+				.peek()
+				.is_none_or(|next| next.to_string_lossy().starts_with('-'));
+		if bare {
+			continue;
+		}
+		rest.extend(new.map(OsString::from));
+		if value.is_none() {
+			value = args.next();
+		}
+		rest.extend(value);
+	}
+	program
+		.into_iter()
+		.chain(task.map(OsString::from))
+		.chain(rest)
+		.collect()
+}
+
+/// Gets the name that an enumerated flag value is written as.
+fn value_name(value: impl ValueEnum) -> String {
+	value
+		.to_possible_value()
+		.map(|value| value.get_name().to_owned())
+		.unwrap_or_default()
+}
+
+/// Replaces the default values of arguments, wherever they appear in the
+/// command and its subcommands.
+fn with_defaults(
+	mut cmd: Command,
+	defaults: &[(&str, &'static str)],
+) -> Command {
+	for &(id, value) in defaults {
+		if cmd.get_arguments().any(|arg| arg.get_id() == id) {
+			cmd = cmd.mut_arg(id, |arg| arg.default_value(value));
+		}
+	}
+	let subcommands = cmd
+		.get_subcommands()
+		.map(|sub| sub.get_name().to_owned())
+		.collect::<Vec<_>>();
+	for name in subcommands {
+		cmd = cmd.mut_subcommand(name, |sub| with_defaults(sub, defaults));
+	}
+	cmd
+}
+
+/// Installs the tracing sinks: a formatter, the `RUST_LOG` filter, and the
+/// phase timer.
+fn install_tracing(
+	format: TraceFormat,
+	metrics: &PhaseMetrics,
+) -> eyre::Result<()> {
 	let trace_fmt = tracing_subscriber::fmt::layer()
 		.with_timer(tracing_subscriber::fmt::time::ChronoLocal::rfc_3339());
-	let trace_fmt = match args.format {
+	let trace_fmt = match format {
 		TraceFormat::Compact => trace_fmt.compact().boxed(),
 		TraceFormat::Plain => trace_fmt.boxed(),
 		TraceFormat::Pretty => trace_fmt.pretty().boxed(),
@@ -211,34 +465,52 @@ fn main() -> eyre::Result<()> {
 	tracing_subscriber::registry()
 		.with(trace_fmt)
 		.with(trace_filt)
+		.with(metrics.clone())
 		.try_init()
 		.wrap_err("failed to install a trace sink")?;
+	Ok(())
+}
 
-	// Dispatch to the solvers! *Off* the main thread, just in case I ever
-	// figure out how to do window drawings.
-	let handle = std::thread::spawn(move || args.execute_program());
-	handle
-		.join()
-		.map_err(|_| eyre::eyre!("solver thread panicked"))?
+/// Logs a classified failure, in as much detail as its class deserves.
+fn report_failure(err: &eyre::Report, class: &AocError) {
+	match class {
+		AocError::NotImplemented { part } => {
+			tracing::warn!(part, "this part has not been solved yet");
+		},
+		// Bugs get the whole report, with its span trace, to chase them down.
+		class if class.is_bug() => {
+			tracing::error!(class = class.class(), "solver bug: {err:?}");
+		},
+		class => tracing::error!(class = class.class(), "{err:#}"),
+	}
 }
 
-fn render_known_puzzles() -> String {
-	let mut show = String::new();
-	writeln!(&mut show, "Known solutions are:").ok();
-	for (year, days) in wyz_aoc::solutions() {
-		let mut days = days.keys();
-		if let Some(day) = days.next() {
-			write!(&mut show, "- y{year}: d{day:0>2}").ok();
+/// Emits the per-phase timings collected over the run, and how long after the
+/// start each answer was ready.
+#[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+fn report_metrics(metrics: &PhaseMetrics, format: TraceFormat) {
+	let timings = metrics.snapshot();
+	let lags = metrics.answer_lags();
+	#[cfg(feature = "serde")]
+	if format == TraceFormat::Json {
+		if !timings.is_empty() {
+			match serde_json::to_string(&timings) {
+				Ok(timings) => tracing::info!(%timings, "phase metrics"),
+				Err(err) => tracing::warn!(%err, "could not serialize metrics"),
+			}
 		}
-		for day in days {
-			write!(&mut show, ", d{day:0>2}").ok();
+		if !lags.is_empty() {
+			match serde_json::to_string(&lags) {
+				Ok(lags) => tracing::info!(%lags, "answer lag"),
+				Err(err) => tracing::warn!(%err, "could not serialize lags"),
+			}
 		}
-		writeln!(&mut show).ok();
-	}
-	write!(
-		&mut show,
-		"Do not use the `y` or `d` prefixes when providing arguments."
-	)
-	.ok();
-	show
+		return;
+	}
+	for timing in timings {
+		tracing::info!(%timing, "phase metrics");
+	}
+	for lag in lags {
+		tracing::info!(%lag, "answer lag");
+	}
 }
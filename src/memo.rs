@@ -0,0 +1,134 @@
+//! Memoized recursion.
+//!
+//! Counting puzzles (how many ways can these springs be arranged, how many
+//! ways can this pattern be built from these towels) tend to have a short
+//! recursive answer that revisits the same subproblems exponentially often.
+//! Caching each subproblem's answer by its key makes them linear, but the
+//! recursive function then has to drag a map through every call.
+//!
+//! [`memoize`] takes the recursive step as a closure which receives, as its
+//! first argument, the function to call for subproblems. That function checks
+//! the cache before recursing, so the step can be written as though it were
+//! ordinary recursion:
+//!
+//! ```rust
+//! # use wyz_aoc::memo::memoize;
+//! let mut fib = memoize(|fib, n: u64| match n {
+//!     0 | 1 => n,
+//!     n => fib(n - 1) + fib(n - 2),
+//! });
+//! assert_eq!(fib(90), 2_880_067_194_370_816_120);
+//! ```
+//!
+//! A [`Memo`] is the cache on its own, for solvers which want to keep it
+//! between calls, or to look into it afterwards.
+
+use std::{
+	collections::HashMap,
+	hash::Hash,
+};
+
+/// A cache of answers to the subproblems of a recursive function.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+	cache: HashMap<K, V>,
+}
+
+impl<K, V> Memo<K, V>
+where
+	K: Clone + Eq + Hash,
+	V: Clone,
+{
+	/// Creates an empty cache.
+	pub fn new() -> Self {
+		Self {
+			cache: HashMap::new(),
+		}
+	}
+
+	/// Looks up a subproblem that has already been solved.
+	pub fn get(&self, key: &K) -> Option<&V> {
+		self.cache.get(key)
+	}
+
+	/// Counts the subproblems that have been solved.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+
+	/// Forgets every answer, for when the function's inputs change.
+	pub fn clear(&mut self) {
+		self.cache.clear();
+	}
+
+	/// Solves a subproblem, or recalls its answer.
+	///
+	/// `step` computes the answer for one key, calling its first argument for
+	/// any subproblems it needs; those answers are cached too. The same `step`
+	/// should be used with a cache every time, since its answers are kept.
+	pub fn solve<F>(&mut self, key: K, step: &F) -> V
+	where F: Fn(&mut dyn FnMut(K) -> V, K) -> V {
+		if let Some(value) = self.cache.get(&key) {
+			return value.clone();
+		}
+		let value = step(&mut |sub| self.solve(sub, step), key.clone());
+		self.cache.insert(key, value.clone());
+		value
+	}
+}
+
+impl<K, V> Default for Memo<K, V>
+where
+	K: Clone + Eq + Hash,
+	V: Clone,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Turns a recursive step into a memoized function.
+///
+/// The step receives the memoized function itself, for recursing into
+/// subproblems, and the key to solve. The returned function owns its own
+/// cache, which lives as long as it does.
+pub fn memoize<K, V, F>(step: F) -> impl FnMut(K) -> V
+where
+	K: Clone + Eq + Hash,
+	V: Clone,
+	F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+	let mut memo = Memo::new();
+	move |key| memo.solve(key, &step)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn recursion() {
+		// Lattice paths through a 16x16 grid: exponential without the cache.
+		let mut paths = memoize(|paths, (x, y): (u32, u32)| -> u64 {
+			if x == 0 || y == 0 {
+				return 1;
+			}
+			paths((x - 1, y)) + paths((x, y - 1))
+		});
+		assert_eq!(paths((16, 16)), 601_080_390);
+
+		let mut memo = Memo::new();
+		let step = |len: &mut dyn FnMut(u64) -> u32, n: u64| match n {
+			1 => 1,
+			n if n % 2 == 0 => 1 + len(n / 2),
+			n => 1 + len(3 * n + 1),
+		};
+		assert_eq!(memo.solve(27, &step), 112);
+		assert_eq!(memo.get(&82), Some(&111));
+		assert_eq!(memo.len(), 112);
+	}
+}
@@ -0,0 +1,342 @@
+//! Phase timing collection.
+//!
+//! ## Span Conventions
+//!
+//! Every run of a solver passes through the same phases, and each phase is
+//! wrapped in a `tracing` span with a well-known name:
+//!
+//! - `gather`: loading the input text from disk
+//! - `parse`: turning the text into a solver
+//! - `process`: the solver's `after_parse` validation
+//! - `prepare`: a `prepare_N` call, with a `part = N` field, or the
+//!   `prepare_common` call shared by both parts, with no `part` field
+//! - `solve`: a `part_N` call, with a `part = N` field
+//!
+//! Other spans (such as `run`, which wraps an entire day, or spans opened by
+//! the solvers themselves) are free to use any name that is not one of these.
+//!
+//! The [`PhaseMetrics`] layer watches for spans with these names, measures how
+//! long each one was open, and aggregates the results so that the harness can
+//! print a summary at the end of the run rather than leaving me to subtract
+//! timestamps in the log. It is only a layer with the `trace` feature; without
+//! it, there are no spans, and it never measures anything.
+
+use std::{
+	collections::BTreeMap,
+	fmt,
+	sync::{
+		Arc,
+		Mutex,
+	},
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+#[cfg(feature = "trace")]
+use tracing::{
+	field::{
+		Field,
+		Visit,
+	},
+	span,
+	Subscriber,
+};
+#[cfg(feature = "trace")]
+use tracing_subscriber::{
+	layer::Context,
+	registry::LookupSpan,
+	Layer,
+};
+
+/// The span name for loading input.
+pub const GATHER: &str = "gather";
+/// The span name for parsing input.
+pub const PARSE: &str = "parse";
+/// The span name for post-parse validation.
+pub const PROCESS: &str = "process";
+/// The span name for preparing to solve a part.
+pub const PREPARE: &str = "prepare";
+/// The span name for solving a part.
+pub const SOLVE: &str = "solve";
+
+/// All of the span names which are considered phases, in execution order.
+pub const PHASES: [&str; 5] = [GATHER, PARSE, PROCESS, PREPARE, SOLVE];
+
+/// A `tracing` layer which aggregates the time spent in each phase span.
+///
+/// The layer is cheaply cloneable; all clones share the same measurements, so
+/// the harness can install one copy in the subscriber and keep another to read
+/// the results.
+///
+/// It also notes how long after its creation each answer was produced, which
+/// is the lag between starting the harness and having something to submit.
+/// Runs which wait before starting, as `--race` does, can restart that clock.
+#[derive(Clone, Debug)]
+pub struct PhaseMetrics {
+	timings: Arc<Mutex<BTreeMap<PhaseKey, PhaseTiming>>>,
+	/// When each group's answer to each part was produced, measured from
+	/// `start`.
+	answers: Arc<Mutex<BTreeMap<(String, u8), Duration>>>,
+	/// Shared between clones, so that restarting the clock in one restarts it
+	/// in all.
+	start:   Arc<Mutex<Instant>>,
+}
+
+impl PhaseMetrics {
+	/// Creates a new, empty, metrics collector. Answer lags are measured from
+	/// now, so the harness creates this as soon as it starts.
+	pub fn new() -> Self {
+		Self {
+			timings: Arc::default(),
+			answers: Arc::default(),
+			start:   Arc::new(Mutex::new(Instant::now())),
+		}
+	}
+
+	/// Measures answer lags from now, rather than from the harness' start,
+	/// for runs which wait before they begin.
+	pub fn restart_clock(&self) {
+		if let Ok(mut start) = self.start.lock() {
+			*start = Instant::now();
+		}
+	}
+
+	/// Notes that a part's answer for a data group has just been produced, and
+	/// returns how long after the start that was.
+	pub fn record_answer(&self, group: &str, part: u8) -> Duration {
+		let lag = self
+			.start
+			.lock()
+			.map(|start| start.elapsed())
+			.unwrap_or_default();
+		if let Ok(mut answers) = self.answers.lock() {
+			answers.insert((group.to_owned(), part), lag);
+		}
+		lag
+	}
+
+	/// Copies out the answer lags noted so far, in group and then part order.
+	pub fn answer_lags(&self) -> Vec<AnswerLag> {
+		self.answers
+			.lock()
+			.map(|answers| {
+				answers
+					.iter()
+					.map(|((group, part), &lag)| AnswerLag {
+						group: group.clone(),
+						part: *part,
+						lag,
+					})
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	/// Copies out all measurements taken so far, in phase order.
+	pub fn snapshot(&self) -> Vec<PhaseTiming> {
+		self.timings
+			.lock()
+			.map(|timings| timings.values().cloned().collect())
+			.unwrap_or_default()
+	}
+}
+
+#[cfg(feature = "trace")]
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for PhaseMetrics {
+	fn on_new_span(
+		&self,
+		attrs: &span::Attributes<'_>,
+		id: &span::Id,
+		ctx: Context<'_, S>,
+	) {
+		let name = attrs.metadata().name();
+		let Some(order) = PHASES.iter().position(|&p| p == name)
+		else {
+			return;
+		};
+		let mut part = PartVisitor(None);
+		attrs.record(&mut part);
+		if let Some(span) = ctx.span(id) {
+			span.extensions_mut().insert(OpenPhase {
+				key:   PhaseKey {
+					order,
+					part: part.0,
+				},
+				begin: Instant::now(),
+			});
+		}
+	}
+
+	fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+		let Some(span) = ctx.span(&id)
+		else {
+			return;
+		};
+		let Some(open) = span.extensions_mut().remove::<OpenPhase>()
+		else {
+			return;
+		};
+		let elapsed = open.begin.elapsed();
+		let Ok(mut timings) = self.timings.lock()
+		else {
+			return;
+		};
+		let entry = timings.entry(open.key).or_insert_with(|| PhaseTiming {
+			phase: PHASES[open.key.order],
+			part:  open.key.part,
+			count: 0,
+			total: Duration::ZERO,
+			max:   Duration::ZERO,
+		});
+		entry.count += 1;
+		entry.total += elapsed;
+		entry.max = entry.max.max(elapsed);
+	}
+}
+
+/// The aggregated measurements of one phase.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PhaseTiming {
+	/// The name of the phase span.
+	pub phase: &'static str,
+	/// The puzzle part that the phase served, if it is part-specific.
+	pub part:  Option<u8>,
+	/// How many times the phase ran.
+	pub count: usize,
+	/// The total time spent in the phase.
+	pub total: Duration,
+	/// The longest single run of the phase.
+	pub max:   Duration,
+}
+
+impl fmt::Display for PhaseTiming {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}", self.phase)?;
+		if let Some(part) = self.part {
+			write!(fmt, "#{part}")?;
+		}
+		write!(fmt, ": {:?}", self.total)?;
+		if self.count > 1 {
+			write!(fmt, " over {} runs (max {:?})", self.count, self.max)?;
+		}
+		Ok(())
+	}
+}
+
+impl Default for PhaseMetrics {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// How long after the harness started a part's answer was ready.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AnswerLag {
+	/// The data group whose input was answered.
+	pub group: String,
+	/// The puzzle part which was answered.
+	pub part:  u8,
+	/// The wall-clock time from the start of the run to the answer.
+	pub lag:   Duration,
+}
+
+impl fmt::Display for AnswerLag {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			fmt,
+			"answer#{} ({}): {:?} after start",
+			self.part, self.group, self.lag
+		)
+	}
+}
+
+/// Sorts phases by execution order, then by part.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct PhaseKey {
+	order: usize,
+	part:  Option<u8>,
+}
+
+/// Stored in a phase span while it is open.
+#[cfg(feature = "trace")]
+struct OpenPhase {
+	key:   PhaseKey,
+	begin: Instant,
+}
+
+/// Pulls the `part` field out of a span's attributes.
+#[cfg(feature = "trace")]
+struct PartVisitor(Option<u8>);
+
+#[cfg(feature = "trace")]
+impl Visit for PartVisitor {
+	fn record_u64(&mut self, field: &Field, value: u64) {
+		if field.name() == "part" {
+			self.0 = u8::try_from(value).ok();
+		}
+	}
+
+	fn record_i64(&mut self, field: &Field, value: i64) {
+		if field.name() == "part" {
+			self.0 = u8::try_from(value).ok();
+		}
+	}
+
+	fn record_debug(&mut self, _: &Field, _: &dyn fmt::Debug) {
+	}
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod tests {
+	use tracing_subscriber::prelude::*;
+
+	use super::*;
+
+	#[test]
+	fn aggregates_phases() {
+		let metrics = PhaseMetrics::new();
+		let subscriber = tracing_subscriber::registry().with(metrics.clone());
+		tracing::subscriber::with_default(subscriber, || {
+			tracing::error_span!("run").in_scope(|| {
+				tracing::error_span!(PARSE).in_scope(|| {});
+				for _ in 0 .. 2 {
+					tracing::error_span!(SOLVE, part = 1).in_scope(|| {});
+				}
+				tracing::error_span!(SOLVE, part = 2).in_scope(|| {});
+			});
+		});
+		let timings = metrics.snapshot();
+		let summary = timings
+			.iter()
+			.map(|t| (t.phase, t.part, t.count))
+			.collect::<Vec<_>>();
+		assert_eq!(summary, [
+			(PARSE, None, 1),
+			(SOLVE, Some(1), 2),
+			(SOLVE, Some(2), 1)
+		]);
+	}
+
+	#[test]
+	fn keeps_lags_apart_by_group() {
+		let metrics = PhaseMetrics::new();
+		metrics.record_answer("sample", 1);
+		metrics.clone().restart_clock();
+		metrics.record_answer("input", 1);
+		metrics.record_answer("sample", 2);
+		let lags = metrics
+			.answer_lags()
+			.into_iter()
+			.map(|lag| (lag.group, lag.part))
+			.collect::<Vec<_>>();
+		assert_eq!(lags, [
+			("input".to_owned(), 1),
+			("sample".to_owned(), 1),
+			("sample".to_owned(), 2)
+		]);
+	}
+}
@@ -0,0 +1,269 @@
+//! Indices into rings.
+//!
+//! Circular lists, dials, and hash rings all move a cursor by some amount and
+//! wrap it back into range. `%` keeps the sign of its left operand, so moving
+//! backwards needs `rem_euclid`, and then the offset has to be widened to a
+//! signed type first, and the product of a large offset and a large step can
+//! overflow before it is reduced. [`ModIdx`] does all of that once: it is an
+//! index which knows the size of its ring, and every operation on it lands
+//! back in range.
+
+use std::{
+	fmt,
+	iter::FusedIterator,
+	ops::{
+		Add,
+		AddAssign,
+		Mul,
+		MulAssign,
+		Neg,
+		Sub,
+		SubAssign,
+	},
+};
+
+/// An index into a ring of `modulus` slots, always less than `modulus`.
+///
+/// Adding or subtracting a signed or unsigned offset moves the index around
+/// the ring. Arithmetic between two indices requires that they share a
+/// modulus, and panics otherwise.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModIdx {
+	value:   usize,
+	modulus: usize,
+}
+
+impl ModIdx {
+	/// Reduces an index into a ring.
+	///
+	/// # Panics
+	///
+	/// A ring must have at least one slot.
+	pub fn new(value: usize, modulus: usize) -> Self {
+		assert!(modulus > 0, "a ring cannot be empty");
+		Self {
+			value: value % modulus,
+			modulus,
+		}
+	}
+
+	/// Reduces a signed index into a ring, counting negative values backwards
+	/// from the end.
+	pub fn from_signed(value: i64, modulus: usize) -> Self {
+		Self::new(0, modulus) + value
+	}
+
+	/// The index, in `0 .. modulus`.
+	pub fn value(self) -> usize {
+		self.value
+	}
+
+	/// The number of slots in the ring.
+	pub fn modulus(self) -> usize {
+		self.modulus
+	}
+
+	/// Moves to the same position in a ring of a different size.
+	pub fn with_modulus(self, modulus: usize) -> Self {
+		Self::new(self.value, modulus)
+	}
+
+	/// How many steps forward it takes to get from this index to another.
+	pub fn distance_to(self, other: Self) -> usize {
+		(other - self).value
+	}
+
+	/// Walks around the ring forever, starting here and moving `stride` slots
+	/// at a time.
+	pub fn walk(self, stride: i64) -> Walk {
+		Walk { next: self, stride }
+	}
+
+	/// Visits every slot of the ring once, starting here and going forwards.
+	pub fn around(self) -> impl Iterator<Item = Self> {
+		self.walk(1).take(self.modulus)
+	}
+
+	/// Moves by a signed offset, in `i128` so that no offset or modulus can
+	/// overflow before it is reduced.
+	fn shift(self, by: i128) -> Self {
+		let modulus = self.modulus as i128;
+		Self {
+			value:   (self.value as i128 + by.rem_euclid(modulus))
+				.rem_euclid(modulus) as usize,
+			modulus: self.modulus,
+		}
+	}
+
+	fn check(self, other: Self) {
+		assert_eq!(
+			self.modulus, other.modulus,
+			"cannot combine indices into rings of different sizes"
+		);
+	}
+}
+
+impl Add<usize> for ModIdx {
+	type Output = Self;
+
+	fn add(self, rhs: usize) -> Self {
+		self.shift(rhs as i128)
+	}
+}
+
+impl Add<i64> for ModIdx {
+	type Output = Self;
+
+	fn add(self, rhs: i64) -> Self {
+		self.shift(rhs as i128)
+	}
+}
+
+impl Add for ModIdx {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self {
+		self.check(rhs);
+		self + rhs.value
+	}
+}
+
+impl Sub<usize> for ModIdx {
+	type Output = Self;
+
+	fn sub(self, rhs: usize) -> Self {
+		self.shift(-(rhs as i128))
+	}
+}
+
+impl Sub<i64> for ModIdx {
+	type Output = Self;
+
+	fn sub(self, rhs: i64) -> Self {
+		self.shift(-(rhs as i128))
+	}
+}
+
+impl Sub for ModIdx {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		self.check(rhs);
+		self - rhs.value
+	}
+}
+
+impl Mul<usize> for ModIdx {
+	type Output = Self;
+
+	fn mul(self, rhs: usize) -> Self {
+		let product = self.value as u128 * (rhs % self.modulus) as u128;
+		Self {
+			value:   (product % self.modulus as u128) as usize,
+			modulus: self.modulus,
+		}
+	}
+}
+
+impl Mul for ModIdx {
+	type Output = Self;
+
+	fn mul(self, rhs: Self) -> Self {
+		self.check(rhs);
+		self * rhs.value
+	}
+}
+
+impl Neg for ModIdx {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self::new(0, self.modulus) - self.value
+	}
+}
+
+impl<T> AddAssign<T> for ModIdx
+where Self: Add<T, Output = Self>
+{
+	fn add_assign(&mut self, rhs: T) {
+		*self = *self + rhs;
+	}
+}
+
+impl<T> SubAssign<T> for ModIdx
+where Self: Sub<T, Output = Self>
+{
+	fn sub_assign(&mut self, rhs: T) {
+		*self = *self - rhs;
+	}
+}
+
+impl<T> MulAssign<T> for ModIdx
+where Self: Mul<T, Output = Self>
+{
+	fn mul_assign(&mut self, rhs: T) {
+		*self = *self * rhs;
+	}
+}
+
+impl From<ModIdx> for usize {
+	fn from(idx: ModIdx) -> Self {
+		idx.value
+	}
+}
+
+impl fmt::Display for ModIdx {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{} (mod {})", self.value, self.modulus)
+	}
+}
+
+/// Walks around a ring forever, by a fixed stride. See [`ModIdx::walk`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Walk {
+	next:   ModIdx,
+	stride: i64,
+}
+
+impl Iterator for Walk {
+	type Item = ModIdx;
+
+	fn next(&mut self) -> Option<ModIdx> {
+		let here = self.next;
+		self.next += self.stride;
+		Some(here)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(usize::MAX, None)
+	}
+}
+
+impl FusedIterator for Walk {
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wraps() {
+		let idx = ModIdx::new(3, 7);
+		assert_eq!((idx + 5usize).value(), 1);
+		assert_eq!((idx - 5usize).value(), 5);
+		assert_eq!((idx + -10i64).value(), 0);
+		assert_eq!(
+			(idx + i64::MIN).value(),
+			(3 + i64::MIN).rem_euclid(7) as usize
+		);
+		assert_eq!((idx * usize::MAX).value(), 3 * (usize::MAX % 7) % 7);
+		assert_eq!((-idx).value(), 4);
+		assert_eq!(ModIdx::from_signed(-1, 7).value(), 6);
+		assert_eq!(idx.distance_to(ModIdx::new(1, 7)), 5);
+		assert_eq!(idx.walk(-3).take(4).map(usize::from).collect::<Vec<_>>(), [
+			3, 0, 4, 1
+		]);
+		assert_eq!(idx.around().count(), 7);
+	}
+}
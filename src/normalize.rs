@@ -0,0 +1,103 @@
+//! Cleaning up input text before it is parsed.
+//!
+//! Puzzle inputs downloaded from the site use `\n` line endings and end with
+//! exactly one of them, and most parsers are written against that. Inputs that
+//! pass through other hands differ in ways that carry no puzzle data: a copy
+//! saved on Windows has `\r\n` endings, an example pasted into an editor gains
+//! or loses blank lines at the end. Strict `nom` parsers reject these, or
+//! worse, stop early and leave part of the input unparsed.
+//!
+//! [`Solver::load_input`](crate::Solver::load_input) applies a
+//! [`Normalization`] to every input it reads, chosen by the solver's
+//! [`Puzzle::input_normalization`](crate::Puzzle::input_normalization).
+
+use std::borrow::Cow;
+
+use bitflags::bitflags;
+
+bitflags! {
+	/// The changes to make to input text before it is parsed.
+	#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+	pub struct Normalization: u8 {
+		/// Rewrite `\r\n` line endings as `\n`.
+		const LINE_ENDINGS = 1;
+		/// Remove spaces and tabs from the end of each line.
+		const TRAILING_WHITESPACE = 2;
+		/// End the text with exactly one newline, removing any blank lines
+		/// after the last line of text, or adding the newline if it is
+		/// missing. Empty text stays empty.
+		const FINAL_NEWLINE = 4;
+	}
+}
+
+impl Default for Normalization {
+	/// Line endings and the end of the text are normalized. Trailing spaces are
+	/// kept, since some puzzles pad lines out to draw a diagram.
+	fn default() -> Self {
+		Self::LINE_ENDINGS | Self::FINAL_NEWLINE
+	}
+}
+
+impl Normalization {
+	/// Applies the normalizations to a text, borrowing it if nothing changes.
+	pub fn apply(self, text: &str) -> Cow<'_, str> {
+		let mut text = Cow::Borrowed(text);
+		if self.contains(Self::LINE_ENDINGS) && text.contains('\r') {
+			text = Cow::Owned(text.replace("\r\n", "\n"));
+		}
+		if self.contains(Self::TRAILING_WHITESPACE)
+			&& text.lines().any(|line| line.ends_with([' ', '\t']))
+		{
+			let mut out = String::with_capacity(text.len());
+			for line in text.split_inclusive('\n') {
+				let (body, ending) = match line.strip_suffix('\n') {
+					Some(body) => (body, "\n"),
+					None => (line, ""),
+				};
+				out.push_str(body.trim_end_matches([' ', '\t']));
+				out.push_str(ending);
+			}
+			text = Cow::Owned(out);
+		}
+		if self.contains(Self::FINAL_NEWLINE) {
+			let content = text.trim_end().len();
+			// The last line of text keeps its own line ending, and everything
+			// after that goes.
+			match text[content ..].find('\n') {
+				_ if content == 0 => text = Cow::Borrowed(""),
+				Some(idx) => {
+					let end = content + idx + 1;
+					text = match text {
+						Cow::Borrowed(text) => Cow::Borrowed(&text[.. end]),
+						Cow::Owned(mut text) => {
+							text.truncate(end);
+							Cow::Owned(text)
+						},
+					};
+				},
+				None => text.to_mut().push('\n'),
+			}
+		}
+		text
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn normalizes() {
+		let default = Normalization::default();
+		assert!(matches!(default.apply("a\nb\n"), Cow::Borrowed("a\nb\n")));
+		assert_eq!(default.apply("a\r\nb"), "a\nb\n");
+		assert_eq!(default.apply("a  \n\n \n\n"), "a  \n");
+		assert_eq!(default.apply("\n\n"), "");
+		assert_eq!(
+			Normalization::TRAILING_WHITESPACE.apply("a \nb\t\n\nc "),
+			"a\nb\n\nc"
+		);
+		assert_eq!(Normalization::FINAL_NEWLINE.apply("a\r\n\r\n"), "a\r\n");
+		assert_eq!(Normalization::empty().apply("a\r\n\r\n"), "a\r\n\r\n");
+	}
+}
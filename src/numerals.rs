@@ -0,0 +1,389 @@
+//! Numbers written out in English words.
+//!
+//! Now and then a puzzle (or a test fixture written to look like one) spells
+//! its numbers out: "twenty-three", "one hundred and four", "the forty-second
+//! step". [`written_number`] and [`written_ordinal`] read those as nom
+//! parsers, in lowercase, British or American style, up to the quintillions.
+//! [`written_digit`] reads only a single digit word, without looking past it
+//! for more, which is what calibration-style puzzles want when the words run
+//! together.
+
+use funty::Integral;
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	combinator::value,
+	error::{
+		Error,
+		ErrorKind,
+	},
+	IResult,
+};
+
+/// One word of a written number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Word {
+	/// Zero through nineteen.
+	Unit(u128),
+	/// Twenty, thirty, and so on through ninety.
+	Tens(u128),
+	Hundred,
+	/// Thousand, million, and so on.
+	Scale(u128),
+}
+
+/// Every word, with its spelling and whether it is the ordinal form.
+///
+/// Longer spellings must be preferred over their prefixes ("seventeen" over
+/// "seven"), which [`next_word`] handles by taking the longest match.
+const WORDS: &[(&str, Word, bool)] = &[
+	("zero", Word::Unit(0), false),
+	("one", Word::Unit(1), false),
+	("two", Word::Unit(2), false),
+	("three", Word::Unit(3), false),
+	("four", Word::Unit(4), false),
+	("five", Word::Unit(5), false),
+	("six", Word::Unit(6), false),
+	("seven", Word::Unit(7), false),
+	("eight", Word::Unit(8), false),
+	("nine", Word::Unit(9), false),
+	("ten", Word::Unit(10), false),
+	("eleven", Word::Unit(11), false),
+	("twelve", Word::Unit(12), false),
+	("thirteen", Word::Unit(13), false),
+	("fourteen", Word::Unit(14), false),
+	("fifteen", Word::Unit(15), false),
+	("sixteen", Word::Unit(16), false),
+	("seventeen", Word::Unit(17), false),
+	("eighteen", Word::Unit(18), false),
+	("nineteen", Word::Unit(19), false),
+	("twenty", Word::Tens(20), false),
+	("thirty", Word::Tens(30), false),
+	("forty", Word::Tens(40), false),
+	("fifty", Word::Tens(50), false),
+	("sixty", Word::Tens(60), false),
+	("seventy", Word::Tens(70), false),
+	("eighty", Word::Tens(80), false),
+	("ninety", Word::Tens(90), false),
+	("hundred", Word::Hundred, false),
+	("thousand", Word::Scale(1_000), false),
+	("million", Word::Scale(1_000_000), false),
+	("billion", Word::Scale(1_000_000_000), false),
+	("trillion", Word::Scale(1_000_000_000_000), false),
+	("quadrillion", Word::Scale(1_000_000_000_000_000), false),
+	("quintillion", Word::Scale(1_000_000_000_000_000_000), false),
+	("zeroth", Word::Unit(0), true),
+	("first", Word::Unit(1), true),
+	("second", Word::Unit(2), true),
+	("third", Word::Unit(3), true),
+	("fourth", Word::Unit(4), true),
+	("fifth", Word::Unit(5), true),
+	("sixth", Word::Unit(6), true),
+	("seventh", Word::Unit(7), true),
+	("eighth", Word::Unit(8), true),
+	("ninth", Word::Unit(9), true),
+	("tenth", Word::Unit(10), true),
+	("eleventh", Word::Unit(11), true),
+	("twelfth", Word::Unit(12), true),
+	("thirteenth", Word::Unit(13), true),
+	("fourteenth", Word::Unit(14), true),
+	("fifteenth", Word::Unit(15), true),
+	("sixteenth", Word::Unit(16), true),
+	("seventeenth", Word::Unit(17), true),
+	("eighteenth", Word::Unit(18), true),
+	("nineteenth", Word::Unit(19), true),
+	("twentieth", Word::Tens(20), true),
+	("thirtieth", Word::Tens(30), true),
+	("fortieth", Word::Tens(40), true),
+	("fiftieth", Word::Tens(50), true),
+	("sixtieth", Word::Tens(60), true),
+	("seventieth", Word::Tens(70), true),
+	("eightieth", Word::Tens(80), true),
+	("ninetieth", Word::Tens(90), true),
+	("hundredth", Word::Hundred, true),
+	("thousandth", Word::Scale(1_000), true),
+	("millionth", Word::Scale(1_000_000), true),
+	("billionth", Word::Scale(1_000_000_000), true),
+	("trillionth", Word::Scale(1_000_000_000_000), true),
+	("quadrillionth", Word::Scale(1_000_000_000_000_000), true),
+	(
+		"quintillionth",
+		Word::Scale(1_000_000_000_000_000_000),
+		true,
+	),
+];
+
+/// Parses a single digit word, from "zero" to "nine".
+///
+/// This does not look past the word, so `"eightwo"` parses as 8 with `"wo"`
+/// left over, and `"seventeen"` as 7 with `"teen"` left over.
+pub fn written_digit<T: Integral>(text: &str) -> IResult<&str, T>
+where <T as TryFrom<i8>>::Error: std::fmt::Debug {
+	alt((
+		value(T::try_from(0i8).expect("infallible"), tag("zero")),
+		value(T::try_from(1i8).expect("infallible"), tag("one")),
+		value(T::try_from(2i8).expect("infallible"), tag("two")),
+		value(T::try_from(3i8).expect("infallible"), tag("three")),
+		value(T::try_from(4i8).expect("infallible"), tag("four")),
+		value(T::try_from(5i8).expect("infallible"), tag("five")),
+		value(T::try_from(6i8).expect("infallible"), tag("six")),
+		value(T::try_from(7i8).expect("infallible"), tag("seven")),
+		value(T::try_from(8i8).expect("infallible"), tag("eight")),
+		value(T::try_from(9i8).expect("infallible"), tag("nine")),
+	))(text)
+}
+
+/// Parses a cardinal number written in words, such as "zero", "forty-two",
+/// or "one million, two hundred and three thousand and four".
+///
+/// Tens and units are joined by a hyphen or a space, "and" may precede the
+/// tens and units after a hundred or a scale word, and a comma may follow a
+/// scale word. Numbers may be negated by a leading "minus" or "negative".
+/// The parser stops at the first word which cannot continue the number, so
+/// `"three blind mice"` produces 3 and leaves `" blind mice"`.
+pub fn written_number<T: Integral>(text: &str) -> IResult<&str, T> {
+	parse(text, false)
+}
+
+/// Parses an ordinal number written in words, such as "first",
+/// "twenty-second", or "one hundredth", producing its value.
+///
+/// The words before the last are written as for [`written_number`], and the
+/// last word is in its ordinal form.
+pub fn written_ordinal<T: Integral>(text: &str) -> IResult<&str, T> {
+	parse(text, true)
+}
+
+/// Finds the longest word at the start of the text.
+fn next_word(text: &str, ordinal: bool) -> Option<(Word, bool, &str)> {
+	WORDS
+		.iter()
+		.filter(|&&(_, _, is_ordinal)| ordinal || !is_ordinal)
+		.filter(|(spelling, ..)| text.starts_with(spelling))
+		.max_by_key(|(spelling, ..)| spelling.len())
+		.map(|&(spelling, word, is_ordinal)| {
+			(word, is_ordinal, &text[spelling.len() ..])
+		})
+}
+
+/// The progress of the parser through the group of three digits which it is
+/// currently reading.
+#[derive(Clone, Copy, Debug, Default)]
+struct Group {
+	value:   u128,
+	/// Whether the group has a hundreds place.
+	hundred: bool,
+	/// Whether the group has a word for its tens place.
+	tens:    bool,
+	/// Whether the group has a word for its units place.
+	units:   bool,
+}
+
+fn parse<T: Integral>(text: &str, ordinal: bool) -> IResult<&str, T> {
+	let fail = |kind| Err(nom::Err::Error(Error::new(text, kind)));
+	let (negative, mut rest) = ["minus ", "negative "]
+		.iter()
+		.find_map(|sign| text.strip_prefix(sign))
+		.map_or((false, text), |rest| (true, rest));
+
+	let mut total = 0u128;
+	let mut group = Group::default();
+	// The smallest scale word so far. Scales must shrink as the number goes.
+	let mut last_scale = u128::MAX;
+	let mut finished_ordinal = false;
+	let mut first = true;
+	loop {
+		// Words after the first need a separator, and some separators are
+		// only allowed before some words.
+		let (joiner, after) = if first {
+			("", rest)
+		}
+		else {
+			match ["-", ", and ", ", ", " and ", " "].iter().find_map(|sep| {
+				rest.strip_prefix(sep).map(|after| (*sep, after))
+			}) {
+				Some(found) => found,
+				None => break,
+			}
+		};
+		let Some((word, is_ordinal, after)) = next_word(after, ordinal)
+		else {
+			break;
+		};
+		// A scale word was the last word read.
+		let after_scale = !first && group.value == 0;
+		let fits = match word {
+			Word::Unit(0) => first,
+			Word::Unit(n) if group.tens => {
+				!group.units && n < 10 && matches!(joiner, "-" | " ")
+			},
+			Word::Unit(_) | Word::Tens(_) => {
+				!group.units
+					&& !group.tens && (first || group.hundred || after_scale)
+					&& joiner != "-"
+					&& (!joiner.starts_with(',') || after_scale)
+			},
+			// "Twelve hundred" and "twenty-three hundred" are common enough.
+			Word::Hundred => !group.hundred && group.value > 0 && joiner == " ",
+			Word::Scale(scale) => {
+				group.value > 0 && scale < last_scale && joiner == " "
+			},
+		};
+		if !fits {
+			break;
+		}
+		match word {
+			Word::Unit(n) => {
+				group.value += n;
+				group.units = true;
+			},
+			Word::Tens(n) => {
+				group.value += n;
+				group.tens = true;
+			},
+			Word::Hundred => {
+				group.value *= 100;
+				group.hundred = true;
+				group.tens = false;
+				group.units = false;
+			},
+			Word::Scale(scale) => {
+				let Some(scaled) = group
+					.value
+					.checked_mul(scale)
+					.and_then(|v| total.checked_add(v))
+				else {
+					return fail(ErrorKind::TooLarge);
+				};
+				total = scaled;
+				group = Group::default();
+				last_scale = scale;
+			},
+		}
+		rest = after;
+		first = false;
+		if is_ordinal {
+			finished_ordinal = true;
+			break;
+		}
+		if word == Word::Unit(0) {
+			break;
+		}
+	}
+	if first {
+		return fail(ErrorKind::Tag);
+	}
+	if ordinal && !finished_ordinal {
+		return fail(ErrorKind::Tag);
+	}
+	let Some(magnitude) = total
+		.checked_add(group.value)
+		.and_then(|v| i128::try_from(v).ok())
+	else {
+		return fail(ErrorKind::TooLarge);
+	};
+	let signed = if negative { -magnitude } else { magnitude };
+	match T::try_from(signed) {
+		Ok(value) => Ok((rest, value)),
+		Err(_) => fail(ErrorKind::TooLarge),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn number(text: &str) -> Option<(&str, i64)> {
+		written_number::<i64>(text).ok()
+	}
+
+	fn ordinal(text: &str) -> Option<(&str, i64)> {
+		written_ordinal::<i64>(text).ok()
+	}
+
+	#[test]
+	fn cardinals() {
+		assert_eq!(number("zero"), Some(("", 0)));
+		assert_eq!(number("seven"), Some(("", 7)));
+		assert_eq!(number("seventeen"), Some(("", 17)));
+		assert_eq!(number("forty"), Some(("", 40)));
+		assert_eq!(number("twenty-three"), Some(("", 23)));
+		assert_eq!(number("ninety nine"), Some(("", 99)));
+		assert_eq!(number("one hundred"), Some(("", 100)));
+		assert_eq!(number("one hundred and four"), Some(("", 104)));
+		assert_eq!(number("one hundred four"), Some(("", 104)));
+		assert_eq!(number("three hundred and sixty-five"), Some(("", 365)));
+		assert_eq!(number("twelve hundred"), Some(("", 1200)));
+		assert_eq!(number("nineteen hundred and eighty-four"), Some(("", 1984)));
+		assert_eq!(number("two thousand and twenty-three"), Some(("", 2023)));
+		assert_eq!(
+			number("one million, two hundred and three thousand and four"),
+			Some(("", 1_203_004))
+		);
+		assert_eq!(
+			number("four hundred thousand, one hundred and twelve"),
+			Some(("", 400_112))
+		);
+		assert_eq!(number("minus forty"), Some(("", -40)));
+		assert_eq!(number("negative one"), Some(("", -1)));
+	}
+
+	#[test]
+	fn cardinals_stop_where_numbers_do() {
+		assert_eq!(number("three blind mice"), Some((" blind mice", 3)));
+		assert_eq!(number("one hundred and"), Some((" and", 100)));
+		assert_eq!(number("ten and six"), Some((" and six", 10)));
+		assert_eq!(number("twenty twenty"), Some((" twenty", 20)));
+		assert_eq!(number("twelve-three"), Some(("-three", 12)));
+		assert_eq!(number("twenty-thirteen"), Some(("-thirteen", 20)));
+		assert_eq!(number("one thousand one million"), Some((" million", 1001)));
+		assert_eq!(number("zero one"), Some((" one", 0)));
+		assert_eq!(number("eightwo"), Some(("wo", 8)));
+		assert_eq!(number("fourth"), Some(("th", 4)));
+		assert_eq!(number("a dozen"), None);
+		assert_eq!(number(""), None);
+	}
+
+	#[test]
+	fn cardinals_check_their_range() {
+		assert_eq!(
+			written_number::<u8>("two hundred and fifty-five").ok(),
+			Some(("", 255))
+		);
+		assert!(written_number::<u8>("two hundred and fifty-six").is_err());
+		assert!(written_number::<u8>("minus one").is_err());
+		assert_eq!(
+			written_number::<i8>("minus one hundred and twenty-eight").ok(),
+			Some(("", -128))
+		);
+		assert_eq!(
+			written_number::<u64>("eighteen quintillion").ok(),
+			Some(("", 18_000_000_000_000_000_000))
+		);
+		assert!(written_number::<i64>("ten quintillion").is_err());
+	}
+
+	#[test]
+	fn ordinals() {
+		assert_eq!(ordinal("first"), Some(("", 1)));
+		assert_eq!(ordinal("second place"), Some((" place", 2)));
+		assert_eq!(ordinal("twelfth"), Some(("", 12)));
+		assert_eq!(ordinal("twentieth"), Some(("", 20)));
+		assert_eq!(ordinal("twenty-first"), Some(("", 21)));
+		assert_eq!(ordinal("forty-second"), Some(("", 42)));
+		assert_eq!(ordinal("one hundredth"), Some(("", 100)));
+		assert_eq!(ordinal("one hundred and first"), Some(("", 101)));
+		assert_eq!(ordinal("two thousandth"), Some(("", 2000)));
+		assert_eq!(ordinal("zeroth"), Some(("", 0)));
+		assert_eq!(ordinal("twenty-three"), None);
+		assert_eq!(ordinal("first second"), Some((" second", 1)));
+	}
+
+	#[test]
+	fn digits() {
+		let digit = |text| written_digit::<i64>(text).ok();
+		assert_eq!(digit("seventeen"), Some(("teen", 7)));
+		assert_eq!(digit("twenty"), None);
+		assert_eq!(digit("ninetyeight"), Some(("tyeight", 9)));
+	}
+}
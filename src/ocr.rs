@@ -0,0 +1,234 @@
+//! Reading the block letters that some puzzles draw as their answer.
+//!
+//! A few puzzles (2016 day 8, 2019 day 11, 2021 day 13, 2022 day 10) end with
+//! a picture of some capital letters, drawn on a grid of pixels six tall, and
+//! the answer is whatever the letters spell. Rather than print the picture and
+//! make me squint at it, solvers can hand the pixels to this module and get the
+//! text back.
+//!
+//! Every puzzle uses the same font, with glyphs four (occasionally five)
+//! pixels wide and at least one blank column between letters. Only the letters
+//! which have been seen in real inputs are known; anything else is an error
+//! which shows the picture, so it can be added to [`FONT`].
+
+use bitvec::{
+	order::BitOrder,
+	slice::BitSlice,
+	store::BitStore,
+};
+use funty::Signed;
+
+use crate::coords::spaces::Dense2D;
+
+/// The height, in pixels, of every letter.
+pub const HEIGHT: usize = 6;
+
+/// The known letters, drawn with `#` for lit pixels.
+///
+/// Each glyph is trimmed to its own width; the blank columns around it are not
+/// part of the glyph.
+pub const FONT: &[(char, [&str; HEIGHT])] = &[
+	('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+	('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+	('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+	('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+	('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+	('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+	('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+	('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+	('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+	('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+	('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+	('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+	('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+	('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+	('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+	('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+	('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+	('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters drawn in rows of pixels.
+///
+/// Blank rows above and below the letters are ignored, as are blank columns
+/// around and between them. The rows must all be the same length.
+pub fn read<R: AsRef<[bool]>>(rows: &[R]) -> eyre::Result<String> {
+	let mut rows = rows.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+	let width = rows.first().map(|row| row.len()).unwrap_or_default();
+	eyre::ensure!(
+		rows.iter().all(|row| row.len() == width),
+		"the rows of pixels have different lengths"
+	);
+	while rows.first().is_some_and(|row| !row.contains(&true)) {
+		rows.remove(0);
+	}
+	while rows.last().is_some_and(|row| !row.contains(&true)) {
+		rows.pop();
+	}
+	eyre::ensure!(
+		rows.len() == HEIGHT,
+		"letters are {HEIGHT} pixels tall, but the picture is {} tall",
+		rows.len()
+	);
+	let pixels = Pixels { rows };
+
+	let mut out = String::new();
+	let mut col = 0;
+	while col < width {
+		if pixels.is_blank(col) {
+			col += 1;
+			continue;
+		}
+		let end = (col .. width)
+			.find(|&c| pixels.is_blank(c))
+			.unwrap_or(width);
+		// Letters which are five pixels wide can touch their neighbor, so a
+		// run of columns which is not a whole letter is read from the left.
+		let (letter, len) = FONT
+			.iter()
+			.filter(|(_, glyph)| glyph[0].len() == end - col)
+			.chain(FONT.iter())
+			.find(|(_, glyph)| pixels.matches(col, glyph))
+			.map(|&(letter, glyph)| (letter, glyph[0].len()))
+			.ok_or_else(|| {
+				eyre::eyre!(
+					"unknown letter at column {col}:\n{}",
+					pixels.render(col .. end)
+				)
+			})?;
+		out.push(letter);
+		col += len;
+	}
+	Ok(out)
+}
+
+/// Reads the letters drawn in a dense grid.
+pub fn read_grid<I: Signed>(grid: &Dense2D<I, bool>) -> eyre::Result<String> {
+	read(grid.raw_data())
+}
+
+/// Reads the letters drawn in a bit-slice, which holds the rows of the picture
+/// one after another.
+pub fn read_bits<T: BitStore, O: BitOrder>(
+	bits: &BitSlice<T, O>,
+	width: usize,
+) -> eyre::Result<String> {
+	eyre::ensure!(width > 0, "the picture has no width");
+	let rows = bits
+		.chunks(width)
+		.map(|row| row.iter().by_vals().collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	read(&rows)
+}
+
+/// Reads the letters in a picture printed as text, where `#` and `█` are lit.
+pub fn read_str(text: &str) -> eyre::Result<String> {
+	let rows = text
+		.lines()
+		.map(|line| line.chars().map(|c| matches!(c, '#' | '█')).collect())
+		.collect::<Vec<Vec<bool>>>();
+	let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+	let rows = rows
+		.into_iter()
+		.map(|mut row| {
+			row.resize(width, false);
+			row
+		})
+		.collect::<Vec<_>>();
+	read(&rows)
+}
+
+/// Exactly [`HEIGHT`] rows of pixels.
+struct Pixels<'a> {
+	rows: Vec<&'a [bool]>,
+}
+
+impl Pixels<'_> {
+	fn is_blank(&self, col: usize) -> bool {
+		self.rows.iter().all(|row| !row[col])
+	}
+
+	/// Tests whether a glyph is drawn with its left edge at a column.
+	fn matches(&self, col: usize, glyph: &[&str; HEIGHT]) -> bool {
+		self.rows.iter().zip(glyph).all(|(row, line)| {
+			row.get(col .. col + line.len()).is_some_and(|pixels| {
+				pixels
+					.iter()
+					.zip(line.chars())
+					.all(|(&lit, c)| lit == (c == '#'))
+			})
+		})
+	}
+
+	fn render(&self, cols: std::ops::Range<usize>) -> String {
+		self.rows
+			.iter()
+			.map(|row| {
+				row[cols.clone()]
+					.iter()
+					.map(|&lit| {
+						if lit {
+							'#'
+						}
+						else {
+							'.'
+						}
+					})
+					.collect::<String>()
+			})
+			.collect::<Vec<_>>()
+			.join("\n")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use bitvec::prelude::*;
+
+	use super::*;
+
+	#[test]
+	fn reads_letters() -> eyre::Result<()> {
+		let picture = [
+			"#..#.####.#....#.....##.",
+			"#..#.#....#....#....#..#",
+			"####.###..#....#....#..#",
+			"#..#.#....#....#....#..#",
+			"#..#.#....#....#....#..#",
+			"#..#.####.####.####..##.",
+		]
+		.join("\n");
+		assert_eq!(read_str(&picture)?, "HELLO");
+
+		let mut bits = bitvec![0; 24 * 8];
+		for (row, line) in picture.lines().enumerate() {
+			for (col, c) in line.chars().enumerate() {
+				bits.set((row + 1) * 24 + col, c == '#');
+			}
+		}
+		assert_eq!(read_bits(&bits, 24)?, "HELLO");
+		Ok(())
+	}
+
+	#[test]
+	fn reads_touching_letters() -> eyre::Result<()> {
+		let picture = [
+			"#...####.",
+			"#...##..#",
+			".#.#.#..#",
+			"..#..###.",
+			"..#..#...",
+			"..#..#...",
+		]
+		.join("\n");
+		assert_eq!(read_str(&picture)?, "YP");
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_unknown_letters() {
+		let err = read_str("#\n#\n#\n#\n#\n#\n").unwrap_err();
+		assert!(err.to_string().contains("unknown letter at column 0"));
+		assert!(read_str("#\n#\n").is_err());
+	}
+}
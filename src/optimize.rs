@@ -0,0 +1,283 @@
+//! Subset sums, and picking the best subset.
+//!
+//! Puzzles of the "fill the containers exactly" and "split the packages into
+//! equal piles" kind all ask which subsets of a list of weights add up to a
+//! target. The list is usually short enough to search, but far too long to
+//! enumerate all `2^n` subsets blindly, so each helper here prunes as it goes
+//! or splits the work in two.
+//!
+//! - [`subsets_with_sum`] lists the exact-sum subsets themselves, lazily, for
+//!   callers which must inspect each one or want only the first.
+//! - [`subsets_by_size`] counts the exact-sum subsets of each cardinality, by
+//!   dynamic programming over the sums. This is the cheapest option when the
+//!   target is small.
+//! - [`count_subsets_meet_in_middle`] counts exact-sum subsets by enumerating
+//!   the sums of each half of the list and pairing them up, for when the
+//!   weights are too large to index by.
+//! - [`min_subset_by_product`] finds the smallest exact-sum subset, breaking
+//!   ties by the product of its weights, and lets the caller veto candidates.
+//! - [`can_partition`] checks whether a list splits into piles of equal sum.
+//!
+//! Weights are `u64`. Subsets are reported as indices into the original list,
+//! so callers can recover which items were chosen even when weights repeat.
+
+use std::{
+	cmp::Reverse,
+	iter,
+};
+
+/// Lists the subsets of `items` which sum to exactly `target`.
+///
+/// Each subset is a list of indices into `items`, in increasing order. They
+/// are found one at a time, by a depth-first search which abandons any branch
+/// that the items after it are too light to complete, so a caller which stops
+/// early does not pay for the rest.
+pub fn subsets_with_sum(
+	items: &[u64],
+	target: u64,
+) -> impl '_ + Iterator<Item = Vec<usize>> {
+	// `reach[idx]` is the weight of `items[idx ..]`, the most that a branch
+	// standing at `idx` can still add.
+	let mut reach = vec![0; items.len() + 1];
+	for idx in (0 .. items.len()).rev() {
+		reach[idx] = reach[idx + 1] + items[idx];
+	}
+	let mut stack = vec![(0, target, Vec::new())];
+	iter::from_fn(move || {
+		while let Some((idx, remaining, chosen)) = stack.pop() {
+			if idx == items.len() {
+				if remaining == 0 {
+					return Some(chosen);
+				}
+				continue;
+			}
+			if reach[idx] < remaining {
+				continue;
+			}
+			// The branch which takes the item is pushed last, so that it is
+			// explored first.
+			stack.push((idx + 1, remaining, chosen.clone()));
+			if let Some(left) = remaining.checked_sub(items[idx]) {
+				let mut taken = chosen;
+				taken.push(idx);
+				stack.push((idx + 1, left, taken));
+			}
+		}
+		None
+	})
+}
+
+/// Counts the subsets of `items` which sum to exactly `target`, by how many
+/// items they use.
+///
+/// Entry `n` of the result is the number of `n`-item subsets which hit the
+/// target; the result has `items.len() + 1` entries. Equal weights at
+/// different indices are different items.
+///
+/// This takes `O(items² × target)` time, and `O(items × target)` space.
+pub fn subsets_by_size(items: &[u64], target: u64) -> Vec<u64> {
+	let count = items.len();
+	let target = target as usize;
+	// `ways[sum][n]` counts the `n`-item subsets seen so far summing to `sum`.
+	let mut ways = vec![vec![0u64; count + 1]; target + 1];
+	ways[0][0] = 1;
+	for &item in items {
+		let item = item as usize;
+		// Walking both axes downwards reads only entries which do not yet
+		// include this item, so that it is used at most once.
+		for sum in (item ..= target).rev() {
+			for n in (1 ..= count).rev() {
+				ways[sum][n] += ways[sum - item][n - 1];
+			}
+		}
+	}
+	ways.swap_remove(target)
+}
+
+/// Counts the subsets of `items` which sum to exactly `target`, by meeting in
+/// the middle.
+///
+/// Each half of the list has its `2^(n/2)` subset sums enumerated; every sum
+/// on the left is then paired with the matching sums on the right. This does
+/// not depend on the magnitude of the weights, only on how many there are, so
+/// it suits lists of up to about forty large weights.
+pub fn count_subsets_meet_in_middle(items: &[u64], target: u64) -> u64 {
+	let (left, right) = items.split_at(items.len() / 2);
+	let mut right = all_sums(right);
+	right.sort_unstable();
+	all_sums(left)
+		.into_iter()
+		.filter_map(|sum| target.checked_sub(sum))
+		.map(|need| {
+			let lo = right.partition_point(|&s| s < need);
+			let hi = right.partition_point(|&s| s <= need);
+			(hi - lo) as u64
+		})
+		.sum()
+}
+
+/// Lists the sum of every subset of `items`, including the empty one.
+fn all_sums(items: &[u64]) -> Vec<u64> {
+	let mut sums = Vec::with_capacity(1 << items.len());
+	sums.push(0);
+	for &item in items {
+		for idx in 0 .. sums.len() {
+			sums.push(sums[idx] + item);
+		}
+	}
+	sums
+}
+
+/// Finds the subset of `items` which sums to exactly `target` with the fewest
+/// items, breaking ties by the smallest product of its weights.
+///
+/// `accept` sees each candidate (as indices into `items`) which would improve
+/// on the best found so far, and can reject it; this is where callers check
+/// constraints on the items left over. Cardinalities are tried in increasing
+/// order, and the search stops at the first one with an accepted subset.
+///
+/// Within a cardinality, the search takes the heaviest items first and
+/// abandons any branch which can no longer reach the target with the slots it
+/// has left, or whose product already matches the best.
+///
+/// The returned indices are in decreasing order of weight.
+pub fn min_subset_by_product(
+	items: &[u64],
+	target: u64,
+	accept: impl FnMut(&[usize]) -> bool,
+) -> Option<Vec<usize>> {
+	let mut order = (0 .. items.len()).collect::<Vec<_>>();
+	order.sort_by_key(|&idx| Reverse(items[idx]));
+	let mut selection = Selection {
+		items,
+		order,
+		size: 0,
+		accept,
+		chosen: Vec::new(),
+		best: None,
+	};
+	for size in 1 ..= items.len() {
+		selection.size = size;
+		selection.search(0, target, 1);
+		if let Some((_, best)) = selection.best {
+			return Some(best);
+		}
+	}
+	None
+}
+
+/// The state of a [`min_subset_by_product`] search at one cardinality.
+struct Selection<'a, F> {
+	items:  &'a [u64],
+	/// Indices into `items`, heaviest first.
+	order:  Vec<usize>,
+	/// How many items the subset must have.
+	size:   usize,
+	accept: F,
+	/// The indices picked on the current branch.
+	chosen: Vec<usize>,
+	/// The best accepted product, and its subset.
+	best:   Option<(u64, Vec<usize>)>,
+}
+
+impl<F: FnMut(&[usize]) -> bool> Selection<'_, F> {
+	/// Extends the current branch with items from `order[start ..]`.
+	fn search(&mut self, start: usize, remaining: u64, product: u64) {
+		if self.best.as_ref().is_some_and(|&(best, _)| product >= best) {
+			return;
+		}
+		let slots = self.size - self.chosen.len();
+		if slots == 0 {
+			if remaining == 0 && (self.accept)(&self.chosen) {
+				self.best = Some((product, self.chosen.clone()));
+			}
+			return;
+		}
+		// The heaviest items still available are the most that the open slots
+		// can hold.
+		let reach = self.order[start ..]
+			.iter()
+			.take(slots)
+			.map(|&idx| self.items[idx])
+			.sum::<u64>();
+		if reach < remaining {
+			return;
+		}
+		for pos in start .. self.order.len() {
+			let idx = self.order[pos];
+			let item = self.items[idx];
+			if item > remaining {
+				continue;
+			}
+			self.chosen.push(idx);
+			self.search(pos + 1, remaining - item, product.saturating_mul(item));
+			self.chosen.pop();
+		}
+	}
+}
+
+/// Checks whether `items` can be split into `groups` piles which each sum to
+/// exactly `target`, using every item.
+pub fn can_partition(items: &[u64], target: u64, groups: usize) -> bool {
+	if items.iter().sum::<u64>() != target * groups as u64 {
+		return false;
+	}
+	if groups <= 1 {
+		return true;
+	}
+	let mut sorted = items.to_vec();
+	sorted.sort_unstable_by_key(|&item| Reverse(item));
+	// Some pile holds the heaviest item, so only those piles need trying. Once
+	// it is full, the rest of the items must split among the other piles.
+	let Some((&first, rest)) = sorted.split_first()
+	else {
+		return true;
+	};
+	let Some(need) = target.checked_sub(first)
+	else {
+		return false;
+	};
+	let found = subsets_with_sum(rest, need).any(|pile| {
+		let left = rest
+			.iter()
+			.enumerate()
+			.filter(|(idx, _)| pile.binary_search(idx).is_err())
+			.map(|(_, &item)| item)
+			.collect::<Vec<_>>();
+		can_partition(&left, target, groups - 1)
+	});
+	found
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn subset_sums() {
+		let items = [20, 15, 10, 5, 5];
+		assert_eq!(subsets_by_size(&items, 25), [0, 0, 3, 1, 0, 0]);
+		assert_eq!(count_subsets_meet_in_middle(&items, 25), 4);
+		assert_eq!(count_subsets_meet_in_middle(&items, 0), 1);
+		let subsets = subsets_with_sum(&items, 25).collect::<Vec<_>>();
+		assert_eq!(subsets, [vec![0, 3], vec![0, 4], vec![1, 2], vec![1, 3, 4]]);
+		assert_eq!(subsets_with_sum(&items, 0).collect::<Vec<_>>(), [Vec::<
+			usize,
+		>::new(
+		)]);
+		assert_eq!(subsets_with_sum(&items, 100).next(), None);
+
+		let items = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+		let picked = min_subset_by_product(&items, 20, |chosen| {
+			let rest = (0 .. items.len())
+				.filter(|idx| !chosen.contains(idx))
+				.map(|idx| items[idx])
+				.collect::<Vec<_>>();
+			can_partition(&rest, 20, 2)
+		});
+		assert_eq!(picked, Some(vec![9, 7]));
+		assert!(can_partition(&items, 15, 4));
+		assert!(!can_partition(&[5, 3, 2, 2], 6, 2));
+		assert_eq!(min_subset_by_product(&items, 100, |_| true), None);
+	}
+}
@@ -0,0 +1,204 @@
+//! Puzzle constants which differ between data groups.
+//!
+//! Some puzzles change a constant between the worked example and the real
+//! input: the row to scan, the number of seconds to simulate, the size of the
+//! search area. Rather than recompile to switch, a solver exposes those
+//! constants through [`Puzzle::set_param`](crate::Puzzle::set_param), and the
+//! values for each data group are kept in
+//! `assets/params/{group}/{year}/d{day}.toml`, as flat `key = value` pairs.
+//!
+//! The harness loads the file for the group being run, if there is one, and
+//! hands it to [`Puzzle::set_params`](crate::Puzzle::set_params) right after
+//! parsing, before any other phase runs.
+
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::PathBuf,
+	str::FromStr,
+};
+
+use eyre::WrapErr as _;
+
+/// A set of named parameter values, kept as the text the solver will parse.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParamMap {
+	values: BTreeMap<String, String>,
+}
+
+impl ParamMap {
+	/// Creates an empty parameter set.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Parses a parameter file.
+	///
+	/// The file must be a TOML table of scalars. Strings are taken without
+	/// their quotes; numbers and booleans are taken as written.
+	pub fn parse(text: &str) -> eyre::Result<Self> {
+		let table = text.parse::<toml::Table>()?;
+		let mut out = Self::new();
+		for (key, value) in table {
+			let value = match value {
+				toml::Value::String(text) => text,
+				toml::Value::Integer(num) => num.to_string(),
+				toml::Value::Float(num) => num.to_string(),
+				toml::Value::Boolean(flag) => flag.to_string(),
+				other => eyre::bail!(
+					"parameter `{key}` must be a scalar, not a {}",
+					other.type_str()
+				),
+			};
+			out.insert(key, value);
+		}
+		Ok(out)
+	}
+
+	/// Computes the path of the parameter file for a data group.
+	pub fn path(group: &str, year: u16, day: u8) -> eyre::Result<PathBuf> {
+		let mut path = crate::config::asset_root()?;
+		path.push("assets");
+		path.push("params");
+		path.push(group);
+		path.push(year.to_string());
+		path.push(format!("d{day:0>2}.toml"));
+		Ok(path)
+	}
+
+	/// Loads the parameters for a data group, or an empty set if the group
+	/// has no parameter file.
+	#[cfg_attr(feature = "trace", tracing::instrument)]
+	pub fn load(group: &str, year: u16, day: u8) -> eyre::Result<Self> {
+		let path = Self::path(group, year, day)?;
+		#[cfg(feature = "embed")]
+		if !path.exists() {
+			if let Some(text) = crate::embedded::params(group, year, day) {
+				return Self::parse(text).wrap_err_with(|| {
+					format!(
+						"could not parse embedded parameters {}",
+						path.display()
+					)
+				});
+			}
+		}
+		if !path.exists() {
+			tracing::trace!(file=%path.display(), "no parameter file");
+			return Ok(Self::new());
+		}
+		let text = fs::read_to_string(&path).wrap_err_with(|| {
+			format!("could not read parameters {}", path.display())
+		})?;
+		Self::parse(&text).wrap_err_with(|| {
+			format!("could not parse parameters {}", path.display())
+		})
+	}
+
+	/// Parses a `key=value` assignment, as given on the command line.
+	pub fn parse_assignment(text: &str) -> eyre::Result<(String, String)> {
+		let (key, value) = text.split_once('=').ok_or_else(|| {
+			eyre::eyre!("expected `key=value`, found `{text}`")
+		})?;
+		let key = key.trim();
+		if key.is_empty() {
+			eyre::bail!("parameter name is empty in `{text}`");
+		}
+		Ok((key.to_owned(), value.trim().to_owned()))
+	}
+
+	/// Sets a parameter, replacing any previous value.
+	pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+		self.values.insert(key.into(), value.into());
+	}
+
+	/// Gets the text of a parameter.
+	pub fn get(&self, key: &str) -> Option<&str> {
+		self.values.get(key).map(String::as_str)
+	}
+
+	/// Gets a parameter and parses it.
+	///
+	/// This produces `Ok(None)` if the parameter is not set, and an error if
+	/// it is set but does not parse.
+	pub fn get_parsed<T: FromStr>(&self, key: &str) -> eyre::Result<Option<T>>
+	where T::Err: 'static + std::error::Error + Send + Sync {
+		self.get(key)
+			.map(|value| {
+				value.parse().wrap_err_with(|| {
+					format!("could not parse parameter `{key}` from `{value}`")
+				})
+			})
+			.transpose()
+	}
+
+	/// Counts the parameters in the set.
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+
+	/// Iterates over the parameters, in key order.
+	pub fn iter(&self) -> impl '_ + Iterator<Item = (&str, &str)> {
+		self.values
+			.iter()
+			.map(|(key, value)| (key.as_str(), value.as_str()))
+	}
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for ParamMap {
+	fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+		let mut out = Self::new();
+		for (key, value) in iter {
+			out.insert(key, value);
+		}
+		out
+	}
+}
+
+impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for ParamMap {
+	/// Sets each parameter in turn, so that later values replace earlier
+	/// ones.
+	fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+		for (key, value) in iter {
+			self.insert(key, value);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_params() -> eyre::Result<()> {
+		let params = ParamMap::parse(
+			"# the example scans a smaller area\nrow = 10\nrange = 20\nmode = \
+			 \"fast\"\nverbose = true\n",
+		)?;
+		assert_eq!(params.len(), 4);
+		assert_eq!(params.get("mode"), Some("fast"));
+		assert_eq!(params.get_parsed::<i64>("row")?, Some(10));
+		assert_eq!(params.get_parsed::<bool>("verbose")?, Some(true));
+		assert_eq!(params.get_parsed::<i64>("missing")?, None);
+		assert!(params.get_parsed::<i64>("mode").is_err());
+		assert!(ParamMap::parse("nested = { a = 1 }").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn override_params() -> eyre::Result<()> {
+		let mut params = ParamMap::parse("row = 10\nrange = 20\n")?;
+		let (key, value) = ParamMap::parse_assignment("row = 2000000")?;
+		assert_eq!((key.as_str(), value.as_str()), ("row", "2000000"));
+		params.extend([(key, value)]);
+		assert_eq!(params.get("row"), Some("2000000"));
+		assert_eq!(params.get("range"), Some("20"));
+		assert!(ParamMap::parse_assignment("row").is_err());
+		assert!(ParamMap::parse_assignment("=3").is_err());
+		Ok(())
+	}
+}
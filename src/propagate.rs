@@ -0,0 +1,221 @@
+//! Solving systems of names defined in terms of one another.
+//!
+//! Some puzzles list definitions in no particular order (wire `d` carries
+//! `x AND y`, monkey `root` yells `pppw + sjmn`) and ask for the value of one
+//! name. A [`Solver`] resolves every definition whose operands are all known,
+//! which makes more operands known, until nothing changes. Whatever is left is
+//! stuck: it depends on a cycle, on a name with no definition, or on a name
+//! that was deliberately [forgotten](Solver::forget). What is left after
+//! forgetting a name is the part of the system which depends on it, ready to
+//! be handed to something like [`algebra`](crate::algebra) to solve for it.
+
+use std::{
+	collections::{
+		BTreeMap,
+		BTreeSet,
+	},
+	fmt,
+};
+
+use eyre::WrapErr as _;
+
+/// The definition of a name, in terms of other names.
+pub trait Expression<K, V> {
+	/// Lists the names whose values the expression needs.
+	fn operands(&self) -> Vec<&K>;
+
+	/// Computes the expression, once every operand is known.
+	fn evaluate(&self, operand: impl Fn(&K) -> V) -> eyre::Result<V>;
+}
+
+/// A set of definitions, and the values worked out from them so far.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Solver<K, V, E> {
+	/// Names whose values are known.
+	values:  BTreeMap<K, V>,
+	/// Definitions which have not been resolved yet.
+	pending: BTreeMap<K, E>,
+}
+
+impl<K, V, E> Solver<K, V, E>
+where
+	K: Clone + Ord + fmt::Display,
+	V: Clone,
+	E: Expression<K, V>,
+{
+	/// Collects a set of definitions, none of which are resolved yet.
+	pub fn new(definitions: impl IntoIterator<Item = (K, E)>) -> Self {
+		Self {
+			values:  BTreeMap::new(),
+			pending: definitions.into_iter().collect(),
+		}
+	}
+
+	/// Fixes a name's value, replacing its definition.
+	pub fn set(&mut self, key: K, value: V) {
+		self.pending.remove(&key);
+		self.values.insert(key, value);
+	}
+
+	/// Removes a name's definition and value, so that it is unknown.
+	pub fn forget(&mut self, key: &K) {
+		self.pending.remove(key);
+		self.values.remove(key);
+	}
+
+	/// Gets a name's value, if it is known.
+	pub fn get(&self, key: &K) -> Option<&V> {
+		self.values.get(key)
+	}
+
+	/// Lists the definitions which could not be resolved.
+	pub fn stuck(&self) -> impl Iterator<Item = &K> {
+		self.pending.keys()
+	}
+
+	/// Lists the names which the stuck definitions need, but which have no
+	/// definition or value at all.
+	pub fn missing(&self) -> BTreeSet<&K> {
+		self.pending
+			.values()
+			.flat_map(E::operands)
+			.filter(|key| {
+				!self.values.contains_key(key) && !self.pending.contains_key(key)
+			})
+			.collect()
+	}
+
+	/// Resolves every definition that can be resolved, and counts them.
+	///
+	/// Each definition is evaluated once, as soon as the last of its operands
+	/// becomes known, so this runs in time proportional to the size of the
+	/// system rather than to its depth.
+	pub fn reduce(&mut self) -> eyre::Result<usize> {
+		// How many operands each definition is still waiting on, and which
+		// definitions are waiting on each name.
+		let mut waiting = BTreeMap::new();
+		let mut dependents = BTreeMap::<K, Vec<K>>::new();
+		let mut ready = Vec::new();
+		for (key, expr) in &self.pending {
+			let unknown = expr
+				.operands()
+				.into_iter()
+				.filter(|op| !self.values.contains_key(op))
+				.collect::<BTreeSet<_>>();
+			for &op in &unknown {
+				dependents.entry(op.clone()).or_default().push(key.clone());
+			}
+			if unknown.is_empty() {
+				ready.push(key.clone());
+			}
+			else {
+				waiting.insert(key.clone(), unknown.len());
+			}
+		}
+
+		let mut resolved = 0;
+		while let Some(key) = ready.pop() {
+			let Some(expr) = self.pending.get(&key)
+			else {
+				continue;
+			};
+			let value = expr
+				.evaluate(|op| self.values[op].clone())
+				.wrap_err_with(|| format!("could not evaluate `{key}`"))?;
+			self.pending.remove(&key);
+			self.values.insert(key.clone(), value);
+			resolved += 1;
+			for dependent in dependents.remove(&key).into_iter().flatten() {
+				if let Some(count) = waiting.get_mut(&dependent) {
+					*count -= 1;
+					if *count == 0 {
+						waiting.remove(&dependent);
+						ready.push(dependent);
+					}
+				}
+			}
+		}
+		tracing::debug!(resolved, stuck = self.pending.len(), "reduced");
+		Ok(resolved)
+	}
+
+	/// Resolves as much as possible, and produces the value of one name.
+	pub fn solve(&mut self, key: &K) -> eyre::Result<V> {
+		self.reduce()?;
+		if let Some(value) = self.values.get(key) {
+			return Ok(value.clone());
+		}
+		if !self.pending.contains_key(key) {
+			eyre::bail!("nothing defines `{key}`");
+		}
+		match self.missing().into_iter().next() {
+			Some(missing) => {
+				eyre::bail!("`{key}` is stuck, as nothing defines `{missing}`")
+			},
+			None => eyre::bail!("`{key}` is stuck in a cycle"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A sum of names and a constant.
+	struct Sum(Vec<&'static str>, i64);
+
+	impl Expression<&'static str, i64> for Sum {
+		fn operands(&self) -> Vec<&&'static str> {
+			self.0.iter().collect()
+		}
+
+		fn evaluate(
+			&self,
+			operand: impl Fn(&&'static str) -> i64,
+		) -> eyre::Result<i64> {
+			Ok(self.0.iter().map(operand).sum::<i64>() + self.1)
+		}
+	}
+
+	fn system() -> Solver<&'static str, i64, Sum> {
+		Solver::new([
+			("d", Sum(vec!["b", "c"], 0)),
+			("a", Sum(vec![], 1)),
+			("c", Sum(vec!["a"], 10)),
+			("b", Sum(vec!["a", "a"], 0)),
+		])
+	}
+
+	#[test]
+	fn reduces_around_unknowns() -> eyre::Result<()> {
+		let mut solver = system();
+		assert_eq!(solver.solve(&"d")?, 13);
+		assert_eq!(solver.stuck().count(), 0);
+
+		let mut solver = system();
+		solver.set("a", 5);
+		assert_eq!(solver.solve(&"d")?, 25);
+
+		let mut solver = system();
+		solver.forget(&"a");
+		let err = solver.solve(&"d").unwrap_err();
+		assert_eq!(err.to_string(), "`d` is stuck, as nothing defines `a`");
+		assert_eq!(solver.stuck().collect::<Vec<_>>(), [&"b", &"c", &"d"]);
+		assert_eq!(solver.missing().into_iter().collect::<Vec<_>>(), [&"a"]);
+
+		let mut solver = system();
+		solver.set("a", 0);
+		solver.forget(&"b");
+		solver.reduce()?;
+		assert_eq!(solver.get(&"c"), Some(&10));
+		assert_eq!(solver.get(&"d"), None);
+
+		let mut cycle =
+			Solver::new([("x", Sum(vec!["y"], 0)), ("y", Sum(vec!["x"], 0))]);
+		assert_eq!(
+			cycle.solve(&"x").unwrap_err().to_string(),
+			"`x` is stuck in a cycle"
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,234 @@
+//! Fingerprints of the input files that answers were computed from.
+//!
+//! Input files are easy to clobber: a download from the wrong account, a paste
+//! into the wrong day's folder, a sample saved over the real thing. Nothing
+//! about the file itself says that it changed, so every time the harness runs
+//! a solver over an input it hashes the text and compares it with the hash
+//! recorded in `target/aoc-provenance/{year}/d{day}.json`, keyed by data group.
+//! When the hash differs, the answers recorded against the old text (and, for
+//! the real input, the [submission history](crate::history)) no longer
+//! describe this file, and the harness says so.
+//!
+//! The records describe one checkout's files, so, like the
+//! [answer cache](crate::cache), they live in the build directory rather than
+//! beside the inputs.
+//!
+//! The parts of one day can run at the same time, under `verify --jobs`, and
+//! each of them updates the same record file. [`with_day_lock`] keeps their
+//! updates from interleaving, and [`write_atomic`] keeps a reader from ever
+//! seeing a half-written file.
+
+use std::{
+	collections::BTreeMap,
+	fs,
+	io,
+	path::{
+		Path,
+		PathBuf,
+	},
+	sync::{
+		Arc,
+		LazyLock,
+		Mutex,
+		PoisonError,
+	},
+};
+
+use chrono::{
+	DateTime,
+	Utc,
+};
+use eyre::Context;
+
+/// What is known about one data group's input file.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputRecord {
+	/// The MD5 digest of the input text, in hex.
+	pub hash:    String,
+	/// When the input was first seen with this hash.
+	pub seen:    DateTime<Utc>,
+	/// The most recent answer computed from this input, by part.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub answers: BTreeMap<u8, String>,
+}
+
+/// The outcome of comparing an input file with its record.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Check {
+	/// The group had never been seen before.
+	New,
+	/// The input is the one the record describes.
+	Unchanged,
+	/// The input differs from the one the record describes, which is returned
+	/// so that the caller can report what no longer applies.
+	Changed(InputRecord),
+}
+
+/// The input records for one day's puzzle.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Provenance {
+	pub year:   u16,
+	pub day:    u8,
+	pub groups: BTreeMap<String, InputRecord>,
+}
+
+impl Provenance {
+	/// Creates an empty record set for a day.
+	pub fn new(year: u16, day: u8) -> Self {
+		Self {
+			year,
+			day,
+			groups: BTreeMap::new(),
+		}
+	}
+
+	/// Computes the path of the record file for a day.
+	pub fn path(year: u16, day: u8) -> eyre::Result<PathBuf> {
+		let mut path = crate::config::asset_root()?;
+		path.push("target");
+		path.push("aoc-provenance");
+		path.push(year.to_string());
+		path.push(format!("d{day:0>2}.json"));
+		Ok(path)
+	}
+
+	/// Loads the records for a day, or creates an empty set if none exist
+	/// yet.
+	#[cfg_attr(feature = "trace", tracing::instrument)]
+	pub fn load(year: u16, day: u8) -> eyre::Result<Self> {
+		let path = Self::path(year, day)?;
+		if !path.exists() {
+			tracing::trace!(file=%path.display(), "no input provenance");
+			return Ok(Self::new(year, day));
+		}
+		let text = fs::read_to_string(&path).wrap_err_with(|| {
+			format!("could not read provenance {}", path.display())
+		})?;
+		serde_json::from_str(&text).wrap_err_with(|| {
+			format!("could not parse provenance {}", path.display())
+		})
+	}
+
+	/// Writes the records back to disk.
+	#[cfg_attr(feature = "trace", tracing::instrument(skip(self), fields(year=%self.year, day=%self.day)))]
+	pub fn save(&self) -> eyre::Result<()> {
+		let path = Self::path(self.year, self.day)?;
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let text = serde_json::to_string_pretty(self)?;
+		write_atomic(&path, &text).wrap_err_with(|| {
+			format!("could not write provenance {}", path.display())
+		})
+	}
+
+	/// Compares an input text with the group's record, and makes the record
+	/// describe it.
+	///
+	/// When the text has changed, the old record is discarded (along with its
+	/// answers) and returned.
+	pub fn check(
+		&mut self,
+		group: &str,
+		text: &str,
+		now: DateTime<Utc>,
+	) -> Check {
+		let hash = hash(text);
+		let fresh = || InputRecord {
+			hash:    hash.clone(),
+			seen:    now,
+			answers: BTreeMap::new(),
+		};
+		match self.groups.get_mut(group) {
+			None => {
+				self.groups.insert(group.to_owned(), fresh());
+				Check::New
+			},
+			Some(record) if record.hash == hash => Check::Unchanged,
+			Some(record) => Check::Changed(std::mem::replace(record, fresh())),
+		}
+	}
+
+	/// Gets the answer recorded for a part from a group's current input.
+	pub fn answer(&self, group: &str, part: u8) -> Option<&str> {
+		self.groups
+			.get(group)?
+			.answers
+			.get(&part)
+			.map(String::as_str)
+	}
+
+	/// Records an answer computed from a group's current input.
+	///
+	/// Answers for groups that have not been [checked](Self::check) are
+	/// dropped, since there is no input to attribute them to.
+	pub fn record_answer(
+		&mut self,
+		group: &str,
+		part: u8,
+		answer: impl ToString,
+	) {
+		if let Some(record) = self.groups.get_mut(group) {
+			record.answers.insert(part, answer.to_string());
+		}
+	}
+}
+
+/// Fingerprints an input text.
+pub fn hash(text: &str) -> String {
+	format!("{:x}", md5::compute(text))
+}
+
+/// Runs `func` while no other thread in the process is inside it for the same
+/// day.
+///
+/// Loading a day's records, changing them, and saving them again must happen
+/// under this lock, or two parts finishing together lose one of their updates.
+pub fn with_day_lock<T>(year: u16, day: u8, func: impl FnOnce() -> T) -> T {
+	type Locks = BTreeMap<(u16, u8), Arc<Mutex<()>>>;
+	static LOCKS: LazyLock<Mutex<Locks>> = LazyLock::new(Default::default);
+	let lock = LOCKS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.entry((year, day))
+		.or_default()
+		.clone();
+	let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+	func()
+}
+
+/// Replaces a file's contents by writing them beside it and renaming them into
+/// place, so that the file is always either wholly old or wholly new.
+pub fn write_atomic(path: &Path, text: &str) -> io::Result<()> {
+	let mut temp = path.as_os_str().to_owned();
+	temp.push(".tmp");
+	fs::write(&temp, text)?;
+	fs::rename(&temp, path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn detects_changed_input() {
+		let now = Utc::now();
+		let mut records = Provenance::new(2022, 1);
+		assert_eq!(records.check("input", "1\n2\n", now), Check::New);
+		records.record_answer("input", 1, 3);
+		assert_eq!(records.answer("input", 1), Some("3"));
+		assert_eq!(records.check("input", "1\n2\n", now), Check::Unchanged);
+		assert_eq!(records.check("sample", "1\n2\n", now), Check::New);
+
+		let Check::Changed(old) = records.check("input", "4\n5\n", now)
+		else {
+			panic!("replaced input was not noticed");
+		};
+		assert_eq!(old.hash, hash("1\n2\n"));
+		assert_eq!(old.answers.get(&1).map(String::as_str), Some("3"));
+		assert_eq!(records.answer("input", 1), None);
+		assert_eq!(records.check("input", "4\n5\n", now), Check::Unchanged);
+	}
+}
@@ -0,0 +1,78 @@
+//! Seeded randomness, for solvers which use randomized algorithms.
+//!
+//! A few puzzles are most easily cracked by guessing: Karger's contraction
+//! for a minimum cut, annealing towards an optimum, shuffling until a greedy
+//! pass succeeds. Runs of those solvers should still be repeatable, so the
+//! harness owns the seed. It hands every solver a generator seeded from it
+//! through [`Puzzle::set_rng`], logs the seed, and accepts `--seed` to replay
+//! a run that went badly.
+//!
+//! Outside the harness the seed is [`DEFAULT_SEED`], so tests and benchmarks
+//! see the same guesses every time.
+//!
+//! [`Puzzle::set_rng`]: crate::Puzzle::set_rng
+
+use std::sync::atomic::{
+	AtomicU64,
+	Ordering,
+};
+
+pub use rand::rngs::StdRng;
+use rand::SeedableRng as _;
+
+/// The seed used until the harness chooses another.
+pub const DEFAULT_SEED: u64 = 0x2015_1201;
+
+/// The seed shared by every solver in the process.
+static SEED: AtomicU64 = AtomicU64::new(DEFAULT_SEED);
+
+/// Gets the process-wide seed.
+pub fn seed() -> u64 {
+	SEED.load(Ordering::Relaxed)
+}
+
+/// Replaces the process-wide seed.
+pub fn set_seed(seed: u64) {
+	SEED.store(seed, Ordering::Relaxed);
+}
+
+/// Chooses a seed at random, for runs which were not given one.
+pub fn fresh_seed() -> u64 {
+	rand::random()
+}
+
+/// Creates a generator from the process-wide seed.
+///
+/// Every generator made between changes of the seed produces the same
+/// sequence, so a solver which is parsed and run twice guesses the same way
+/// both times.
+pub fn rng() -> StdRng {
+	StdRng::seed_from_u64(seed())
+}
+
+#[cfg(test)]
+mod tests {
+	use rand::Rng as _;
+
+	use super::*;
+
+	/// Draws a few numbers, to compare generators by.
+	fn draw(mut rng: StdRng) -> [u64; 4] {
+		[(); 4].map(|()| rng.gen())
+	}
+
+	#[test]
+	fn seeds_are_deterministic() {
+		// Other tests may be running solvers, so the shared seed is only read.
+		assert_eq!(draw(rng()), draw(rng()));
+		assert_eq!(draw(rng()), draw(StdRng::seed_from_u64(seed())));
+		assert_eq!(
+			draw(StdRng::seed_from_u64(DEFAULT_SEED)),
+			draw(StdRng::seed_from_u64(DEFAULT_SEED))
+		);
+		assert_ne!(
+			draw(StdRng::seed_from_u64(DEFAULT_SEED)),
+			draw(StdRng::seed_from_u64(DEFAULT_SEED + 1))
+		);
+	}
+}
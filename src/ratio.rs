@@ -0,0 +1,234 @@
+//! Exact fractions, for puzzles whose intermediate math must not round.
+//!
+//! Line intersections, probabilities, and averages of integers all pass through
+//! fractions on the way to an integer answer. Done in `f64`, the fractions are
+//! rounded, and puzzle inputs are usually large enough (coordinates in the
+//! hundreds of trillions) that the rounding changes the answer. [`Rational`]
+//! keeps every intermediate value exact, in lowest terms.
+//!
+//! `num`'s operators on ratios panic when a numerator or denominator overflows,
+//! which `i128` gives plenty of room to avoid but does not rule out. The
+//! [`Exact`] methods do the same arithmetic and report overflow, division by
+//! zero, and inexact conversion as errors instead.
+
+use std::{
+	any,
+	collections::BTreeMap,
+	fmt,
+};
+
+pub use num::rational::Ratio;
+use num::{
+	CheckedAdd,
+	CheckedDiv,
+	CheckedMul,
+	CheckedSub,
+	Signed as _,
+	Zero,
+};
+
+use crate::AocError;
+
+/// The exact numbers used for fractional arithmetic.
+pub type Rational = Ratio<i128>;
+
+/// Checked arithmetic and conversions for [`Rational`].
+pub trait Exact: Sized {
+	/// Adds two fractions, failing if the result overflows.
+	fn try_add(&self, rhs: &Self) -> eyre::Result<Self>;
+
+	/// Subtracts two fractions, failing if the result overflows.
+	fn try_sub(&self, rhs: &Self) -> eyre::Result<Self>;
+
+	/// Multiplies two fractions, failing if the result overflows.
+	fn try_mul(&self, rhs: &Self) -> eyre::Result<Self>;
+
+	/// Divides two fractions, failing on a zero divisor or if the result
+	/// overflows.
+	fn try_div(&self, rhs: &Self) -> eyre::Result<Self>;
+
+	/// Converts to an integer, failing if the fraction is not a whole number
+	/// or does not fit in `T`.
+	fn to_exact<T: TryFrom<i128>>(&self) -> eyre::Result<T>;
+
+	/// Writes the fraction out in decimal by long division, with at most
+	/// `max_digits` digits after the point.
+	fn to_decimal(&self, max_digits: usize) -> Decimal;
+}
+
+impl Exact for Rational {
+	fn try_add(&self, rhs: &Self) -> eyre::Result<Self> {
+		checked(self, "+", rhs, self.checked_add(rhs))
+	}
+
+	fn try_sub(&self, rhs: &Self) -> eyre::Result<Self> {
+		checked(self, "-", rhs, self.checked_sub(rhs))
+	}
+
+	fn try_mul(&self, rhs: &Self) -> eyre::Result<Self> {
+		checked(self, "*", rhs, self.checked_mul(rhs))
+	}
+
+	fn try_div(&self, rhs: &Self) -> eyre::Result<Self> {
+		if rhs.is_zero() {
+			eyre::bail!("cannot divide {self} by zero");
+		}
+		checked(self, "/", rhs, self.checked_div(rhs))
+	}
+
+	fn to_exact<T: TryFrom<i128>>(&self) -> eyre::Result<T> {
+		if !self.is_integer() {
+			eyre::bail!("{self} is not a whole number");
+		}
+		T::try_from(self.to_integer()).map_err(|_| {
+			AocError::Overflow(format!(
+				"{self} does not fit in {}",
+				any::type_name::<T>()
+			))
+			.into()
+		})
+	}
+
+	fn to_decimal(&self, max_digits: usize) -> Decimal {
+		let numer = self.numer().unsigned_abs();
+		let denom = self.denom().unsigned_abs();
+		let mut remainder = numer % denom;
+		let mut digits = Vec::new();
+		// Where each remainder was first seen. A remainder seen again means
+		// the digits since then repeat forever.
+		let mut seen = BTreeMap::new();
+		let mut repeat_from = None;
+		while remainder != 0 && digits.len() < max_digits {
+			if let Some(&at) = seen.get(&remainder) {
+				repeat_from = Some(at);
+				break;
+			}
+			seen.insert(remainder, digits.len());
+			let (digit, next) = next_digit(remainder, denom);
+			digits.push(digit);
+			remainder = next;
+		}
+		Decimal {
+			negative: self.is_negative(),
+			whole: numer / denom,
+			truncated: remainder != 0 && repeat_from.is_none(),
+			digits,
+			repeat_from,
+		}
+	}
+}
+
+/// Turns the result of one of `num`'s checked operations into an error.
+fn checked(
+	lhs: &Rational,
+	op: &str,
+	rhs: &Rational,
+	result: Option<Rational>,
+) -> eyre::Result<Rational> {
+	result.ok_or_else(|| {
+		AocError::Overflow(format!("({lhs}) {op} ({rhs}) overflowed i128"))
+			.into()
+	})
+}
+
+/// Computes one step of long division: the next digit of `remainder / denom`,
+/// and the remainder left after it.
+///
+/// `remainder * 10` can overflow when `denom` is large, so this multiplies by
+/// adding, reducing after each addition. Both operands are below `denom`,
+/// which is at most `2^127`, so no sum can overflow.
+fn next_digit(remainder: u128, denom: u128) -> (u8, u128) {
+	let (mut digit, mut acc) = (0, 0u128);
+	for _ in 0 .. 10 {
+		acc += remainder;
+		if acc >= denom {
+			acc -= denom;
+			digit += 1;
+		}
+	}
+	(digit, acc)
+}
+
+/// The decimal expansion of a fraction.
+///
+/// This displays as, for example, `-1.1(6)` for `-7/6`, with the repeating
+/// digits in parentheses, and with a trailing `…` if the expansion was cut off
+/// before it ended or started repeating.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Decimal {
+	/// Whether the fraction is below zero.
+	pub negative:    bool,
+	/// The magnitude of the whole-number part.
+	pub whole:       u128,
+	/// The digits after the point.
+	pub digits:      Vec<u8>,
+	/// The index in `digits` at which the digits start repeating forever.
+	pub repeat_from: Option<usize>,
+	/// Whether the expansion continues past `digits` without repeating.
+	pub truncated:   bool,
+}
+
+impl fmt::Display for Decimal {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		if self.negative {
+			fmt.write_str("-")?;
+		}
+		write!(fmt, "{}", self.whole)?;
+		if self.digits.is_empty() {
+			return Ok(());
+		}
+		fmt.write_str(".")?;
+		for (idx, digit) in self.digits.iter().enumerate() {
+			if self.repeat_from == Some(idx) {
+				fmt.write_str("(")?;
+			}
+			write!(fmt, "{digit}")?;
+		}
+		if self.repeat_from.is_some() {
+			fmt.write_str(")")?;
+		}
+		if self.truncated {
+			fmt.write_str("…")?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn exact_arithmetic() -> eyre::Result<()> {
+		let third = Rational::new(1, 3);
+		let sixth = Rational::new(-1, 6);
+		assert_eq!(third.try_add(&sixth)?, Rational::new(1, 6));
+		assert_eq!(third.try_sub(&sixth)?, Rational::new(1, 2));
+		assert_eq!(third.try_mul(&sixth)?, Rational::new(-1, 18));
+		assert_eq!(third.try_div(&sixth)?, Rational::from_integer(-2));
+		assert!(third.try_div(&Rational::zero()).is_err());
+		assert!(Rational::new(2, 4) < Rational::new(2, 3));
+
+		let huge = Rational::from_integer(i128::MAX);
+		assert!(huge.try_add(&huge).is_err());
+		assert!(huge.try_mul(&third).is_ok());
+
+		assert_eq!(Rational::new(-12, 4).to_exact::<i64>()?, -3);
+		assert!(Rational::new(7, 2).to_exact::<i64>().is_err());
+		assert!(huge.to_exact::<i64>().is_err());
+		assert!(Rational::from_integer(-1).to_exact::<u8>().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn long_division() {
+		let show = |n, d, max| Rational::new(n, d).to_decimal(max).to_string();
+		assert_eq!(show(-7, 6, 20), "-1.1(6)");
+		assert_eq!(show(1, 7, 20), "0.(142857)");
+		assert_eq!(show(3, 8, 20), "0.375");
+		assert_eq!(show(22, 1, 20), "22");
+		assert_eq!(show(1, 7, 3), "0.142…");
+		assert_eq!(show(1, i128::MAX, 3), "0.000…");
+	}
+}
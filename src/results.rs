@@ -0,0 +1,424 @@
+//! Answers and timings from a run over every puzzle, for comparing builds.
+//!
+//! Changes to the shared infrastructure (coördinates, parsers, searches) touch
+//! dozens of solvers at once, and running each of them by hand to see whether
+//! anything broke is tedious. Instead, [`Results::collect`] runs every
+//! registered solver against one data group and writes down what each part
+//! produced and how long it took. A run from before the change can be saved,
+//! and [`Results::diff`] then lists every part whose answer changed, and every
+//! part which became noticeably slower or faster.
+//!
+//! Each part is run separately, so its time includes loading and parsing the
+//! input. Puzzles with no input file in the group are skipped entirely, as are
+//! second parts of puzzles which have none. Parts which fail or panic are
+//! recorded as such.
+//!
+//! The parts can run on several threads at once, [scheduled](crate::schedule)
+//! by their solvers' estimated difficulty. This finishes sooner, but the
+//! threads compete for the machine, so timings recorded this way should only
+//! be compared with others recorded on the same number of threads.
+
+use std::{
+	cmp,
+	collections::BTreeMap,
+	fmt,
+	fs,
+	panic::{
+		self,
+		AssertUnwindSafe,
+	},
+	path::Path,
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+use chrono::{
+	DateTime,
+	Utc,
+};
+use eyre::Context;
+
+use crate::{
+	cancel,
+	schedule::{
+		run_parallel,
+		Difficulty,
+	},
+	solutions,
+	Answer,
+	ParamMap,
+	Solver,
+};
+
+/// Timings shorter than this are too noisy to compare.
+pub const MIN_NOTICEABLE: Duration = Duration::from_millis(1);
+
+/// What one part of one puzzle did.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartResult {
+	pub year:    u16,
+	pub day:     u8,
+	pub part:    u8,
+	/// The answer, or the error which prevented one.
+	pub outcome: Result<Answer, String>,
+	/// How long the run took, from loading the input to checking the answer.
+	pub elapsed: Duration,
+}
+
+impl PartResult {
+	fn key(&self) -> (u16, u8, u8) {
+		(self.year, self.day, self.part)
+	}
+
+	/// The estimated difficulty of the part's solver, if it is still
+	/// registered.
+	pub fn difficulty(&self) -> Difficulty {
+		solutions()
+			.get(&self.year)
+			.and_then(|days| days.get(&self.day))
+			.map(Solver::difficulty)
+			.unwrap_or_default()
+	}
+}
+
+/// The parts of one difficulty class, in a set of results.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ClassSummary {
+	pub parts:   usize,
+	pub failed:  usize,
+	/// The total time of every part in the class.
+	pub elapsed: Duration,
+	/// The time of the slowest part in the class.
+	pub slowest: Duration,
+}
+
+/// The outcome of every part of every puzzle, from one build.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Results {
+	/// The data group which was run.
+	pub group:    String,
+	/// When the run finished.
+	pub recorded: DateTime<Utc>,
+	/// Results for each part, in calendar order.
+	pub parts:    Vec<PartResult>,
+}
+
+impl Results {
+	/// Runs the requested parts of every registered solver against a data
+	/// group, on up to `threads` threads.
+	///
+	/// The most expensive solvers are started first. With `skip_slow`, those
+	/// estimated to be [slow](Difficulty::Slow) are not run at all.
+	///
+	/// Failures are recorded rather than returned; only an interruption stops
+	/// the collection early.
+	pub fn collect(
+		group: &str,
+		part_1: bool,
+		part_2: bool,
+		overrides: &ParamMap,
+		threads: usize,
+		skip_slow: bool,
+	) -> eyre::Result<Self> {
+		let wanted = [(1, part_1), (2, part_2)]
+			.into_iter()
+			.filter_map(|(part, run)| run.then_some(part))
+			.collect::<Vec<u8>>();
+		let mut jobs = Vec::new();
+		for entry in solutions().values().flat_map(|days| days.values()) {
+			if !entry.has_group(group) {
+				continue;
+			}
+			if skip_slow && entry.difficulty() == Difficulty::Slow {
+				tracing::info!(
+					year = entry.year,
+					day = entry.day,
+					"skipping a slow solver"
+				);
+				continue;
+			}
+			jobs.extend(wanted.iter().map(|&part| (entry, part)));
+		}
+		// The sort is stable, so each class stays in calendar order.
+		jobs.sort_by_key(|(entry, _)| cmp::Reverse(entry.difficulty()));
+		let mut parts =
+			run_parallel(jobs, threads, |(entry, part)| -> eyre::Result<_> {
+				cancel::check()?;
+				Ok(Self::collect_part(entry, group, part, overrides))
+			})
+			.into_iter()
+			.filter_map(Result::transpose)
+			.collect::<eyre::Result<Vec<_>>>()?;
+		parts.sort_by_key(PartResult::key);
+		Ok(Self {
+			group: group.to_owned(),
+			recorded: Utc::now(),
+			parts,
+		})
+	}
+
+	/// Runs one part of one solver, producing nothing if the puzzle does not
+	/// have that part.
+	fn collect_part(
+		entry: &Solver,
+		group: &str,
+		part: u8,
+		overrides: &ParamMap,
+	) -> Option<PartResult> {
+		let start = Instant::now();
+		// One unfinished solver must not stop the comparison of all the
+		// others.
+		let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+			entry.solve_with_params(group, part == 1, part == 2, overrides)
+		}))
+		.unwrap_or_else(|payload| {
+			let message = payload
+				.downcast_ref::<&str>()
+				.map(|msg| msg.to_string())
+				.or_else(|| payload.downcast_ref::<String>().cloned())
+				.unwrap_or_default();
+			Err(eyre::eyre!("panicked: {message}"))
+		})
+		.map(|(one, two)| {
+			if part == 1 {
+				one
+			}
+			else {
+				two
+			}
+		});
+		// A requested part only goes unanswered when the puzzle does not have
+		// it.
+		let outcome = match outcome {
+			Ok(Some(answer)) => Ok(answer),
+			Ok(None) => return None,
+			Err(err) => Err(format!("{err:#}")),
+		};
+		let elapsed = start.elapsed();
+		tracing::debug!(
+			year = entry.year,
+			day = entry.day,
+			part,
+			?outcome,
+			?elapsed,
+			"collected"
+		);
+		Some(PartResult {
+			year: entry.year,
+			day: entry.day,
+			part,
+			outcome,
+			elapsed,
+		})
+	}
+
+	/// Totals the parts' times and failures by their solvers' difficulty.
+	pub fn by_difficulty(&self) -> BTreeMap<Difficulty, ClassSummary> {
+		let mut classes = BTreeMap::<_, ClassSummary>::new();
+		for result in &self.parts {
+			let class = classes.entry(result.difficulty()).or_default();
+			class.parts += 1;
+			class.failed += result.outcome.is_err() as usize;
+			class.elapsed += result.elapsed;
+			class.slowest = class.slowest.max(result.elapsed);
+		}
+		classes
+	}
+
+	/// Loads results saved by an earlier run.
+	pub fn load(path: &Path) -> eyre::Result<Self> {
+		let text = fs::read_to_string(path).wrap_err_with(|| {
+			format!("could not read results {}", path.display())
+		})?;
+		serde_json::from_str(&text).wrap_err_with(|| {
+			format!("could not parse results {}", path.display())
+		})
+	}
+
+	/// Saves the results for a later run to compare against.
+	pub fn save(&self, path: &Path) -> eyre::Result<()> {
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let text = serde_json::to_string_pretty(self)?;
+		fs::write(path, text).wrap_err_with(|| {
+			format!("could not write results {}", path.display())
+		})
+	}
+
+	/// Lists every difference between an earlier run and this one.
+	///
+	/// A timing has changed when the slower of the two runs took more than
+	/// `1 + threshold` times as long as the faster, and at least
+	/// [`MIN_NOTICEABLE`].
+	pub fn diff(&self, before: &Self, threshold: f64) -> Vec<Change> {
+		let mut pairs = BTreeMap::<_, (Option<_>, Option<_>)>::new();
+		for result in &before.parts {
+			pairs.entry(result.key()).or_default().0 = Some(result);
+		}
+		for result in &self.parts {
+			pairs.entry(result.key()).or_default().1 = Some(result);
+		}
+		let mut changes = Vec::new();
+		for ((year, day, part), pair) in pairs {
+			let kind = match pair {
+				(None, None) => continue,
+				(None, Some(_)) => ChangeKind::Added,
+				(Some(_), None) => ChangeKind::Removed,
+				// Answers are compared as the text that would be submitted, so
+				// that a number which became a formatted answer is not a
+				// change.
+				(Some(old), Some(new))
+					if show(&old.outcome) != show(&new.outcome) =>
+				{
+					ChangeKind::Answer {
+						before: show(&old.outcome),
+						after:  show(&new.outcome),
+					}
+				},
+				(Some(old), Some(new)) => {
+					let (slow, fast) = if new.elapsed > old.elapsed {
+						(new.elapsed, old.elapsed)
+					}
+					else {
+						(old.elapsed, new.elapsed)
+					};
+					if slow < MIN_NOTICEABLE
+						|| slow.as_secs_f64()
+							<= fast.as_secs_f64() * (1.0 + threshold)
+					{
+						continue;
+					}
+					ChangeKind::Timing {
+						before: old.elapsed,
+						after:  new.elapsed,
+					}
+				},
+			};
+			changes.push(Change {
+				year,
+				day,
+				part,
+				kind,
+			});
+		}
+		changes
+	}
+}
+
+/// A difference between two runs, in one part of one puzzle.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Change {
+	pub year: u16,
+	pub day:  u8,
+	pub part: u8,
+	pub kind: ChangeKind,
+}
+
+impl Change {
+	/// Tests whether the part produced something different, rather than
+	/// taking a different time or being run in only one of the two runs.
+	pub fn is_answer(&self) -> bool {
+		matches!(self.kind, ChangeKind::Answer { .. })
+	}
+
+	/// Tests whether the part was run in only one of the two runs.
+	pub fn is_coverage(&self) -> bool {
+		matches!(self.kind, ChangeKind::Added | ChangeKind::Removed)
+	}
+}
+
+/// How a part differs between two runs.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ChangeKind {
+	/// The part produced a different answer, or a different error.
+	Answer { before: String, after: String },
+	/// The part produced the same answer in a noticeably different time.
+	Timing { before: Duration, after: Duration },
+	/// The part was not run before.
+	Added,
+	/// The part was not run this time.
+	Removed,
+}
+
+impl fmt::Display for Change {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}-{:0>2}#{}: ", self.year, self.day, self.part)?;
+		match &self.kind {
+			ChangeKind::Answer { before, after } => {
+				write!(fmt, "{before} -> {after}")
+			},
+			ChangeKind::Timing { before, after } => {
+				let ratio = after.as_secs_f64() / before.as_secs_f64();
+				write!(fmt, "{before:?} -> {after:?} ({ratio:.2}x)")
+			},
+			ChangeKind::Added => fmt.write_str("newly run"),
+			ChangeKind::Removed => fmt.write_str("no longer run"),
+		}
+	}
+}
+
+fn show(outcome: &Result<Answer, String>) -> String {
+	match outcome {
+		Ok(answer) => answer.to_string(),
+		Err(err) => format!("error ({err})"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A part's key, its outcome, and its time in milliseconds.
+	type Row<'a> = ((u16, u8, u8), Result<i64, &'a str>, u64);
+
+	fn run(parts: &[Row]) -> Results {
+		Results {
+			group:    "sample".to_owned(),
+			recorded: Utc::now(),
+			parts:    parts
+				.iter()
+				.map(|&((year, day, part), outcome, ms)| PartResult {
+					year,
+					day,
+					part,
+					outcome: outcome.map(Answer::from).map_err(str::to_owned),
+					elapsed: Duration::from_millis(ms),
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn finds_changes() {
+		let before = run(&[
+			((2022, 1, 1), Ok(24000), 5),
+			((2022, 1, 2), Ok(45000), 5),
+			((2022, 2, 1), Ok(15), 100),
+			((2022, 2, 2), Ok(12), 0),
+			((2022, 3, 1), Err("bad input"), 1),
+		]);
+		let after = run(&[
+			((2022, 1, 1), Ok(24000), 6),
+			((2022, 1, 2), Ok(45001), 5),
+			((2022, 2, 1), Ok(15), 200),
+			((2022, 2, 2), Ok(12), 0),
+			((2022, 4, 1), Ok(2), 1),
+		]);
+		let changes = after.diff(&before, 0.25);
+		let shown = changes.iter().map(ToString::to_string).collect::<Vec<_>>();
+		assert_eq!(shown, [
+			"2022-01#2: 45000 -> 45001",
+			"2022-02#1: 100ms -> 200ms (2.00x)",
+			"2022-03#1: no longer run",
+			"2022-04#1: newly run",
+		]);
+		assert_eq!(changes.iter().filter(|c| c.is_answer()).count(), 1);
+		assert_eq!(changes.iter().filter(|c| c.is_coverage()).count(), 2);
+		assert!(after.diff(&after, 0.0).is_empty());
+	}
+}
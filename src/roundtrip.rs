@@ -0,0 +1,235 @@
+//! Parse-render round trips, for catching parsers that drop or shift input.
+//!
+//! Grid parsers are prone to off-by-one mistakes which do not stop them from
+//! producing *a* grid: the last column goes missing, a blank line becomes an
+//! empty row, the origin lands one cell over. The quickest way to see those is
+//! to write the parsed value back out in the puzzle's own text format and
+//! compare it with what went in.
+//!
+//! Types that can do this implement `Display` in the input format and can be
+//! checked directly with [`assert_round_trip`]. Solvers opt in to the
+//! registry-wide check, [`check_registered`], by overriding
+//! [`Puzzle::render_input`].
+
+use std::fmt;
+
+use bitflags::bitflags;
+
+use crate::{
+	solutions,
+	Parsed,
+	Puzzle,
+	Solver,
+};
+
+bitflags! {
+	/// Differences between the input and its rendering which do not count as
+	/// a mismatch.
+	#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+	pub struct Normalize: u8 {
+		/// Ignore whitespace at the end of each line.
+		const TRAILING_WHITESPACE = 1;
+		/// Ignore newlines at the end of the text.
+		const FINAL_NEWLINES = 2;
+		/// Ignore lines which are empty (after any other normalization).
+		const BLANK_LINES = 4;
+	}
+}
+
+impl Default for Normalize {
+	/// Editors disagree about trailing whitespace and final newlines, and
+	/// neither carries puzzle data, so both are ignored by default.
+	fn default() -> Self {
+		Self::TRAILING_WHITESPACE | Self::FINAL_NEWLINES
+	}
+}
+
+impl Normalize {
+	/// Applies the normalizations to a text, producing its lines.
+	pub fn lines(self, text: &str) -> Vec<&str> {
+		let mut lines = text
+			.lines()
+			.map(|line| {
+				if self.contains(Self::TRAILING_WHITESPACE) {
+					line.trim_end()
+				}
+				else {
+					line
+				}
+			})
+			.filter(|line| {
+				!(self.contains(Self::BLANK_LINES) && line.is_empty())
+			})
+			.collect::<Vec<_>>();
+		if self.contains(Self::FINAL_NEWLINES) {
+			while lines.last().is_some_and(|line| line.is_empty()) {
+				lines.pop();
+			}
+		}
+		lines
+	}
+}
+
+/// The first place where a rendering differs from its input.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Mismatch {
+	/// The line number, counting from 1, after normalization.
+	pub line:     usize,
+	/// The column, counting from 1, of the first differing character.
+	pub column:   usize,
+	/// The line as it appears in the input, if it exists.
+	pub expected: Option<String>,
+	/// The line as it appears in the rendering, if it exists.
+	pub actual:   Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let show = |line: &Option<String>| {
+			line.as_deref()
+				.map_or_else(|| "<missing>".to_owned(), str::to_owned)
+		};
+		writeln!(fmt, "round trip differs at {}:{}", self.line, self.column)?;
+		writeln!(fmt, "  input:    {}", show(&self.expected))?;
+		writeln!(fmt, "  rendered: {}", show(&self.actual))?;
+		write!(fmt, "            {:>1$}", "^", self.column)
+	}
+}
+
+impl std::error::Error for Mismatch {
+}
+
+/// Compares an input text with its rendering.
+pub fn compare(
+	input: &str,
+	rendered: &str,
+	normalize: Normalize,
+) -> Result<(), Mismatch> {
+	let (input, rendered) = (normalize.lines(input), normalize.lines(rendered));
+	for idx in 0 .. input.len().max(rendered.len()) {
+		let (want, got) = (input.get(idx), rendered.get(idx));
+		if want == got {
+			continue;
+		}
+		let column = match (want, got) {
+			(Some(want), Some(got)) => {
+				want.chars()
+					.zip(got.chars())
+					.take_while(|(a, b)| a == b)
+					.count() + 1
+			},
+			_ => 1,
+		};
+		return Err(Mismatch {
+			line: idx + 1,
+			column,
+			expected: want.map(|s| (*s).to_owned()),
+			actual: got.map(|s| (*s).to_owned()),
+		});
+	}
+	Ok(())
+}
+
+/// Parses a text, renders the result with `Display`, and checks that the
+/// rendering reproduces the text.
+///
+/// ## Panics
+///
+/// This panics if the text does not parse completely, or if the rendering
+/// differs from it.
+pub fn assert_round_trip<P>(text: &str, normalize: Normalize)
+where P: for<'a> Parsed<&'a str> + fmt::Display {
+	let (rest, parsed) =
+		P::parse_wyz(text).unwrap_or_else(|err| panic!("parse failed: {err}"));
+	assert!(
+		rest.trim().is_empty(),
+		"parser stopped early, leaving {:?}",
+		rest.lines().next().unwrap_or_default()
+	);
+	if let Err(err) = compare(text, &parsed.to_string(), normalize) {
+		panic!("{err}");
+	}
+}
+
+/// Runs the round trip for every registered solver which can render its
+/// input, over one data group.
+///
+/// Solvers which have no input file for the group are skipped, as are those
+/// which parse successfully but do not override [`Puzzle::render_input`].
+/// Failures to parse are reported for every solver, since it is not possible
+/// to know whether they would have rendered. A rendering that does not match
+/// its input is reported as a [`Mismatch`]. The input is rendered straight
+/// after parsing, before [`Puzzle::after_parse`] has had a chance to change it.
+pub fn check_registered(group: &str) -> Vec<(Solver, eyre::Result<()>)> {
+	solutions()
+		.values()
+		.flat_map(|days| days.values().copied())
+		.filter(|solver| solver.has_group(group))
+		.filter_map(|solver| {
+			check_one(solver, group).map(|outcome| (solver, outcome))
+		})
+		.collect()
+}
+
+/// Runs the round trip for one solver, producing `None` if it cannot render
+/// its input.
+fn check_one(solver: Solver, group: &str) -> Option<eyre::Result<()>> {
+	let text = match solver.load_input(group) {
+		Ok(text) => text,
+		Err(err) => return Some(Err(err)),
+	};
+	let parsed: Box<dyn Puzzle + Send> = match solver.parse(&text) {
+		Ok((rest, _)) if !rest.trim().is_empty() => {
+			let rest = rest.lines().next().unwrap_or_default();
+			return Some(Err(eyre::eyre!("parser stopped early, at {rest:?}")));
+		},
+		Ok((_, parsed)) => parsed,
+		Err(err) => return Some(Err(eyre::eyre!("parse failed: {err}"))),
+	};
+	let rendered = parsed.render_input()?;
+	Some(compare(&text, &rendered, Normalize::default()).map_err(Into::into))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn compares_lines() {
+		assert!(compare("ab \ncd\n\n", "ab\ncd", Normalize::default()).is_ok());
+		let err = compare("abc\ndef\n", "abc\ndxf\n", Normalize::default())
+			.unwrap_err();
+		assert_eq!((err.line, err.column), (2, 2));
+		let err =
+			compare("abc\ndef\n", "abc\n", Normalize::default()).unwrap_err();
+		assert_eq!((err.line, err.actual), (2, None));
+		assert!(compare("a\n\nb", "a\nb", Normalize::BLANK_LINES).is_ok());
+	}
+
+	#[test]
+	fn registered_round_trips() -> eyre::Result<()> {
+		// Every registered parser runs, and some of them build structures too
+		// large for the default test-thread stack.
+		let failures = std::thread::Builder::new()
+			.stack_size(64 << 20)
+			.spawn(|| {
+				check_registered("sample")
+					.into_iter()
+					.filter_map(|(solver, outcome)| {
+						// A sample which does not parse at all is the
+						// business of that day's own tests; this only looks
+						// for parsers which read their input wrongly.
+						let err = outcome.err()?.downcast::<Mismatch>().ok()?;
+						Some(format!(
+							"{}-{:0>2}: {err:#}",
+							solver.year, solver.day
+						))
+					})
+					.collect::<Vec<_>>()
+			})?
+			.join()
+			.map_err(|_| eyre::eyre!("round-trip thread panicked"))?;
+		assert!(failures.is_empty(), "{}", failures.join("\n"));
+		Ok(())
+	}
+}
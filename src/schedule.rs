@@ -0,0 +1,138 @@
+//! Estimated solver costs, and running batches of solvers in a useful order.
+//!
+//! Most days finish in milliseconds, but a few brute-force their second part
+//! and take minutes. In a batch over every puzzle, a slow day started last
+//! keeps the whole batch waiting long after every other worker has gone idle.
+//! Solvers advertise a rough [`Difficulty`] through
+//! [`Puzzle::estimated_difficulty`](crate::Puzzle::estimated_difficulty), and
+//! [`run_parallel`] hands out the most expensive work first, so that the quick
+//! days fill in around the slow ones.
+
+use std::{
+	fmt,
+	sync::{
+		atomic::{
+			AtomicUsize,
+			Ordering,
+		},
+		Mutex,
+	},
+	thread,
+};
+
+/// A rough class of how long a solver takes on its real input, in an
+/// optimized build.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Difficulty {
+	/// Well under a second. Most solvers are here.
+	#[default]
+	Quick,
+	/// A few seconds: a large search, or a simulation with many steps.
+	Moderate,
+	/// Tens of seconds or more, usually a brute-force part 2.
+	Slow,
+}
+
+impl Difficulty {
+	/// Every class, from cheapest to most expensive.
+	pub const ALL: [Self; 3] = [Self::Quick, Self::Moderate, Self::Slow];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Quick => "quick",
+			Self::Moderate => "moderate",
+			Self::Slow => "slow",
+		}
+	}
+}
+
+impl fmt::Display for Difficulty {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.pad(self.name())
+	}
+}
+
+/// Runs `work` over every item on up to `threads` threads, and collects the
+/// results in the items' order.
+///
+/// Items are handed out one at a time, in order, to whichever thread is free,
+/// so a list sorted from most to least expensive keeps every thread busy
+/// until close to the end. With one thread, or one item, everything runs on
+/// the calling thread.
+///
+/// The worker threads have large stacks, since some solvers recurse deeply.
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+pub fn run_parallel<T, R>(
+	items: Vec<T>,
+	threads: usize,
+	work: impl Fn(T) -> R + Sync,
+) -> Vec<R>
+where
+	T: Send,
+	R: Send,
+{
+	let threads = threads.clamp(1, items.len().max(1));
+	if threads == 1 {
+		return items.into_iter().map(work).collect();
+	}
+	let count = items.len();
+	let queue = Mutex::new(items.into_iter().enumerate());
+	let done = AtomicUsize::new(0);
+	let mut results = thread::scope(|scope| {
+		let workers = (0 .. threads)
+			.map(|_| {
+				thread::Builder::new()
+					.stack_size(64 << 20)
+					.spawn_scoped(scope, || {
+						let mut out = Vec::new();
+						loop {
+							let next = queue
+								.lock()
+								.unwrap_or_else(|err| err.into_inner())
+								.next();
+							let Some((idx, item)) = next
+							else {
+								break;
+							};
+							out.push((idx, work(item)));
+							let finished =
+								done.fetch_add(1, Ordering::Relaxed) + 1;
+							tracing::trace!(finished, count, "finished a job");
+						}
+						out
+					})
+					.expect("could not start a worker thread")
+			})
+			.collect::<Vec<_>>();
+		workers
+			.into_iter()
+			.flat_map(|worker| match worker.join() {
+				Ok(out) => out,
+				Err(payload) => std::panic::resume_unwind(payload),
+			})
+			.collect::<Vec<_>>()
+	});
+	results.sort_by_key(|&(idx, _)| idx);
+	results.into_iter().map(|(_, out)| out).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+
+	#[test]
+	fn keeps_order() {
+		let items = (0 .. 20u64).rev().collect::<Vec<_>>();
+		let squares = run_parallel(items.clone(), 4, |n| {
+			thread::sleep(Duration::from_millis(n));
+			n * n
+		});
+		assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+		assert!(run_parallel(Vec::<u8>::new(), 4, |n| n).is_empty());
+		assert!(Difficulty::Slow > Difficulty::Quick);
+	}
+}
@@ -0,0 +1,311 @@
+//! Generic state-space searches.
+//!
+//! Many puzzles boil down to "start somewhere, keep stepping to neighbors,
+//! never go back". The searches in this module own the worklist and the
+//! bookkeeping of which states have already been seen, so that solvers only
+//! need to describe how a single state expands into its successors.
+//!
+//! The visited-set is injected rather than fixed, because the right storage
+//! depends heavily on the state type: `BTreeSet` works for anything `Ord`,
+//! `HashSet` is faster for large unordered state spaces, and a bitset is far
+//! smaller when the states are coördinates inside a known bounding box.
+
+use std::{
+	cmp::Reverse,
+	collections::{
+		BTreeSet,
+		BinaryHeap,
+		HashSet,
+		VecDeque,
+	},
+	hash::{
+		BuildHasher,
+		Hash,
+	},
+	ops::Add,
+};
+
+use bitvec::vec::BitVec;
+use funty::Signed;
+
+use crate::coords::{
+	Cartesian2DPoint,
+	Cartesian3DPoint,
+};
+
+/// A record of which states a search has already reached.
+pub trait Visited<T> {
+	/// Marks a state as visited.
+	///
+	/// Returns `true` if this is the first time the state has been marked, and
+	/// `false` if it had already been visited (or cannot be tracked at all).
+	fn visit(&mut self, state: &T) -> bool;
+
+	/// Tests whether a state has already been visited.
+	fn has_visited(&self, state: &T) -> bool;
+}
+
+impl<T: Ord + Clone> Visited<T> for BTreeSet<T> {
+	fn visit(&mut self, state: &T) -> bool {
+		!self.contains(state) && self.insert(state.clone())
+	}
+
+	fn has_visited(&self, state: &T) -> bool {
+		self.contains(state)
+	}
+}
+
+impl<T: Eq + Hash + Clone, S: BuildHasher> Visited<T> for HashSet<T, S> {
+	fn visit(&mut self, state: &T) -> bool {
+		!self.contains(state) && self.insert(state.clone())
+	}
+
+	fn has_visited(&self, state: &T) -> bool {
+		self.contains(state)
+	}
+}
+
+/// A visited-set which stores one bit per state.
+///
+/// The states must be mappable to a dense index; any state which the indexing
+/// function rejects is treated as permanently visited, so the search will never
+/// expand it. For coördinates, this has the useful side effect of keeping the
+/// search inside the bounding box that the set was built for.
+pub struct BitVisited<F> {
+	bits:  BitVec,
+	index: F,
+}
+
+impl<F> BitVisited<F> {
+	/// Creates a bitset for `len` states, using `index` to place each state.
+	pub fn new<T>(len: usize, index: F) -> Self
+	where F: Fn(&T) -> Option<usize> {
+		Self {
+			bits: BitVec::repeat(false, len),
+			index,
+		}
+	}
+
+	/// Counts how many states have been visited.
+	pub fn count(&self) -> usize {
+		self.bits.count_ones()
+	}
+}
+
+/// Counts the positions in an inclusive span of one axis, which is empty if
+/// its bounds are inverted.
+fn axis_len<I: Signed>(min: I, max: I) -> usize {
+	if max < min {
+		return 0;
+	}
+	(max - min).as_usize() + 1
+}
+
+impl<I: Signed> BitVisited<Box<dyn Fn(&Cartesian2DPoint<I>) -> Option<usize>>> {
+	/// Creates a bitset covering every point in an inclusive bounding box.
+	///
+	/// A box whose bounds are inverted on either axis is empty, and treats
+	/// every point as already visited.
+	pub fn for_2d(min: Cartesian2DPoint<I>, max: Cartesian2DPoint<I>) -> Self {
+		let width = axis_len(min.x, max.x);
+		let height = axis_len(min.y, max.y);
+		Self::new(
+			width * height,
+			Box::new(move |pt: &Cartesian2DPoint<I>| {
+				if pt.x < min.x || pt.x > max.x || pt.y < min.y || pt.y > max.y {
+					return None;
+				}
+				let Cartesian2DPoint { x, y } = *pt - min;
+				Some(y.as_usize() * width + x.as_usize())
+			}),
+		)
+	}
+}
+
+impl<I: Signed> BitVisited<Box<dyn Fn(&Cartesian3DPoint<I>) -> Option<usize>>> {
+	/// Creates a bitset covering every point in an inclusive bounding volume.
+	///
+	/// As with [`for_2d`](BitVisited::for_2d), inverted bounds on any axis
+	/// make the volume empty.
+	pub fn for_3d(min: Cartesian3DPoint<I>, max: Cartesian3DPoint<I>) -> Self {
+		let width = axis_len(min.x, max.x);
+		let height = axis_len(min.y, max.y);
+		let depth = axis_len(min.z, max.z);
+		Self::new(
+			width * height * depth,
+			Box::new(move |pt: &Cartesian3DPoint<I>| {
+				if pt.x < min.x
+					|| pt.x > max.x || pt.y < min.y
+					|| pt.y > max.y || pt.z < min.z
+					|| pt.z > max.z
+				{
+					return None;
+				}
+				let Cartesian3DPoint { x, y, z } = *pt - min;
+				Some(
+					(z.as_usize() * height + y.as_usize()) * width
+						+ x.as_usize(),
+				)
+			}),
+		)
+	}
+}
+
+impl<T, F: Fn(&T) -> Option<usize>> Visited<T> for BitVisited<F> {
+	fn visit(&mut self, state: &T) -> bool {
+		match (self.index)(state) {
+			Some(idx) if idx < self.bits.len() => !self.bits.replace(idx, true),
+			_ => false,
+		}
+	}
+
+	fn has_visited(&self, state: &T) -> bool {
+		match (self.index)(state) {
+			Some(idx) => self.bits.get(idx).map(|b| *b).unwrap_or(true),
+			None => true,
+		}
+	}
+}
+
+/// Performs a breadth-first search from a set of seed states, remembering
+/// visited states in a `BTreeSet`.
+///
+/// See [`bfs_visit_with`] for details. The completed visited-set is returned.
+pub fn bfs_visit<T: Ord + Clone>(
+	seeds: impl IntoIterator<Item = T>,
+	expand: impl FnMut(T, &mut VecDeque<T>),
+) -> BTreeSet<T> {
+	bfs_visit_with(BTreeSet::new(), seeds, expand)
+}
+
+/// Performs a breadth-first search from a set of seed states.
+///
+/// The seeds are placed in a FIFO worklist. Each state is popped off the
+/// worklist, and if it has not already been recorded in `visited`, it is
+/// recorded and then handed to `expand`, which may push any number of
+/// successor states onto the back of the worklist. Duplicates are fine: they
+/// are discarded when they come up for processing.
+///
+/// The search ends when the worklist is exhausted, and the visited-set is
+/// returned so that callers can inspect (or count) everything reached.
+pub fn bfs_visit_with<T, V: Visited<T>>(
+	mut visited: V,
+	seeds: impl IntoIterator<Item = T>,
+	mut expand: impl FnMut(T, &mut VecDeque<T>),
+) -> V {
+	let mut worklist = seeds.into_iter().collect::<VecDeque<_>>();
+	while let Some(state) = worklist.pop_front() {
+		if !visited.visit(&state) {
+			continue;
+		}
+		expand(state, &mut worklist);
+	}
+	visited
+}
+
+/// Finds the cheapest path from a set of seed states to any state which
+/// satisfies `is_goal`, by Dijkstra's algorithm.
+///
+/// `expand` pushes each successor of a state onto the given list, along with
+/// the cost of the step to it. Step costs must not be negative. The seeds
+/// start at the default (zero) cost. The goal state which was reached, and
+/// the total cost of reaching it, are returned; `None` means that no goal
+/// state is reachable.
+pub fn dijkstra<T, C>(
+	seeds: impl IntoIterator<Item = T>,
+	mut is_goal: impl FnMut(&T) -> bool,
+	mut expand: impl FnMut(&T, &mut Vec<(T, C)>),
+) -> Option<(T, C)>
+where
+	T: Ord + Clone,
+	C: Ord + Copy + Default + Add<Output = C>,
+{
+	let mut frontier = seeds
+		.into_iter()
+		.map(|seed| Reverse((C::default(), seed)))
+		.collect::<BinaryHeap<_>>();
+	let mut settled = BTreeSet::new();
+	let mut successors = Vec::new();
+	while let Some(Reverse((cost, state))) = frontier.pop() {
+		if !settled.visit(&state) {
+			continue;
+		}
+		if is_goal(&state) {
+			return Some((state, cost));
+		}
+		expand(&state, &mut successors);
+		frontier.extend(
+			successors
+				.drain(..)
+				.filter(|(next, _)| !settled.has_visited(next))
+				.map(|(next, step)| Reverse((cost + step, next))),
+		);
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Coord2D;
+
+	#[test]
+	fn visited_policies_agree() {
+		// A 5x5 box with a wall down column 2, except for a gap in row 4.
+		let open = |pt: Coord2D<i8>| {
+			(0 .. 5).contains(&pt.x)
+				&& (0 .. 5).contains(&pt.y)
+				&& (pt.x != 2 || pt.y == 4)
+		};
+		let expand = |pt: Coord2D<i8>, queue: &mut VecDeque<Coord2D<i8>>| {
+			queue.extend(pt.direct_neighbors().into_iter().filter(|&n| open(n)));
+		};
+
+		let tree = bfs_visit([Coord2D::ZERO], expand);
+		let hash = bfs_visit_with(HashSet::new(), [Coord2D::ZERO], expand);
+		let bits = bfs_visit_with(
+			BitVisited::for_2d(Coord2D::new(0, 0), Coord2D::new(4, 4)),
+			[Coord2D::ZERO],
+			expand,
+		);
+
+		assert_eq!(tree.len(), 21);
+		assert_eq!(hash.len(), 21);
+		assert_eq!(bits.count(), 21);
+		assert!(bits.has_visited(&Coord2D::new(4, 0)));
+		assert!(!bits.has_visited(&Coord2D::new(2, 0)));
+
+		let inverted = bfs_visit_with(
+			BitVisited::for_2d(Coord2D::new(4, 0), Coord2D::new(0, 4)),
+			[Coord2D::ZERO],
+			expand,
+		);
+		assert_eq!(inverted.count(), 0);
+	}
+
+	#[test]
+	fn dijkstra_detours() {
+		// The same box, where going around the wall costs 12 steps and
+		// stepping through it costs 20.
+		let (start, goal) = (Coord2D::<i8>::ZERO, Coord2D::new(4, 0));
+		let found = dijkstra(
+			[start],
+			|&pt| pt == goal,
+			|pt, next| {
+				next.extend(
+					pt.direct_neighbors()
+						.into_iter()
+						.filter(|n| {
+							(0 .. 5).contains(&n.x) && (0 .. 5).contains(&n.y)
+						})
+						.map(|n| (n, if n.x == 2 && n.y != 4 { 17 } else { 1 })),
+				);
+			},
+		);
+		assert_eq!(found, Some((goal, 12)));
+		assert_eq!(
+			dijkstra([start], |_| false, |_, _: &mut Vec<(_, u8)>| {}),
+			None
+		);
+	}
+}
@@ -0,0 +1,449 @@
+//! A tiny HTTP front end for the solvers.
+//!
+//! `serve ADDR` turns the harness into a server, so that other tools (load
+//! generators, benchmark drivers, a web page) can run the solvers without any
+//! glue code of their own. There is one endpoint:
+//!
+//! ```text
+//! POST /{year}/{day}?part=1
+//! ```
+//!
+//! The request body is the puzzle input. `part` may be `1` or `2`, and both
+//! parts are run when it is absent. Every other query pair is handed to the
+//! solver as a parameter, exactly like `--param`; data-group parameter files
+//! are not read. The response is a JSON object:
+//!
+//! ```json
+//! {"year": 2023, "day": 18, "part_1": "62", "part_2": null, "elapsed_ms": 0.4}
+//! ```
+//!
+//! Answers are sent as strings, since they can be wider than the numbers that
+//! JavaScript can hold. Failures are sent as `{"error": "..."}` with a status
+//! code saying whose fault they were. Solver failures also have a `class`,
+//! naming their [`AocError`] class, or `null` when they have none.
+//!
+//! Only as much HTTP/1.1 is spoken as `curl` and `fetch` need: each connection
+//! carries one request, whose body must have a `Content-Length`. Every
+//! connection is served on its own thread, up to [`MAX_CONNECTIONS`] at once;
+//! connections beyond that are turned away with a 503.
+
+use std::{
+	io::{
+		self,
+		BufRead,
+		BufReader,
+		Write,
+	},
+	net::{
+		SocketAddr,
+		TcpListener,
+		TcpStream,
+	},
+	panic::{
+		self,
+		AssertUnwindSafe,
+	},
+	sync::{
+		atomic::{
+			AtomicUsize,
+			Ordering,
+		},
+		Arc,
+	},
+	thread,
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+use serde_json::json;
+
+use crate::{
+	cancel,
+	solutions,
+	Answer,
+	AocError,
+	ParamMap,
+};
+
+/// The largest request body accepted. Puzzle inputs are rarely more than a
+/// few tens of kilobytes.
+pub const MAX_BODY: usize = 16 << 20;
+
+/// The longest request line or header line accepted, in bytes.
+pub const MAX_LINE: usize = 8 << 10;
+
+/// The most headers accepted in one request.
+pub const MAX_HEADERS: usize = 100;
+
+/// The most connections served at once.
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the accept loop checks whether it has been cancelled.
+const POLL: Duration = Duration::from_millis(50);
+
+/// Serves solvers over HTTP until the run is cancelled.
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+pub fn serve(addr: SocketAddr) -> eyre::Result<()> {
+	let listener = TcpListener::bind(addr)?;
+	// Blocking in `accept` would never notice a Ctrl-C.
+	listener.set_nonblocking(true)?;
+	tracing::info!(addr = %listener.local_addr()?, "serving solvers");
+	let open = Arc::new(AtomicUsize::new(0));
+	loop {
+		cancel::check()?;
+		match listener.accept() {
+			Ok((mut stream, peer)) => {
+				if open.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+					open.fetch_sub(1, Ordering::AcqRel);
+					tracing::warn!(%peer, "too many connections; turned away");
+					Response::error(503, "the server is busy")
+						.write(&mut stream)
+						.ok();
+					continue;
+				}
+				let open = open.clone();
+				thread::spawn(move || {
+					if let Err(err) = handle(stream, peer) {
+						tracing::warn!(%peer, %err, "connection failed");
+					}
+					open.fetch_sub(1, Ordering::AcqRel);
+				});
+			},
+			Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+				thread::sleep(POLL);
+			},
+			Err(err) => tracing::warn!(%err, "could not accept a connection"),
+		}
+	}
+}
+
+/// Answers one connection.
+#[cfg_attr(
+	feature = "trace",
+	tracing::instrument(name = "request", skip(stream))
+)]
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+fn handle(mut stream: TcpStream, peer: SocketAddr) -> io::Result<()> {
+	stream.set_nonblocking(false)?;
+	stream.set_read_timeout(Some(READ_TIMEOUT))?;
+	let response = match Request::read(&mut BufReader::new(&mut stream)) {
+		Ok(request) => {
+			tracing::info!(method = %request.method, path = %request.path, "received");
+			route(&request)
+		},
+		Err(response) => response,
+	};
+	tracing::info!(status = response.status, "responded");
+	response.write(&mut stream)
+}
+
+/// The parts of an HTTP request that the server looks at.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Request {
+	pub method: String,
+	/// The request target, without its query string.
+	pub path:   String,
+	/// The decoded query pairs, in order.
+	pub query:  Vec<(String, String)>,
+	pub body:   String,
+}
+
+impl Request {
+	/// Reads a request from a client.
+	///
+	/// Malformed requests produce the response which should be sent back.
+	pub fn read(reader: &mut impl BufRead) -> Result<Self, Response> {
+		let bad = |msg: &str| Response::error(400, msg);
+		let mut line = String::new();
+		let fits = read_line(reader, &mut line)
+			.map_err(|_| bad("could not read the request line"))?;
+		if !fits {
+			return Err(Response::error(414, "the request line is too long"));
+		}
+		let mut words = line.split_whitespace();
+		let (Some(method), Some(target), Some(_)) =
+			(words.next(), words.next(), words.next())
+		else {
+			return Err(bad("malformed request line"));
+		};
+		let (path, query) = target.split_once('?').unwrap_or((target, ""));
+		let mut this = Self {
+			method: method.to_owned(),
+			path:   path.to_owned(),
+			query:  query
+				.split('&')
+				.filter(|pair| !pair.is_empty())
+				.map(|pair| {
+					let (key, value) =
+						pair.split_once('=').unwrap_or((pair, ""));
+					Ok((percent_decode(key)?, percent_decode(value)?))
+				})
+				.collect::<Result<_, Response>>()?,
+			body:   String::new(),
+		};
+
+		let mut length = 0;
+		for count in 0 .. {
+			if count == MAX_HEADERS {
+				return Err(Response::error(431, "too many headers"));
+			}
+			line.clear();
+			let fits = read_line(reader, &mut line)
+				.map_err(|_| bad("could not read the headers"))?;
+			if !fits {
+				return Err(Response::error(431, "a header is too long"));
+			}
+			let header = line.trim_end();
+			if header.is_empty() {
+				break;
+			}
+			let Some((name, value)) = header.split_once(':')
+			else {
+				return Err(bad("malformed header"));
+			};
+			if name.eq_ignore_ascii_case("content-length") {
+				length = value
+					.trim()
+					.parse()
+					.map_err(|_| bad("malformed Content-Length"))?;
+			}
+			else if name.eq_ignore_ascii_case("transfer-encoding") {
+				return Err(Response::error(411, "send a Content-Length"));
+			}
+		}
+		if length > MAX_BODY {
+			return Err(Response::error(413, "the input is too large"));
+		}
+		let mut body = vec![0; length];
+		reader
+			.read_exact(&mut body)
+			.map_err(|_| bad("the body was shorter than its Content-Length"))?;
+		this.body = String::from_utf8(body)
+			.map_err(|_| bad("the input is not UTF-8"))?;
+		Ok(this)
+	}
+}
+
+/// Reads one line, of at most [`MAX_LINE`] bytes, into `line`, and reports
+/// whether it fit. The rest of a line which does not fit is left unread.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<bool> {
+	let read = io::Read::take(reader, MAX_LINE as u64).read_line(line)?;
+	Ok(read < MAX_LINE || line.ends_with('\n'))
+}
+
+/// A JSON response, and its status code.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Response {
+	pub status: u16,
+	pub body:   serde_json::Value,
+}
+
+impl Response {
+	/// Creates an error response.
+	pub fn error(status: u16, message: impl AsRef<str>) -> Self {
+		Self {
+			status,
+			body: json!({ "error": message.as_ref() }),
+		}
+	}
+
+	/// Sends the response, and closes the connection.
+	pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+		let body = self.body.to_string();
+		let reason = match self.status {
+			200 => "OK",
+			400 => "Bad Request",
+			404 => "Not Found",
+			405 => "Method Not Allowed",
+			411 => "Length Required",
+			413 => "Content Too Large",
+			414 => "URI Too Long",
+			422 => "Unprocessable Content",
+			431 => "Request Header Fields Too Large",
+			501 => "Not Implemented",
+			503 => "Service Unavailable",
+			_ => "Internal Server Error",
+		};
+		write!(
+			out,
+			"HTTP/1.1 {} {reason}\r\nContent-Type: \
+			 application/json\r\nContent-Length: {}\r\nConnection: \
+			 close\r\n\r\n{body}",
+			self.status,
+			body.len(),
+		)?;
+		out.flush()
+	}
+}
+
+/// Runs the solver that a request names, on the input it carries.
+pub fn route(request: &Request) -> Response {
+	let segments = request
+		.path
+		.split('/')
+		.filter(|seg| !seg.is_empty())
+		.collect::<Vec<_>>();
+	let [year, day] = segments[..]
+	else {
+		return Response::error(404, "solvers live at /{year}/{day}");
+	};
+	let (Ok(year), Ok(day)) = (year.parse::<u16>(), day.parse::<u8>())
+	else {
+		return Response::error(404, "solvers live at /{year}/{day}");
+	};
+	let Some(&entry) = solutions().get(&year).and_then(|days| days.get(&day))
+	else {
+		return Response::error(404, format!("no solver for {year}-{day:0>2}"));
+	};
+	if request.method != "POST" {
+		return Response::error(405, "send the puzzle input with POST");
+	}
+
+	let (mut part_1, mut part_2) = (true, true);
+	let mut params = ParamMap::new();
+	for (key, value) in &request.query {
+		match (key.as_str(), value.as_str()) {
+			("part", "1") => part_2 = false,
+			("part", "2") => part_1 = false,
+			("part", other) => {
+				return Response::error(
+					400,
+					format!("there is no part {other}"),
+				);
+			},
+			_ => params.insert(key.as_str(), value.as_str()),
+		}
+	}
+
+	let start = Instant::now();
+	let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+		entry.solve_with_input(&request.body, &params, part_1, part_2)
+	}));
+	let elapsed = start.elapsed();
+	match outcome {
+		Ok(Ok((one, two))) => Response {
+			status: 200,
+			body:   json!({
+				"year": year,
+				"day": day,
+				"part_1": one.as_ref().map(Answer::to_string),
+				"part_2": two.as_ref().map(Answer::to_string),
+				"elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+			}),
+		},
+		Ok(Err(err)) => {
+			let class = AocError::find(&err);
+			let status = match class {
+				Some(AocError::NotImplemented { .. }) => 501,
+				Some(class) if class.is_bug() => 500,
+				_ => 422,
+			};
+			let mut response = Response::error(status, format!("{err:#}"));
+			response.body["class"] = class.map(AocError::class).into();
+			response
+		},
+		Err(_) => Response::error(500, "the solver panicked"),
+	}
+}
+
+/// Decodes `%XX` escapes, and `+` for space, in a query string component.
+fn percent_decode(text: &str) -> Result<String, Response> {
+	let mut bytes = Vec::with_capacity(text.len());
+	let mut rest = text.as_bytes();
+	while let Some((&byte, tail)) = rest.split_first() {
+		rest = tail;
+		bytes.push(match byte {
+			b'+' => b' ',
+			b'%' => {
+				let hex = rest
+					.get(.. 2)
+					.and_then(|hex| std::str::from_utf8(hex).ok())
+					.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+					.ok_or_else(|| {
+						Response::error(400, "malformed escape in query")
+					})?;
+				rest = &rest[2 ..];
+				hex
+			},
+			other => other,
+		});
+	}
+	String::from_utf8(bytes)
+		.map_err(|_| Response::error(400, "the query is not UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn post(target: &str, body: &str) -> Result<Request, Response> {
+		let raw = format!(
+			"POST {target} HTTP/1.1\r\nHost: localhost\r\nContent-Length: \
+			 {}\r\n\r\n{body}",
+			body.len()
+		);
+		Request::read(&mut raw.as_bytes())
+	}
+
+	#[test]
+	fn solves_posted_input() -> Result<(), Response> {
+		let input = include_str!("y2023/d18/sample.txt");
+		let request = post("/2023/18?part=1", input)?;
+		assert_eq!(request.body, input);
+		let response = route(&request);
+		assert_eq!(response.status, 200);
+		assert_eq!(response.body["part_1"], "62");
+		assert!(response.body["part_2"].is_null());
+
+		let response = route(&post(
+			"/2022/15?row=10&max=20",
+			include_str!("y2022/d15/sample.txt"),
+		)?);
+		assert_eq!(response.body["part_1"], "26");
+		assert_eq!(response.body["part_2"], "56000011");
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_bad_requests() -> Result<(), Response> {
+		assert_eq!(route(&post("/2023", "")?).status, 404);
+		assert_eq!(route(&post("/1999/1", "")?).status, 404);
+		assert_eq!(route(&post("/2023/18?part=3", "")?).status, 400);
+		let response = route(&post("/2023/18", "nonsense")?);
+		assert_eq!(response.status, 422);
+		assert_eq!(response.body["class"], "invalid input");
+		let garden = include_str!("y2023/d21/sample.txt");
+		assert_eq!(route(&post("/2023/21?part=2", garden)?).status, 501);
+		let get = Request::read(&mut &b"GET /2023/18 HTTP/1.1\r\n\r\n"[..])?;
+		assert_eq!(route(&get).status, 405);
+		assert_eq!(
+			post("/2023/18?a=%zz", "").map_err(|resp| resp.status),
+			Err(400)
+		);
+		assert_eq!(percent_decode("a%20b+c").ok().as_deref(), Some("a b c"));
+
+		let long = format!("POST /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE));
+		let status = |raw: &str| {
+			Request::read(&mut raw.as_bytes())
+				.map(drop)
+				.map_err(|resp| resp.status)
+		};
+		assert_eq!(status(&long), Err(414));
+		let header = format!("x-pad: {}\r\n", "a".repeat(MAX_LINE));
+		assert_eq!(
+			status(&format!("POST / HTTP/1.1\r\n{header}\r\n")),
+			Err(431)
+		);
+		let headers = "x: y\r\n".repeat(MAX_HEADERS);
+		assert_eq!(
+			status(&format!("POST / HTTP/1.1\r\n{headers}\r\n")),
+			Err(431)
+		);
+		Ok(())
+	}
+}
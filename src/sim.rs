@@ -0,0 +1,38 @@
+//! Reusable simulation engines.
+//!
+//! Some puzzle mechanics recur across days (and across years) with only the
+//! parameters changed. The engines in this module own the stepping logic and
+//! any record-keeping that visualizations or tests might want, so that the day
+//! modules only need to parse their input and ask questions of the result.
+
+pub mod asm;
+pub mod automaton;
+pub mod cycles;
+pub mod event;
+pub mod lines;
+pub mod record;
+pub mod rope;
+pub mod until;
+
+pub use self::{
+	automaton::{
+		step_automaton,
+		Automaton,
+		Edges,
+	},
+	cycles::{
+		find_orbits,
+		Orbits,
+	},
+	event::EventQueue,
+	lines::{
+		LineProgram,
+		StateMachine,
+	},
+	record::Recorder,
+	until::{
+		simulate_traced,
+		simulate_until,
+		Run,
+	},
+};
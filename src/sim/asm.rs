@@ -0,0 +1,387 @@
+//! Text form of register-machine programs.
+//!
+//! Several puzzles (assembunny in 2016, elfcode in 2018, and others) hand over
+//! a program for a tiny register machine, and their second parts can only be
+//! solved by working out what that program computes. This module converts
+//! such programs between text and instructions, with named labels in place of
+//! numeric jump distances and comments for notes, so that an input can be
+//! disassembled, studied, annotated, hand-patched, and assembled again.
+//!
+//! Each instruction set implements [`Instruction`], which maps an instruction
+//! to a mnemonic and a list of [`Operand`]s and back. [`Program`] handles the
+//! rest of the text:
+//!
+//! - `;` begins a comment, which runs to the end of the line.
+//! - `name:` labels the next instruction. It may share a line with it.
+//! - Operands are separated by spaces or commas. Integers are immediates,
+//!   `@name` refers to a label, and anything else names a register.
+//!
+//! Labels may only appear in an instruction's jump operand, and are replaced
+//! by the address or the distance to the instruction they name, as the
+//! instruction set requires. The disassembler reverses this, inventing labels
+//! for any jump targets which do not have one.
+
+use std::{
+	collections::BTreeMap,
+	fmt::{
+		self,
+		Write as _,
+	},
+};
+
+use eyre::WrapErr;
+
+/// How the instruction set encodes the destination of a jump.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Addressing {
+	/// The operand is the distance from the jumping instruction, as in
+	/// assembunny's `jnz x -2`.
+	Relative,
+	/// The operand is the index of the destination instruction.
+	Absolute,
+}
+
+/// One operand of an instruction, as written.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Operand {
+	/// A register, by name.
+	Register(String),
+	/// A literal number.
+	Immediate(i64),
+	/// A reference to a labeled instruction. These are resolved to
+	/// immediates before the instruction set sees them.
+	Label(String),
+}
+
+impl Operand {
+	/// Reads an operand from a single word of program text.
+	pub fn parse(word: &str) -> eyre::Result<Self> {
+		if let Some(label) = word.strip_prefix('@') {
+			if !is_name(label) {
+				eyre::bail!("`{word}` is not a valid label reference");
+			}
+			return Ok(Self::Label(label.to_owned()));
+		}
+		if let Ok(num) = word.parse() {
+			return Ok(Self::Immediate(num));
+		}
+		if !is_name(word) {
+			eyre::bail!("`{word}` is not a number, register, or label");
+		}
+		Ok(Self::Register(word.to_owned()))
+	}
+
+	/// Gets the value of an immediate operand.
+	pub fn immediate(&self) -> Option<i64> {
+		match *self {
+			Self::Immediate(num) => Some(num),
+			_ => None,
+		}
+	}
+
+	/// Gets the name of a register operand.
+	pub fn register(&self) -> Option<&str> {
+		match self {
+			Self::Register(name) => Some(name),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Operand {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Register(name) => fmt.write_str(name),
+			Self::Immediate(num) => fmt::Display::fmt(num, fmt),
+			Self::Label(name) => write!(fmt, "@{name}"),
+		}
+	}
+}
+
+/// An instruction set which can be written as text.
+pub trait Instruction: Sized {
+	/// How jump operands encode their destination.
+	const ADDRESSING: Addressing;
+
+	/// Builds an instruction from its mnemonic and operands.
+	///
+	/// Labels have already been resolved, so the operands are only registers
+	/// and immediates.
+	fn assemble(mnemonic: &str, operands: &[Operand]) -> eyre::Result<Self>;
+
+	/// Splits an instruction into its mnemonic and operands.
+	fn disassemble(&self) -> (&str, Vec<Operand>);
+
+	/// Finds which operand of an instruction, if any, is the destination of a
+	/// jump.
+	///
+	/// The default has no jumps, so labels cannot be used.
+	fn jump_operand(mnemonic: &str) -> Option<usize> {
+		let _ = mnemonic;
+		None
+	}
+}
+
+/// A sequence of instructions, with names for some of their addresses.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Program<I> {
+	pub instructions: Vec<I>,
+	/// Names for instruction addresses. The address one past the last
+	/// instruction may be named, as a jump there ends the program.
+	pub labels:       BTreeMap<usize, String>,
+}
+
+impl<I: Instruction> Program<I> {
+	/// Wraps instructions which have no labels.
+	pub fn new(instructions: Vec<I>) -> Self {
+		Self {
+			instructions,
+			labels: BTreeMap::new(),
+		}
+	}
+
+	/// Reads a program from text, resolving labels and discarding comments.
+	pub fn assemble(text: &str) -> eyre::Result<Self> {
+		let mut labels = BTreeMap::new();
+		let mut names = BTreeMap::new();
+		let mut lines = Vec::new();
+		// The first pass finds every label, so that jumps may refer forwards.
+		for (num, line) in text.lines().enumerate() {
+			let mut line = line.split(';').next().unwrap_or_default().trim();
+			while let Some((name, rest)) = line.split_once(':') {
+				let name = name.trim();
+				if !is_name(name) {
+					break;
+				}
+				let here = lines.len();
+				if names.insert(name.to_owned(), here).is_some() {
+					eyre::bail!(
+						"line {}: label `{name}` is already defined",
+						num + 1
+					);
+				}
+				labels.entry(here).or_insert_with(|| name.to_owned());
+				line = rest.trim();
+			}
+			if !line.is_empty() {
+				lines.push((num + 1, line));
+			}
+		}
+		let instructions = lines
+			.into_iter()
+			.enumerate()
+			.map(|(here, (num, line))| {
+				assemble_line::<I>(here, line, &names)
+					.wrap_err_with(|| format!("line {num}: `{line}`"))
+			})
+			.collect::<eyre::Result<Vec<_>>>()?;
+		if let Some((&addr, name)) = labels.last_key_value() {
+			if addr > instructions.len() {
+				eyre::bail!("label `{name}` is past the end of the program");
+			}
+		}
+		Ok(Self {
+			instructions,
+			labels,
+		})
+	}
+
+	/// Writes the program out as text, with a label on every instruction
+	/// that a jump lands on.
+	///
+	/// Jumps to targets which have no name are given one; jumps which leave
+	/// the program, or which go wherever a register says, are left as they
+	/// are. Assembling the text produces the same instructions.
+	pub fn disassemble(&self) -> String {
+		let decoded = self
+			.instructions
+			.iter()
+			.map(Instruction::disassemble)
+			.collect::<Vec<_>>();
+		let mut labels = self.labels.clone();
+		let mut counter = 0;
+		for (here, (mnemonic, operands)) in decoded.iter().enumerate() {
+			if let Some(target) = self.jump_target(here, mnemonic, operands) {
+				labels.entry(target).or_insert_with(|| {
+					let name = fresh_name(&self.labels, &mut counter);
+					counter += 1;
+					name
+				});
+			}
+		}
+
+		let mut out = String::new();
+		for (here, (mnemonic, mut operands)) in decoded.into_iter().enumerate() {
+			if let Some(name) = labels.get(&here) {
+				let _ = writeln!(out, "{name}:");
+			}
+			if let Some(target) = self.jump_target(here, mnemonic, &operands) {
+				let idx = I::jump_operand(mnemonic).unwrap_or_default();
+				operands[idx] = Operand::Label(labels[&target].clone());
+			}
+			out.push('\t');
+			out.push_str(mnemonic);
+			for operand in operands {
+				let _ = write!(out, " {operand}");
+			}
+			out.push('\n');
+		}
+		if let Some(name) = labels.get(&self.instructions.len()) {
+			let _ = writeln!(out, "{name}:");
+		}
+		out
+	}
+
+	/// Finds the address that an instruction jumps to, if it is a fixed jump
+	/// which stays within the program.
+	fn jump_target(
+		&self,
+		here: usize,
+		mnemonic: &str,
+		operands: &[Operand],
+	) -> Option<usize> {
+		let value = operands.get(I::jump_operand(mnemonic)?)?.immediate()?;
+		let target = match I::ADDRESSING {
+			Addressing::Relative => here as i64 + value,
+			Addressing::Absolute => value,
+		};
+		usize::try_from(target)
+			.ok()
+			.filter(|&target| target <= self.instructions.len())
+	}
+}
+
+/// Builds one instruction, with its label operand replaced by a number.
+fn assemble_line<I: Instruction>(
+	here: usize,
+	line: &str,
+	names: &BTreeMap<String, usize>,
+) -> eyre::Result<I> {
+	let mut words = line
+		.split(|c: char| c.is_whitespace() || c == ',')
+		.filter(|word| !word.is_empty());
+	let mnemonic = words.next().unwrap_or_default();
+	let jump = I::jump_operand(mnemonic);
+	let operands = words
+		.map(Operand::parse)
+		.enumerate()
+		.map(|(idx, operand)| match operand? {
+			Operand::Label(name) => {
+				if jump != Some(idx) {
+					eyre::bail!("`@{name}` is not in a jump operand");
+				}
+				let &target = names
+					.get(&name)
+					.ok_or_else(|| eyre::eyre!("no label named `{name}`"))?;
+				Ok(Operand::Immediate(match I::ADDRESSING {
+					Addressing::Relative => target as i64 - here as i64,
+					Addressing::Absolute => target as i64,
+				}))
+			},
+			other => Ok(other),
+		})
+		.collect::<eyre::Result<Vec<_>>>()?;
+	I::assemble(mnemonic, &operands)
+}
+
+/// Makes up a label name which the program does not already use.
+fn fresh_name(taken: &BTreeMap<usize, String>, counter: &mut usize) -> String {
+	loop {
+		let name = format!("l{counter}");
+		if !taken.values().any(|used| *used == name) {
+			return name;
+		}
+		*counter += 1;
+	}
+}
+
+/// Tests whether text can be used as a register or label name.
+fn is_name(text: &str) -> bool {
+	let mut chars = text.chars();
+	chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+		&& chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Assembunny, from 2016 days 12, 23, and 25.
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	enum Bunny {
+		Cpy(Operand, Operand),
+		Inc(Operand),
+		Dec(Operand),
+		Jnz(Operand, Operand),
+	}
+
+	impl Instruction for Bunny {
+		const ADDRESSING: Addressing = Addressing::Relative;
+
+		fn assemble(mnemonic: &str, operands: &[Operand]) -> eyre::Result<Self> {
+			Ok(match (mnemonic, operands) {
+				("cpy", [a, b]) => Self::Cpy(a.clone(), b.clone()),
+				("inc", [a]) => Self::Inc(a.clone()),
+				("dec", [a]) => Self::Dec(a.clone()),
+				("jnz", [a, b]) => Self::Jnz(a.clone(), b.clone()),
+				_ => eyre::bail!("unknown instruction"),
+			})
+		}
+
+		fn disassemble(&self) -> (&str, Vec<Operand>) {
+			match self {
+				Self::Cpy(a, b) => ("cpy", vec![a.clone(), b.clone()]),
+				Self::Inc(a) => ("inc", vec![a.clone()]),
+				Self::Dec(a) => ("dec", vec![a.clone()]),
+				Self::Jnz(a, b) => ("jnz", vec![a.clone(), b.clone()]),
+			}
+		}
+
+		fn jump_operand(mnemonic: &str) -> Option<usize> {
+			(mnemonic == "jnz").then_some(1)
+		}
+	}
+
+	#[test]
+	fn round_trip() -> eyre::Result<()> {
+		// The 2016 day 12 example, as written in the puzzle.
+		let raw = "cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a\n";
+		let program = Program::<Bunny>::assemble(raw)?;
+		assert_eq!(program.instructions.len(), 6);
+		assert_eq!(
+			program.disassemble(),
+			"\tcpy 41 a\n\tinc a\n\tinc a\n\tdec a\n\tjnz a @l0\n\tdec a\nl0:\n"
+		);
+
+		let annotated = "
+			; a = 41 + 1
+			        cpy 41, a
+			again:  inc a
+			        dec b      ; loops until b runs out
+			        jnz b @again
+			        jnz 1 @done
+			        inc c
+			done:
+		";
+		let program = Program::<Bunny>::assemble(annotated)?;
+		assert_eq!(
+			program.instructions[4],
+			Bunny::Jnz(Operand::Immediate(1), Operand::Immediate(2))
+		);
+		let text = program.disassemble();
+		assert_eq!(
+			text,
+			"\tcpy 41 a\nagain:\n\tinc a\n\tdec b\n\tjnz b @again\n\tjnz 1 \
+			 @done\n\tinc c\ndone:\n"
+		);
+		assert_eq!(Program::assemble(&text)?, program);
+		Ok(())
+	}
+
+	#[test]
+	fn label_errors() {
+		for text in ["jnz a @nowhere", "cpy @x a\nx: inc a", "x:\nx: inc a"] {
+			assert!(Program::<Bunny>::assemble(text).is_err(), "{text}");
+		}
+	}
+}
@@ -0,0 +1,315 @@
+//! Cellular automata on rectangular grids of any dimension.
+//!
+//! Every year has at least one Game of Life: each cell's next state depends
+//! only on its own state and on how many of its neighbors (including the
+//! diagonal ones) are in each state. The [`Automaton`] owns the grid and a
+//! second buffer to write the next generation into, so that a day module only
+//! has to supply the rule. Grids can have hard edges, beyond which there is
+//! nothing, or wrap around into a torus. Grids which are supposed to be
+//! infinite can be [padded](Automaton::pad) before each step.
+//!
+//! [`step_automaton`] runs a single generation directly on a
+//! [`Dense2DSpace`](crate::coords::Dense2DSpace).
+
+use std::mem;
+
+use funty::Signed;
+
+use crate::coords::spaces::Dense2D;
+
+/// What lies beyond the edges of the grid.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edges {
+	/// Nothing: cells on the edge have fewer neighbors.
+	#[default]
+	Bounded,
+	/// The opposite edge: every cell has a full set of neighbors.
+	Toroidal,
+}
+
+/// The states of the cells around one cell.
+#[derive(Clone, Copy, Debug)]
+pub struct Neighborhood<'a, T> {
+	cells: &'a [T],
+}
+
+impl<'a, T> Neighborhood<'a, T> {
+	/// Counts the neighbors whose state satisfies a predicate.
+	pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+		self.cells.iter().filter(|cell| pred(cell)).count()
+	}
+
+	/// Counts the neighbors in a given state.
+	pub fn count_eq(&self, state: &T) -> usize
+	where T: PartialEq {
+		self.count(|cell| cell == state)
+	}
+
+	/// Counts the neighbors, which is fewer than usual at a bounded edge.
+	pub fn len(&self) -> usize {
+		self.cells.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.cells.is_empty()
+	}
+
+	pub fn iter(&self) -> impl 'a + Iterator<Item = &'a T> {
+		self.cells.iter()
+	}
+}
+
+/// A grid of cells, and the rules of its world.
+///
+/// Cells are addressed by one coördinate per dimension, with the first
+/// dimension varying fastest in memory. Dense 2-D spaces are `[column, row]`.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Automaton<T> {
+	dims:    Vec<usize>,
+	cells:   Vec<T>,
+	/// The buffer the next generation is written into.
+	next:    Vec<T>,
+	edges:   Edges,
+	/// The offset, in each dimension, to each neighbor of a cell.
+	offsets: Vec<Vec<isize>>,
+}
+
+impl<T: Clone> Automaton<T> {
+	/// Creates an automaton from the lengths of its dimensions and its cells.
+	///
+	/// ## Panics
+	///
+	/// This panics if the number of cells is not the product of the lengths.
+	pub fn new(dims: Vec<usize>, cells: Vec<T>, edges: Edges) -> Self {
+		assert_eq!(
+			dims.iter().product::<usize>(),
+			cells.len(),
+			"the grid must be completely filled"
+		);
+		let offsets = (0 .. 3usize.pow(dims.len() as u32))
+			.map(|mut idx| {
+				dims.iter()
+					.map(|_| {
+						let offset = (idx % 3) as isize - 1;
+						idx /= 3;
+						offset
+					})
+					.collect::<Vec<_>>()
+			})
+			.filter(|offset| offset.iter().any(|&o| o != 0))
+			.collect();
+		Self {
+			dims,
+			next: cells.clone(),
+			cells,
+			edges,
+			offsets,
+		}
+	}
+
+	/// Copies the cells out of a dense 2-D space.
+	pub fn from_grid<I: Signed>(grid: &Dense2D<I, T>, edges: Edges) -> Self {
+		let rows = grid.raw_data();
+		let width = rows.first().map(Vec::len).unwrap_or_default();
+		Self::new(vec![width, rows.len()], rows.concat(), edges)
+	}
+
+	/// Copies the cells back into a dense 2-D space.
+	///
+	/// ## Panics
+	///
+	/// This panics if the automaton does not have exactly two dimensions.
+	pub fn write_to<I: Signed>(&self, grid: &mut Dense2D<I, T>) {
+		let [width, _] = self.dims[..]
+		else {
+			panic!("a {}-D automaton is not a 2-D grid", self.dims.len());
+		};
+		let origin = grid.dimensions().map(|(min, _)| min).unwrap_or_default();
+		let rows = self.cells.chunks(width.max(1)).map(<[T]>::to_vec).collect();
+		*grid = Dense2D::from_raw(origin, rows);
+	}
+
+	/// Gets the lengths of the dimensions.
+	pub fn dims(&self) -> &[usize] {
+		&self.dims
+	}
+
+	/// Views all of the cells, first dimension fastest.
+	pub fn cells(&self) -> &[T] {
+		&self.cells
+	}
+
+	/// Gets the cell at a coördinate.
+	pub fn get(&self, coord: &[usize]) -> Option<&T> {
+		self.index_of(coord).map(|idx| &self.cells[idx])
+	}
+
+	/// Counts the cells whose state satisfies a predicate.
+	pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+		self.cells.iter().filter(|cell| pred(cell)).count()
+	}
+
+	/// Surrounds the grid with `by` layers of a fill state on every side, in
+	/// every dimension.
+	///
+	/// Automata which are meant to grow without limit need to be padded by
+	/// one cell before each step.
+	pub fn pad(&mut self, by: usize, fill: T) {
+		let dims = self.dims.iter().map(|len| len + 2 * by).collect::<Vec<_>>();
+		let mut cells = vec![fill; dims.iter().product()];
+		let mut coord = vec![0; dims.len()];
+		for (idx, cell) in mem::take(&mut self.cells).into_iter().enumerate() {
+			self.decode(idx, &mut coord);
+			let moved = coord
+				.iter()
+				.zip(&dims)
+				.rev()
+				.fold(0, |acc, (c, len)| acc * len + c + by);
+			cells[moved] = cell;
+		}
+		*self = Self::new(dims, cells, self.edges);
+	}
+
+	/// Computes the next generation.
+	///
+	/// The rule receives each cell's current state and its neighbors' states,
+	/// and produces the cell's next state. Every cell sees the current
+	/// generation, never a partly-updated one.
+	pub fn step(&mut self, mut rule: impl FnMut(&T, Neighborhood<T>) -> T) {
+		let mut coord = vec![0; self.dims.len()];
+		let mut around = Vec::with_capacity(self.offsets.len());
+		let mut next = mem::take(&mut self.next);
+		for (idx, slot) in next.iter_mut().enumerate() {
+			self.decode(idx, &mut coord);
+			around.clear();
+			around.extend(
+				self.offsets
+					.iter()
+					.filter_map(|offset| self.neighbor(&coord, offset))
+					.map(|idx| self.cells[idx].clone()),
+			);
+			*slot = rule(&self.cells[idx], Neighborhood { cells: &around });
+		}
+		self.next = mem::replace(&mut self.cells, next);
+	}
+
+	/// Computes some number of generations.
+	pub fn run(
+		&mut self,
+		steps: usize,
+		mut rule: impl FnMut(&T, Neighborhood<T>) -> T,
+	) {
+		for _ in 0 .. steps {
+			self.step(&mut rule);
+		}
+	}
+
+	fn index_of(&self, coord: &[usize]) -> Option<usize> {
+		if coord.len() != self.dims.len() {
+			return None;
+		}
+		coord
+			.iter()
+			.zip(&self.dims)
+			.rev()
+			.try_fold(0, |acc, (&c, &len)| (c < len).then(|| acc * len + c))
+	}
+
+	fn decode(&self, mut idx: usize, coord: &mut [usize]) {
+		for (c, len) in coord.iter_mut().zip(&self.dims) {
+			*c = idx % len;
+			idx /= len;
+		}
+	}
+
+	/// Finds the index of a cell's neighbor, if it has one in that direction.
+	fn neighbor(&self, coord: &[usize], offset: &[isize]) -> Option<usize> {
+		let mut out = 0;
+		for ((&c, &o), &len) in coord.iter().zip(offset).zip(&self.dims).rev() {
+			let moved = c as isize + o;
+			let moved = match self.edges {
+				Edges::Bounded if moved < 0 || moved >= len as isize => {
+					return None;
+				},
+				Edges::Bounded => moved as usize,
+				Edges::Toroidal => moved.rem_euclid(len as isize) as usize,
+			};
+			out = out * len + moved;
+		}
+		Some(out)
+	}
+}
+
+/// Computes the next generation of a dense 2-D space in place.
+///
+/// This copies the space into an [`Automaton`] and back; keep an automaton
+/// around instead when running many generations.
+pub fn step_automaton<I: Signed, T: Clone>(
+	space: &mut Dense2D<I, T>,
+	edges: Edges,
+	rule: impl FnMut(&T, Neighborhood<T>) -> T,
+) {
+	let mut automaton = Automaton::from_grid(space, edges);
+	automaton.step(rule);
+	automaton.write_to(space);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Coord2D;
+
+	/// Conway's rules.
+	fn life(cell: &bool, around: Neighborhood<bool>) -> bool {
+		matches!((cell, around.count_eq(&true)), (true, 2) | (_, 3))
+	}
+
+	#[test]
+	fn blinker() {
+		let row = |bits: [u8; 5]| bits.map(|b| b == 1).to_vec();
+		let mut grid = Dense2D::<i8, bool>::from_raw(Coord2D::ZERO, vec![
+			row([0, 0, 0, 0, 0]),
+			row([0, 0, 1, 0, 0]),
+			row([0, 0, 1, 0, 0]),
+			row([0, 0, 1, 0, 0]),
+			row([0, 0, 0, 0, 0]),
+		]);
+		let start = grid.clone();
+		step_automaton(&mut grid, Edges::Bounded, life);
+		assert_eq!(grid.get_row(2), Some(&row([0, 1, 1, 1, 0])[..]));
+		step_automaton(&mut grid, Edges::Bounded, life);
+		assert_eq!(grid, start);
+	}
+
+	#[test]
+	fn glider_wraps() {
+		let mut cells = vec![false; 36];
+		for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+			cells[y * 6 + x] = true;
+		}
+		let mut world =
+			Automaton::new(vec![6, 6], cells.clone(), Edges::Toroidal);
+		// A glider moves one cell diagonally every four generations.
+		world.run(24, life);
+		assert_eq!(world.cells(), cells);
+	}
+
+	#[test]
+	fn conway_cubes() {
+		// The 2020 day 17 example, in three and four dimensions.
+		let plane = ".#./..#/###"
+			.split('/')
+			.flat_map(|row| row.chars().map(|c| c == '#'))
+			.collect::<Vec<_>>();
+		for (dims, active) in [(vec![3, 3, 1], 112), (vec![3, 3, 1, 1], 848)] {
+			let mut world = Automaton::new(dims, plane.clone(), Edges::Bounded);
+			for _ in 0 .. 6 {
+				world.pad(1, false);
+				world.step(life);
+			}
+			assert_eq!(world.count(|&cell| cell), active);
+		}
+	}
+}
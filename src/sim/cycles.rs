@@ -0,0 +1,135 @@
+//! Periods of systems made of independent parts.
+//!
+//! Some puzzles run a system far too long to simulate, but the system is made
+//! of parts which never affect each other: ghosts walking their own paths
+//! through a map, or the axes of a set of orbiting moons. Each part falls into
+//! a loop much sooner than the whole, and the whole repeats once every part
+//! has completed a whole number of its own loops. [`find_orbits`] finds each
+//! part's loop by stepping it until a state repeats, and combines the loops
+//! with the least common multiple of their lengths.
+//!
+//! The combined length is often larger than `u64` can hold, so it is an
+//! `i128`, and overflow is an error rather than a wrong answer.
+
+use std::{
+	collections::HashMap,
+	hash::Hash,
+};
+
+use crate::checked::CheckedIterator;
+
+/// Where a single part enters its loop, and how long the loop is.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Period {
+	/// How many steps the part takes before its first looping state.
+	pub start:  u64,
+	/// How many steps the loop takes to come back around.
+	pub length: u64,
+}
+
+impl Period {
+	/// Whether the initial state is itself part of the loop.
+	pub fn is_pure(self) -> bool {
+		self.start == 0
+	}
+}
+
+/// The loops of every part of a system, and of the whole.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Orbits {
+	/// Each part's loop, in the order the parts were given.
+	pub periods: Vec<Period>,
+	/// How many steps the whole system takes before every part is looping.
+	pub start:   u64,
+	/// How many steps the whole system takes to come back to a state it has
+	/// been in: the least common multiple of the parts' loop lengths.
+	pub length:  i128,
+}
+
+/// Steps one part from its initial state until some state repeats.
+///
+/// `step` computes the state after a given one. `key` reduces a state to
+/// whatever identifies it; this can be the state itself, or only the fields
+/// which affect future steps.
+pub fn find_period<S, K: Eq + Hash>(
+	initial: S,
+	mut step: impl FnMut(&S) -> S,
+	mut key: impl FnMut(&S) -> K,
+) -> eyre::Result<Period> {
+	let mut seen = HashMap::new();
+	let mut state = initial;
+	for now in 0 .. {
+		if now % 4096 == 0 {
+			crate::cancel::check()?;
+		}
+		if let Some(then) = seen.insert(key(&state), now) {
+			return Ok(Period {
+				start:  then,
+				length: now - then,
+			});
+		}
+		state = step(&state);
+	}
+	unreachable!("the step counter cannot run out")
+}
+
+/// Finds the loop of every part of a system, and of the whole.
+///
+/// Each part is stepped with the same `step` and identified with the same
+/// `key`; see [`find_period`].
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+pub fn find_orbits<S, K: Eq + Hash>(
+	parts: impl IntoIterator<Item = S>,
+	mut step: impl FnMut(&S) -> S,
+	mut key: impl FnMut(&S) -> K,
+) -> eyre::Result<Orbits> {
+	let periods = parts
+		.into_iter()
+		.map(|part| find_period(part, &mut step, &mut key))
+		.collect::<eyre::Result<Vec<_>>>()?;
+	for (idx, period) in periods.iter().enumerate() {
+		tracing::debug!(
+			part = idx,
+			start = period.start,
+			length = period.length
+		);
+	}
+	Ok(Orbits {
+		start: periods.iter().map(|p| p.start).max().unwrap_or_default(),
+		length: periods.iter().map(|p| p.length as i128).checked_lcm()?,
+		periods,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn combines_periods() -> eyre::Result<()> {
+		// Counters modulo 4, 6, and 10, all starting from zero.
+		let orbits = find_orbits(
+			[4, 6, 10].map(|m| (0, m)),
+			|&(n, m)| ((n + 1) % m, m),
+			|&s| s,
+		)?;
+		assert_eq!(
+			orbits.periods.iter().map(|p| p.length).collect::<Vec<_>>(),
+			[4, 6, 10]
+		);
+		assert!(orbits.periods.iter().all(|p| p.is_pure()));
+		assert_eq!((orbits.start, orbits.length), (0, 60));
+
+		// A tail of 3 steps into a loop of 5.
+		let period =
+			find_period(0u32, |&n| if n < 7 { n + 1 } else { 3 }, |&n| n)?;
+		assert_eq!(period, Period {
+			start:  3,
+			length: 5,
+		});
+		Ok(())
+	}
+}
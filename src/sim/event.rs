@@ -0,0 +1,174 @@
+//! A discrete-event scheduler.
+//!
+//! Puzzles about things that happen at particular times (reindeer that fly
+//! and rest, buses that depart on schedules, fighters that take turns) are
+//! usually simplest to express as "at time T, this happens", with each event
+//! free to schedule more events in the future. The [`EventQueue`] keeps those
+//! events in time order, so the simulation can skip straight from one
+//! interesting moment to the next instead of ticking through every unit of
+//! time.
+
+use std::{
+	cmp::{
+		Ordering,
+		Reverse,
+	},
+	collections::BinaryHeap,
+};
+
+/// A queue of events, each scheduled to occur at a timestamp.
+///
+/// Events come out in timestamp order. Events scheduled for the same timestamp
+/// come out in the order they were scheduled. The queue keeps a clock, which
+/// advances to each event's timestamp as it is popped.
+#[derive(Clone, Debug)]
+pub struct EventQueue<T> {
+	events: BinaryHeap<Reverse<Scheduled<T>>>,
+	now:    i64,
+	/// Counts scheduled events, to break ties between simultaneous ones.
+	serial: u64,
+}
+
+impl<T> EventQueue<T> {
+	/// Creates an empty queue with its clock at zero.
+	pub fn new() -> Self {
+		Self::starting_at(0)
+	}
+
+	/// Creates an empty queue with its clock at the given time.
+	pub fn starting_at(now: i64) -> Self {
+		Self {
+			events: BinaryHeap::new(),
+			now,
+			serial: 0,
+		}
+	}
+
+	/// Gets the current time: the timestamp of the most recently popped event,
+	/// or the starting time if none have been popped.
+	pub fn now(&self) -> i64 {
+		self.now
+	}
+
+	/// Counts the events still waiting in the queue.
+	pub fn len(&self) -> usize {
+		self.events.len()
+	}
+
+	/// Tests if no events are waiting.
+	pub fn is_empty(&self) -> bool {
+		self.events.is_empty()
+	}
+
+	/// Schedules an event at an absolute time.
+	///
+	/// Scheduling an event in the past is permitted; it will be popped next,
+	/// and the clock will not run backwards to meet it.
+	pub fn schedule(&mut self, at: i64, event: T) {
+		self.serial += 1;
+		self.events.push(Reverse(Scheduled {
+			at,
+			serial: self.serial,
+			event,
+		}));
+	}
+
+	/// Schedules an event some amount of time after the current time.
+	pub fn schedule_in(&mut self, delay: i64, event: T) {
+		self.schedule(self.now + delay, event);
+	}
+
+	/// Views the next event without removing it.
+	pub fn peek(&self) -> Option<(i64, &T)> {
+		self.events
+			.peek()
+			.map(|Reverse(Scheduled { at, event, .. })| (*at, event))
+	}
+
+	/// Removes the next event, advancing the clock to its time.
+	pub fn pop(&mut self) -> Option<(i64, T)> {
+		let Reverse(Scheduled { at, event, .. }) = self.events.pop()?;
+		self.now = self.now.max(at);
+		Some((at, event))
+	}
+
+	/// Removes the next event only if it occurs no later than `limit`.
+	///
+	/// This makes it easy to run a simulation for a fixed duration:
+	/// `while let Some((at, event)) = queue.pop_until(end) { ... }`.
+	pub fn pop_until(&mut self, limit: i64) -> Option<(i64, T)> {
+		match self.peek() {
+			Some((at, _)) if at <= limit => self.pop(),
+			_ => None,
+		}
+	}
+}
+
+impl<T> Default for EventQueue<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> Extend<(i64, T)> for EventQueue<T> {
+	fn extend<I: IntoIterator<Item = (i64, T)>>(&mut self, iter: I) {
+		for (at, event) in iter {
+			self.schedule(at, event);
+		}
+	}
+}
+
+/// An event and its position in the queue.
+///
+/// Only the timestamp and serial number participate in ordering, so the event
+/// payload does not need to be comparable.
+#[derive(Clone, Debug)]
+struct Scheduled<T> {
+	at:     i64,
+	serial: u64,
+	event:  T,
+}
+
+impl<T> PartialEq for Scheduled<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<T> Eq for Scheduled<T> {
+}
+
+impl<T> PartialOrd for Scheduled<T> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T> Ord for Scheduled<T> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.at, self.serial).cmp(&(other.at, other.serial))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn orders_events() {
+		let mut queue = EventQueue::new();
+		queue.schedule(5, "c");
+		queue.schedule(1, "a");
+		queue.schedule(5, "d");
+		queue.schedule(3, "b");
+		assert_eq!(queue.peek(), Some((1, &"a")));
+		assert_eq!(queue.pop(), Some((1, "a")));
+		assert_eq!(queue.now(), 1);
+		queue.schedule_in(1, "a2");
+		assert_eq!(queue.pop_until(2), Some((2, "a2")));
+		assert_eq!(queue.pop_until(2), None);
+		let rest = std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>();
+		assert_eq!(rest, [(3, "b"), (5, "c"), (5, "d")]);
+		assert_eq!(queue.now(), 5);
+	}
+}
@@ -0,0 +1,185 @@
+//! Command streams which drive a state machine, one line at a time.
+//!
+//! A lot of inputs are a list of commands (shell transcripts, crane moves,
+//! register updates) which each change some state, and the puzzle asks about
+//! the state at the end. Parsing each line into an operation and folding the
+//! operations over the state is the same every time; what changes is the
+//! operation enum and the state. [`LineProgram`] does the folding, and
+//! remembers which line of the input each operation came from, so that an
+//! operation which fails says where it was.
+//!
+//! States implement [`StateMachine`]. Modules which already parse their own
+//! list of operations can hand it to [`LineProgram::from_ops`], and closures
+//! can stand in for a state machine with [`LineProgram::fold`], so a day can
+//! adopt this one piece at a time.
+
+use std::fmt;
+
+use eyre::WrapErr;
+
+use crate::ParseResult;
+
+/// A state which is changed by one kind of operation.
+pub trait StateMachine<Op> {
+	/// Carries out one operation.
+	fn apply(&mut self, op: &Op) -> eyre::Result<()>;
+}
+
+/// A list of operations, each tagged with the input line it came from.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineProgram<Op> {
+	/// The operations in order, with their one-based line numbers.
+	ops: Vec<(usize, Op)>,
+}
+
+impl<Op> LineProgram<Op> {
+	/// Parses every non-blank line of `text` with a line parser.
+	///
+	/// The parser must consume the whole line. Failures name the line.
+	pub fn parse_lines(
+		text: &str,
+		mut parse: impl FnMut(&str) -> ParseResult<&str, Op>,
+	) -> eyre::Result<Self> {
+		let mut ops = Vec::new();
+		for (idx, line) in text.lines().enumerate() {
+			let line = line.trim_end();
+			if line.is_empty() {
+				continue;
+			}
+			let number = idx + 1;
+			let op = match parse(line) {
+				Ok(("", op)) => op,
+				Ok((rest, _)) => {
+					eyre::bail!("line {number}: `{line}` has `{rest}` left over")
+				},
+				Err(err) => eyre::bail!("line {number}: `{line}`: {err}"),
+			};
+			ops.push((number, op));
+		}
+		Ok(Self { ops })
+	}
+
+	/// Numbers a list of operations which was parsed elsewhere, with one line
+	/// per operation counting up from `first_line`.
+	pub fn from_ops(
+		first_line: usize,
+		ops: impl IntoIterator<Item = Op>,
+	) -> Self {
+		Self {
+			ops: (first_line ..).zip(ops).collect(),
+		}
+	}
+
+	/// Views the operations, in order.
+	pub fn ops(&self) -> impl '_ + DoubleEndedIterator<Item = &Op> {
+		self.ops.iter().map(|(_, op)| op)
+	}
+
+	/// Views the operations, in order, with their line numbers.
+	pub fn numbered(
+		&self,
+	) -> impl '_ + DoubleEndedIterator<Item = (usize, &Op)> {
+		self.ops.iter().map(|(line, op)| (*line, op))
+	}
+
+	pub fn len(&self) -> usize {
+		self.ops.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.ops.is_empty()
+	}
+
+	/// Carries out every operation against a state machine, in order.
+	///
+	/// Stops at the first operation which fails, and names its line.
+	pub fn run<S: StateMachine<Op>>(&self, state: &mut S) -> eyre::Result<()>
+	where Op: fmt::Debug {
+		self.fold(state, |state: &mut &mut S, op| state.apply(op))
+			.map(drop)
+	}
+
+	/// Carries out every operation with a closure instead of a
+	/// [`StateMachine`] implementation.
+	pub fn fold<S>(
+		&self,
+		mut state: S,
+		mut apply: impl FnMut(&mut S, &Op) -> eyre::Result<()>,
+	) -> eyre::Result<S>
+	where
+		Op: fmt::Debug,
+	{
+		for (line, op) in &self.ops {
+			crate::cancel::check()?;
+			apply(&mut state, op)
+				.wrap_err_with(|| format!("line {line}: {op:?} failed"))?;
+		}
+		Ok(state)
+	}
+}
+
+impl<Op> Default for LineProgram<Op> {
+	fn default() -> Self {
+		Self { ops: Vec::new() }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use nom::{
+		branch::alt,
+		bytes::complete::tag,
+		character::complete::i64 as get_i64,
+		combinator::map,
+		sequence::preceded,
+	};
+
+	use super::*;
+
+	#[derive(Debug)]
+	enum Op {
+		Add(i64),
+		Halve,
+	}
+
+	fn op(text: &str) -> ParseResult<&str, Op> {
+		alt((
+			map(preceded(tag("add "), get_i64), Op::Add),
+			map(tag("halve"), |_| Op::Halve),
+		))(text)
+	}
+
+	#[derive(Debug, Default)]
+	struct Acc(i64);
+
+	impl StateMachine<Op> for Acc {
+		fn apply(&mut self, op: &Op) -> eyre::Result<()> {
+			match *op {
+				Op::Add(n) => self.0 += n,
+				Op::Halve if self.0 % 2 == 0 => self.0 /= 2,
+				Op::Halve => eyre::bail!("{} is odd", self.0),
+			}
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn runs_and_reports_lines() -> eyre::Result<()> {
+		let program = LineProgram::parse_lines("add 4\n\nhalve\nadd 3\n", op)?;
+		assert_eq!(program.numbered().map(|(n, _)| n).collect::<Vec<_>>(), [
+			1, 3, 4
+		]);
+		let mut acc = Acc::default();
+		program.run(&mut acc)?;
+		assert_eq!(acc.0, 5);
+
+		let err = program.run(&mut acc).unwrap_err();
+		assert_eq!(err.to_string(), "line 3: Halve failed");
+		assert!(LineProgram::parse_lines("add 1\nadd x", op)
+			.unwrap_err()
+			.to_string()
+			.starts_with("line 2: `add x`"));
+		Ok(())
+	}
+}
@@ -0,0 +1,133 @@
+//! Step-by-step recordings of a simulation, for replaying after the fact.
+//!
+//! A simulation that goes wrong usually goes wrong several hundred steps
+//! before anything visibly breaks. Rather than sprinkle trace events through
+//! the stepping logic and squint at the log, a solver can keep a [`Recorder`]
+//! and hand it a rendering of its state after each step. Recorders start out
+//! switched off, and only render anything once the harness enables them (with
+//! `--replay`), so leaving the calls in place costs a branch per step.
+
+use std::{
+	fmt,
+	mem,
+};
+
+/// One recorded step of a simulation.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+	/// A short description of the step, such as its number.
+	pub label: String,
+	/// The simulation's rendered state after the step.
+	pub state: String,
+}
+
+/// Collects [`Frame`]s from a running simulation.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Recorder {
+	frames:  Vec<Frame>,
+	enabled: bool,
+	/// The most frames to keep. Later frames are counted, but discarded.
+	limit:   usize,
+	dropped: usize,
+}
+
+impl Recorder {
+	/// The number of frames kept unless told otherwise.
+	pub const DEFAULT_LIMIT: usize = 10_000;
+
+	/// Creates a recorder which is switched off.
+	pub fn new() -> Self {
+		Self {
+			frames:  Vec::new(),
+			enabled: false,
+			limit:   Self::DEFAULT_LIMIT,
+			dropped: 0,
+		}
+	}
+
+	/// Changes the number of frames the recorder will keep.
+	pub fn with_limit(mut self, limit: usize) -> Self {
+		self.limit = limit;
+		self
+	}
+
+	/// Switches the recorder on.
+	pub fn enable(&mut self) {
+		self.enabled = true;
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+
+	/// Records a step, if the recorder is on.
+	///
+	/// Neither the label nor the state is rendered while the recorder is off,
+	/// so callers should pass something cheap for the label and do the real
+	/// work inside `state`.
+	pub fn record(
+		&mut self,
+		label: impl fmt::Display,
+		state: impl FnOnce() -> String,
+	) {
+		if !self.enabled {
+			return;
+		}
+		if self.frames.len() >= self.limit {
+			self.dropped += 1;
+			return;
+		}
+		self.frames.push(Frame {
+			label: label.to_string(),
+			state: state(),
+		});
+	}
+
+	/// Views the frames recorded so far.
+	pub fn frames(&self) -> &[Frame] {
+		&self.frames
+	}
+
+	/// Counts the frames which were discarded for being over the limit.
+	pub fn dropped(&self) -> usize {
+		self.dropped
+	}
+
+	/// Removes the recorded frames, leaving the recorder on (if it was) and
+	/// empty.
+	pub fn take(&mut self) -> Vec<Frame> {
+		self.dropped = 0;
+		mem::take(&mut self.frames)
+	}
+}
+
+impl Default for Recorder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn records_when_enabled() {
+		let mut recorder = Recorder::new().with_limit(2);
+		recorder.record(0, || unreachable!("rendered while switched off"));
+		assert!(recorder.frames().is_empty());
+
+		recorder.enable();
+		for step in 1 ..= 3 {
+			recorder.record(step, || "#".repeat(step));
+		}
+		assert_eq!(recorder.frames().len(), 2);
+		assert_eq!(recorder.dropped(), 1);
+		let frames = recorder.take();
+		assert_eq!(frames[1].label, "2");
+		assert_eq!(frames[1].state, "##");
+		assert!(recorder.frames().is_empty() && recorder.is_enabled());
+	}
+}
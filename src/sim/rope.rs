@@ -0,0 +1,168 @@
+//! A chain of knots dragged around a grid by its head.
+//!
+//! Each knot follows the one ahead of it: whenever the two stop touching
+//! (including diagonally), the follower takes one step, diagonal if needed,
+//! directly toward its leader. This first appeared in 2022 day 9.
+
+use std::{
+	collections::BTreeSet,
+	ops::Neg,
+};
+
+use funty::Signed;
+
+use crate::coords::{
+	points::Direction2D,
+	Cartesian2DPoint,
+};
+
+/// A rope made of some number of knots, all of which start at the origin.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rope<I: Signed> {
+	/// The current position of each knot. The head is at index 0.
+	knots:   Vec<Cartesian2DPoint<I>>,
+	/// For each knot, every position it has occupied, if it is being tracked.
+	visits:  Vec<Option<BTreeSet<Cartesian2DPoint<I>>>>,
+	/// Snapshots of every knot after every step, if history is being kept.
+	history: Option<Vec<Vec<Cartesian2DPoint<I>>>>,
+}
+
+impl<I: Signed> Rope<I> {
+	/// Creates a rope with the given number of knots, all at the origin.
+	///
+	/// ## Panics
+	///
+	/// A rope must have at least one knot.
+	pub fn new(len: usize) -> Self {
+		assert!(len > 0, "a rope must have at least one knot");
+		Self {
+			knots:   vec![Cartesian2DPoint::ZERO; len],
+			visits:  vec![None; len],
+			history: None,
+		}
+	}
+
+	/// Starts recording every position that a knot occupies, beginning with
+	/// its current one.
+	///
+	/// Knot 0 is the head; `len - 1` is the tail.
+	pub fn track_visits(mut self, knot: usize) -> Self {
+		if let Some(slot) = self.visits.get_mut(knot) {
+			*slot = Some(BTreeSet::from([self.knots[knot]]));
+		}
+		self
+	}
+
+	/// Starts recording the position of every knot after every step,
+	/// beginning with the current positions.
+	pub fn keep_history(mut self) -> Self {
+		self.history = Some(vec![self.knots.clone()]);
+		self
+	}
+
+	/// Views the current positions of all knots, head first.
+	pub fn knots(&self) -> &[Cartesian2DPoint<I>] {
+		&self.knots
+	}
+
+	/// Gets the current position of the head knot.
+	pub fn head(&self) -> Cartesian2DPoint<I> {
+		self.knots[0]
+	}
+
+	/// Gets the current position of the tail knot.
+	pub fn tail(&self) -> Cartesian2DPoint<I> {
+		self.knots[self.knots.len() - 1]
+	}
+
+	/// Views every position a knot has occupied, if it is being tracked.
+	pub fn visits(&self, knot: usize) -> Option<&BTreeSet<Cartesian2DPoint<I>>> {
+		self.visits.get(knot)?.as_ref()
+	}
+
+	/// Views the recorded positions of every knot after every step, if
+	/// history is being kept. Index 0 is the state before any steps.
+	pub fn history(&self) -> Option<&[Vec<Cartesian2DPoint<I>>]> {
+		self.history.as_deref()
+	}
+
+	/// Gets where a knot was after a given number of steps, if history is
+	/// being kept.
+	pub fn position_at(
+		&self,
+		step: usize,
+		knot: usize,
+	) -> Option<Cartesian2DPoint<I>> {
+		self.history.as_ref()?.get(step)?.get(knot).copied()
+	}
+}
+
+impl<I: Signed + Neg<Output = I>> Rope<I> {
+	/// Drags the head some number of steps in a direction, letting the rest of
+	/// the rope follow after each step.
+	pub fn pull(&mut self, direction: Direction2D, distance: usize) {
+		for step in direction.steps(distance) {
+			self.step(step);
+		}
+	}
+
+	/// Moves the head one step in a direction, and lets the rest of the rope
+	/// follow.
+	pub fn step(&mut self, direction: Direction2D) {
+		self.knots[0] += direction.unit();
+		if let Some(Some(seen)) = self.visits.first_mut() {
+			seen.insert(self.knots[0]);
+		}
+		for idx in 1 .. self.knots.len() {
+			let lead = self.knots[idx - 1];
+			let knot = &mut self.knots[idx];
+			let Cartesian2DPoint { x: dx, y: dy } = lead - *knot;
+			if dx.abs() <= I::ONE && dy.abs() <= I::ONE {
+				// Once a knot doesn't move, none behind it will either.
+				break;
+			}
+			*knot += Cartesian2DPoint::new(dx.signum(), dy.signum());
+			if let Some(seen) = &mut self.visits[idx] {
+				seen.insert(*knot);
+			}
+		}
+		if let Some(history) = &mut self.history {
+			history.push(self.knots.clone());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::coords::points::Direction2D::*;
+
+	#[test]
+	fn follows_the_head() {
+		let moves = [
+			(East, 5),
+			(North, 8),
+			(West, 8),
+			(South, 3),
+			(East, 17),
+			(South, 10),
+			(West, 25),
+			(North, 20),
+		];
+		let mut rope = Rope::<i32>::new(10).track_visits(9).keep_history();
+		for (dir, dist) in moves {
+			rope.pull(dir, dist);
+		}
+		assert_eq!(rope.visits(9).map(BTreeSet::len), Some(36));
+		assert!(rope.visits(1).is_none());
+
+		let steps = moves.iter().map(|&(_, dist)| dist).sum::<usize>();
+		assert_eq!(rope.history().map(<[_]>::len), Some(steps + 1));
+		// After the first five steps east, the head is five ahead and the knot
+		// behind it is four.
+		assert_eq!(rope.position_at(5, 0), Some(Cartesian2DPoint::new(5, 0)));
+		assert_eq!(rope.position_at(5, 1), Some(Cartesian2DPoint::new(4, 0)));
+		assert_eq!(rope.position_at(5, 5), Some(Cartesian2DPoint::new(0, 0)));
+	}
+}
@@ -0,0 +1,158 @@
+//! Stepping a simulation until it is done, or has gone on too long.
+//!
+//! A guard walks until it leaves the map, a probe flies until it passes its
+//! target, an expedition wades through blizzards until it reaches the exit.
+//! Each of these is the same loop: check whether the state is finished, step
+//! it if not, and give up after some number of steps, because a state that
+//! never finishes (a guard walking in circles, a probe that overshoots) would
+//! otherwise run forever. [`simulate_until`] writes that loop once, checks for
+//! cancellation while it runs, and reports whether the state finished or ran
+//! out of steps. [`simulate_traced`] also keeps the last few states, for
+//! working out how a simulation went wrong.
+
+use std::collections::VecDeque;
+
+use eyre::WrapErr as _;
+
+/// How often, in steps, a simulation checks whether it has been cancelled.
+const CANCEL_INTERVAL: u64 = 4096;
+
+/// The end of a simulation.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Run<S> {
+	/// The state the simulation stopped in.
+	pub state:    S,
+	/// How many steps were taken.
+	pub steps:    u64,
+	/// Whether the stopping condition was met, rather than the simulation
+	/// running out of steps.
+	pub finished: bool,
+	/// The states before `state`, oldest first. This is empty unless the
+	/// simulation was [traced](simulate_traced).
+	pub trace:    VecDeque<S>,
+}
+
+impl<S> Run<S> {
+	/// Gets the final state, if the stopping condition was met.
+	pub fn into_finished(self) -> Option<S> {
+		self.finished.then_some(self.state)
+	}
+}
+
+/// Steps `state` until `stop` accepts it, or until `max_steps` steps have
+/// been taken.
+///
+/// `stop` is checked before every step, including the first, so a state
+/// which is already finished is returned as it is, after zero steps. Running
+/// out of steps is not an error; it is reported in [`Run::finished`], for the
+/// caller to decide what it means. An error from `step` ends the simulation,
+/// and is returned with the number of the step that failed.
+pub fn simulate_until<S>(
+	state: S,
+	step: impl FnMut(&mut S) -> eyre::Result<()>,
+	stop: impl FnMut(&S) -> bool,
+	max_steps: u64,
+) -> eyre::Result<Run<S>> {
+	simulate(state, step, stop, max_steps, |_| {})
+}
+
+/// Runs [`simulate_until`], keeping the last `keep` states before the final
+/// one.
+///
+/// Every step clones the state into the trace, so this is best kept for
+/// debugging, or for states that are cheap to copy.
+pub fn simulate_traced<S: Clone>(
+	state: S,
+	step: impl FnMut(&mut S) -> eyre::Result<()>,
+	stop: impl FnMut(&S) -> bool,
+	max_steps: u64,
+	keep: usize,
+) -> eyre::Result<Run<S>> {
+	let mut trace = VecDeque::with_capacity(keep);
+	let mut run = simulate(state, step, stop, max_steps, |state: &S| {
+		if keep == 0 {
+			return;
+		}
+		if trace.len() == keep {
+			trace.pop_front();
+		}
+		trace.push_back(state.clone());
+	})?;
+	run.trace = trace;
+	Ok(run)
+}
+
+fn simulate<S>(
+	mut state: S,
+	mut step: impl FnMut(&mut S) -> eyre::Result<()>,
+	mut stop: impl FnMut(&S) -> bool,
+	max_steps: u64,
+	mut before_step: impl FnMut(&S),
+) -> eyre::Result<Run<S>> {
+	let mut steps = 0;
+	let finished = loop {
+		if stop(&state) {
+			break true;
+		}
+		if steps == max_steps {
+			break false;
+		}
+		if steps % CANCEL_INTERVAL == 0 {
+			crate::cancel::check()?;
+		}
+		before_step(&state);
+		step(&mut state).wrap_err_with(|| {
+			format!("simulation failed on step {}", steps + 1)
+		})?;
+		steps += 1;
+	};
+	if !finished {
+		tracing::debug!(steps, "simulation ran out of steps");
+	}
+	Ok(Run {
+		state,
+		steps,
+		finished,
+		trace: VecDeque::new(),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A probe's position and velocity, as in 2021's trick shot.
+	type Probe = ((i32, i32), (i32, i32));
+
+	fn fly(((x, y), (dx, dy)): &mut Probe) -> eyre::Result<()> {
+		(*x, *y) = (*x + *dx, *y + *dy);
+		*dx -= dx.signum();
+		*dy -= 1;
+		Ok(())
+	}
+
+	#[test]
+	fn stops_or_runs_out() -> eyre::Result<()> {
+		let below = |&((_, y), _): &Probe| y < -10;
+		let run = simulate_until(((0, 0), (6, 3)), fly, below, 100)?;
+		assert!(run.finished);
+		assert_eq!(run.steps, 10);
+		assert_eq!(run.state.0, (21, -15));
+		assert!(run.trace.is_empty());
+
+		let run = simulate_traced(((0, 0), (6, 3)), fly, below, 5, 2)?;
+		assert!(!run.finished);
+		assert_eq!(run.steps, 5);
+		let trace = run.trace.iter().map(|&(at, _)| at).collect::<Vec<_>>();
+		assert_eq!(trace, [(15, 6), (18, 6)]);
+		assert_eq!(run.into_finished(), None);
+
+		let done = simulate_until(5, |_| eyre::bail!("stepped"), |_| true, 1)?;
+		assert_eq!((done.steps, done.into_finished()), (0, Some(5)));
+		let err = simulate_until(0, |_| eyre::bail!("stuck"), |_| false, 10)
+			.unwrap_err();
+		assert_eq!(err.to_string(), "simulation failed on step 1");
+		Ok(())
+	}
+}
@@ -0,0 +1,130 @@
+//! Golden-file tests for rendered puzzle state.
+//!
+//! Simulations tend to go wrong visibly before they go wrong numerically: a
+//! grid that fills in the wrong corner still has roughly the right number of
+//! cells. These helpers run a solver through its phases, capture
+//! [`Puzzle::render_state`] after each one, and compare the captures against
+//! files checked in under `tests/snapshots/{year}/d{day}/`.
+//!
+//! When a rendering changes on purpose, run the tests again with
+//! `AOC_UPDATE_SNAPSHOTS=1` set to rewrite the files, and review the diff
+//! before committing it.
+
+use std::{
+	env,
+	fmt::Write as _,
+	fs,
+	path::PathBuf,
+};
+
+use crate::Puzzle;
+
+/// Set this environment variable to rewrite snapshots rather than check them.
+pub const UPDATE_VAR: &str = "AOC_UPDATE_SNAPSHOTS";
+
+/// Computes the directory which holds a day's snapshots.
+pub fn snapshot_dir(year: u16, day: u8) -> PathBuf {
+	let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+	path.push("tests");
+	path.push("snapshots");
+	path.push(year.to_string());
+	path.push(format!("d{day:0>2}"));
+	path
+}
+
+/// Runs a freshly parsed solver through every phase, capturing its rendering
+/// after each one.
+///
+/// Phases after which the solver has nothing to render are left out. Part
+/// answers are recorded in the capture too, so that a snapshot shows both what
+/// the state looked like and what it produced.
+pub fn capture_phases(
+	solver: &mut dyn Puzzle,
+) -> eyre::Result<Vec<(&'static str, String)>> {
+	let mut captures = Vec::new();
+	let mut capture = |phase, solver: &dyn Puzzle, answer: Option<String>| {
+		let Some(mut text) = solver.render_state()
+		else {
+			return;
+		};
+		if let Some(answer) = answer {
+			if !text.ends_with('\n') {
+				text.push('\n');
+			}
+			let _ = writeln!(text, "answer: {answer}");
+		}
+		captures.push((phase, text));
+	};
+	solver.after_parse()?;
+	capture("after_parse", solver, None);
+	solver.prepare_common()?;
+	solver.prepare_1()?;
+	capture("prepare_1", solver, None);
+	let answer = solver.answer_1()?;
+	capture("part_1", solver, Some(answer.to_string()));
+	if !solver.has_part_2() {
+		return Ok(captures);
+	}
+	solver.prepare_2()?;
+	capture("prepare_2", solver, None);
+	let answer = solver.answer_2()?;
+	capture("part_2", solver, Some(answer.to_string()));
+	Ok(captures)
+}
+
+/// Compares a rendering against its checked-in snapshot.
+///
+/// ## Panics
+///
+/// This panics, showing the first line that differs, if the snapshot does not
+/// match or does not exist, unless [`UPDATE_VAR`] is set, in which case the
+/// snapshot is overwritten instead.
+pub fn assert_snapshot(year: u16, day: u8, name: &str, actual: &str) {
+	let path = snapshot_dir(year, day).join(format!("{name}.snap"));
+	if env::var_os(UPDATE_VAR).is_some() {
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent).expect("could not create snapshot dir");
+		}
+		fs::write(&path, actual).expect("could not write snapshot");
+		return;
+	}
+	let Ok(expected) = fs::read_to_string(&path)
+	else {
+		panic!(
+			"no snapshot at {}; run with {UPDATE_VAR}=1 to create it",
+			path.display()
+		);
+	};
+	if expected == actual {
+		return;
+	}
+	let (line, want, got) = expected
+		.lines()
+		.map(Some)
+		.chain(std::iter::repeat(None))
+		.zip(actual.lines().map(Some).chain(std::iter::repeat(None)))
+		.enumerate()
+		.find(|(_, (want, got))| want != got)
+		.map(|(idx, (want, got))| (idx + 1, want, got))
+		.unwrap_or((0, None, None));
+	panic!(
+		"snapshot {} differs at line {line}:\n  expected: {}\n  actual:   \
+		 {}\nrun with {UPDATE_VAR}=1 to accept the new rendering",
+		path.display(),
+		want.unwrap_or("<end of snapshot>"),
+		got.unwrap_or("<end of rendering>"),
+	);
+}
+
+/// Runs a solver through every phase and checks each capture against its
+/// snapshot, named for the phase.
+pub fn assert_phases(
+	year: u16,
+	day: u8,
+	solver: &mut dyn Puzzle,
+) -> eyre::Result<()> {
+	for (phase, text) in capture_phases(solver)? {
+		assert_snapshot(year, day, phase, &text);
+	}
+	Ok(())
+}
@@ -0,0 +1,84 @@
+//! Entry point for the per-day crates under `years/`.
+//!
+//! Some days keep a crate of their own so that `cargo run` works from inside
+//! the day's folder. Those crates should not carry their own copy of the
+//! solution; instead their `main.rs` is a single [`standalone_main!`] call,
+//! which looks the day up in [`SOLVERS`](crate::SOLVERS) and runs it exactly
+//! as the harness would. A template for such a crate lives in
+//! `years/template/`.
+
+use std::{
+	env,
+	path::{
+		Path,
+		PathBuf,
+	},
+};
+
+use eyre::WrapErr as _;
+
+use crate::try_solutions;
+
+/// The error type returned from a standalone `main`.
+pub type Error = eyre::Report;
+
+/// Generates the `main` function of a per-day crate.
+///
+/// The crate must depend on this library by path, so that the day's solver is
+/// linked in. The generated `main` solves both parts against the `input` data
+/// group, and prints the answers.
+///
+/// ```rust,ignore
+/// wyz_aoc::standalone_main!(2015, 6);
+/// ```
+#[macro_export]
+macro_rules! standalone_main {
+	($year:literal, $day:literal) => {
+		fn main() -> ::core::result::Result<(), $crate::standalone::Error> {
+			$crate::standalone::run($year, $day, env!("CARGO_MANIFEST_DIR"))
+		}
+	};
+}
+
+/// Runs a registered day on behalf of a per-day crate.
+///
+/// Input files stay where the harness keeps them, in `src/y{year}/d{day}/`, so
+/// this walks up from the calling crate's manifest directory until it finds
+/// that folder, and runs from there.
+pub fn run(year: u16, day: u8, manifest_dir: &str) -> eyre::Result<()> {
+	let solver = try_solutions()?
+		.get(&year)
+		.and_then(|days| days.get(&day))
+		.copied()
+		.ok_or_else(|| eyre::eyre!("{year}-{day:0>2} is not registered"))?;
+	let root = project_root(year, day, manifest_dir.as_ref())?;
+	env::set_current_dir(&root).wrap_err_with(|| {
+		format!("could not enter the project root at {}", root.display())
+	})?;
+	let (one, two) = solver.solve("input", true, true)?;
+	if let Some(answer) = one {
+		println!("part 1: {answer}");
+	}
+	if let Some(answer) = two {
+		println!("part 2: {answer}");
+	}
+	Ok(())
+}
+
+/// Finds the nearest ancestor of `start` which holds a day's input folder.
+fn project_root(year: u16, day: u8, start: &Path) -> eyre::Result<PathBuf> {
+	let day_dir = Path::new("src")
+		.join(format!("y{year}"))
+		.join(format!("d{day:0>2}"));
+	start
+		.ancestors()
+		.find(|dir| dir.join(&day_dir).is_dir())
+		.map(Path::to_path_buf)
+		.ok_or_else(|| {
+			eyre::eyre!(
+				"no ancestor of {} contains {}",
+				start.display(),
+				day_dir.display()
+			)
+		})
+}
@@ -0,0 +1,308 @@
+//! Counting and comparing the pieces of puzzle text.
+//!
+//! Plenty of puzzles hide their answer in how often things occur: the most
+//! common letter in each column of a garbled message, the five most common
+//! letters of a room name, the first run of characters with no repeats. These
+//! functions do the counting once, over any iterator, so that solvers can say
+//! what they want rather than how to keep score.
+//!
+//! Others ask how alike two strings are: the pair of box IDs which differ in
+//! one place, the fewest edits between two molecules, what two sequences have
+//! in common. The comparisons here work on slices of any item, so text can be
+//! compared by bytes (`s.as_bytes()`) or by `char`s collected into a `Vec`.
+
+use std::{
+	collections::{
+		BTreeMap,
+		HashMap,
+	},
+	hash::Hash,
+	iter,
+};
+
+/// Counts how many times each character appears in some text.
+pub fn char_histogram(text: &str) -> BTreeMap<char, usize> {
+	histogram(text.chars())
+}
+
+/// Counts how many times each item appears in a sequence.
+pub fn histogram<T: Ord>(
+	items: impl IntoIterator<Item = T>,
+) -> BTreeMap<T, usize> {
+	let mut counts = BTreeMap::new();
+	for item in items {
+		*counts.entry(item).or_default() += 1;
+	}
+	counts
+}
+
+/// Finds the `k` most common items in a sequence, with their counts.
+///
+/// Items are ordered from most to least common, and items which are equally
+/// common are ordered by their own value.
+pub fn most_common<T: Ord>(
+	items: impl IntoIterator<Item = T>,
+	k: usize,
+) -> Vec<(T, usize)> {
+	let mut counts = histogram(items).into_iter().collect::<Vec<_>>();
+	// The sort is stable, so ties stay in the histogram's order.
+	counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+	counts.truncate(k);
+	counts
+}
+
+/// Finds the `k` least common items in a sequence, with their counts.
+///
+/// Items are ordered from least to most common, and items which are equally
+/// common are ordered by their own value. Items which do not appear at all
+/// are, of course, not counted.
+pub fn least_common<T: Ord>(
+	items: impl IntoIterator<Item = T>,
+	k: usize,
+) -> Vec<(T, usize)> {
+	let mut counts = histogram(items).into_iter().collect::<Vec<_>>();
+	counts.sort_by_key(|&(_, count)| count);
+	counts.truncate(k);
+	counts
+}
+
+/// Finds every window of `len` items in which no item repeats, yielding the
+/// index at which each window starts.
+///
+/// This keeps a running count of the items in the window, so each step costs
+/// the same no matter how long the window is.
+pub fn windows_distinct<T: Eq + Hash>(
+	items: &[T],
+	len: usize,
+) -> DistinctWindows<'_, T> {
+	DistinctWindows {
+		items,
+		len,
+		start: 0,
+		counts: HashMap::new(),
+		repeats: 0,
+	}
+}
+
+/// An iterator over the start of each window without repeated items.
+///
+/// See [`windows_distinct`].
+#[derive(Clone, Debug)]
+pub struct DistinctWindows<'a, T: Eq + Hash> {
+	items:   &'a [T],
+	len:     usize,
+	/// The start of the next window to consider.
+	start:   usize,
+	/// How many of each item are in the window last considered.
+	counts:  HashMap<&'a T, usize>,
+	/// How many extra copies of items are in the window last considered.
+	repeats: usize,
+}
+
+impl<'a, T: Eq + Hash> DistinctWindows<'a, T> {
+	fn add(&mut self, item: &'a T) {
+		let count = self.counts.entry(item).or_default();
+		*count += 1;
+		if *count > 1 {
+			self.repeats += 1;
+		}
+	}
+
+	fn remove(&mut self, item: &'a T) {
+		if let Some(count) = self.counts.get_mut(item) {
+			*count -= 1;
+			if *count > 0 {
+				self.repeats -= 1;
+			}
+		}
+	}
+}
+
+impl<'a, T: Eq + Hash> Iterator for DistinctWindows<'a, T> {
+	type Item = usize;
+
+	fn next(&mut self) -> Option<usize> {
+		if self.len == 0 {
+			return None;
+		}
+		while self.start + self.len <= self.items.len() {
+			let start = self.start;
+			if start == 0 {
+				for item in &self.items[.. self.len - 1] {
+					self.add(item);
+				}
+			}
+			else {
+				self.remove(&self.items[start - 1]);
+			}
+			self.add(&self.items[start + self.len - 1]);
+			self.start += 1;
+			if self.repeats == 0 {
+				return Some(start);
+			}
+		}
+		None
+	}
+}
+
+/// Counts the fewest single-item insertions, deletions, and substitutions
+/// which turn `a` into `b`: their Levenshtein distance.
+///
+/// This keeps only one row of the edit table, so it needs `O(b.len())` space.
+pub fn levenshtein<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+	// `row[j]` is the distance between the prefix of `a` handled so far and
+	// `b[.. j]`.
+	let mut row = (0 ..= b.len()).collect::<Vec<_>>();
+	for (i, x) in a.iter().enumerate() {
+		let mut diagonal = row[0];
+		row[0] = i + 1;
+		for (j, y) in b.iter().enumerate() {
+			let substitute = diagonal + usize::from(x != y);
+			diagonal = row[j + 1];
+			row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+		}
+	}
+	row[b.len()]
+}
+
+/// Finds a longest sequence of items which appears, in order but not
+/// necessarily contiguously, in both `a` and `b`.
+///
+/// When there are several, this picks the one which takes items from `a` as
+/// early as possible.
+pub fn longest_common_subsequence<T: PartialEq + Clone>(
+	a: &[T],
+	b: &[T],
+) -> Vec<T> {
+	// `lens[i][j]` is the length of the longest common subsequence of
+	// `a[i ..]` and `b[j ..]`.
+	let mut lens = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+	for i in (0 .. a.len()).rev() {
+		for j in (0 .. b.len()).rev() {
+			lens[i][j] = if a[i] == b[j] {
+				lens[i + 1][j + 1] + 1
+			}
+			else {
+				lens[i + 1][j].max(lens[i][j + 1])
+			};
+		}
+	}
+	let (mut i, mut j) = (0, 0);
+	let mut out = Vec::with_capacity(lens[0][0]);
+	while i < a.len() && j < b.len() {
+		if a[i] == b[j] {
+			out.push(a[i].clone());
+			i += 1;
+			j += 1;
+		}
+		else if lens[i + 1][j] >= lens[i][j + 1] {
+			i += 1;
+		}
+		else {
+			j += 1;
+		}
+	}
+	out
+}
+
+/// Checks whether two equally long sequences differ at exactly one index,
+/// and if so, returns that index.
+pub fn differs_by_one<T: PartialEq>(a: &[T], b: &[T]) -> Option<usize> {
+	if a.len() != b.len() {
+		return None;
+	}
+	let mut diffs = iter::zip(a, b)
+		.enumerate()
+		.filter(|(_, (x, y))| x != y)
+		.map(|(idx, _)| idx);
+	let idx = diffs.next()?;
+	diffs.next().is_none().then_some(idx)
+}
+
+/// Finds two sequences in a list which differ at exactly one index.
+///
+/// Returns the indices of the two sequences in the list, and the index at
+/// which they differ. Rather than comparing every pair, this blanks out each
+/// index in turn and looks for two sequences which then match, which takes
+/// `O(n × len²)` time for `n` sequences of length `len`.
+pub fn find_one_apart<T: Eq + Hash, S: AsRef<[T]>>(
+	items: &[S],
+) -> Option<(usize, usize, usize)> {
+	let width = items.iter().map(|s| s.as_ref().len()).max()?;
+	for col in 0 .. width {
+		let mut seen = HashMap::<_, usize>::new();
+		for (idx, item) in items.iter().enumerate() {
+			let item = item.as_ref();
+			if item.len() <= col {
+				continue;
+			}
+			// Sequences of different lengths never collide, since the halves
+			// around the blank would have to differ in length.
+			let key = (&item[.. col], &item[col + 1 ..]);
+			match seen.get(&key) {
+				// Equal sequences match with any index blanked; they differ
+				// nowhere, so they do not count.
+				Some(&prev) if items[prev].as_ref()[col] != item[col] => {
+					return Some((prev, idx, col));
+				},
+				Some(_) => {},
+				None => {
+					seen.insert(key, idx);
+				},
+			}
+		}
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn counts_and_windows() {
+		let counts = char_histogram("aaaaa-bbb-z-y-x");
+		assert_eq!(counts[&'a'], 5);
+		assert_eq!(counts[&'-'], 4);
+		assert_eq!(most_common("aaaaabbbzyx".chars(), 5), [
+			('a', 5),
+			('b', 3),
+			('x', 1),
+			('y', 1),
+			('z', 1)
+		]);
+		assert_eq!(least_common("aabbbc".chars(), 2), [('c', 1), ('a', 2)]);
+
+		let text = "mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes();
+		assert_eq!(windows_distinct(text, 4).next(), Some(3));
+		assert_eq!(windows_distinct(text, 14).next(), Some(5));
+
+		let naive = text
+			.windows(4)
+			.enumerate()
+			.filter(|(_, w)| histogram(w.iter()).len() == 4)
+			.map(|(idx, _)| idx)
+			.collect::<Vec<_>>();
+		assert_eq!(windows_distinct(text, 4).collect::<Vec<_>>(), naive);
+		assert_eq!(windows_distinct(text, 40).next(), None);
+	}
+
+	#[test]
+	fn distances() {
+		assert_eq!(levenshtein(b"kitten", b"sitting"), 3);
+		assert_eq!(levenshtein(b"", b"abc"), 3);
+		assert_eq!(levenshtein(b"flaw", b"flaw"), 0);
+
+		assert_eq!(longest_common_subsequence(b"ABCBDAB", b"BDCABA").len(), 4);
+		assert_eq!(longest_common_subsequence(b"XMJYAUZ", b"MZJAWXU"), b"MJAU");
+
+		assert_eq!(differs_by_one(b"fghij", b"fguij"), Some(2));
+		assert_eq!(differs_by_one(b"abcde", b"axcye"), None);
+		assert_eq!(differs_by_one(b"abc", b"abc"), None);
+
+		let ids = ["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye"];
+		let ids = ids.map(str::as_bytes);
+		assert_eq!(find_one_apart(&ids), Some((1, 4, 2)));
+		assert_eq!(find_one_apart(&[b"aa", b"aa"]), None);
+	}
+}
@@ -0,0 +1,53 @@
+//! Stand-ins for the `tracing` macros, for builds without the `trace` feature.
+//!
+//! The crate logs through `tracing::info!` and friends everywhere. Rather than
+//! wrap every call site in a `cfg`, builds without `trace` name this crate
+//! `tracing` (see the `extern crate self` in `lib.rs`, which also imports
+//! these macros into the crate root), so that the same paths reach these
+//! macros instead. Events expand to `()` and spans to an inert [`Span`]; none
+//! of their arguments are evaluated, so logging costs nothing. Values which are
+//! computed only to be logged go unused, so the functions which compute them
+//! allow `unused_variables` when `trace` is off.
+//!
+//! Only the macros which the library uses are provided. `#[instrument]`
+//! attributes are written as `#[cfg_attr(feature = "trace", ...)]` instead.
+
+/// A span which records nothing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Span;
+
+/// The guard returned by [`Span::enter`].
+#[derive(Debug)]
+pub struct Entered;
+
+impl Span {
+	pub fn enter(&self) -> Entered {
+		Entered
+	}
+
+	pub fn in_scope<T>(&self, func: impl FnOnce() -> T) -> T {
+		func()
+	}
+}
+
+/// Stands in for `tracing::info!` and the other event macros.
+macro_rules! event {
+	($($args:tt)*) => {
+		()
+	};
+}
+
+/// Stands in for `tracing::error_span!` and the other span macros.
+macro_rules! span {
+	($($args:tt)*) => {
+		$crate::tracing_shim::Span
+	};
+}
+
+pub(crate) use event as trace;
+pub(crate) use event as debug;
+pub(crate) use event as info;
+pub(crate) use event as warn;
+pub(crate) use event as error;
+pub(crate) use span as debug_span;
+pub(crate) use span as error_span;
@@ -119,6 +119,7 @@ impl Web {
 	///
 	/// On my machine, routing a 1500-node graph with an average connectivity
 	/// factor of ~3-4 takes about 5 minutes.
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	pub fn find_all_routes(&mut self) {
 		self.clear_routes();
 		let parallelism = std::thread::available_parallelism()
@@ -172,6 +173,7 @@ impl Web {
 	/// that it crossed, and then only commit the best-path with the lightest
 	/// traffic score, but this is an AoC library, not an IP routing library. So
 	/// I am not doing that.
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	pub fn find_route(
 		&self,
 		src: Identifier,
@@ -292,6 +294,7 @@ impl Web {
 	/// Deletes a one-directional link between two nodes, and informs all nodes
 	/// which routed through `src -> dst` that `dst` (and any further nodes on
 	/// that path) are no longer reachable through `src`.
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn remove_link_one_way(&mut self, src: Identifier, dst: Identifier) {
 		let src_name = self.get_name(src);
 		let dst_name = self.get_name(dst);
@@ -473,7 +476,7 @@ impl<'a> Node<'a> {
 }
 
 impl fmt::Debug for Node<'_> {
-	#[tracing::instrument(skip(self, fmt))]
+	#[cfg_attr(feature = "trace", tracing::instrument(skip(self, fmt)))]
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		// let this = self.web.get_name(self.name);
 		fmt.debug_list()
@@ -569,7 +572,7 @@ impl<'a> Spider<'a> {
 	/// This generational process, combined with a FIFO queue acting as a
 	/// scheduler, ensures that the first spider to reach the goal has the best
 	/// possible path, at the cost of some additional memory pressure.
-	#[tracing::instrument(skip(self, spawner, crawled), fields(node=%self.node.web.get_name(self.node.id), goal=%self.node.web.get_name(self.goal)))]
+	#[cfg_attr(feature = "trace", tracing::instrument(skip(self, spawner, crawled), fields(node=%self.node.web.get_name(self.node.id), goal=%self.node.web.get_name(self.goal))))]
 	pub fn crawl_seq(
 		mut self,
 		spawner: &mut VecDeque<Self>,
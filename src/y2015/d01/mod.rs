@@ -13,9 +13,9 @@ use nom::{
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2015, 1, |t| t.parse_dyn_puzzle::<Elevator>());
+crate::register_solver!(y2015, d01, Elevator);
 
+#[derive(Clone, Debug)]
 pub struct Elevator {
 	sequence: String,
 }
@@ -32,6 +32,7 @@ impl<'a> Parsed<&'a str> for Elevator {
 }
 
 impl Puzzle for Elevator {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn part_1(&mut self) -> eyre::Result<i64> {
 		self.sequence
 			.chars()
@@ -47,6 +48,7 @@ impl Puzzle for Elevator {
 			.ok_or_else(|| eyre::eyre!("no characters in input"))
 	}
 
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn part_2(&mut self) -> eyre::Result<i64> {
 		let mut current = 0;
 		for (c, pos) in self.sequence.chars().zip(1 ..) {
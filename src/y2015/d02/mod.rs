@@ -11,9 +11,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2015, 2, |t| t.parse_dyn_puzzle::<Dimensions>());
+crate::register_solver!(y2015, d02, Dimensions);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
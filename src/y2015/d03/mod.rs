@@ -14,8 +14,7 @@ use crate::{
 	Grid2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2015, 3, |t| t.parse_dyn_puzzle::<Map>());
+crate::register_solver!(y2015, d03, Map);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Map {
@@ -2,8 +2,7 @@ use std::fmt::Write as _;
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2015, 4, |t| t.parse_dyn_puzzle::<Miner>());
+crate::register_solver!(y2015, d04, Miner);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
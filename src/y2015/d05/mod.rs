@@ -4,9 +4,7 @@ use tap::Pipe;
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2015, 5, |t| t.parse_dyn_puzzle::<NaughtyList>());
+crate::register_solver!(y2015, d05, NaughtyList);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -27,9 +27,7 @@ use tap::Pipe;
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2015, 6, |t| t.parse_dyn_puzzle::<LightGrid>());
+crate::register_solver!(y2015, d06, LightGrid);
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct LightGrid {
@@ -123,7 +121,7 @@ pub enum Grid {
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-struct Instruction {
+pub struct Instruction {
 	cols: RangeInclusive<usize>,
 	rows: RangeInclusive<usize>,
 	insn: Opcode,
@@ -243,7 +241,7 @@ impl<'a> Parsed<&'a str> for Instruction {
 
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-enum Opcode {
+pub enum Opcode {
 	On,
 	Off,
 	Toggle,
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{
+		alpha1,
+		newline,
+	},
+	combinator::{
+		map,
+		value,
+	},
+	multi::separated_list1,
+	sequence::{
+		preceded,
+		separated_pair,
+		tuple,
+	},
+};
+
+use crate::{
+	parse_number,
+	prelude::*,
+	propagate::{
+		self,
+		Expression,
+	},
+};
+
+crate::register_solver!(y2015, d07, Circuit);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Circuit {
+	/// What drives each wire, by name.
+	wires:    BTreeMap<String, Gate>,
+	/// The wire whose signal is the answer.
+	probe:    String,
+	/// The wire which part 2 overrides with part 1's answer.
+	feedback: String,
+}
+
+impl Circuit {
+	/// Measures the probed wire, after overriding some wires' signals.
+	fn measure(&self, overrides: &[(&str, u16)]) -> eyre::Result<u16> {
+		let mut solver = propagate::Solver::new(self.wires.clone());
+		for &(wire, signal) in overrides {
+			solver.set(wire.to_owned(), signal);
+		}
+		solver.solve(&self.probe)
+	}
+}
+
+impl<'a> Parsed<&'a str> for Circuit {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(
+			separated_list1(
+				newline,
+				separated_pair(Gate::parse_wyz, tag(" -> "), alpha1),
+			),
+			|wires| Self {
+				wires:    wires
+					.into_iter()
+					.map(|(gate, wire)| (wire.to_owned(), gate))
+					.collect(),
+				probe:    "a".to_owned(),
+				feedback: "b".to_owned(),
+			},
+		)(text)
+	}
+}
+
+impl Puzzle for Circuit {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![("wires", self.wires.len().to_string())]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.measure(&[])?.into())
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		let signal = self.measure(&[])?;
+		Ok(self.measure(&[(&self.feedback, signal)])?.into())
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"probe" => self.probe = value.to_owned(),
+			"feedback" => self.feedback = value.to_owned(),
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+/// What drives a wire.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gate {
+	/// A signal passed straight through.
+	Pass(Signal),
+	/// The bitwise complement of a signal.
+	Not(Signal),
+	/// Two signals combined.
+	Binary(Signal, BinaryOp, Signal),
+}
+
+impl<'a> Parsed<&'a str> for Gate {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let op = alt((
+			value(BinaryOp::And, tag(" AND ")),
+			value(BinaryOp::Or, tag(" OR ")),
+			value(BinaryOp::LShift, tag(" LSHIFT ")),
+			value(BinaryOp::RShift, tag(" RSHIFT ")),
+		));
+		alt((
+			map(preceded(tag("NOT "), Signal::parse_wyz), Self::Not),
+			map(
+				tuple((Signal::parse_wyz, op, Signal::parse_wyz)),
+				|(lhs, op, rhs)| Self::Binary(lhs, op, rhs),
+			),
+			map(Signal::parse_wyz, Self::Pass),
+		))(text)
+	}
+}
+
+impl Expression<String, u16> for Gate {
+	fn operands(&self) -> Vec<&String> {
+		let signals = match self {
+			Self::Pass(signal) | Self::Not(signal) => vec![signal],
+			Self::Binary(lhs, _, rhs) => vec![lhs, rhs],
+		};
+		signals.into_iter().filter_map(Signal::wire).collect()
+	}
+
+	fn evaluate(&self, operand: impl Fn(&String) -> u16) -> eyre::Result<u16> {
+		let read = |signal: &Signal| match signal {
+			&Signal::Value(value) => value,
+			Signal::Wire(wire) => operand(wire),
+		};
+		Ok(match self {
+			Self::Pass(signal) => read(signal),
+			Self::Not(signal) => !read(signal),
+			Self::Binary(lhs, op, rhs) => op.apply(read(lhs), read(rhs)),
+		})
+	}
+}
+
+/// A binary logic gate.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinaryOp {
+	And,
+	Or,
+	LShift,
+	RShift,
+}
+
+impl BinaryOp {
+	/// Shifting every bit out of the wire leaves it dark, rather than
+	/// overflowing.
+	fn apply(self, lhs: u16, rhs: u16) -> u16 {
+		match self {
+			Self::And => lhs & rhs,
+			Self::Or => lhs | rhs,
+			Self::LShift => lhs.checked_shl(rhs.into()).unwrap_or(0),
+			Self::RShift => lhs.checked_shr(rhs.into()).unwrap_or(0),
+		}
+	}
+}
+
+/// An input to a gate.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Signal {
+	/// A fixed signal.
+	Value(u16),
+	/// Whatever another wire carries.
+	Wire(String),
+}
+
+impl Signal {
+	fn wire(&self) -> Option<&String> {
+		match self {
+			Self::Value(_) => None,
+			Self::Wire(wire) => Some(wire),
+		}
+	}
+}
+
+impl<'a> Parsed<&'a str> for Signal {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		alt((
+			map(parse_number, Self::Value),
+			map(alpha1, |wire: &str| Self::Wire(wire.to_owned())),
+		))(text)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_circuit() -> eyre::Result<()> {
+		let (_, mut circuit) =
+			include_str!("sample.txt").parse_wyz::<Circuit>()?;
+		for (wire, signal) in [("d", 72), ("e", 507), ("g", 114), ("h", 65412)] {
+			circuit.set_param("probe", wire)?;
+			assert_eq!(circuit.part_1()?, signal);
+		}
+		circuit.set_param("probe", "f")?;
+		circuit.set_param("feedback", "x")?;
+		assert_eq!(circuit.part_1()?, 492);
+		assert_eq!(circuit.part_2()?, 1968);
+		Ok(())
+	}
+}
@@ -0,0 +1,236 @@
+use nom::{
+	bytes::complete::tag,
+	character::complete::{
+		alpha1,
+		newline,
+	},
+	combinator::map,
+	multi::separated_list1,
+	sequence::tuple,
+};
+
+use crate::{
+	parse_number,
+	prelude::*,
+	sim::EventQueue,
+};
+
+crate::register_solver!(y2015, d14, Race);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Race {
+	racers:   Vec<Reindeer>,
+	/// How many seconds the race lasts.
+	duration: i64,
+}
+
+impl Race {
+	/// Runs the race, and reports each racer's final distance and score.
+	///
+	/// Each reindeer alternates between flying and resting, and each change is
+	/// an event. A scoring event fires at the end of every second, awarding a
+	/// point to whoever is in the lead at that moment.
+	pub fn run(&self) -> Vec<Standing> {
+		let mut standings = vec![Standing::default(); self.racers.len()];
+		let mut queue = EventQueue::new();
+		queue.extend((0 .. self.racers.len()).map(|idx| (0, Event::Fly(idx))));
+		queue.extend((1 ..= self.duration).map(|sec| (sec, Event::Score)));
+
+		while let Some((now, event)) = queue.pop_until(self.duration) {
+			match event {
+				Event::Fly(idx) => {
+					let racer = &self.racers[idx];
+					standings[idx].settle(now);
+					standings[idx].speed = racer.speed;
+					queue.schedule(now + racer.fly_time, Event::Rest(idx));
+				},
+				Event::Rest(idx) => {
+					let racer = &self.racers[idx];
+					standings[idx].settle(now);
+					standings[idx].speed = 0;
+					queue.schedule(now + racer.rest_time, Event::Fly(idx));
+				},
+				Event::Score => {
+					let lead = standings
+						.iter()
+						.map(|s| s.distance_at(now))
+						.max()
+						.unwrap_or_default();
+					for standing in &mut standings {
+						if standing.distance_at(now) == lead {
+							standing.score += 1;
+						}
+					}
+				},
+			}
+		}
+		for standing in &mut standings {
+			standing.settle(self.duration);
+		}
+		standings
+	}
+}
+
+impl<'a> Parsed<&'a str> for Race {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(separated_list1(newline, Reindeer::parse_wyz), |racers| {
+			Self {
+				racers,
+				duration: 2503,
+			}
+		})(text)
+	}
+}
+
+impl Puzzle for Race {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		let speeds = self.racers.iter().map(|r| r.speed);
+		vec![
+			("reindeer", self.racers.len().to_string()),
+			("duration", self.duration.to_string()),
+			(
+				"speeds",
+				format!(
+					"{}..={}",
+					speeds.clone().min().unwrap_or_default(),
+					speeds.max().unwrap_or_default()
+				),
+			),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		self.run()
+			.into_iter()
+			.map(|s| s.distance)
+			.max()
+			.ok_or_else(|| eyre::eyre!("nobody entered the race"))
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		self.run()
+			.into_iter()
+			.map(|s| s.score)
+			.max()
+			.ok_or_else(|| eyre::eyre!("nobody entered the race"))
+	}
+
+	fn validate_answer(&self, part: u8, answer: &Answer) -> eyre::Result<()> {
+		// Nobody can outfly the fastest reindeer flying non-stop, and nobody can
+		// score more than one point per second.
+		let bound = match part {
+			1 => {
+				self.racers
+					.iter()
+					.map(|r| r.speed)
+					.max()
+					.unwrap_or_default()
+					* self.duration
+			},
+			_ => self.duration,
+		};
+		if !answer.to_i64().is_some_and(|a| (0 ..= bound).contains(&a)) {
+			eyre::bail!("answer must be between 0 and {bound}");
+		}
+		Ok(())
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"duration" => self.duration = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reindeer {
+	name:      String,
+	/// Flying speed, in km/s.
+	speed:     i64,
+	/// How many seconds the reindeer can fly before it must rest.
+	fly_time:  i64,
+	/// How many seconds the reindeer must rest before it can fly again.
+	rest_time: i64,
+}
+
+impl<'a> Parsed<&'a str> for Reindeer {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(
+			tuple((
+				alpha1,
+				tag(" can fly "),
+				parse_number::<i64>,
+				tag(" km/s for "),
+				parse_number::<i64>,
+				tag(" seconds, but then must rest for "),
+				parse_number::<i64>,
+				tag(" seconds."),
+			)),
+			|(name, _, speed, _, fly_time, _, rest_time, _)| Self {
+				name: name.to_owned(),
+				speed,
+				fly_time,
+				rest_time,
+			},
+		)(text)
+	}
+}
+
+/// Things that happen during the race.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Event {
+	/// The indexed reindeer begins flying.
+	Fly(usize),
+	/// The indexed reindeer begins resting.
+	Rest(usize),
+	/// Points are awarded to the leaders.
+	Score,
+}
+
+/// A reindeer's progress through the race.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Standing {
+	/// The distance covered as of `since`.
+	pub distance: i64,
+	/// The number of points awarded for being in the lead.
+	pub score:    i64,
+	/// The current speed: either the flying speed or zero.
+	speed:        i64,
+	/// When the speed last changed.
+	since:        i64,
+}
+
+impl Standing {
+	/// Computes the distance covered at a given time, assuming the speed has
+	/// not changed since it was last settled.
+	fn distance_at(&self, now: i64) -> i64 {
+		self.distance + self.speed * (now - self.since)
+	}
+
+	/// Banks the distance covered up to now, in preparation for a change in
+	/// speed.
+	fn settle(&mut self, now: i64) {
+		self.distance = self.distance_at(now);
+		self.since = now;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_race() -> eyre::Result<()> {
+		let (_, mut race) = include_str!("sample.txt").parse_wyz::<Race>()?;
+		race.set_param("duration", "1000")?;
+		assert_eq!(race.part_1()?, 1120);
+		assert_eq!(race.part_2()?, 689);
+		assert!(race.validate_answer(2, &689.into()).is_ok());
+		assert!(race.validate_answer(2, &1001.into()).is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,86 @@
+use nom::{
+	character::complete::newline,
+	combinator::map,
+	multi::separated_list1,
+};
+
+use crate::{
+	optimize,
+	parse_number,
+	prelude::*,
+};
+
+crate::register_solver!(y2015, d17, Containers);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Containers {
+	/// The capacity of each container, in liters.
+	sizes:  Vec<u64>,
+	/// How much eggnog must be stored.
+	liters: u64,
+}
+
+impl Containers {
+	/// Counts the ways to fill containers with exactly the eggnog, by how many
+	/// containers each way uses.
+	fn ways_by_count(&self) -> Vec<u64> {
+		optimize::subsets_by_size(&self.sizes, self.liters)
+	}
+}
+
+impl<'a> Parsed<&'a str> for Containers {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(separated_list1(newline, parse_number), |sizes| Self {
+			sizes,
+			liters: 150,
+		})(text)
+	}
+}
+
+impl Puzzle for Containers {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("containers", self.sizes.len().to_string()),
+			("liters", self.liters.to_string()),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(
+			optimize::count_subsets_meet_in_middle(&self.sizes, self.liters)
+				as i64,
+		)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		self.ways_by_count()
+			.into_iter()
+			.find(|&ways| ways > 0)
+			.map(|ways| ways as i64)
+			.ok_or_else(|| eyre::eyre!("the eggnog does not fit exactly"))
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"liters" => self.liters = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_containers() -> eyre::Result<()> {
+		let (_, mut containers) =
+			include_str!("sample.txt").parse_wyz::<Containers>()?;
+		containers.set_param("liters", "25")?;
+		assert_eq!(containers.part_1()?, 4);
+		assert_eq!(containers.part_2()?, 3);
+		Ok(())
+	}
+}
@@ -15,9 +15,9 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2015, 19, |t| t.parse_dyn_puzzle::<Synth>());
+crate::register_solver!(y2015, d19, Synth);
 
+#[derive(Clone, Debug)]
 pub struct Synth {
 	rules:    Vec<Rule>,
 	products: Dictionary<str>,
@@ -41,7 +41,7 @@ impl Puzzle for Synth {
 	}
 
 	fn prepare_2(&mut self) -> eyre::Result<()> {
-		todo!()
+		Err(AocError::NotImplemented { part: 2 }.into())
 	}
 }
 
@@ -0,0 +1,96 @@
+use nom::{
+	character::complete::newline,
+	combinator::map,
+	multi::separated_list1,
+};
+
+use crate::{
+	optimize,
+	parse_number,
+	prelude::*,
+};
+
+crate::register_solver!(y2015, d24, Sleigh);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sleigh {
+	/// The weight of each package.
+	packages: Vec<u64>,
+}
+
+impl Sleigh {
+	/// Splits the packages into `groups` piles of equal weight, and reports the
+	/// quantum entanglement of the pile which goes in the passenger
+	/// compartment.
+	///
+	/// That pile must have as few packages as possible, and then the smallest
+	/// product of weights, and the other packages must still split evenly
+	/// among the remaining piles.
+	fn balance(&self, groups: usize) -> eyre::Result<i64> {
+		let total = self.packages.iter().sum::<u64>();
+		if total % groups as u64 != 0 {
+			eyre::bail!("{total} does not split into {groups} equal piles");
+		}
+		let target = total / groups as u64;
+		let front =
+			optimize::min_subset_by_product(&self.packages, target, |chosen| {
+				let rest = self
+					.packages
+					.iter()
+					.enumerate()
+					.filter(|(idx, _)| !chosen.contains(idx))
+					.map(|(_, &weight)| weight)
+					.collect::<Vec<_>>();
+				optimize::can_partition(&rest, target, groups - 1)
+			})
+			.ok_or_else(|| {
+				eyre::eyre!(
+					"the packages do not split into {groups} equal piles"
+				)
+			})?;
+		Ok(front
+			.into_iter()
+			.map(|idx| self.packages[idx])
+			.product::<u64>() as i64)
+	}
+}
+
+impl<'a> Parsed<&'a str> for Sleigh {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(separated_list1(newline, parse_number), |packages| Self {
+			packages,
+		})(text)
+	}
+}
+
+impl Puzzle for Sleigh {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("packages", self.packages.len().to_string()),
+			("weight", self.packages.iter().sum::<u64>().to_string()),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		self.balance(3)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		self.balance(4)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_sleigh() -> eyre::Result<()> {
+		let (_, mut sleigh) =
+			include_str!("sample.txt").parse_wyz::<Sleigh>()?;
+		assert_eq!(sleigh.part_1()?, 99);
+		assert_eq!(sleigh.part_2()?, 44);
+		Ok(())
+	}
+}
@@ -4,4 +4,8 @@ pub mod d03;
 pub mod d04;
 pub mod d05;
 pub mod d06;
+pub mod d07;
+pub mod d14;
+pub mod d17;
 pub mod d19;
+pub mod d24;
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use nom::{
+	character::complete::{
+		i64 as get_i64,
+		newline,
+	},
+	combinator::map,
+	sequence::terminated,
+};
+
+use crate::{
+	coords::orders::spiral,
+	prelude::*,
+	Coord2D,
+};
+
+crate::register_solver!(y2017, d03, SpiralMemory);
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpiralMemory {
+	/// The square whose data must be carried to the access port, in part 1,
+	/// and the value the stress test must exceed, in part 2.
+	square: i64,
+}
+
+impl<'a> Parsed<&'a str> for SpiralMemory {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(terminated(get_i64, newline), |square| Self { square })(text)
+	}
+}
+
+impl Puzzle for SpiralMemory {
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		if self.square < 1 {
+			eyre::bail!("squares are numbered from 1, not {}", self.square);
+		}
+		let at = spiral(Coord2D::<i64>::ZERO)
+			.nth(self.square as usize - 1)
+			.ok_or_else(|| eyre::eyre!("the spiral ended early"))?;
+		Ok(at.abs_manhattan())
+	}
+
+	/// The stress test writes each square as the sum of all its neighbors
+	/// which have already been written.
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		let mut written = BTreeMap::new();
+		written.insert(Coord2D::<i64>::ZERO, 1);
+		for at in spiral(Coord2D::ZERO).skip(1) {
+			let value = at
+				.all_neighbors()
+				.iter()
+				.filter_map(|n| written.get(n))
+				.sum::<i64>();
+			if value > self.square {
+				return Ok(value);
+			}
+			written.insert(at, value);
+		}
+		unreachable!("the spiral never ends")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_spiral() -> eyre::Result<()> {
+		for (square, steps) in [(1, 0), (12, 3), (23, 2), (1024, 31)] {
+			assert_eq!(SpiralMemory { square }.part_1()?, steps);
+		}
+		let (_, mut memory) =
+			include_str!("sample.txt").parse_wyz::<SpiralMemory>()?;
+		assert_eq!(memory.part_2()?, 1968);
+		assert_eq!(SpiralMemory { square: 747 }.part_2()?, 806);
+		Ok(())
+	}
+}
@@ -1 +1 @@
-
+pub mod d03;
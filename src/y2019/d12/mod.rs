@@ -0,0 +1,162 @@
+use nom::{
+	bytes::complete::tag,
+	character::complete::{
+		i64 as get_i64,
+		newline,
+	},
+	combinator::map,
+	multi::separated_list1,
+	sequence::{
+		delimited,
+		preceded,
+		tuple,
+	},
+};
+
+use crate::{
+	prelude::*,
+	sim::find_orbits,
+	Coord3D,
+};
+
+crate::register_solver!(y2019, d12, Moons);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Moons {
+	/// The starting position of each moon. All moons start at rest.
+	moons: Vec<Coord3D<i64>>,
+	/// How many steps to simulate before measuring the energy in part 1.
+	steps: usize,
+}
+
+impl Moons {
+	/// Splits the moons into their three axes, which never affect each other:
+	/// gravity along one axis depends only on positions along that axis.
+	fn axes(&self) -> [Axis; 3] {
+		let getters: [fn(&Coord3D<i64>) -> i64; 3] =
+			[|pt| pt.x, |pt| pt.y, |pt| pt.z];
+		getters.map(|get| {
+			self.moons
+				.iter()
+				.map(|pt| Body {
+					pos: get(pt),
+					vel: 0,
+				})
+				.collect()
+		})
+	}
+}
+
+/// One moon's position and velocity along a single axis.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Body {
+	pos: i64,
+	vel: i64,
+}
+
+/// Every moon, along a single axis.
+type Axis = Vec<Body>;
+
+/// Applies gravity between every pair of moons, then velocity.
+fn step(axis: &Axis) -> Axis {
+	axis.iter()
+		.map(|&Body { pos, vel }| {
+			let pull = axis
+				.iter()
+				.map(|other| (other.pos - pos).signum())
+				.sum::<i64>();
+			let vel = vel + pull;
+			Body {
+				pos: pos + vel,
+				vel,
+			}
+		})
+		.collect()
+}
+
+impl<'a> Parsed<&'a str> for Moons {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let moon = delimited(
+			tag("<"),
+			tuple((
+				preceded(tag("x="), get_i64),
+				preceded(tag(", y="), get_i64),
+				preceded(tag(", z="), get_i64),
+			)),
+			tag(">"),
+		);
+		map(
+			separated_list1(newline, map(moon, Coord3D::from)),
+			|moons| Self { moons, steps: 1000 },
+		)(text)
+	}
+}
+
+impl Puzzle for Moons {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("moons", self.moons.len().to_string()),
+			("steps", self.steps.to_string()),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		let axes = self.axes().map(|mut axis| {
+			for _ in 0 .. self.steps {
+				axis = step(&axis);
+			}
+			axis
+		});
+		// A moon's energy is the product of its potential and kinetic
+		// energies, which each sum over all three axes.
+		Ok((0 .. self.moons.len())
+			.map(|idx| {
+				let potential =
+					axes.iter().map(|a| a[idx].pos.abs()).sum::<i64>();
+				let kinetic = axes.iter().map(|a| a[idx].vel.abs()).sum::<i64>();
+				potential * kinetic
+			})
+			.sum())
+	}
+
+	/// The simulation is reversible, so every state has exactly one
+	/// predecessor and each axis loops all the way back to its starting state.
+	/// The whole system does so once every axis has.
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		let orbits = find_orbits(self.axes(), step, Axis::clone)?;
+		if orbits.start != 0 {
+			eyre::bail!("the moons did not return to their starting state");
+		}
+		i64::try_from(orbits.length).map_err(|_| {
+			AocError::Overflow(format!(
+				"{} steps does not fit in i64",
+				orbits.length
+			))
+			.into()
+		})
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"steps" => self.steps = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_moons() -> eyre::Result<()> {
+		let (_, mut moons) = include_str!("sample.txt").parse_wyz::<Moons>()?;
+		moons.set_param("steps", "10")?;
+		assert_eq!(moons.part_1()?, 179);
+		assert_eq!(moons.part_2()?, 2772);
+		Ok(())
+	}
+}
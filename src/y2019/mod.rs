@@ -1 +1 @@
-
+pub mod d12;
@@ -0,0 +1,189 @@
+use std::iter;
+
+use tap::Pipe;
+
+use crate::{
+	coords::Dense2DSpace,
+	prelude::*,
+	sim::automaton::{
+		Automaton,
+		Edges,
+	},
+	Coord2D,
+};
+
+crate::register_solver!(y2020, d11, Seating);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Seating {
+	/// The waiting area as written, with the top-left position at origin.
+	seats:   Dense2DSpace<i16, Seat>,
+	/// The waiting area once everyone has stopped moving. Each part starts
+	/// again from the written layout.
+	settled: Dense2DSpace<i16, Seat>,
+}
+
+impl<'a> Parsed<&'a str> for Seating {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		Dense2DSpace::from_raw(
+			Coord2D::ZERO,
+			text.lines()
+				.map(|line| line.chars().map(Seat::from).collect())
+				.collect(),
+		)
+		.pipe(|seats| {
+			Ok(("", Self {
+				settled: seats.clone(),
+				seats,
+			}))
+		})
+	}
+}
+
+impl Puzzle for Seating {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	fn after_parse(&mut self) -> eyre::Result<()> {
+		let seats = self
+			.seats
+			.iter()
+			.filter(|&(_, &s)| s != Seat::Floor)
+			.count();
+		tracing::info!(%seats, "found seats");
+		Ok(())
+	}
+
+	/// People sit down if none of the eight adjacent seats is taken, and get
+	/// up if four or more of them are.
+	#[cfg_attr(
+		not(feature = "trace"),
+		allow(unused_variables, unused_assignments)
+	)]
+	fn prepare_1(&mut self) -> eyre::Result<()> {
+		let mut world = Automaton::from_grid(&self.seats, Edges::Bounded);
+		let mut rounds = 0;
+		loop {
+			let before = world.cells().to_vec();
+			world.step(|&seat, around| {
+				seat.next(around.count_eq(&Seat::Occupied), 4)
+			});
+			if world.cells() == before {
+				break;
+			}
+			rounds += 1;
+		}
+		tracing::debug!(%rounds, "seating stabilized");
+		world.write_to(&mut self.settled);
+		Ok(())
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.count(Seat::Occupied) as i64)
+	}
+
+	/// People look past the floor to the first seat in each of the eight
+	/// directions, and tolerate up to four occupied seats in view.
+	#[cfg_attr(
+		not(feature = "trace"),
+		allow(unused_variables, unused_assignments)
+	)]
+	fn prepare_2(&mut self) -> eyre::Result<()> {
+		let sightlines = self.sightlines();
+		let mut current = self.seats.clone();
+		let mut rounds = 0;
+		loop {
+			let mut next = current.clone();
+			let mut changed = false;
+			for (seat, visible) in &sightlines {
+				let occupied = visible
+					.iter()
+					.filter(|&&pt| current[pt] == Seat::Occupied)
+					.count();
+				let before = current[*seat];
+				let after = before.next(occupied, 5);
+				changed |= before != after;
+				next[*seat] = after;
+			}
+			if !changed {
+				break;
+			}
+			current = next;
+			rounds += 1;
+		}
+		tracing::debug!(%rounds, "seating stabilized");
+		self.settled = current;
+		Ok(())
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		Ok(self.count(Seat::Occupied) as i64)
+	}
+}
+
+impl Seating {
+	/// Counts the settled positions in a given state.
+	pub fn count(&self, state: Seat) -> usize {
+		self.settled
+			.iter()
+			.filter(|&(_, &seat)| seat == state)
+			.count()
+	}
+
+	/// Finds, for each seat, the nearest seat in each of the eight directions.
+	pub fn sightlines(&self) -> Vec<(Coord2D<i16>, Vec<Coord2D<i16>>)> {
+		self.seats
+			.iter()
+			.filter(|&(_, &seat)| seat != Seat::Floor)
+			.map(|(pt, _)| {
+				let visible = pt
+					.all_neighbors()
+					.into_iter()
+					.filter_map(|next| {
+						let step = next - pt;
+						iter::successors(Some(next), move |&pt| Some(pt + step))
+							.map_while(|pt| self.seats.get(pt).map(|s| (pt, s)))
+							.find(|&(_, &seat)| seat != Seat::Floor)
+							.map(|(pt, _)| pt)
+					})
+					.collect();
+				(pt, visible)
+			})
+			.collect()
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seat {
+	#[default]
+	Floor,
+	Empty,
+	Occupied,
+}
+
+impl Seat {
+	/// Computes the next state of a position, given how many occupied seats
+	/// its occupant can see and how many they will put up with.
+	pub fn next(self, occupied: usize, crowded: usize) -> Self {
+		match self {
+			Self::Empty if occupied == 0 => Self::Occupied,
+			Self::Occupied if occupied >= crowded => Self::Empty,
+			other => other,
+		}
+	}
+}
+
+impl From<char> for Seat {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	fn from(c: char) -> Self {
+		match c {
+			'L' => Self::Empty,
+			'#' => Self::Occupied,
+			'.' => Self::Floor,
+			c => {
+				tracing::warn!(?c, "unknown seat character");
+				Self::Floor
+			},
+		}
+	}
+}
@@ -0,0 +1,88 @@
+use tap::Pipe;
+
+use crate::{
+	coords::Dense2DSpace,
+	prelude::*,
+	sim::automaton::{
+		Automaton,
+		Edges,
+		Neighborhood,
+	},
+	Coord2D,
+};
+
+crate::register_solver!(y2020, d17, PocketDimension);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PocketDimension {
+	/// The initial slice of the pocket dimension, in which every higher
+	/// coördinate is zero.
+	slice:  Dense2DSpace<i16, bool>,
+	/// The number of boot cycles to run.
+	cycles: usize,
+}
+
+impl<'a> Parsed<&'a str> for PocketDimension {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		Dense2DSpace::from_raw(
+			Coord2D::ZERO,
+			text.lines()
+				.map(|line| line.chars().map(|c| c == '#').collect())
+				.collect(),
+		)
+		.pipe(|slice| Ok(("", Self { slice, cycles: 6 })))
+	}
+}
+
+impl Puzzle for PocketDimension {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	fn after_parse(&mut self) -> eyre::Result<()> {
+		let active = self.slice.iter().filter(|&(_, &cube)| cube).count();
+		tracing::info!(%active, "found active cubes");
+		Ok(())
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.boot(3) as i64)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		Ok(self.boot(4) as i64)
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"cycles" => self.cycles = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+impl PocketDimension {
+	/// Runs the boot process in a space of some number of dimensions, and
+	/// counts the cubes left active at the end.
+	///
+	/// The space grows by one cube in every direction each cycle, so it is
+	/// padded before every step.
+	pub fn boot(&self, dimensions: usize) -> usize {
+		let rows = self.slice.raw_data();
+		let width = rows.first().map(Vec::len).unwrap_or_default();
+		let mut dims = vec![1; dimensions.max(2)];
+		dims[0] = width;
+		dims[1] = rows.len();
+		let mut world = Automaton::new(dims, rows.concat(), Edges::Bounded);
+		for _ in 0 .. self.cycles {
+			world.pad(1, false);
+			world.step(Self::rule);
+		}
+		world.count(|&cube| cube)
+	}
+
+	/// Active cubes stay active with two or three active neighbors; inactive
+	/// cubes become active with exactly three.
+	fn rule(cube: &bool, around: Neighborhood<bool>) -> bool {
+		matches!((cube, around.count_eq(&true)), (true, 2) | (_, 3))
+	}
+}
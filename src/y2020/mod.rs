@@ -1 +1,2 @@
-
+pub mod d11;
+pub mod d17;
@@ -0,0 +1,89 @@
+use std::cmp::Reverse;
+
+use tap::Pipe;
+
+use crate::{
+	coords::{
+		spaces::Heatmap,
+		Dense2DSpace,
+	},
+	prelude::*,
+	search,
+	Coord2D,
+};
+
+crate::register_solver!(y2021, d09, SmokeBasin);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SmokeBasin {
+	/// The heightmap as written, with the top-left location at origin.
+	heights: Dense2DSpace<i16, u8>,
+}
+
+impl<'a> Parsed<&'a str> for SmokeBasin {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let table = text
+			.lines()
+			.map(|line| line.bytes().map(|b| b - b'0').collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		Ok(("", Self {
+			heights: Dense2DSpace::from_raw(Coord2D::ZERO, table),
+		}))
+	}
+}
+
+impl Puzzle for SmokeBasin {
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		self.low_points()
+			.map(|pt| self.heights[pt] as i64 + 1)
+			.sum::<i64>()
+			.pipe(Ok)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		let mut sizes = self
+			.low_points()
+			.map(|pt| self.basin_size(pt))
+			.collect::<Vec<_>>();
+		if sizes.len() < 3 {
+			eyre::bail!("only found {} basins", sizes.len());
+		}
+		sizes.sort_unstable_by_key(|&size| Reverse(size));
+		Ok(sizes[.. 3].iter().map(|&size| size as i64).product())
+	}
+
+	fn render_state(&self) -> Option<String> {
+		Some(format!("{:#}", Heatmap::new(&self.heights)))
+	}
+}
+
+impl SmokeBasin {
+	/// Finds the locations which are lower than all of their orthogonal
+	/// neighbors.
+	pub fn low_points(&self) -> impl '_ + Iterator<Item = Coord2D<i16>> {
+		self.heights
+			.iter()
+			.filter(|&(pt, &height)| {
+				pt.direct_neighbors()
+					.into_iter()
+					.filter_map(|next| self.heights.get(next))
+					.all(|&other| other > height)
+			})
+			.map(|(pt, _)| pt)
+	}
+
+	/// Counts the locations which flow down to a low point.
+	///
+	/// Basins are bounded by height-9 locations, which belong to no basin.
+	pub fn basin_size(&self, low: Coord2D<i16>) -> usize {
+		search::bfs_visit([low], |pt, queue| {
+			queue.extend(
+				pt.direct_neighbors().into_iter().filter(|&next| {
+					self.heights.get(next).is_some_and(|&h| h < 9)
+				}),
+			);
+		})
+		.len()
+	}
+}
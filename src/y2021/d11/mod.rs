@@ -0,0 +1,124 @@
+use crate::{
+	coords::Dense2DSpace,
+	prelude::*,
+	Coord2D,
+};
+
+crate::register_solver!(y2021, d11, DumboOctopus);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DumboOctopus {
+	/// Each octopus's energy level, with the top-left octopus at origin.
+	energy: Dense2DSpace<i16, u8>,
+	/// The number of steps to count flashes over in part 1.
+	steps:  usize,
+	/// The number of steps taken so far.
+	taken:  usize,
+	/// The first step on which every octopus flashed at once, if it has
+	/// happened yet.
+	synced: Option<usize>,
+}
+
+impl<'a> Parsed<&'a str> for DumboOctopus {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let table = text
+			.lines()
+			.map(|line| line.bytes().map(|b| b - b'0').collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		Ok(("", Self {
+			energy: Dense2DSpace::from_raw(Coord2D::ZERO, table),
+			steps:  100,
+			taken:  0,
+			synced: None,
+		}))
+	}
+}
+
+impl Puzzle for DumboOctopus {
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		let mut flashes = 0;
+		while self.taken < self.steps {
+			flashes += self.step();
+		}
+		Ok(flashes as i64)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		if self.energy.is_empty() {
+			eyre::bail!("there are no octopi to synchronize");
+		}
+		// Part 1 may already have stepped past the synchronization.
+		while self.synced.is_none() {
+			self.step();
+		}
+		Ok(self.synced.unwrap_or_default() as i64)
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"steps" => self.steps = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+impl DumboOctopus {
+	/// Advances every octopus by one step, and counts how many flashed.
+	///
+	/// Each octopus gains one energy. Any octopus above 9 flashes, which gives
+	/// one energy to each of its eight neighbors and can set them off in turn.
+	/// An octopus flashes at most once per step, and everything which flashed
+	/// ends the step at zero.
+	pub fn step(&mut self) -> usize {
+		self.taken += 1;
+		let mut ready = Vec::new();
+		for (pt, energy) in self.energy.iter_mut() {
+			*energy += 1;
+			if *energy > 9 {
+				ready.push(pt);
+			}
+		}
+		let mut flashed = 0;
+		while let Some(pt) = ready.pop() {
+			flashed += 1;
+			for next in pt.all_neighbors() {
+				let Some(energy) = self.energy.get_mut(next)
+				else {
+					continue;
+				};
+				// Only the charge which tips an octopus over the edge queues
+				// it, so that it flashes once.
+				*energy += 1;
+				if *energy == 10 {
+					ready.push(next);
+				}
+			}
+		}
+		for (_, energy) in self.energy.iter_mut() {
+			if *energy > 9 {
+				*energy = 0;
+			}
+		}
+		if self.synced.is_none() && self.energy.iter().all(|(_, &e)| e == 0) {
+			self.synced = Some(self.taken);
+		}
+		flashed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn flashing() -> eyre::Result<()> {
+		let text = "11111\n19991\n19191\n19991\n11111";
+		let (_, mut solver): (_, DumboOctopus) = text.parse_wyz()?;
+		assert_eq!(solver.step(), 9);
+		assert_eq!(solver.energy.get_row(2), Some(&[5, 0, 0, 0, 5][..]));
+		assert_eq!(solver.step(), 0);
+		Ok(())
+	}
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{
+		i16 as get_i16,
+		newline,
+	},
+	combinator::{
+		map,
+		value,
+	},
+	multi::many1,
+	sequence::{
+		preceded,
+		separated_pair,
+		terminated,
+	},
+};
+
+use crate::{
+	coords::spaces::{
+		BitGrid2D,
+		Sparse2D,
+	},
+	prelude::*,
+	Coord2D,
+};
+
+crate::register_solver!(y2021, d13, Origami);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Origami {
+	/// The dots visible through the sheet.
+	dots:   Sparse2D<i16, ()>,
+	/// The fold instructions, in order.
+	folds:  Vec<Fold>,
+	/// How many of the instructions have been carried out.
+	folded: usize,
+}
+
+impl<'a> Parsed<&'a str> for Origami {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(
+			separated_pair(
+				many1(terminated(Coord2D::parse_wyz, newline)),
+				newline,
+				many1(terminated(Fold::parse_wyz, newline)),
+			),
+			|(dots, folds)| Self {
+				dots: dots.into_iter().map(|pt| (pt, ())).collect(),
+				folds,
+				folded: 0,
+			},
+		)(text)
+	}
+}
+
+impl Puzzle for Origami {
+	fn after_parse(&mut self) -> eyre::Result<()> {
+		tracing::info!(dots=%self.dots.len(), folds=%self.folds.len(), "parsed");
+		Ok(())
+	}
+
+	fn prepare_1(&mut self) -> eyre::Result<()> {
+		self.fold_until(1);
+		Ok(())
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.dots.len() as i64)
+	}
+
+	/// Finishes folding. The dots then draw eight capital letters.
+	fn prepare_2(&mut self) -> eyre::Result<()> {
+		self.fold_until(self.folds.len());
+		tracing::info!("activation code:\n{}", self.sheet());
+		Ok(())
+	}
+
+	/// The answer is the letters the dots draw, not a number.
+	///
+	/// Sheets which do not spell anything, such as the worked example's
+	/// square, have no answer; the drawing is still logged once the folding
+	/// is done.
+	fn answer_2(&mut self) -> eyre::Result<Answer> {
+		let sheet = self.sheet();
+		crate::ocr::read_bits(sheet.raw_data(), sheet.width())
+			.map(Answer::from)
+			.map_err(|err| {
+				AocError::InvalidInput(format!(
+					"the dots do not spell a code: {err}"
+				))
+				.into()
+			})
+	}
+
+	fn render_state(&self) -> Option<String> {
+		Some(self.sheet().to_string())
+	}
+
+	fn artifacts(&self) -> Vec<Artifact> {
+		vec![Artifact::text("code", self.sheet().to_string())]
+	}
+}
+
+impl Origami {
+	/// Carries out fold instructions until `count` of them have been done.
+	pub fn fold_until(&mut self, count: usize) {
+		let count = count.min(self.folds.len());
+		while self.folded < count {
+			let fold = self.folds[self.folded];
+			tracing::debug!(%fold, "folding");
+			self.dots = self
+				.dots
+				.iter()
+				.map(|(pt, _)| (fold.apply(pt), ()))
+				.collect();
+			self.folded += 1;
+		}
+	}
+
+	/// Draws the dots on the sheet as it currently stands.
+	pub fn sheet(&self) -> BitGrid2D<i16> {
+		BitGrid2D::from(&self.dots)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fold {
+	/// The axis whose coördinates the fold changes.
+	axis: Axis,
+	/// The position of the fold line along that axis.
+	line: i16,
+}
+
+impl Fold {
+	/// Moves a point from the far side of the fold line to its mirror image.
+	/// Points on the near side stay where they are.
+	pub fn apply(self, mut point: Coord2D<i16>) -> Coord2D<i16> {
+		let coord = match self.axis {
+			Axis::X => &mut point.x,
+			Axis::Y => &mut point.y,
+		};
+		if *coord > self.line {
+			*coord = 2 * self.line - *coord;
+		}
+		point
+	}
+}
+
+impl<'a> Parsed<&'a str> for Fold {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(
+			preceded(
+				tag("fold along "),
+				separated_pair(
+					alt((value(Axis::X, tag("x")), value(Axis::Y, tag("y")))),
+					tag("="),
+					get_i16,
+				),
+			),
+			|(axis, line)| Self { axis, line },
+		)(text)
+	}
+}
+
+impl fmt::Display for Fold {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let axis = match self.axis {
+			Axis::X => 'x',
+			Axis::Y => 'y',
+		};
+		write!(fmt, "{axis}={}", self.line)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+	X,
+	Y,
+}
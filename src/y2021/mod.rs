@@ -1 +1,3 @@
-
+pub mod d09;
+pub mod d11;
+pub mod d13;
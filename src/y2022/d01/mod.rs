@@ -3,21 +3,19 @@ use std::cmp;
 use nom::{
 	character::complete::{
 		i64 as get_i64,
-		newline,
+		line_ending,
 	},
-	multi::{
-		many1,
-		separated_list1,
-	},
-	sequence::terminated,
+	combinator::map,
+	multi::separated_list1,
 };
 use tap::Pipe;
 
-use crate::prelude::*;
+use crate::{
+	blocks,
+	prelude::*,
+};
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2022, 1, |t| t.parse_dyn_puzzle::<Commissary>());
+crate::register_solver!(y2022, d01, Commissary);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -27,15 +25,15 @@ pub struct Commissary {
 
 impl<'a> Parsed<&'a str> for Commissary {
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
-		let (rest, packs) =
-			separated_list1(newline, many1(terminated(get_i64, newline)))(text)?;
-		let packs = packs.into_iter().map(|v| v.into_iter().sum()).collect();
-		Ok((rest, Self { packs }))
+		let pack = map(separated_list1(line_ending, get_i64), |items| {
+			items.into_iter().sum()
+		});
+		map(blocks(pack), |packs| Self { packs })(text)
 	}
 }
 
 impl Puzzle for Commissary {
-	fn prepare_1(&mut self) -> eyre::Result<()> {
+	fn prepare_common(&mut self) -> eyre::Result<()> {
 		self.packs.sort_by_key(|&i| cmp::Reverse(i));
 		Ok(())
 	}
@@ -47,11 +45,6 @@ impl Puzzle for Commissary {
 			.ok_or_else(|| eyre::eyre!("cannot handle an empty group"))
 	}
 
-	fn prepare_2(&mut self) -> eyre::Result<()> {
-		self.packs.sort_by_key(|&i| cmp::Reverse(i));
-		Ok(())
-	}
-
 	fn part_2(&mut self) -> eyre::Result<i64> {
 		self.packs[.. 3]
 			.iter()
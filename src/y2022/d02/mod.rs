@@ -26,9 +26,7 @@ use tap::Pipe;
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2022, 2, |t| t.parse_dyn_puzzle::<RockPaperScissors>());
+crate::register_solver!(y2022, d02, RockPaperScissors);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -11,9 +11,7 @@ use tap::Pipe;
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2022, 3, |t| t.parse_dyn_puzzle::<Commissary>());
+crate::register_solver!(y2022, d03, Commissary);
 
 type Priorities = BitArr!(for 53);
 
@@ -32,6 +30,7 @@ impl<'a> Parsed<&'a str> for Commissary {
 }
 
 impl Puzzle for Commissary {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn part_1(&mut self) -> eyre::Result<i64> {
 		self.packs
 			.iter()
@@ -114,6 +113,7 @@ pub struct Item {
 }
 
 impl From<char> for Item {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn from(c: char) -> Self {
 		Self {
 			priority: match c {
@@ -17,8 +17,7 @@ use tap::Pipe;
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2022, 4, |t| t.parse_dyn_puzzle::<Camp>());
+crate::register_solver!(y2022, d04, Camp);
 
 #[derive(Clone, Debug, Default)]
 pub struct Camp {
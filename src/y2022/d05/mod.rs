@@ -27,68 +27,79 @@ use nom::{
 	},
 };
 
-use crate::prelude::*;
+use crate::{
+	prelude::*,
+	sim::{
+		LineProgram,
+		StateMachine,
+	},
+};
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2022, 5, |t| t.parse_dyn_puzzle::<Dockyard>());
+crate::register_solver!(y2022, d05, Dockyard);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dockyard {
 	pile:   BTreeMap<usize, Vec<Crate>>,
-	moves:  Vec<Move>,
+	moves:  LineProgram<Move>,
 	answer: String,
 }
 
 impl Dockyard {
-	pub fn run_singly(&mut self) -> eyre::Result<()> {
-		let mut pile = self.pile.clone();
-		for Move { cnt, src, dst } in &self.moves {
-			for _ in 0 .. *cnt {
-				let tmp = pile
-					.get_mut(src)
-					.ok_or_else(|| eyre::eyre!("no such source column {src}"))?
-					.pop()
-					.ok_or_else(|| {
-						eyre::eyre!("cannot pull from an empty column")
-					})?;
-				pile.get_mut(dst)
-					.ok_or_else(|| {
-						eyre::eyre!("no such destination column {dst}")
-					})?
-					.push(tmp);
-			}
-		}
-		self.answer = pile
+	/// Runs every move with a model of crane, starting from the original
+	/// pile, and records the crates left on top.
+	pub fn run(&mut self, model: Model) -> eyre::Result<()> {
+		let mut crane = Crane {
+			pile: self.pile.clone(),
+			model,
+		};
+		self.moves.run(&mut crane)?;
+		self.answer = crane
+			.pile
 			.values()
 			.flat_map(|v| v.last())
 			.map(|Crate { ident }| ident)
 			.collect();
 		Ok(())
 	}
+}
+
+/// The crane models, which differ in how many crates they lift at once.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Model {
+	/// Moves crates one at a time, reversing their order.
+	CrateMover9000,
+	/// Moves a whole stack at once, keeping its order.
+	CrateMover9001,
+}
+
+/// A crane working through the moves.
+#[derive(Clone, Debug)]
+struct Crane {
+	pile:  BTreeMap<usize, Vec<Crate>>,
+	model: Model,
+}
 
-	pub fn run_multi(&mut self) -> eyre::Result<()> {
-		let mut pile = self.pile.clone();
-		for Move { cnt, src, dst } in &self.moves {
-			let from = pile
-				.get_mut(src)
-				.ok_or_else(|| eyre::eyre!("no such source column {src}"))?;
-			let mid = from.len().checked_sub(*cnt).ok_or_else(|| {
-				eyre::eyre!(
-					"cannot move {cnt} items from stack {src} (size {len})",
-					len = from.len(),
-				)
-			})?;
-			let tmp = from.split_off(mid);
-			pile.get_mut(dst)
-				.ok_or_else(|| eyre::eyre!("no such destination column {dst}"))?
-				.extend(tmp);
+impl StateMachine<Move> for Crane {
+	fn apply(&mut self, &Move { cnt, src, dst }: &Move) -> eyre::Result<()> {
+		let from = self
+			.pile
+			.get_mut(&src)
+			.ok_or_else(|| eyre::eyre!("no such source column {src}"))?;
+		let mid = from.len().checked_sub(cnt).ok_or_else(|| {
+			eyre::eyre!(
+				"cannot move {cnt} items from stack {src} (size {len})",
+				len = from.len(),
+			)
+		})?;
+		let mut tmp = from.split_off(mid);
+		if self.model == Model::CrateMover9000 {
+			tmp.reverse();
 		}
-		self.answer = pile
-			.values()
-			.flat_map(|v| v.last())
-			.map(|Crate { ident }| ident)
-			.collect();
+		self.pile
+			.get_mut(&dst)
+			.ok_or_else(|| eyre::eyre!("no such destination column {dst}"))?
+			.extend(tmp);
 		Ok(())
 	}
 }
@@ -109,6 +120,8 @@ impl<'a> Parsed<&'a str> for Dockyard {
 			),
 			newline,
 		)(rest)?;
+		// The moves start after the diagram, its labels, and a blank line.
+		let first_move = rows.len() + 3;
 		let mut pile = BTreeMap::<usize, Vec<Crate>>::new();
 		// Accumulate from the bottom of the diagram upwards
 		for row in rows.into_iter().rev() {
@@ -122,7 +135,7 @@ impl<'a> Parsed<&'a str> for Dockyard {
 		let (rest, moves) = separated_list1(newline, Move::parse_wyz)(rest)?;
 		Ok((rest, Self {
 			pile,
-			moves,
+			moves: LineProgram::from_ops(first_move, moves),
 			answer: String::new(),
 		}))
 	}
@@ -131,23 +144,24 @@ impl<'a> Parsed<&'a str> for Dockyard {
 impl Puzzle for Dockyard {
 	fn prepare_1(&mut self) -> eyre::Result<()> {
 		tracing::debug!("parsed\n{self:#}");
-		self.run_singly()?;
+		self.run(Model::CrateMover9000)?;
 		tracing::info!(%self.answer, "top crates");
 		Ok(())
 	}
 
-	fn part_1(&mut self) -> eyre::Result<i64> {
-		Ok(0)
+	/// The answer is the letters of the top crates, not a number.
+	fn answer_1(&mut self) -> eyre::Result<Answer> {
+		Ok(self.answer.as_str().into())
 	}
 
 	fn prepare_2(&mut self) -> eyre::Result<()> {
-		self.run_multi()?;
+		self.run(Model::CrateMover9001)?;
 		tracing::info!(%self.answer, "top crates");
 		Ok(())
 	}
 
-	fn part_2(&mut self) -> eyre::Result<i64> {
-		Ok(0)
+	fn answer_2(&mut self) -> eyre::Result<Answer> {
+		Ok(self.answer.as_str().into())
 	}
 }
 
@@ -235,3 +249,19 @@ impl<'a> Parsed<&'a str> for Move {
 		)(text)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_crates() -> eyre::Result<()> {
+		let (_, mut dockyard) =
+			include_str!("sample.txt").parse_wyz::<Dockyard>()?;
+		dockyard.prepare_1()?;
+		assert_eq!(dockyard.answer_1()?, "CMZ");
+		dockyard.prepare_2()?;
+		assert_eq!(dockyard.answer_2()?, "MCD");
+		Ok(())
+	}
+}
@@ -1,9 +1,9 @@
-use bitvec::bitarr;
+use crate::{
+	prelude::*,
+	text,
+};
 
-use crate::prelude::*;
-
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2022, 6, |t| t.parse_dyn_puzzle::<Message>());
+crate::register_solver!(y2022, d06, Message);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -32,18 +32,11 @@ impl Puzzle for Message {
 }
 
 impl Message {
+	/// Finds how many characters must be read before the last `len` of them
+	/// are all different.
 	pub fn find_sync(&self, len: usize) -> Option<i64> {
-		self.text
-			.as_bytes()
-			.windows(len)
-			.enumerate()
-			.find(|&(_, window)| {
-				let mut seen = bitarr![0; 128];
-				for &b in window {
-					seen.set(b as usize, true);
-				}
-				seen.count_ones() == len
-			})
-			.map(|(start, _)| (start + len) as i64)
+		text::windows_distinct(self.text.trim().as_bytes(), len)
+			.next()
+			.map(|start| (start + len) as i64)
 	}
 }
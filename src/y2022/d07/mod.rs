@@ -28,17 +28,21 @@ use nom::{
 	},
 };
 
-use crate::prelude::*;
+use crate::{
+	prelude::*,
+	sim::{
+		LineProgram,
+		StateMachine,
+	},
+};
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2022, 7, |t| t.parse_dyn_puzzle::<Navigator>());
+crate::register_solver!(y2022, d07, Navigator);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Navigator {
 	fs:     FsNode,
-	script: Vec<Command>,
+	script: LineProgram<Command>,
 }
 
 impl<'a> Parsed<&'a str> for Navigator {
@@ -46,7 +50,8 @@ impl<'a> Parsed<&'a str> for Navigator {
 		let (rest, _) = terminated(Command::root, newline)(text)?;
 		let (rest, cmds) = separated_list1(newline, Command::parse_wyz)(rest)?;
 		Ok((rest, Self {
-			script: cmds,
+			// The first line, `$ cd /`, is not kept.
+			script: LineProgram::from_ops(2, cmds),
 			fs:     FsNode::default(),
 		}))
 	}
@@ -54,26 +59,40 @@ impl<'a> Parsed<&'a str> for Navigator {
 
 impl Puzzle for Navigator {
 	fn after_parse(&mut self) -> eyre::Result<()> {
-		let mut cwd = PathBuf::from("/");
-		for cmd in &self.script {
-			match cmd {
-				Command::List => continue,
-				Command::Root => cwd = PathBuf::from("/"),
-				Command::GoUp => drop(cwd.pop()),
-				Command::GoDown { name } => cwd.push(name),
-				Command::Dir { name } => {
-					self.fs.dig(&cwd, name.clone(), FsNode::mkdir())?
-				},
-				Command::File { name, size } => {
-					self.fs.dig(&cwd, name.clone(), FsNode::touch(*size))?
-				},
-			}
-		}
+		let mut shell = Shell {
+			cwd: PathBuf::from("/"),
+			fs:  &mut self.fs,
+		};
+		self.script.run(&mut shell)?;
 		tracing::debug!("structured:\n{self:#}");
 		Ok(())
 	}
 }
 
+/// Replays the transcript, building up the filesystem it explored.
+struct Shell<'a> {
+	cwd: PathBuf,
+	fs:  &'a mut FsNode,
+}
+
+impl StateMachine<Command> for Shell<'_> {
+	fn apply(&mut self, cmd: &Command) -> eyre::Result<()> {
+		match cmd {
+			Command::List => {},
+			Command::Root => self.cwd = PathBuf::from("/"),
+			Command::GoUp => drop(self.cwd.pop()),
+			Command::GoDown { name } => self.cwd.push(name),
+			Command::Dir { name } => {
+				self.fs.dig(&self.cwd, name.clone(), FsNode::mkdir())?
+			},
+			Command::File { name, size } => {
+				self.fs.dig(&self.cwd, name.clone(), FsNode::touch(*size))?
+			},
+		}
+		Ok(())
+	}
+}
+
 impl fmt::Display for Navigator {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		fmt::Display::fmt(&self.fs, fmt)
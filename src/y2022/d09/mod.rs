@@ -0,0 +1,65 @@
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{
+		newline,
+		space1,
+	},
+	combinator::value,
+	multi::separated_list1,
+	sequence::separated_pair,
+};
+
+use crate::{
+	coords::points::Direction2D,
+	parse_number,
+	prelude::*,
+	sim::rope::Rope,
+};
+
+crate::register_solver!(y2022, d09, Bridge);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bridge {
+	/// The motions of the rope's head, in order.
+	moves: Vec<(Direction2D, usize)>,
+}
+
+impl Bridge {
+	/// Drags a rope of the given length through all the moves, and counts the
+	/// distinct positions visited by its tail.
+	fn count_tail_visits(&self, knots: usize) -> usize {
+		let mut rope = Rope::<i32>::new(knots).track_visits(knots - 1);
+		for &(direction, distance) in &self.moves {
+			rope.pull(direction, distance);
+		}
+		rope.visits(knots - 1).map(|v| v.len()).unwrap_or_default()
+	}
+}
+
+impl<'a> Parsed<&'a str> for Bridge {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let direction = alt((
+			value(Direction2D::North, tag("U")),
+			value(Direction2D::South, tag("D")),
+			value(Direction2D::West, tag("L")),
+			value(Direction2D::East, tag("R")),
+		));
+		let (rest, moves) = separated_list1(
+			newline,
+			separated_pair(direction, space1, parse_number::<usize>),
+		)(text)?;
+		Ok((rest, Self { moves }))
+	}
+}
+
+impl Puzzle for Bridge {
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.count_tail_visits(2) as i64)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		Ok(self.count_tail_visits(10) as i64)
+	}
+}
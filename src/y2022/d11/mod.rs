@@ -0,0 +1,322 @@
+use std::{
+	fmt,
+	mem,
+	str::FromStr,
+};
+
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::newline,
+	combinator::{
+		map,
+		value,
+	},
+	multi::separated_list1,
+	sequence::{
+		delimited,
+		preceded,
+	},
+};
+
+use crate::{
+	blocks,
+	parse_number,
+	prelude::*,
+};
+
+crate::register_solver!(y2022, d11, Troop);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Troop {
+	/// The monkeys, in the order they take their turns. Each monkey's position
+	/// in this list is also its ID.
+	monkeys: Vec<Monkey>,
+	/// Overrides the number of rounds played in either part.
+	#[cfg_attr(feature = "serde", serde(default))]
+	rounds:  Option<usize>,
+	/// Overrides how worry is kept under control in either part.
+	#[cfg_attr(feature = "serde", serde(default))]
+	relief:  Option<Relief>,
+}
+
+impl Troop {
+	/// Plays some rounds of keep-away on a copy of the troop, and computes the
+	/// level of monkey business: the product of the two highest inspection
+	/// counts.
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	pub fn monkey_business(
+		&self,
+		rounds: usize,
+		relief: Relief,
+	) -> eyre::Result<i64> {
+		let mut troop = self.clone();
+		for round in 1 ..= rounds {
+			troop.play_round(relief)?;
+			tracing::trace!(%round, state=%troop, "finished round");
+		}
+		let mut inspections = troop
+			.monkeys
+			.iter()
+			.map(|m| m.inspections)
+			.collect::<Vec<_>>();
+		inspections.sort_unstable_by(|a, b| b.cmp(a));
+		let business = inspections.into_iter().take(2).checked_product()?;
+		Ok(i64::try_from(business)?)
+	}
+
+	/// Gives each monkey one turn to inspect and throw all of its items.
+	pub fn play_round(&mut self, relief: Relief) -> eyre::Result<()> {
+		// All divisors are checked against the same worry value, so it can be
+		// reduced modulo their product without changing any test outcome.
+		let lcm = self.monkeys.iter().map(|m| m.divisor).product::<i64>();
+		for id in 0 .. self.monkeys.len() {
+			let monkey = &mut self.monkeys[id];
+			let items = mem::take(&mut monkey.items);
+			monkey.inspections += items.len();
+			let Monkey {
+				operation,
+				divisor,
+				on_true,
+				on_false,
+				..
+			} = *monkey;
+			for item in items {
+				let worry = relief.apply(operation.compute(item)?, lcm);
+				let target = if worry % divisor == 0 {
+					on_true
+				}
+				else {
+					on_false
+				};
+				self.monkeys
+					.get_mut(target)
+					.ok_or_else(|| {
+						eyre::eyre!(
+							"monkey {id} threw to absent monkey {target}"
+						)
+					})?
+					.items
+					.push(worry);
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<'a> Parsed<&'a str> for Troop {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(blocks(Monkey::parse_wyz), |monkeys| Self {
+			monkeys,
+			..Self::default()
+		})(text)
+	}
+}
+
+impl Puzzle for Troop {
+	fn after_parse(&mut self) -> eyre::Result<()> {
+		for (idx, monkey) in self.monkeys.iter().enumerate() {
+			if monkey.id != idx {
+				eyre::bail!("monkey {} is listed in position {idx}", monkey.id);
+			}
+			if monkey.divisor == 0 {
+				eyre::bail!("monkey {idx} tests divisibility by zero");
+			}
+		}
+		Ok(())
+	}
+
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		let items = self.monkeys.iter().map(|m| m.items.len()).sum::<usize>();
+		let divisors = self
+			.monkeys
+			.iter()
+			.map(|m| m.divisor.to_string())
+			.collect::<Vec<_>>();
+		vec![
+			("monkeys", self.monkeys.len().to_string()),
+			("items", items.to_string()),
+			("divisors", divisors.join(", ")),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		self.monkey_business(
+			self.rounds.unwrap_or(20),
+			self.relief.unwrap_or(Relief::Divide(3)),
+		)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		self.monkey_business(
+			self.rounds.unwrap_or(10_000),
+			self.relief.unwrap_or(Relief::Modulo),
+		)
+	}
+
+	fn render_state(&self) -> Option<String> {
+		Some(self.to_string())
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"rounds" => self.rounds = Some(value.parse()?),
+			"relief" => self.relief = Some(value.parse()?),
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for Troop {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for monkey in &self.monkeys {
+			write!(fmt, "Monkey {} ({}):", monkey.id, monkey.inspections)?;
+			for item in &monkey.items {
+				write!(fmt, " {item}")?;
+			}
+			writeln!(fmt)?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Monkey {
+	id: usize,
+	/// The worry levels of the items the monkey is holding.
+	items: Vec<i64>,
+	operation: Operation,
+	/// The monkey throws to `on_true` if the worry level is divisible by this,
+	/// and `on_false` if not.
+	divisor: i64,
+	on_true: usize,
+	on_false: usize,
+	/// How many items the monkey has inspected so far.
+	inspections: usize,
+}
+
+impl<'a> Parsed<&'a str> for Monkey {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let (rest, id) =
+			delimited(tag("Monkey "), parse_number::<usize>, tag(":\n"))(text)?;
+		let (rest, items) = delimited(
+			tag("  Starting items: "),
+			separated_list1(tag(", "), parse_number::<i64>),
+			newline,
+		)(rest)?;
+		let (rest, operation) = delimited(
+			tag("  Operation: new = old "),
+			alt((
+				map(preceded(tag("+ "), parse_number), Operation::Add),
+				value(Operation::Square, tag("* old")),
+				map(preceded(tag("* "), parse_number), Operation::Mul),
+			)),
+			newline,
+		)(rest)?;
+		let (rest, divisor) = delimited(
+			tag("  Test: divisible by "),
+			parse_number::<i64>,
+			newline,
+		)(rest)?;
+		let (rest, on_true) =
+			preceded(tag("    If true: throw to monkey "), parse_number)(rest)?;
+		let (rest, on_false) = preceded(
+			tag("\n    If false: throw to monkey "),
+			parse_number,
+		)(rest)?;
+		Ok((rest, Self {
+			id,
+			items,
+			operation,
+			divisor,
+			on_true,
+			on_false,
+			inspections: 0,
+		}))
+	}
+}
+
+/// How a monkey changes an item's worry level when inspecting it.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operation {
+	Add(i64),
+	Mul(i64),
+	#[default]
+	Square,
+}
+
+impl Operation {
+	fn compute(self, old: i64) -> eyre::Result<i64> {
+		match self {
+			Self::Add(a) => old.checked_add(a),
+			Self::Mul(m) => old.checked_mul(m),
+			Self::Square => old.checked_mul(old),
+		}
+		.ok_or_else(|| eyre::eyre!("worry level overflowed computing {self:?}"))
+	}
+}
+
+/// How worry levels are kept from growing without bound.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Relief {
+	/// The worry level is divided by this after each inspection.
+	Divide(i64),
+	/// The worry level is reduced modulo the product of all the monkeys'
+	/// divisors after each inspection.
+	Modulo,
+}
+
+impl Relief {
+	fn apply(self, worry: i64, lcm: i64) -> i64 {
+		match self {
+			Self::Divide(div) => worry / div,
+			Self::Modulo => worry % lcm,
+		}
+	}
+}
+
+impl FromStr for Relief {
+	type Err = eyre::Report;
+
+	/// Parses `modulo`, or `divide:N`.
+	fn from_str(text: &str) -> eyre::Result<Self> {
+		match text.split_once(':') {
+			None if text == "modulo" => Ok(Self::Modulo),
+			Some(("divide", div)) => match div.parse()? {
+				0 => eyre::bail!("cannot divide worry by zero"),
+				div => Ok(Self::Divide(div)),
+			},
+			_ => eyre::bail!("expected `modulo` or `divide:N`, found `{text}`"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_rounds() -> eyre::Result<()> {
+		let text = include_str!("sample.txt");
+		let (_, mut troop) = text.parse_wyz::<Troop>()?;
+		troop.after_parse()?;
+
+		let mut snapshot = troop.clone();
+		snapshot.play_round(Relief::Divide(3))?;
+		assert_eq!(snapshot.monkeys[0].items, [20, 23, 27, 26]);
+		assert_eq!(snapshot.monkeys[1].items, [2080, 25, 167, 207, 401, 1046]);
+		assert!(snapshot.monkeys[2].items.is_empty());
+
+		assert_eq!(troop.part_1()?, 10605);
+		assert_eq!(troop.part_2()?, 2713310158);
+		troop.set_param("rounds", "1")?;
+		troop.set_param("relief", "modulo")?;
+		assert_eq!(troop.part_1()?, 4 * 6);
+		Ok(())
+	}
+}
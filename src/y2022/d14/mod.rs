@@ -0,0 +1,139 @@
+use nom::{
+	bytes::complete::tag,
+	character::complete::newline,
+	multi::separated_list1,
+};
+
+use crate::{
+	coords::spaces::Sparse2D,
+	prelude::*,
+	Coord2D,
+};
+
+crate::register_solver!(y2022, d14, Cave);
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cave {
+	/// The rock drawn by the scan, and any sand which has come to rest.
+	grid:  Sparse2D<i16, Fill>,
+	/// The lowest row holding rock. Anything which falls past it falls forever,
+	/// unless the cave has a floor.
+	depth: i16,
+}
+
+impl Cave {
+	/// Where the sand pours in.
+	pub const SOURCE: Coord2D<i16> = Coord2D::new(500, 0);
+
+	/// Views the cave, with any sand from the most recent pour.
+	pub fn grid(&self) -> &Sparse2D<i16, Fill> {
+		&self.grid
+	}
+
+	/// Pours sand into the cave until it stops coming to rest, and counts the
+	/// grains which did.
+	///
+	/// Without a floor, pouring stops at the first grain to fall past the
+	/// lowest rock. With one, two rows below the lowest rock, pouring stops
+	/// once the sand piles up to cover the source.
+	///
+	/// Every grain follows the previous grain's path down to the point where
+	/// that grain came to rest, so the path is kept as a stack: when a grain
+	/// settles, it is popped, and the next grain resumes falling from the
+	/// point above it instead of from the source. This visits each cell a
+	/// bounded number of times, rather than once per grain.
+	#[cfg_attr(feature = "trace", tracing::instrument(skip(self)))]
+	pub fn pour(&mut self, floor: bool) -> eyre::Result<usize> {
+		self.grid.retain(|_, fill| *fill == Fill::Rock);
+		let floor_y = self.depth + 2;
+		let open = |grid: &Sparse2D<i16, Fill>, pt: Coord2D<i16>| {
+			!grid.contains(pt) && (!floor || pt.y < floor_y)
+		};
+		let mut path = vec![Self::SOURCE];
+		let mut grains = 0;
+		while let Some(&pt) = path.last() {
+			if !floor && pt.y >= self.depth {
+				tracing::debug!(%pt, "sand falls into the abyss");
+				break;
+			}
+			let next = [0, -1, 1]
+				.into_iter()
+				.map(|dx| pt + Coord2D::new(dx, 1))
+				.find(|&next| open(&self.grid, next));
+			match next {
+				Some(next) => path.push(next),
+				None => {
+					crate::cancel::check()?;
+					tracing::trace!(%pt, "grain settles");
+					self.grid.insert(pt, Fill::Sand);
+					path.pop();
+					grains += 1;
+				},
+			}
+		}
+		Ok(grains)
+	}
+}
+
+impl<'a> Parsed<&'a str> for Cave {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let (rest, paths) = separated_list1(
+			newline,
+			separated_list1(tag(" -> "), Coord2D::<i16>::parse_wyz),
+		)(text)?;
+		let mut grid = Sparse2D::new();
+		if grid.draw_paths(paths, Fill::Rock).is_err() {
+			return Err(nom::Err::Failure(nom::error::Error::new(
+				text,
+				nom::error::ErrorKind::Verify,
+			)));
+		}
+		let depth = grid.iter().map(|(pt, _)| pt.y).max().unwrap_or_default();
+		Ok((rest, Self { grid, depth }))
+	}
+}
+
+impl Puzzle for Cave {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		let rock = self.grid.iter().filter(|(_, f)| **f == Fill::Rock).count();
+		vec![
+			("rock", rock.to_string()),
+			("depth", self.depth.to_string()),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.pour(false)? as i64)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		Ok(self.pour(true)? as i64)
+	}
+
+	fn render_state(&self) -> Option<String> {
+		Some(self.grid.to_string())
+	}
+}
+
+/// The contents of a cell in the cave.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fill {
+	Rock,
+	Sand,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_sand() -> eyre::Result<()> {
+		let (_, mut cave) = include_str!("sample.txt").parse_wyz::<Cave>()?;
+		assert_eq!(cave.part_1()?, 24);
+		assert_eq!(cave.part_2()?, 93);
+		assert_eq!(cave.part_1()?, 24);
+		Ok(())
+	}
+}
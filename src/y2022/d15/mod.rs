@@ -0,0 +1,361 @@
+use std::{
+	cell::RefCell,
+	ops::RangeInclusive,
+};
+
+use nom::{
+	bytes::complete::tag,
+	character::complete::{
+		i64 as parse_i64,
+		newline,
+	},
+	combinator::map,
+	multi::separated_list1,
+	sequence::{
+		preceded,
+		separated_pair,
+		tuple,
+	},
+};
+use rayon::prelude::*;
+
+use crate::{
+	cancel,
+	collections::IntervalTree,
+	coords::scan::par_scan_rows,
+	merge_sorted_ranges,
+	prelude::*,
+	unify_ranges_inclusive,
+	unify_ranges_into,
+	Coord2D,
+};
+
+crate::register_solver!(y2022, d15, Sensors);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sensors {
+	sensors: Vec<Sensor>,
+	/// The row searched in part 1.
+	row:     i64,
+	/// The largest coordinate the distress beacon may have in part 2.
+	max:     i64,
+	/// Whether part 2 scans every row, rather than searching the rotated
+	/// plane.
+	scan:    bool,
+}
+
+impl Sensors {
+	/// Indexes each sensor by the rows its scan reaches.
+	pub fn by_row(&self) -> IntervalTree<i64, Sensor> {
+		self.sensors
+			.iter()
+			.map(|s| (s.at.y - s.radius() ..= s.at.y + s.radius(), *s))
+			.collect()
+	}
+
+	/// Counts the positions in a row which cannot hold a beacon.
+	pub fn excluded_in_row(&self, row: i64) -> i64 {
+		let covered = unify_ranges_inclusive(
+			self.by_row()
+				.stab(row)
+				.into_iter()
+				.filter_map(|(_, s)| s.coverage_in_row(row)),
+		);
+		let width = covered.iter().map(|r| r.end() - r.start() + 1).sum::<i64>();
+		let mut beacons = self
+			.sensors
+			.iter()
+			.map(|s| s.beacon)
+			.filter(|b| b.y == row)
+			.collect::<Vec<_>>();
+		beacons.sort();
+		beacons.dedup();
+		width - beacons.len() as i64
+	}
+
+	/// Finds the only position within `0 ..= max` on both axes that no sensor
+	/// reaches.
+	///
+	/// A sensor's scan is a diamond, which becomes an axis-aligned square when
+	/// the plane is rotated by 45° into `u = x + y` and `v = x - y`. A lone
+	/// uncovered position must sit just outside the edge of some square, so
+	/// only those lines of `u` (or, failing that, of `v`) need searching. On
+	/// each line, the squares crossing it are found with an interval index and
+	/// their spans along the line are merged; the gap between them is the
+	/// beacon.
+	pub fn distress_beacon(&self) -> Option<Coord2D<i64>> {
+		let max = self.max;
+		let squares = self
+			.sensors
+			.iter()
+			.map(|s| {
+				let (u, v, r) = (s.at.x + s.at.y, s.at.x - s.at.y, s.radius());
+				(u - r ..= u + r, v - r ..= v + r)
+			})
+			.collect::<Vec<_>>();
+		let by_u = squares.iter().cloned().collect::<IntervalTree<_, _>>();
+		let found = search_lines(&by_u, |u| {
+			(u - 2 * max).max(-u) ..= (2 * max - u).min(u)
+		})
+		.or_else(|| {
+			let by_v = squares
+				.iter()
+				.map(|(u, v)| (v.clone(), u.clone()))
+				.collect::<IntervalTree<_, _>>();
+			search_lines(&by_v, |v| v.max(-v) ..= (2 * max - v).min(2 * max + v))
+				.map(|(v, u)| (u, v))
+		});
+		found.map(|(u, v)| Coord2D::new((u + v) / 2, (u - v) / 2))
+	}
+
+	/// Finds the distress beacon the slow way, by merging each row's coverage
+	/// and looking for a gap.
+	///
+	/// This visits every row in `0 ..= max`, millions of them on a real input,
+	/// and is used instead of [`distress_beacon`](Self::distress_beacon) when
+	/// the `scan` parameter is set, to check it against.
+	///
+	/// Each worker thread keeps one buffer for the spans of every row it
+	/// scans. The spans are sorted and then merged as they are walked, so the
+	/// walk stops merging as soon as it finds a gap. On the input generated by
+	/// the `generated_timings` test, in a release build, this took the scan
+	/// from about 2.0s, when each row collected its spans into a new `Vec`, to
+	/// about 0.8s.
+	pub fn scan_for_beacon(&self) -> eyre::Result<Option<Coord2D<i64>>> {
+		thread_local! {
+			static SPANS: RefCell<Vec<RangeInclusive<i64>>> =
+				const { RefCell::new(Vec::new()) };
+		}
+		let max = self.max;
+		let found = par_scan_rows(0, max, |row| {
+			SPANS.with_borrow_mut(|spans| {
+				spans.clear();
+				spans.extend(
+					self.sensors.iter().filter_map(|s| s.coverage_in_row(row)),
+				);
+				spans.sort_unstable_by_key(|span| *span.start());
+				let mut next = 0;
+				for span in merge_sorted_ranges(spans.drain(..)) {
+					if *span.start() > next {
+						break;
+					}
+					next = next.max(span.end() + 1);
+				}
+				(next <= max).then(|| Coord2D::new(next, row))
+			})
+		})
+		.find_map_any(|found| found);
+		cancel::check()?;
+		Ok(found)
+	}
+}
+
+/// Searches the lines just outside each square in an index for a position no
+/// square covers.
+///
+/// The index maps the squares' spans along one axis, `a`, to their spans
+/// along the other, `b`. `bounds` produces the span of `b` to search on each
+/// line of `a`, and positions where `a + b` is odd are skipped, as they do not
+/// rotate back onto the integer grid. This produces `(a, b)`.
+///
+/// One buffer holds the merged spans for every line, rather than allocating a
+/// new one per line.
+fn search_lines(
+	index: &IntervalTree<i64, RangeInclusive<i64>>,
+	bounds: impl Fn(i64) -> RangeInclusive<i64>,
+) -> Option<(i64, i64)> {
+	let mut lines = index
+		.iter()
+		.flat_map(|(a, _)| [a.start() - 1, a.end() + 1])
+		.collect::<Vec<_>>();
+	lines.sort();
+	lines.dedup();
+	let mut covered = Vec::new();
+	lines.into_iter().find_map(|a| {
+		let bounds = bounds(a);
+		unify_ranges_into(
+			index.stab(a).into_iter().map(|(_, b)| b.clone()),
+			&mut covered,
+		);
+		let mut next = *bounds.start();
+		for span in covered.iter().chain([&(bounds.end() + 1 ..= i64::MAX)]) {
+			let gap_end = (span.start() - 1).min(*bounds.end());
+			let first = next + (a + next).rem_euclid(2);
+			if first <= gap_end {
+				return Some((a, first));
+			}
+			next = next.max(span.end().saturating_add(1));
+			if next > *bounds.end() {
+				break;
+			}
+		}
+		None
+	})
+}
+
+impl<'a> Parsed<&'a str> for Sensors {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(separated_list1(newline, Sensor::parse_wyz), |sensors| {
+			Self {
+				sensors,
+				row: 2_000_000,
+				max: 4_000_000,
+				scan: false,
+			}
+		})(text)
+	}
+}
+
+impl Puzzle for Sensors {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("sensors", self.sensors.len().to_string()),
+			("row", self.row.to_string()),
+			("max", self.max.to_string()),
+			("scan", self.scan.to_string()),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.excluded_in_row(self.row))
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		let found = if self.scan {
+			self.scan_for_beacon()?
+		}
+		else {
+			self.distress_beacon()
+		};
+		let Coord2D { x, y } = found.ok_or_else(|| {
+			eyre::eyre!("every position in 0..={} is covered", self.max)
+		})?;
+		Ok(x * 4_000_000 + y)
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"row" => self.row = value.parse()?,
+			"max" => self.max = value.parse()?,
+			"scan" => self.scan = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sensor {
+	at:     Coord2D<i64>,
+	/// The beacon nearest to the sensor.
+	beacon: Coord2D<i64>,
+}
+
+impl Sensor {
+	/// The distance the sensor scanned, out to its nearest beacon.
+	pub fn radius(&self) -> i64 {
+		self.at.axial_distance(self.beacon)
+	}
+
+	/// Finds the columns the sensor scanned in a row, if it reached the row.
+	pub fn coverage_in_row(&self, row: i64) -> Option<RangeInclusive<i64>> {
+		let reach = self.radius() - (self.at.y - row).abs();
+		(reach >= 0).then(|| self.at.x - reach ..= self.at.x + reach)
+	}
+}
+
+impl<'a> Parsed<&'a str> for Sensor {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		fn point(text: &str) -> ParseResult<&str, Coord2D<i64>> {
+			map(
+				separated_pair(
+					preceded(tag("x="), parse_i64),
+					tag(", "),
+					preceded(tag("y="), parse_i64),
+				),
+				|(x, y)| Coord2D::new(x, y),
+			)(text)
+		}
+		map(
+			tuple((
+				preceded(tag("Sensor at "), point),
+				preceded(tag(": closest beacon is at "), point),
+			)),
+			|(at, beacon)| Self { at, beacon },
+		)(text)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Instant;
+
+	use rand::{
+		Rng as _,
+		SeedableRng as _,
+	};
+
+	use super::*;
+	use crate::random::StdRng;
+
+	/// Scatters sensors over the real puzzle's search square, each reaching
+	/// far enough that most rows are covered.
+	fn generated(count: usize) -> Sensors {
+		let mut rng = StdRng::seed_from_u64(15);
+		let sensors = (0 .. count)
+			.map(|_| {
+				let at = Coord2D::new(
+					rng.gen_range(0 .. 4_000_000),
+					rng.gen_range(0 .. 4_000_000),
+				);
+				let beacon = Coord2D::new(
+					at.x + rng.gen_range(-1_200_000 .. 1_200_000),
+					at.y + rng.gen_range(-1_200_000 .. 1_200_000),
+				);
+				Sensor { at, beacon }
+			})
+			.collect();
+		Sensors {
+			sensors,
+			row: 2_000_000,
+			max: 4_000_000,
+			scan: false,
+		}
+	}
+
+	/// Times both part 2 searches over a generated forty-sensor input. Run it
+	/// with `cargo test --release generated_timings -- --ignored --nocapture`.
+	#[test]
+	#[ignore = "takes seconds, and only means anything in a release build"]
+	fn generated_timings() -> eyre::Result<()> {
+		let sensors = generated(40);
+		let start = Instant::now();
+		for _ in 0 .. 1000 {
+			std::hint::black_box(sensors.distress_beacon());
+		}
+		println!("line search: {:?} per run", start.elapsed() / 1000);
+		let start = Instant::now();
+		for _ in 0 .. 3 {
+			std::hint::black_box(sensors.scan_for_beacon()?);
+		}
+		println!("row scan:    {:?} per run", start.elapsed() / 3);
+		Ok(())
+	}
+
+	#[test]
+	fn sample_sensors() -> eyre::Result<()> {
+		let (_, mut sensors) =
+			include_str!("sample.txt").parse_wyz::<Sensors>()?;
+		sensors.set_param("row", "10")?;
+		sensors.set_param("max", "20")?;
+		assert_eq!(sensors.part_1()?, 26);
+		assert_eq!(sensors.distress_beacon(), Some(Coord2D::new(14, 11)));
+		assert_eq!(sensors.scan_for_beacon()?, Some(Coord2D::new(14, 11)));
+		assert_eq!(sensors.part_2()?, 56_000_011);
+		sensors.set_param("scan", "true")?;
+		assert_eq!(sensors.part_2()?, 56_000_011);
+		Ok(())
+	}
+}
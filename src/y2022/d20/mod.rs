@@ -0,0 +1,113 @@
+use nom::{
+	character::complete::{
+		i64 as get_i64,
+		newline,
+	},
+	combinator::map,
+	multi::separated_list1,
+};
+
+use crate::{
+	modular::ModIdx,
+	prelude::*,
+	Difficulty,
+};
+
+crate::register_solver!(y2022, d20, File);
+
+/// How far past the zero the grove coordinates are.
+const MARKERS: [usize; 3] = [1000, 2000, 3000];
+
+/// What every number is multiplied by before mixing, in part 2.
+const DECRYPTION_KEY: i64 = 811589153;
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct File {
+	/// The encrypted numbers, in their original order.
+	numbers: Vec<i64>,
+}
+
+impl File {
+	/// Mixes the numbers `rounds` times, after multiplying them by `key`, and
+	/// sums the grove coordinates.
+	///
+	/// The arrangement is kept as a list of indices into the original numbers.
+	/// Each number is taken out of the circle, moved by its value, and put
+	/// back. While it is out, the circle is one shorter, so the move is
+	/// measured in a ring of `len - 1` slots.
+	#[cfg_attr(feature = "trace", tracing::instrument(skip(self)))]
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	fn decrypt(&self, key: i64, rounds: usize) -> eyre::Result<i64> {
+		let len = self.numbers.len();
+		if len < 2 {
+			eyre::bail!("cannot mix fewer than two numbers");
+		}
+		let numbers = self.numbers.iter().map(|&n| n * key).collect::<Vec<_>>();
+		let mut order = (0 .. len).collect::<Vec<_>>();
+		for round in 0 .. rounds {
+			crate::cancel::check()?;
+			tracing::debug!(round, "mixing");
+			for (idx, &number) in numbers.iter().enumerate() {
+				let from = order
+					.iter()
+					.position(|&i| i == idx)
+					.expect("every number is in the circle");
+				order.remove(from);
+				let to = ModIdx::new(from, len - 1) + number;
+				order.insert(to.value(), idx);
+			}
+		}
+
+		let zero = order
+			.iter()
+			.position(|&i| numbers[i] == 0)
+			.ok_or_else(|| AocError::InvalidInput("there is no zero".into()))?;
+		let zero = ModIdx::new(zero, len);
+		Ok(MARKERS
+			.into_iter()
+			.map(|offset| numbers[order[(zero + offset).value()]])
+			.sum())
+	}
+}
+
+impl<'a> Parsed<&'a str> for File {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(separated_list1(newline, get_i64), |numbers| Self {
+			numbers,
+		})(text)
+	}
+}
+
+impl Puzzle for File {
+	/// Each mixing round moves every number through a list as long as the
+	/// input, and part 2 mixes ten times.
+	fn estimated_difficulty() -> Difficulty {
+		Difficulty::Moderate
+	}
+
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![("numbers", self.numbers.len().to_string())]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		self.decrypt(1, 1)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		self.decrypt(DECRYPTION_KEY, 10)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_mixing() -> eyre::Result<()> {
+		let (_, mut file) = include_str!("sample.txt").parse_wyz::<File>()?;
+		assert_eq!(file.part_1()?, 3);
+		assert_eq!(file.part_2()?, 1623178306);
+		Ok(())
+	}
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{
+		alpha1,
+		newline,
+	},
+	combinator::{
+		map,
+		value,
+	},
+	multi::separated_list1,
+	sequence::{
+		separated_pair,
+		tuple,
+	},
+};
+
+use crate::{
+	algebra::{
+		self,
+		Expr,
+		Op,
+		Rational,
+	},
+	parse_number,
+	prelude::*,
+	propagate::{
+		self,
+		Expression,
+	},
+};
+
+crate::register_solver!(y2022, d21, Troop);
+
+/// The monkey whose number answers part 1, and whose operands must match in
+/// part 2.
+const ROOT: &str = "root";
+/// The name which actually belongs to the human.
+const HUMAN: &str = "humn";
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Troop {
+	/// What each monkey yells, by name.
+	jobs: BTreeMap<String, Job>,
+}
+
+impl Troop {
+	/// Sets up a solver for what every monkey yells.
+	fn solver(&self) -> propagate::Solver<String, Rational, Job> {
+		propagate::Solver::new(self.jobs.clone())
+	}
+
+	/// Builds the expression that a monkey yells, in terms of the human's
+	/// number.
+	///
+	/// Monkeys whose numbers the solver already knows become constants, so
+	/// only the stuck chain down to the human is built out.
+	fn expression<'a>(
+		&'a self,
+		solver: &propagate::Solver<String, Rational, Job>,
+		name: &'a str,
+		stack: &mut Vec<&'a str>,
+	) -> eyre::Result<Expr> {
+		if name == HUMAN {
+			return Ok(Expr::Unknown);
+		}
+		if let Some(&value) = solver.get(&name.to_owned()) {
+			return Ok(Expr::Const(value));
+		}
+		if stack.contains(&name) {
+			eyre::bail!("monkey {name} depends on itself");
+		}
+		let Some(Job::Math(lhs, op, rhs)) = self.jobs.get(name)
+		else {
+			eyre::bail!("no monkey named {name}");
+		};
+		stack.push(name);
+		let lhs = self.expression(solver, lhs, stack)?;
+		let rhs = self.expression(solver, rhs, stack)?;
+		stack.pop();
+		Ok(Expr::apply(lhs, *op, rhs))
+	}
+}
+
+impl<'a> Parsed<&'a str> for Troop {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let op = alt((
+			value(Op::Add, tag(" + ")),
+			value(Op::Sub, tag(" - ")),
+			value(Op::Mul, tag(" * ")),
+			value(Op::Div, tag(" / ")),
+		));
+		let job = alt((
+			map(parse_number::<i64>, Job::Number),
+			map(
+				tuple((alpha1, op, alpha1)),
+				|(lhs, op, rhs): (&str, _, &str)| {
+					Job::Math(lhs.to_owned(), op, rhs.to_owned())
+				},
+			),
+		));
+		map(
+			separated_list1(newline, separated_pair(alpha1, tag(": "), job)),
+			|jobs| Self {
+				jobs: jobs
+					.into_iter()
+					.map(|(name, job)| (name.to_owned(), job))
+					.collect(),
+			},
+		)(text)
+	}
+}
+
+impl Puzzle for Troop {
+	fn after_parse(&mut self) -> eyre::Result<()> {
+		match self.jobs.get(ROOT) {
+			Some(Job::Math(..)) => Ok(()),
+			Some(Job::Number(_)) => {
+				eyre::bail!("{ROOT} must combine two monkeys")
+			},
+			None => eyre::bail!("no monkey named {ROOT}"),
+		}
+	}
+
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		let numbers = self
+			.jobs
+			.values()
+			.filter(|job| matches!(job, Job::Number(_)))
+			.count();
+		vec![
+			("monkeys", self.jobs.len().to_string()),
+			("yelling numbers", numbers.to_string()),
+			("doing math", (self.jobs.len() - numbers).to_string()),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		algebra::to_integer(self.solver().solve(&ROOT.to_owned())?)
+	}
+
+	/// The human's number is unknown, so everything which depends on it is
+	/// stuck. What remains of the root's two sides is folded into linear
+	/// expressions of the human's number, and set equal to each other.
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		let Some(Job::Math(lhs, _, rhs)) = self.jobs.get(ROOT)
+		else {
+			eyre::bail!("{ROOT} must combine two monkeys");
+		};
+		let mut solver = self.solver();
+		solver.forget(&HUMAN.to_owned());
+		solver.reduce()?;
+		let lhs = self.expression(&solver, lhs, &mut Vec::new())?;
+		let rhs = self.expression(&solver, rhs, &mut Vec::new())?;
+		if !lhs.has_unknown() && !rhs.has_unknown() {
+			eyre::bail!("{ROOT} does not depend on {HUMAN}");
+		}
+		tracing::debug!(%lhs, %rhs, "solving for {HUMAN}");
+		algebra::to_integer(algebra::solve_eq(&lhs, &rhs)?)
+	}
+}
+
+/// What a monkey yells.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Job {
+	/// A specific number.
+	Number(i64),
+	/// The result of an operation on what two other monkeys yell.
+	Math(String, Op, String),
+}
+
+impl Expression<String, Rational> for Job {
+	fn operands(&self) -> Vec<&String> {
+		match self {
+			Self::Number(_) => vec![],
+			Self::Math(lhs, _, rhs) => vec![lhs, rhs],
+		}
+	}
+
+	fn evaluate(
+		&self,
+		operand: impl Fn(&String) -> Rational,
+	) -> eyre::Result<Rational> {
+		match self {
+			&Self::Number(num) => Ok(Rational::from_integer(num.into())),
+			Self::Math(lhs, op, rhs) => op.evaluate(operand(lhs), operand(rhs)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_riddle() -> eyre::Result<()> {
+		let (_, mut troop) = include_str!("sample.txt").parse_wyz::<Troop>()?;
+		troop.after_parse()?;
+		assert_eq!(troop.part_1()?, 152);
+		assert_eq!(troop.part_2()?, 301);
+		Ok(())
+	}
+}
@@ -6,3 +6,9 @@ pub mod d05;
 pub mod d06;
 pub mod d07;
 pub mod d08;
+pub mod d09;
+pub mod d11;
+pub mod d14;
+pub mod d15;
+pub mod d20;
+pub mod d21;
@@ -2,12 +2,10 @@ use tap::Pipe;
 
 use crate::{
 	prelude::*,
-	written_number,
+	written_digit,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2023, 1, |t| t.parse_dyn_puzzle::<Calibration>());
+crate::register_solver!(y2023, d01, Calibration);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -38,7 +36,7 @@ impl<'a> Parsed<&'a str> for Calibration {
 			let (mut ten, mut one) = (None, None);
 			for (idx, sym) in line.char_indices() {
 				let rest = &line[idx ..];
-				let num = if let Ok((_, num)) = written_number::<i64>(rest) {
+				let num = if let Ok((_, num)) = written_digit::<i64>(rest) {
 					num
 				}
 				else if sym.is_ascii_digit() {
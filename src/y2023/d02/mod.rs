@@ -22,8 +22,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 2, |t| t.parse_dyn_puzzle::<GameSet>());
+crate::register_solver!(y2023, d02, GameSet);
 
 static FILTER: Record = Record {
 	red:   12,
@@ -24,8 +24,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 4, |t| t.parse_dyn_puzzle::<Lottery>());
+crate::register_solver!(y2023, d04, Lottery);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -82,6 +81,7 @@ impl Card {
 }
 
 impl<'a> Parsed<&'a str> for Card {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
 		let (text, ident) = delimited(
 			terminated(tag("Card"), space1),
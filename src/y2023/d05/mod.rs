@@ -3,25 +3,39 @@ use std::ops::Range;
 use nom::{
 	bytes::complete::tag,
 	character::complete::{
-		newline,
+		alpha1,
+		line_ending,
 		space1,
 	},
 	multi::many1,
 	sequence::{
-		delimited,
+		pair,
 		preceded,
+		separated_pair,
+		terminated,
 	},
-	IResult,
 };
 use tap::TapFallible;
 
 use crate::{
+	blocks,
 	parse_number,
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 5, |t| t.parse_dyn_puzzle::<Lookup>());
+crate::register_solver!(y2023, d05, Lookup);
+
+/// The kinds of thing the almanac relates, in the order its maps chain them.
+const STAGES: [&str; 8] = [
+	"seed",
+	"soil",
+	"fertilizer",
+	"water",
+	"light",
+	"temperature",
+	"humidity",
+	"location",
+];
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -32,13 +46,14 @@ pub struct Lookup {
 }
 
 impl<'a> Parsed<&'a str> for Lookup {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn parse_wyz(text: &str) -> ParseResult<&str, Lookup> {
 		let (text, seeds) = preceded(
 			tag("seeds:"),
 			many1(preceded(space1, parse_number::<i64>)),
 		)(text)
 		.tap_err(|err| tracing::error!(?err, "could not parse the seed list"))?;
-		let (text, almanac) = text.trim_start().parse_wyz().tap_err(|err| {
+		let (text, almanac) = text.parse_wyz().tap_err(|err| {
 			tracing::error!(?err, "could not parse an almanac")
 		})?;
 		Ok((text, Self {
@@ -50,6 +65,7 @@ impl<'a> Parsed<&'a str> for Lookup {
 }
 
 impl Puzzle for Lookup {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn part_1(&mut self) -> eyre::Result<i64> {
 		self.almanac
 			.min_location(
@@ -74,21 +90,11 @@ impl Puzzle for Lookup {
 	}
 
 	fn part_2(&mut self) -> eyre::Result<i64> {
+		// The seed ranges are around two billion seeds long, so rather than
+		// looking each one up, push whole ranges through each layer of the
+		// almanac, splitting them wherever they straddle a relation boundary.
 		self.almanac
-			.min_location(
-				// This is a brutally slow and stupid way to run the solver.
-				// Checking every single entry in the range description, which
-				// the author helpfully made to be around two billion, is the
-				// kind of naïve idiocy that only works because this is a Rust
-				// program with a very tight inner loop.
-				self.seed_ranges
-					.iter()
-					.cloned()
-					.inspect(
-						|Range { start, end }| tracing::debug!(bgn = ?start, ?end, len = end - start),
-					)
-					.flat_map(|range| range.into_iter()),
-			)
+			.min_location_of_ranges(self.seed_ranges.iter().cloned())
 			.ok_or_else(|| eyre::eyre!("had no input seeds"))
 	}
 }
@@ -135,76 +141,100 @@ impl Almanac {
 			.min()
 	}
 
+	/// Finds the lowest location reachable from any seed in a set of ranges.
+	///
+	/// This is equivalent to calling [`Self::min_location`] with every seed in
+	/// the ranges, but runs in time proportional to the number of relation
+	/// boundaries rather than the number of seeds.
+	pub fn min_location_of_ranges(
+		&self,
+		seeds: impl IntoIterator<Item = Range<i64>>,
+	) -> Option<i64> {
+		let seeds = seeds.into_iter().filter(|r| !r.is_empty()).collect();
+		self.layers()
+			.into_iter()
+			.fold(seeds, |ranges, layer| Self::lookup_ranges(layer, ranges))
+			.into_iter()
+			.map(|range| range.start)
+			.min()
+	}
+
+	/// Lists each layer of the almanac, from seeds to locations.
+	fn layers(&self) -> [&[Relation]; 7] {
+		[
+			&self.seed_soil,
+			&self.soil_fertilizer,
+			&self.fertilizer_water,
+			&self.water_light,
+			&self.light_temperature,
+			&self.temperature_humidity,
+			&self.humidity_location,
+		]
+	}
+
 	fn lookup(maps: &[Relation], key: i64) -> i64 {
 		maps.iter()
 			.filter_map(|rel| rel.lookup(key))
 			.next()
 			.unwrap_or(key)
 	}
+
+	/// Maps a set of ranges through one layer of the almanac.
+	///
+	/// Each relation claims the parts of the pending ranges that fall inside
+	/// its origin span, and translates them. Whatever no relation claims is
+	/// passed through unchanged.
+	fn lookup_ranges(
+		maps: &[Relation],
+		ranges: Vec<Range<i64>>,
+	) -> Vec<Range<i64>> {
+		let mut mapped = Vec::with_capacity(ranges.len());
+		let pending = maps.iter().fold(ranges, |pending, rel| {
+			let mut unmapped = Vec::with_capacity(pending.len());
+			for range in pending {
+				let (hit, misses) = rel.lookup_range(range);
+				mapped.extend(hit);
+				unmapped.extend(misses.into_iter().flatten());
+			}
+			unmapped
+		});
+		mapped.extend(pending);
+		mapped
+	}
 }
 
 impl<'a> Parsed<&'a str> for Almanac {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
-		fn header<'a>(
-			from: &'static str,
-			into: &'static str,
-		) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
-			move |t| {
-				delimited(
-					delimited(tag(from), tag("-to-"), tag(into)),
-					tag(" map:"),
-					newline,
-				)(t)
-			}
-		}
-		let (text, seed_soil) = preceded(
-			header("seed", "soil"),
-			many1(Relation::parse_wyz),
-		)(text.trim_start())
-		.tap_err(|err| tracing::error!(?err, "could not parse seed/soil map"))?;
-		let (text, soil_fertilizer) = preceded(
-			header("soil", "fertilizer"),
-			many1(Relation::parse_wyz),
-		)(text.trim_start())
-		.tap_err(|err| {
-			tracing::error!(?err, "could not parse soil/fertilizer map")
-		})?;
-		let (text, fertilizer_water) = preceded(
-			header("fertilizer", "water"),
-			many1(Relation::parse_wyz),
-		)(text.trim_start())
-		.tap_err(|err| {
-			tracing::error!(?err, "could not parse fertilizer/water map")
-		})?;
-		let (text, water_light) = preceded(
-			header("water", "light"),
-			many1(Relation::parse_wyz),
-		)(text.trim_start())
-		.tap_err(|err| {
-			tracing::error!(?err, "could not parse water/light map")
-		})?;
-		let (text, light_temperature) = preceded(
-			header("light", "temperature"),
+		let header = terminated(
+			separated_pair(alpha1, tag("-to-"), alpha1),
+			pair(tag(" map:"), line_ending),
+		);
+		let (rest, maps) = blocks::<_, Vec<_>, _>(pair(
+			header,
 			many1(Relation::parse_wyz),
-		)(text.trim_start())
-		.tap_err(|err| {
-			tracing::error!(?err, "could not parse light/temperature map")
-		})?;
-		let (text, temperature_humidity) = preceded(
-			header("temperature", "humidity"),
-			many1(Relation::parse_wyz),
-		)(text.trim_start())
-		.tap_err(|err| {
-			tracing::error!(?err, "could not parse temperature/humidity map")
-		})?;
-		let (text, humidity_location) = preceded(
-			header("humidity", "location"),
-			many1(Relation::parse_wyz),
-		)(text.trim_start())
-		.tap_err(|err| {
-			tracing::error!(?err, "could not parse humidity/location map")
-		})?;
-		Ok((text, Self {
+		))(text)
+		.tap_err(|err| tracing::error!(?err, "could not parse the maps"))?;
+		// The maps must chain each stage into the next, in order.
+		let chained = maps.len() == STAGES.len() - 1
+			&& maps
+				.iter()
+				.zip(STAGES.windows(2))
+				.all(|(&((from, into), _), stages)| [from, into] == stages);
+		if !chained {
+			let found = maps.iter().map(|(names, _)| names).collect::<Vec<_>>();
+			tracing::error!(?found, "the maps do not chain seeds to locations");
+			return Err(nom::Err::Failure(nom::error::Error::new(
+				text,
+				nom::error::ErrorKind::Verify,
+			)));
+		}
+		let [seed_soil, soil_fertilizer, fertilizer_water, water_light, light_temperature, temperature_humidity, humidity_location] =
+			<[_; 7]>::try_from(
+				maps.into_iter().map(|(_, map)| map).collect::<Vec<_>>(),
+			)
+			.expect("the maps were counted above");
+		Ok((rest, Self {
 			seed_soil,
 			soil_fertilizer,
 			fertilizer_water,
@@ -233,6 +263,28 @@ impl Relation {
 		}
 		None
 	}
+
+	/// Splits a range of keys at this relation's boundaries.
+	///
+	/// Returns the translated image of whatever part of the range lies inside
+	/// this relation's origin span (if any), and the parts that lie before and
+	/// after it, untranslated.
+	fn lookup_range(
+		&self,
+		Range { start, end }: Range<i64>,
+	) -> (Option<Range<i64>>, [Option<Range<i64>>; 2]) {
+		let orig_end = self.orig + self.span;
+		let nonempty = |r: Range<i64>| (!r.is_empty()).then_some(r);
+		let before = nonempty(start .. end.min(self.orig));
+		let after = nonempty(start.max(orig_end) .. end);
+		let inside = nonempty(start.max(self.orig) .. end.min(orig_end)).map(
+			|Range { start, end }| {
+				let shift = self.dest - self.orig;
+				start + shift .. end + shift
+			},
+		);
+		(inside, [before, after])
+	}
 }
 
 impl<'a> Parsed<&'a str> for Relation {
@@ -243,3 +295,37 @@ impl<'a> Parsed<&'a str> for Relation {
 		Ok((text, Self { orig, dest, span }))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn split_relation() {
+		let rel = Relation {
+			orig: 10,
+			dest: 100,
+			span: 5,
+		};
+		assert_eq!(
+			rel.lookup_range(0 .. 20),
+			(Some(100 .. 105), [Some(0 .. 10), Some(15 .. 20)])
+		);
+		assert_eq!(rel.lookup_range(12 .. 13), (Some(102 .. 103), [None, None]));
+		assert_eq!(rel.lookup_range(20 .. 30), (None, [None, Some(20 .. 30)]));
+	}
+
+	#[test]
+	fn ranges_match_brute_force() -> eyre::Result<()> {
+		let text = include_str!("sample.txt");
+		let (_, mut lookup) = text.parse_wyz::<Lookup>()?;
+		assert_eq!(lookup.part_1()?, 35);
+		lookup.prepare_2()?;
+		let brute = lookup
+			.almanac
+			.min_location(lookup.seed_ranges.iter().cloned().flatten());
+		assert_eq!(brute, Some(46));
+		assert_eq!(lookup.part_2()?, 46);
+		Ok(())
+	}
+}
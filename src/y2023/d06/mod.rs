@@ -21,8 +21,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 6, |t| t.parse_dyn_puzzle::<Races>());
+crate::register_solver!(y2023, d06, Races);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -31,6 +30,7 @@ pub struct Races {
 }
 
 impl Races {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn execute(&self) -> eyre::Result<i64> {
 		self.races
 			.iter()
@@ -48,6 +48,7 @@ impl Races {
 }
 
 impl<'a> Parsed<&'a str> for Races {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
 		let (text, times) = delimited(
 			tag("Time:"),
@@ -10,27 +10,38 @@ use nom::{
 };
 use tap::TapFallible;
 
-use crate::prelude::*;
+use crate::{
+	dict::{
+		Dictionary,
+		Identifier,
+	},
+	prelude::*,
+};
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 8, |t| t.parse_dyn_puzzle::<Maps>());
+crate::register_solver!(y2023, d08, Maps);
 
+#[derive(Clone, Debug)]
 pub struct Maps {
 	switches: String,
-	strings:  BTreeMap<String, CacheKey>,
-	cache:    BTreeMap<CacheKey, String>,
-	graph:    BTreeMap<CacheKey, (CacheKey, CacheKey)>,
+	names:    Dictionary<str, Node>,
+	graph:    BTreeMap<NodeId, (NodeId, NodeId)>,
 }
 
+/// Namespace for the names of nodes.
+#[derive(Clone, Copy, Debug)]
+pub enum Node {}
+
+/// Identifies a node by its interned name.
+pub type NodeId = Identifier<Node>;
+
 impl<'a> Parsed<&'a str> for Maps {
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
 		let mut lines = text.lines();
 		let switches =
 			lines.next().expect("must have a sequence line").to_owned();
 		let _ = lines.next();
-		let mut strings = BTreeMap::new();
+		let mut names = Dictionary::new();
 		let mut graph = BTreeMap::new();
-		let mut key_source = (0 ..).map(|key| CacheKey { key });
 		for line in lines {
 			let (_, (from, (left, right))) = separated_pair(
 				alphanumeric1,
@@ -41,31 +52,13 @@ impl<'a> Parsed<&'a str> for Maps {
 					tag(")"),
 				),
 			)(line)?;
-			let from_key = strings.get(from).copied().unwrap_or_else(|| {
-				let key = key_source.next().unwrap();
-				strings.insert(from.to_owned(), key);
-				key
-			});
-			let left_key = strings.get(left).copied().unwrap_or_else(|| {
-				let key = key_source.next().unwrap();
-				strings.insert(left.to_owned(), key);
-				key
-			});
-			let right_key = strings.get(right).copied().unwrap_or_else(|| {
-				let key = key_source.next().unwrap();
-				strings.insert(right.to_owned(), key);
-				key
-			});
-			graph.insert(from_key, (left_key, right_key));
+			let [from, left, right] =
+				[from, left, right].map(|name| names.insert(name));
+			graph.insert(from, (left, right));
 		}
-		let cache = strings
-			.iter()
-			.map(|(key, &val)| (val, key.clone()))
-			.collect();
 		Ok(("", Self {
 			switches,
-			strings,
-			cache,
+			names,
 			graph,
 		}))
 	}
@@ -73,13 +66,13 @@ impl<'a> Parsed<&'a str> for Maps {
 
 impl Puzzle for Maps {
 	fn part_1(&mut self) -> eyre::Result<i64> {
-		let &bgn = self
-			.strings
-			.get("AAA")
+		let bgn = self
+			.names
+			.lookup_value("AAA")
 			.ok_or_else(|| eyre::eyre!("missing the AAA starting node"))?;
-		let &end = self
-			.strings
-			.get("ZZZ")
+		let end = self
+			.names
+			.lookup_value("ZZZ")
 			.ok_or_else(|| eyre::eyre!("missing the ZZZ ending node"))?;
 		let mut steps = 0;
 		let mut current = bgn;
@@ -94,22 +87,26 @@ impl Puzzle for Maps {
 		Ok(steps)
 	}
 
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn part_2(&mut self) -> eyre::Result<i64> {
-		self.strings
-			.iter()
-			.filter(|(text, _)| text.ends_with("A"))
-			.map(|(_, &key)| self.seek_any_endpoint(key))
+		let steps = self
+			.names
+			.identifiers()
+			.filter(|&key| self.names[key].ends_with("A"))
+			.map(|key| self.seek_any_endpoint(key))
 			.filter_map(|res| res.tap_err(|err| tracing::error!("{err}")).ok())
-			.reduce(num::integer::lcm)
-			.ok_or_else(|| eyre::eyre!("did not find any starting nodes"))
+			.collect::<Vec<_>>();
+		if steps.is_empty() {
+			eyre::bail!("did not find any starting nodes");
+		}
+		steps.into_iter().checked_lcm()
 	}
 }
 
 impl Maps {
-	fn jump(&self, key: CacheKey, switch: char) -> eyre::Result<CacheKey> {
+	fn jump(&self, key: NodeId, switch: char) -> eyre::Result<NodeId> {
 		let &(left, right) = self.graph.get(&key).ok_or_else(|| {
-			let text =
-				self.cache.get(&key).expect("no dangling string-cache keys");
+			let text = &self.names[key];
 			eyre::eyre!("node {text} is referenced but not defined")
 		})?;
 		match switch {
@@ -119,24 +116,13 @@ impl Maps {
 		}
 	}
 
-	fn seek_any_endpoint(&self, mut key: CacheKey) -> eyre::Result<i64> {
+	fn seek_any_endpoint(&self, mut key: NodeId) -> eyre::Result<i64> {
 		let mut ct = 0;
 		let mut switches = self.switches.chars().cycle();
-		while !self
-			.cache
-			.get(&key)
-			.expect("no dangling cache keys")
-			.ends_with("Z")
-		{
+		while !self.names[key].ends_with("Z") {
 			key = self.jump(key, switches.next().unwrap())?;
 			ct += 1;
 		}
 		Ok(ct)
 	}
 }
-
-#[repr(transparent)]
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct CacheKey {
-	key: u16,
-}
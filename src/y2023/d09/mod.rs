@@ -1,31 +1,23 @@
 use eyre::Context;
 use nom::{
-	bytes::complete::tag,
 	character::complete::{
-		digit1,
 		newline,
 		space1,
 	},
-	combinator::{
-		map,
-		map_res,
-		opt,
-		recognize,
-	},
+	combinator::map,
 	multi::{
 		many1,
 		separated_list1,
 	},
-	sequence::{
-		preceded,
-		terminated,
-	},
+	sequence::terminated,
 };
 
-use crate::prelude::*;
+use crate::{
+	from_str_parser,
+	prelude::*,
+};
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 9, |t| t.parse_dyn_puzzle::<Oasis>());
+crate::register_solver!(y2023, d09, Oasis);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -42,7 +34,8 @@ impl<'a> Parsed<&'a str> for Oasis {
 }
 
 impl Puzzle for Oasis {
-	fn prepare_1(&mut self) -> eyre::Result<()> {
+	/// Both predictions extrapolate from the same table of derivatives.
+	fn prepare_common(&mut self) -> eyre::Result<()> {
 		for (row, hist) in self.histories.iter_mut().enumerate() {
 			hist.compute_derivatives().wrap_err_with(|| {
 				eyre::eyre!(
@@ -65,17 +58,6 @@ impl Puzzle for Oasis {
 			})
 	}
 
-	fn prepare_2(&mut self) -> eyre::Result<()> {
-		if self
-			.histories
-			.iter()
-			.any(|hist| hist.derivatives.is_empty())
-		{
-			self.prepare_1()?;
-		}
-		Ok(())
-	}
-
 	fn part_2(&mut self) -> eyre::Result<i64> {
 		self.histories
 			.iter()
@@ -147,13 +129,7 @@ impl<'a> Parsed<&'a str> for HistoricalRecord {
 	fn parse_wyz(line: &'a str) -> ParseResult<&'a str, Self> {
 		map(
 			terminated(
-				separated_list1(
-					space1,
-					map_res(
-						recognize(preceded(opt(tag("-")), digit1)),
-						|s: &str| s.parse::<i64>(),
-					),
-				),
+				separated_list1(space1, from_str_parser::<i64>()),
 				newline,
 			),
 			|readings| Self {
@@ -14,9 +14,7 @@ use crate::{
 	Coord2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2023, 10, |t| t.parse_dyn_puzzle::<Plumbing>());
+crate::register_solver!(y2023, d10, Plumbing);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -26,7 +24,12 @@ pub struct Plumbing {
 	///
 	/// The map is in quadrant IV: North is lesser than South; West is lesser
 	/// than East.
-	map: Sparse2D<i16, Tile>,
+	map:     Sparse2D<i16, Tile>,
+	/// Solve part 2 by inflating the map and flooding it from the outside,
+	/// rather than by counting loop crossings. This is much slower, but leaves
+	/// behind a drawing of which tiles the flood reached.
+	#[cfg_attr(feature = "serde", serde(default))]
+	inflate: bool,
 }
 
 impl Plumbing {
@@ -127,7 +130,12 @@ impl<'a> Parsed<&'a str> for Plumbing {
 				})
 			})
 			.collect::<Sparse2D<i16, Tile>>()
-			.pipe(|map| Ok(("", Self { map })))
+			.pipe(|map| {
+				Ok(("", Self {
+					map,
+					inflate: false,
+				}))
+			})
 	}
 }
 
@@ -187,10 +195,7 @@ impl Puzzle for Plumbing {
 			.map(|(pt, _)| pt)
 			.ok_or_else(|| eyre::eyre!("missing origin tile"))?;
 		let connections = self.find_connections(origin);
-		let origin = self
-			.map
-			.get_mut(origin)
-			.expect("cannot fail to look up the just-discovered origin");
+		let origin = &mut self.map[origin];
 		// We don't care about distance anymore; this just needs to not be zero.
 		origin.distance = -1;
 		origin.sym = match connections {
@@ -198,13 +203,75 @@ impl Puzzle for Plumbing {
 			[Some(_), None, Some(_), None] => Symbol::NorthWest,
 			[Some(_), None, None, Some(_)] => Symbol::NorthEast,
 			[None, Some(_), Some(_), None] => Symbol::SouthWest,
-			[None, Some(_), None, Some(_)] => Symbol::NorthEast,
+			[None, Some(_), None, Some(_)] => Symbol::SouthEast,
 			[None, None, Some(_), Some(_)] => Symbol::EastWest,
 			_ => eyre::bail!("connection matrix must have exactly two links"),
 		};
 
-		// Inflate the map: double its dimensions, and in-fill the new points
-		// by propagating the main loop.
+		if self.inflate {
+			self.inflate_map();
+		}
+		Ok(())
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		if self.inflate {
+			return self.flood_from_outside();
+		}
+		Ok(self.count_interior())
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"inflate" => self.inflate = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
+
+	fn artifacts(&self) -> Vec<Artifact> {
+		if !self.inflate {
+			return Vec::new();
+		}
+		vec![Artifact::text("flood", format!("{self:#}"))]
+	}
+}
+
+impl Plumbing {
+	/// Counts the tiles enclosed by the main loop.
+	///
+	/// Walking a row from west to east, every loop tile with a northward
+	/// connection is a crossing of the loop's boundary: `|` obviously, and also
+	/// exactly one of the two corners of any horizontal run, so that `L--7`
+	/// counts as a crossing but `L--J` does not. A tile not on the loop is
+	/// inside if an odd number of crossings lie west of it.
+	///
+	/// This requires that `prepare_2` has marked the loop and replaced the
+	/// start tile with its real shape.
+	fn count_interior(&self) -> i64 {
+		let mut count = 0;
+		for row in self.map.raw_data().values() {
+			let mut inside = false;
+			for &Tile { sym, distance, .. } in row.values() {
+				if distance == 0 {
+					count += inside as i64;
+				}
+				else if matches!(
+					sym,
+					Symbol::NorthSouth | Symbol::NorthEast | Symbol::NorthWest
+				) {
+					inside = !inside;
+				}
+			}
+		}
+		count
+	}
+
+	/// Inflates the map so that the flood fill can squeeze between adjacent
+	/// pipes.
+	fn inflate_map(&mut self) {
+		// Double the map's dimensions, and in-fill the new points by
+		// propagating the main loop.
 		let mut new_map = Sparse2D::new();
 		for (Coord2D { x, y }, tile @ Tile { sym, distance, .. }) in
 			mem::take(&mut self.map).into_iter()
@@ -244,11 +311,11 @@ impl Puzzle for Plumbing {
 		}
 
 		self.map = new_map;
-
-		Ok(())
 	}
 
-	fn part_2(&mut self) -> eyre::Result<i64> {
+	/// Counts the enclosed tiles of an inflated map by flooding it from the
+	/// outside, then deflates it back to its original size.
+	fn flood_from_outside(&mut self) -> eyre::Result<i64> {
 		// Starting from the rim *beyond* the map, seek inwards until finding a
 		// section of the main loop that is fully perpendicular to the direction
 		// of travel.
@@ -305,8 +372,6 @@ impl Puzzle for Plumbing {
 			.map(|(Coord2D { x, y }, tile)| (Coord2D::new(x / 2, y / 2), tile))
 			.collect();
 
-		eprint!("{self:#}");
-
 		// Reachable points on the original map are:
 		// - reachable on the current map
 		// - have X and Y coordinates in
@@ -401,6 +466,7 @@ pub enum Symbol {
 }
 
 impl From<char> for Symbol {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn from(c: char) -> Self {
 		match c {
 			'S' => Self::Start,
@@ -454,10 +520,15 @@ L7JLJL-JLJLJL--JLJ.L
 		"#
 		.trim();
 		let (_, mut solver): (_, Plumbing) = text.trim().parse_wyz()?;
+		let mut inflated = solver.clone();
 		solver.prepare_1()?;
 		solver.part_1()?;
 		solver.prepare_2()?;
-		solver.part_2()?;
+		assert_eq!(solver.part_2()?, 10);
+
+		inflated.set_param("inflate", "true")?;
+		inflated.prepare_2()?;
+		assert_eq!(inflated.part_2()?, 10);
 		Ok(())
 	}
 }
@@ -14,10 +14,7 @@
 //! that all the expansion slots in the map are *already* scaled by 1, so to
 //! re-scale them to some other value, the multiplicand is `scale - 1`.
 
-use std::{
-	fmt,
-	mem,
-};
+use std::fmt;
 
 use tap::Pipe;
 
@@ -27,21 +24,28 @@ use crate::{
 	Grid2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 11, |t| t.parse_dyn_puzzle::<Cosmos>());
+crate::register_solver!(y2023, d11, Cosmos);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cosmos {
-	map: Grid2D<i32, Galaxy>,
+	/// The galaxies, as they were observed.
+	map:       Grid2D<i32, Galaxy>,
+	/// The galaxies once the empty space between them has grown, for the part
+	/// being solved.
+	expanded:  Grid2D<i32, Galaxy>,
+	/// How much wider each empty row or column is in part 2.
+	expansion: i32,
 }
 
 impl Cosmos {
-	pub fn expand(&mut self, scalar: i32) {
+	/// Moves the observed galaxies apart, as though every empty row and
+	/// column were `scalar` times as wide.
+	pub fn expand(&self, scalar: i32) -> Grid2D<i32, Galaxy> {
 		let Some((min, max)) = self.map.dimensions()
 		else {
 			tracing::warn!("cannot expand an empty star-map");
-			return;
+			return self.map.clone();
 		};
 		let empty_rows = ((min.y) ..= (max.y))
 			.into_iter()
@@ -56,8 +60,8 @@ impl Cosmos {
 					.all(|row| !row.contains_key(col))
 			})
 			.collect::<Vec<_>>();
-		self.map = mem::take(&mut self.map)
-			.into_iter()
+		self.map
+			.iter()
 			.map(|(Coord2D { x, y }, galaxy)| {
 				// Move the galaxies south-east for every empty column or row
 				// that is north-west of them.
@@ -67,14 +71,14 @@ impl Cosmos {
 					y + ((empty_rows.iter().filter(|&&row| row < y).count()
 						as i32) * (scalar - 1)),
 				);
-				(coord, galaxy)
+				(coord, *galaxy)
 			})
-			.collect();
+			.collect()
 	}
 
 	fn distances(&self) -> i64 {
 		let mut accum = 0;
-		let mut walker = self.map.iter().map(|(c, _)| c);
+		let mut walker = self.expanded.iter().map(|(c, _)| c);
 		while let Some(this) = walker.next() {
 			for that in walker.clone() {
 				accum +=
@@ -86,6 +90,7 @@ impl Cosmos {
 }
 
 impl<'a> Parsed<&'a str> for Cosmos {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
 		text.lines()
 			.enumerate()
@@ -102,15 +107,19 @@ impl<'a> Parsed<&'a str> for Cosmos {
 			.flatten()
 			.map(|coord| (coord, Galaxy))
 			.collect::<Grid2D<i32, Galaxy>>()
-			.pipe(|map| Ok(("", Self { map })))
+			.pipe(|map| {
+				Ok(("", Self {
+					expanded: map.clone(),
+					map,
+					expansion: 1_000_000,
+				}))
+			})
 	}
 }
 
 impl Puzzle for Cosmos {
 	fn prepare_1(&mut self) -> eyre::Result<()> {
-		// println!("{}", self);
-		self.expand(2);
-		// println!("{}", self);
+		self.expanded = self.expand(2);
 		Ok(())
 	}
 
@@ -119,18 +128,30 @@ impl Puzzle for Cosmos {
 	}
 
 	fn prepare_2(&mut self) -> eyre::Result<()> {
-		self.expand(1_000_000 / 2); // factor out the prior expansion
+		self.expanded = self.expand(self.expansion);
 		Ok(())
 	}
 
 	fn part_2(&mut self) -> eyre::Result<i64> {
 		self.distances().pipe(Ok)
 	}
+
+	fn render_state(&self) -> Option<String> {
+		Some(format!("{self:#}"))
+	}
+
+	fn set_param(&mut self, key: &str, value: &str) -> eyre::Result<()> {
+		match key {
+			"expansion" => self.expansion = value.parse()?,
+			_ => eyre::bail!("no parameter named `{key}`"),
+		}
+		Ok(())
+	}
 }
 
 impl fmt::Display for Cosmos {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		fmt::Display::fmt(&self.map, fmt)
+		fmt::Display::fmt(&self.expanded, fmt)
 	}
 }
 
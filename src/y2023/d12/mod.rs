@@ -0,0 +1,167 @@
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{
+		newline,
+		space1,
+	},
+	combinator::{
+		map,
+		value,
+	},
+	multi::{
+		many1,
+		separated_list1,
+	},
+	sequence::separated_pair,
+};
+
+use crate::{
+	memo::memoize,
+	parse_number,
+	prelude::*,
+};
+
+crate::register_solver!(y2023, d12, Springs);
+
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Springs {
+	rows: Vec<Row>,
+}
+
+/// One row of the condition records.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Row {
+	springs: Vec<Spring>,
+	/// The length of each contiguous run of damaged springs, in order.
+	groups:  Vec<usize>,
+}
+
+impl Row {
+	/// Counts the ways to resolve the unknown springs so that the damaged
+	/// springs form exactly the recorded groups.
+	///
+	/// A subproblem is identified by how many springs and groups have been
+	/// used up, and the same one is reached by many different choices for the
+	/// springs before it.
+	pub fn arrangements(&self) -> u64 {
+		let Self { springs, groups } = self;
+		let mut count = memoize(|count, (at, group): (usize, usize)| -> u64 {
+			let rest = springs.get(at ..).unwrap_or_default();
+			let Some(&size) = groups.get(group)
+			else {
+				return rest.iter().all(|&s| s != Spring::Damaged) as u64;
+			};
+			let Some(&first) = rest.first()
+			else {
+				return 0;
+			};
+			let mut total = 0;
+			if first != Spring::Damaged {
+				total += count((at + 1, group));
+			}
+			// The group fits here if none of its springs are known to be
+			// working, and the spring just past it is not damaged.
+			let fits = rest.len() >= size
+				&& rest[.. size].iter().all(|&s| s != Spring::Operational)
+				&& rest.get(size) != Some(&Spring::Damaged);
+			if fits {
+				total += count((at + size + 1, group + 1));
+			}
+			total
+		});
+		count((0, 0))
+	}
+
+	/// Unfolds the row into five copies of itself, with an unknown spring
+	/// between each copy.
+	pub fn unfold(&self) -> Self {
+		let mut springs = self.springs.clone();
+		for _ in 1 .. 5 {
+			springs.push(Spring::Unknown);
+			springs.extend_from_slice(&self.springs);
+		}
+		Self {
+			springs,
+			groups: self.groups.repeat(5),
+		}
+	}
+}
+
+impl<'a> Parsed<&'a str> for Row {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let spring = alt((
+			value(Spring::Operational, tag(".")),
+			value(Spring::Damaged, tag("#")),
+			value(Spring::Unknown, tag("?")),
+		));
+		map(
+			separated_pair(
+				many1(spring),
+				space1,
+				separated_list1(tag(","), parse_number),
+			),
+			|(springs, groups)| Self { springs, groups },
+		)(text)
+	}
+}
+
+impl<'a> Parsed<&'a str> for Springs {
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		map(separated_list1(newline, Row::parse_wyz), |rows| Self {
+			rows,
+		})(text)
+	}
+}
+
+impl Puzzle for Springs {
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		let unknown = self
+			.rows
+			.iter()
+			.flat_map(|row| &row.springs)
+			.filter(|&&s| s == Spring::Unknown)
+			.count();
+		vec![
+			("rows", self.rows.len().to_string()),
+			("unknown", unknown.to_string()),
+		]
+	}
+
+	fn part_1(&mut self) -> eyre::Result<i64> {
+		Ok(self.rows.iter().map(Row::arrangements).sum::<u64>() as i64)
+	}
+
+	fn part_2(&mut self) -> eyre::Result<i64> {
+		Ok(self
+			.rows
+			.iter()
+			.map(|row| row.unfold().arrangements())
+			.sum::<u64>() as i64)
+	}
+}
+
+/// The condition of one spring.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Spring {
+	Operational,
+	Damaged,
+	Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_springs() -> eyre::Result<()> {
+		let (_, mut springs) =
+			include_str!("sample.txt").parse_wyz::<Springs>()?;
+		assert_eq!(springs.part_1()?, 21);
+		assert_eq!(springs.part_2()?, 525152);
+		Ok(())
+	}
+}
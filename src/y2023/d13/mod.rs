@@ -1,34 +1,30 @@
 use std::{
 	fmt,
 	iter::Sum,
-	ops::Not,
 };
 
 use nom::{
 	branch::alt,
-	bytes::complete::{
-		tag,
-		take_until1,
-	},
-	character::complete::newline,
+	bytes::complete::tag,
+	character::complete::line_ending,
 	combinator::{
 		map,
 		value,
 	},
-	multi::separated_list1,
-	sequence::terminated,
+	multi::{
+		many1,
+		separated_list1,
+	},
 };
 use tap::Pipe;
 
 use crate::{
-	coords::spaces::DisplayGrid,
+	blocks,
+	coords::Matrix,
 	prelude::*,
-	Coord2D,
-	Grid2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 13, |t| t.parse_dyn_puzzle::<Mirrors>());
+crate::register_solver!(y2023, d13, Mirrors);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -38,9 +34,7 @@ pub struct Mirrors {
 
 impl<'a> Parsed<&'a str> for Mirrors {
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
-		map(separated_list1(newline, Pattern::parse_wyz), |patterns| {
-			Self { patterns }
-		})(text)
+		map(blocks(Pattern::parse_wyz), |patterns| Self { patterns })(text)
 	}
 }
 
@@ -55,37 +49,16 @@ impl Puzzle for Mirrors {
 	fn part_1(&mut self) -> eyre::Result<i64> {
 		self.patterns
 			.iter()
-			.flat_map(|pat| pat.find_reflection(None))
+			.flat_map(|pat| pat.find_reflection(0))
 			.sum::<i64>()
 			.pipe(Ok)
 	}
 
+	/// Every pattern has exactly one smudge, which moves its reflection.
 	fn part_2(&mut self) -> eyre::Result<i64> {
 		self.patterns
-			.iter_mut()
-			.flat_map(|pat| -> Option<Reflection> {
-				let orig = pat.find_reflection(None)?;
-				let (min, max) = pat.grid.dimensions()?;
-				// Note: it takes two points to make a reflection, and one
-				// smudge has two candidate points for inversion. The found
-				// points don't necessarily match the sample data, but that's
-				// okay!
-				for row in min.y ..= max.y {
-					for col in min.x ..= max.x {
-						let coord = Coord2D::new(col, row);
-						pat.grid.update_default(coord, |pt| *pt = !*pt);
-						if let Some(fixed) = pat.find_reflection(Some(orig)) {
-							if fixed != orig {
-								tracing::debug!(%coord, "smudge fixed");
-								return Some(fixed);
-							}
-						}
-						pat.grid.update_default(coord, |pt| *pt = !*pt);
-					}
-				}
-				tracing::warn!(%pat, "never fixed");
-				None
-			})
+			.iter()
+			.flat_map(|pat| pat.find_reflection(1))
 			.sum::<i64>()
 			.pipe(Ok)
 	}
@@ -118,138 +91,53 @@ impl Sum<Reflection> for i64 {
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pattern {
-	grid: Grid2D<i8, Tile>,
+	grid: Matrix<Tile>,
 }
 
 impl Pattern {
-	pub fn find_reflection(
-		&self,
-		ignore: impl Into<Option<Reflection>>,
-	) -> Option<Reflection> {
-		let ignore = ignore.into();
-		let (min, max) = self.grid.dimensions()?;
-		// Scan through each row junction looking for a horizontal axis of
-		// vertical symmetry.
-		'scan_row: for row in (min.y + 1) ..= max.y {
-			tracing::trace!(%row, "scanning");
-			// Terminology: graphs have positive-Y pointing down. These names
-			// refer to the TEXTUAL direction, so "above" is lesser than
-			// "below".
-			let (mut above, mut below) = (row - 1, row);
-			// Beginning with the two rows on either side of the divisor, and
-			// expanding outwards, scan each row-pair for difference.
-			while above >= min.y && below <= max.y {
-				for ((_, a), (_, b)) in
-					self.grid.row(above).zip(self.grid.row(below))
-				{
-					if a != b {
-						tracing::trace!(%above, %below, "symmetry broken");
-						continue 'scan_row;
-					}
-				}
-				tracing::trace!(%above, %below, "symmetrical row-pair");
-				above -= 1;
-				below += 1;
-			}
-			// At loop exit, no divergence has been detected.
-			let horiz = Reflection::Horizontal(row as i64);
-			// If the found reflection matches the ignore pattern, skip it.
-			if let Some(ignore) = ignore {
-				if ignore == horiz {
-					tracing::trace!(?horiz, "ignoring");
-					continue;
-				}
-			}
-			tracing::debug!(upper=%(row-1), lower=%row, "symmetrical rows");
-			return Some(horiz);
+	/// Finds the pattern's axis of symmetry, where exactly `smudges` tiles do
+	/// not match their reflections.
+	///
+	/// Horizontal axes are preferred over vertical ones.
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	pub fn find_reflection(&self, smudges: usize) -> Option<Reflection> {
+		let horiz = self.grid.mirror_rows(smudges).next();
+		let found = horiz
+			.map(|rows| Reflection::Horizontal(rows as i64))
+			.or_else(|| {
+				self.grid
+					.mirror_columns(smudges)
+					.next()
+					.map(|cols| Reflection::Vertical(cols as i64))
+			});
+		match found {
+			Some(reflection) => tracing::debug!(?reflection, %smudges, "found"),
+			None => tracing::warn!(%self, %smudges, "found no symmetry"),
 		}
-		// Scan through each column junction looking for a vertical axis of
-		// horizontal symmmetry.
-		'scan_col: for col in (min.x + 1) ..= max.x {
-			tracing::trace!(%col, "scanning");
-			let (mut left, mut right) = (col - 1, col);
-			while left >= min.x && right <= max.x {
-				for ((_, a), (_, b)) in
-					self.grid.column(left).zip(self.grid.column(right))
-				{
-					if a != b {
-						tracing::trace!(%left, %right, "symmetry broken");
-						continue 'scan_col;
-					}
-				}
-				tracing::trace!(%left, %right, "symmetrical col-pair");
-				left -= 1;
-				right += 1;
-			}
-			let vert = Reflection::Vertical(col as i64);
-			if let Some(ignore) = ignore {
-				if ignore == vert {
-					tracing::trace!(?vert, "ignoring");
-					continue;
-				}
-			}
-			tracing::debug!(left=%(col-1), right=%col, "symmetrical cols");
-			return Some(vert);
-		}
-		// tracing::warn!(%self, "found no symmetry");
-		None
+		found
 	}
 }
 
 impl<'a> Parsed<&'a str> for Pattern {
-	// Strictly speaking, this is not a correct parser, since it discards the
-	// right side of a line if an intermediate error occurs, but we know that
-	// the input is not adversarial to the *parser*.
-	fn parse_wyz(mut text: &'a str) -> ParseResult<&'a str, Self> {
-		let mut this = Self::default();
-		let mut row = 0;
-		loop {
-			// Since we are not bubbling the error, Rust won't infer the error
-			// type of the parser without an explicit bind.
-			let maybe_line: ParseResult<&'a str, &'a str> =
-				terminated(take_until1("\n"), newline)(text);
-			let Ok((rest, mut line)) = maybe_line
-			else {
-				break;
-			};
-			let mut col = 0;
-			while let Ok((rest, tile)) = line.parse_wyz::<Tile>() {
-				this.grid.insert(crate::Coord2D::new(col, row), tile);
-				col += 1;
-				line = rest;
-			}
-			row += 1;
-			text = rest;
-		}
-		Ok((text, this))
-	}
-}
-
-impl DisplayGrid<i8, Tile> for Pattern {
-	fn bounds_inclusive(&self) -> Option<(Coord2D<i8>, Coord2D<i8>)> {
-		self.grid.dimensions()
-	}
-
-	fn print_cell(
-		&self,
-		symbols: &crate::coords::spaces::Symbols,
-		row: i8,
-		col: i8,
-		_row_abs: usize,
-		_col_abs: usize,
-	) -> char {
-		match self.grid.get(Coord2D::new(col, row)) {
-			Some(Tile::Ash) => symbols.middle_dot,
-			Some(Tile::Rock) => symbols.full,
-			None => symbols.empty,
-		}
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
+		let (rest, rows) =
+			separated_list1(line_ending, many1(Tile::parse_wyz))(text)?;
+		let grid = Matrix::from_rows(rows).map_err(|err| {
+			tracing::error!(%err, "patterns must be rectangular");
+			nom::Err::Failure(nom::error::Error::new(
+				text,
+				nom::error::ErrorKind::Verify,
+			))
+		})?;
+		Ok((rest, Self { grid }))
 	}
 }
 
 impl fmt::Display for Pattern {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		writeln!(fmt)?;
-		DisplayGrid::render(self, fmt)
+		fmt::Display::fmt(&self.grid, fmt)
 	}
 }
 
@@ -261,14 +149,15 @@ pub enum Tile {
 	Rock,
 }
 
-impl Not for Tile {
-	type Output = Self;
-
-	fn not(self) -> Self::Output {
-		match self {
-			Self::Ash => Self::Rock,
-			Self::Rock => Self::Ash,
-		}
+impl fmt::Display for Tile {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(
+			match self {
+				Self::Ash => &'.',
+				Self::Rock => &'#',
+			},
+			fmt,
+		)
 	}
 }
 
@@ -1,170 +1,193 @@
 use std::{
-	collections::BTreeSet,
-	mem,
+	collections::BTreeMap,
+	fmt,
 };
 
-use tap::Pipe;
-use wyz::BidiIterator;
-
 use crate::{
 	coords::{
 		points::Direction2D,
-		spaces::{
-			Dense2D,
-			DisplayGrid,
-		},
+		Matrix,
 	},
+	interact::InteractIo,
 	prelude::*,
-	Coord2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 14, |t| t.parse_dyn_puzzle::<Tilting>());
+crate::register_solver!(y2023, d14, Tilting);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tilting {
-	table: Dense2D<i16, Rock>,
+	table: Matrix<Rock>,
 }
 
 impl Tilting {
-	pub fn tilt(&mut self, direction: Direction2D) -> eyre::Result<()> {
-		let (min, max) = self
-			.table
-			.dimensions()
-			.ok_or_else(|| eyre::eyre!("cannot tilt an empty table"))?;
-		let step = direction.unit();
-		for row in ((min.y) ..= max.y).bidi(direction == Direction2D::South) {
-			for col in (min.x ..= max.x).bidi(direction == Direction2D::East) {
-				let current = Coord2D::new(col, row);
-				let mut next = current + step;
-				if self.table[current] != Rock::Sphere {
-					continue;
-				}
-				while self.table.in_bounds(next) {
-					if self.table[next] != Rock::Void {
-						break;
-					}
-					next += step;
+	/// Tilts the table so that the rocks slide north as far as they'll go.
+	pub fn tilt_north(&mut self) {
+		for col in 0 .. self.table.cols() {
+			// The northernmost square that a rock could still roll into.
+			let mut free = 0;
+			for row in 0 .. self.table.rows() {
+				match self.table[(row, col)] {
+					Rock::Cube => free = row + 1,
+					Rock::Sphere => {
+						self.table[(row, col)] = Rock::Void;
+						self.table[(free, col)] = Rock::Sphere;
+						free += 1;
+					},
+					Rock::Void => {},
 				}
-				next -= step;
-				self.table[next] = mem::take(&mut self.table[current]);
 			}
 		}
-		Ok(())
 	}
 
-	pub fn applied_load(&self) -> eyre::Result<i64> {
-		let (min, max) = self
-			.table
-			.dimensions()
-			.ok_or_else(|| eyre::eyre!("cannot process an empty table"))?;
-		let max = max - min;
+	/// Tilts the table so that the rocks slide as far as they'll go in any
+	/// direction, by turning that direction to the north first.
+	pub fn tilt(&mut self, direction: Direction2D) {
+		// Clockwise quarter-turns which bring the direction to the top.
+		let turns = match direction {
+			Direction2D::North => 0,
+			Direction2D::West => 1,
+			Direction2D::South => 2,
+			Direction2D::East => 3,
+		};
+		for _ in 0 .. turns {
+			self.table = self.table.rotate_clockwise();
+		}
+		self.tilt_north();
+		for _ in 0 .. turns {
+			self.table = self.table.rotate_counterclockwise();
+		}
+	}
+
+	/// Tilts the table north, west, south, and east, in that order.
+	///
+	/// Rather than tilt four ways, this tilts north four times, turning the
+	/// table clockwise after each so that the next direction is at the top.
+	/// Four quarter-turns leave it the way it started.
+	pub fn spin(&mut self) {
+		for _ in 0 .. 4 {
+			self.tilt_north();
+			self.table = self.table.rotate_clockwise();
+		}
+	}
+
+	/// Sums, for each sphere, the number of rows from it to the south edge.
+	pub fn applied_load(&self) -> i64 {
+		let rows = self.table.rows();
 		self.table
-			.iter()
-			.filter(|&(_, &rock)| rock == Rock::Sphere)
-			.map(|(Coord2D { y, .. }, _)| (max.y - y + 1) as i64)
-			.sum::<i64>()
-			.pipe(Ok)
+			.iter_rows()
+			.enumerate()
+			.map(|(row, cells)| {
+				let spheres =
+					cells.iter().filter(|&&rock| rock == Rock::Sphere).count();
+				((rows - row) * spheres) as i64
+			})
+			.sum()
 	}
 }
 
 impl<'a> Parsed<&'a str> for Tilting {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
-		Dense2D::from_raw(
-			Coord2D::new(0, 0),
-			text.lines()
-				.map(|line| line.chars().map(Rock::from).collect())
-				.collect(),
+		let table = Matrix::from_rows(
+			text.lines().map(|line| line.chars().map(Rock::from)),
 		)
-		.pipe(|table| Ok(("", Self { table })))
+		.map_err(|err| {
+			tracing::error!(%err, "the table must be rectangular");
+			nom::Err::Failure(nom::error::Error::new(
+				text,
+				nom::error::ErrorKind::Verify,
+			))
+		})?;
+		Ok(("", Self { table }))
 	}
 }
 
 impl Puzzle for Tilting {
-	/// Tilt the table so that the rocks slide north as far as they'll go.
 	fn prepare_1(&mut self) -> eyre::Result<()> {
-		self.tilt(Direction2D::North)?;
+		self.tilt_north();
 		Ok(())
 	}
 
 	fn part_1(&mut self) -> eyre::Result<i64> {
-		self.applied_load()
+		Ok(self.applied_load())
 	}
 
+	/// The table settles into a loop long before the billionth spin, so this
+	/// spins until a table repeats and then skips every whole lap of the loop.
+	///
+	/// A table already tilted north spins the same as the table before it was
+	/// tilted, so this does not care whether part 1 has run.
 	fn prepare_2(&mut self) -> eyre::Result<()> {
 		const CYCLES: usize = 1_000_000_000;
-		self.tilt(Direction2D::West)?;
-		self.tilt(Direction2D::South)?;
-		self.tilt(Direction2D::East)?;
-		let mut observed_patterns = BTreeSet::new();
-		let mut cycle_found = false;
-		let mut step_count = 1;
-		while step_count < CYCLES {
-			self.tilt(Direction2D::North)?;
-			self.tilt(Direction2D::West)?;
-			self.tilt(Direction2D::South)?;
-			self.tilt(Direction2D::East)?;
-			step_count += 1;
-			// We need to be able to produce the pattern twice in the event of
-			// a cache-clear.
-			let pattern = || {
-				self.table
-					.iter()
-					.filter(|&(_, &r)| r == Rock::Sphere)
-					.map(|(p, _)| p)
-					.collect::<Vec<_>>()
-			};
-			// A cycle occurs when a pattern is observed a second time.
-			if !observed_patterns.insert(pattern()) {
-				tracing::debug!("found a repeated observation!");
-				// The first time a pattern repeats, we have *at least* a cycle,
-				// plus a potential introductory path that got us here. Discard
-				// all observed patterns, then insert the duplicate again.
-				if !cycle_found {
-					tracing::debug!(%step_count, "first such repetition; resetting tracker");
-					cycle_found = true;
-					observed_patterns.clear();
-					observed_patterns.insert(pattern());
-					continue;
+		let mut seen = BTreeMap::new();
+		for cycle in 0 .. CYCLES {
+			if let Some(first) = seen.insert(self.table.clone(), cycle) {
+				let period = cycle - first;
+				tracing::debug!(%first, %period, "found a repeated table");
+				for _ in 0 .. (CYCLES - cycle) % period {
+					self.spin();
 				}
-				// Now, we know that the observation has *only* cyclic patterns.
-				tracing::debug!(%step_count, cycle_len=%observed_patterns.len(), "second such repetition; fast-forwarding");
-				let jump = (CYCLES - step_count) / observed_patterns.len();
-				step_count += jump * observed_patterns.len();
-				tracing::debug!(%step_count, "fast-forwarded");
-				// Discard the observations; there are fewer steps remaining
-				// than the cycle length, so we know this branch will not enter
-				// a third time.
-				observed_patterns.clear();
+				return Ok(());
 			}
+			self.spin();
 		}
 		Ok(())
 	}
 
 	fn part_2(&mut self) -> eyre::Result<i64> {
-		self.applied_load()
+		Ok(self.applied_load())
 	}
-}
 
-impl DisplayGrid<i16, Rock> for Tilting {
-	fn bounds_inclusive(&self) -> Option<(Coord2D<i16>, Coord2D<i16>)> {
-		self.table.dimensions()
+	/// Tilts the table by hand, redrawing it after each move.
+	fn interact(&mut self, io: &mut dyn InteractIo) -> eyre::Result<()> {
+		io.write_line(
+			"tilt with n, s, e, or w; `spin [count]` to spin; `load` to weigh \
+			 the north beams; `quit` to stop",
+		)?;
+		io.write_line(&self.to_string())?;
+		while let Some(line) = io.prompt("tilt> ")? {
+			let words = line.split_whitespace().collect::<Vec<_>>();
+			let outcome = match words.as_slice() {
+				[] => continue,
+				["quit" | "q"] => break,
+				["load"] => Ok(format!("load: {}", self.applied_load())),
+				["spin", count @ ..] => count
+					.first()
+					.map_or(Ok(1), |count| count.parse::<usize>())
+					.map_err(eyre::Report::from)
+					.and_then(|count| {
+						for _ in 0 .. count {
+							crate::cancel::check()?;
+							self.spin();
+						}
+						Ok(self.to_string())
+					}),
+				[dir @ ("n" | "s" | "e" | "w")] => {
+					self.tilt(match *dir {
+						"n" => Direction2D::North,
+						"s" => Direction2D::South,
+						"e" => Direction2D::East,
+						_ => Direction2D::West,
+					});
+					Ok(self.to_string())
+				},
+				_ => Err(eyre::eyre!("unknown command")),
+			};
+			match outcome {
+				Ok(text) => io.write_line(&text)?,
+				Err(err) => io.write_line(&format!("error: {err:#}"))?,
+			}
+			// Ctrl-C only raises the cancellation flag, so look for it here.
+			crate::cancel::check()?;
+		}
+		Ok(())
 	}
+}
 
-	fn print_cell(
-		&self,
-		symbols: &crate::coords::spaces::Symbols,
-		row: i16,
-		col: i16,
-		_row_abs: usize,
-		_col_abs: usize,
-	) -> char {
-		match self.table.get(Coord2D::new(col, row)) {
-			Some(Rock::Sphere) => 'O', // '○',
-			Some(Rock::Cube) => symbols.full,
-			Some(Rock::Void) | None => symbols.empty,
-		}
+impl fmt::Display for Tilting {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.table, fmt)
 	}
 }
 
@@ -178,6 +201,7 @@ pub enum Rock {
 }
 
 impl From<char> for Rock {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn from(c: char) -> Self {
 		match c {
 			'O' => Self::Sphere,
@@ -190,3 +214,55 @@ impl From<char> for Rock {
 		}
 	}
 }
+
+impl fmt::Display for Rock {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(
+			match self {
+				Self::Sphere => &'O',
+				Self::Cube => &'#',
+				Self::Void => &'.',
+			},
+			fmt,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::interact::Script;
+
+	#[test]
+	fn tilts_by_hand() -> eyre::Result<()> {
+		let (_, mut table) =
+			include_str!("sample.txt").parse_wyz::<Tilting>()?;
+		let mut script = Script::new(["spin", "load", "up", "q", "load"]);
+		table.interact(&mut script)?;
+		let output = script.output();
+		// The worked example shows the table after one spin.
+		let once = [
+			".....#....",
+			"....#...O#",
+			"...OO##...",
+			".OO#......",
+			".....OOO#.",
+			".O#...O#.#",
+			"....O#....",
+			"......OOOO",
+			"#...O###..",
+			"#..OO#....",
+		]
+		.join("\n");
+		assert!(output.contains(&once));
+		assert!(output.contains("load: 87\n"));
+		assert!(output.contains("error: unknown command\n"));
+		assert_eq!(output.matches("load:").count(), 1);
+
+		table.tilt(Direction2D::East);
+		let tilted = table.clone();
+		table.tilt(Direction2D::East);
+		assert_eq!(table, tilted);
+		Ok(())
+	}
+}
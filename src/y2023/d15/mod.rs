@@ -24,8 +24,7 @@ use tap::Pipe;
 
 use crate::prelude::*;
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 15, |t| t.parse_dyn_puzzle::<Lenses>());
+crate::register_solver!(y2023, d15, Lenses);
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Lenses {
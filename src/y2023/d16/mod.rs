@@ -37,9 +37,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2023, 16, |t| t.parse_dyn_puzzle::<LightGrid>());
+crate::register_solver!(y2023, d16, LightGrid);
 
 /// A dense grid of tiles which may contain light-beam manipulators and/or light
 /// beams.
@@ -226,6 +224,7 @@ impl Beam {
 	///
 	/// The `id` argument is only for being able to distinguish workers in the
 	/// debug traces.
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn walk<'a, 'scope: 'a>(
 		mut self,
 		id: &'scope AtomicUsize,
@@ -32,19 +32,19 @@ use rayon::prelude::*;
 use tap::Tap;
 
 use crate::{
+	cancel,
 	coords::{
 		points::{
 			Cartesian2D as Point2D,
 			Direction2D,
 		},
-		spaces::dense::Axis,
+		scan::par_scan_rows,
+		Polygon,
 	},
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2023, 18, |t| t.parse_dyn_puzzle::<Lavagoon>());
+crate::register_solver!(y2023, d18, Lavagoon);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -74,7 +74,7 @@ impl Puzzle for Lavagoon {
 	}
 
 	fn part_1(&mut self) -> eyre::Result<i64> {
-		self.stroked_area()
+		self.enclosed_area()
 	}
 
 	fn prepare_2(&mut self) -> eyre::Result<()> {
@@ -86,7 +86,7 @@ impl Puzzle for Lavagoon {
 	}
 
 	fn part_2(&mut self) -> eyre::Result<i64> {
-		self.stroked_area()
+		self.enclosed_area()
 	}
 }
 
@@ -113,8 +113,8 @@ impl Lavagoon {
 		}
 	}
 
-	/// Computes the area enclosed by the strokes.
-	pub fn stroked_area(&self) -> eyre::Result<i64> {
+	/// Ensures that the strokes form a closed circuit.
+	fn check_closed(&self) -> eyre::Result<()> {
 		if self
 			.segments
 			.last()
@@ -126,6 +126,27 @@ impl Lavagoon {
 				 closed circuit"
 			);
 		}
+		Ok(())
+	}
+
+	/// Computes the area enclosed by the strokes, including the trench itself,
+	/// from the corners of the curve.
+	pub fn enclosed_area(&self) -> eyre::Result<i64> {
+		self.check_closed()?;
+		Ok(self
+			.segments
+			.iter()
+			.map(|s| s.bgn)
+			.collect::<Polygon<_>>()
+			.enclosed_lattice_points())
+	}
+
+	/// Computes the area enclosed by the strokes by scanning every row.
+	///
+	/// This is much slower than [`Self::enclosed_area`], and is kept as a
+	/// cross-check.
+	pub fn stroked_area(&self) -> eyre::Result<i64> {
+		self.check_closed()?;
 
 		// Get the bounding box of the entire drawn area.
 		let (min, max) = self.segments.iter().fold(
@@ -139,13 +160,8 @@ impl Lavagoon {
 			},
 		);
 
-		let mut accum = 0;
-		let mut progress = 0.0;
-		tracing::debug!("beginning computation");
-		for row in Axis::new_inclusive(min.y, max.y) {
-			let pct = ((row as f64) / (max.y as f64) * 100.0).floor();
-			let span = tracing::error_span!("row", %pct);
-			let _span = span.enter();
+		let accum = par_scan_rows(min.y.into(), max.y.into(), |row| {
+			let row = row as i32;
 			// Get all strokes which contact the current scan row.
 			let strokes_in_row = self
 				.segments
@@ -159,7 +175,7 @@ impl Lavagoon {
 					s.dir == Direction2D::West || s.dir == Direction2D::East
 				});
 			// Only take the cells which are *not* in a vertical stroke.
-			accum += horiz.iter().map(|h| h.len_exclusive()).sum::<i64>();
+			let mut accum = horiz.iter().map(|h| h.len_exclusive()).sum::<i64>();
 			// We know that this is a perfect non-convex curve in rectangular
 			// space. This means that for any pair of adjacent vertical strokes
 			// in a row, if the strokes point in the same direction, then they
@@ -194,11 +210,10 @@ impl Lavagoon {
 				}
 				inside = !inside;
 			}
-			if pct > progress {
-				progress = pct;
-				tracing::debug!(%accum);
-			}
-		}
+			accum
+		})
+		.sum::<i64>();
+		cancel::check()?;
 		Ok(accum)
 	}
 }
@@ -366,3 +381,20 @@ impl fmt::Display for Stroke {
 		write!(fmt, "{{{} | {}/{}}}", self.dir, self.bgn, dist)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_lagoon() -> eyre::Result<()> {
+		let (_, mut lagoon) =
+			include_str!("sample.txt").parse_wyz::<Lavagoon>()?;
+		lagoon.prepare_1()?;
+		assert_eq!(lagoon.part_1()?, 62);
+		assert_eq!(lagoon.stroked_area()?, 62);
+		lagoon.prepare_2()?;
+		assert_eq!(lagoon.part_2()?, 952408144115);
+		Ok(())
+	}
+}
@@ -19,7 +19,6 @@ use nom::{
 	branch::alt,
 	bytes::complete::tag,
 	character::complete::{
-		alpha1,
 		newline,
 		u16 as get_u16,
 	},
@@ -48,18 +47,24 @@ use crate::{
 		Identifier,
 	},
 	prelude::*,
+	y2023::shared::cached_label,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2023, 19, |t| t.parse_dyn_puzzle::<QualityControl>());
+crate::register_solver!(y2023, d19, QualityControl);
+
+/// Namespace for the names of workflows.
+#[derive(Clone, Copy, Debug)]
+pub enum Workflow {}
+
+/// Identifies a workflow by its interned name.
+pub type WorkflowId = Identifier<Workflow>;
 
 #[derive(Clone, Debug)]
 pub struct QualityControl {
-	rules: BTreeMap<Identifier, RuleSet>,
+	rules: BTreeMap<WorkflowId, RuleSet>,
 	items: Vec<Item>,
-	names: Dictionary<str>,
-	start: Identifier,
+	names: Dictionary<str, Workflow>,
+	start: WorkflowId,
 }
 
 impl<'a> Parsed<&'a str> for QualityControl {
@@ -99,6 +104,7 @@ impl Puzzle for QualityControl {
 }
 
 impl QualityControl {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	pub fn execute(&self, item: &Item) -> eyre::Result<Option<i64>> {
 		let mut rule_id = self.start;
 		loop {
@@ -158,7 +164,7 @@ impl QualityControl {
 			.pipe(Ok)
 	}
 
-	pub fn get_ruleset(&self, id: Identifier) -> eyre::Result<&RuleSet> {
+	pub fn get_ruleset(&self, id: WorkflowId) -> eyre::Result<&RuleSet> {
 		self.rules.get(&id).ok_or_else(|| {
 			let name = self.names.lookup(id);
 			let name = name.as_deref().unwrap_or("<unnamed>");
@@ -188,8 +194,8 @@ impl RuleSet {
 
 	pub fn parse_with_cache<'a>(
 		text: &'a str,
-		names: &RwLock<Dictionary<str>>,
-	) -> ParseResult<&'a str, (Identifier, Self)> {
+		names: &RwLock<Dictionary<str, Workflow>>,
+	) -> ParseResult<&'a str, (WorkflowId, Self)> {
 		let (rest, (name, (rules, default))) = pair(
 			|t| cached_label(t, names),
 			delimited(
@@ -241,7 +247,7 @@ impl Rule {
 
 	pub fn parse_with_cache<'a>(
 		text: &'a str,
-		names: &RwLock<Dictionary<str>>,
+		names: &RwLock<Dictionary<str, Workflow>>,
 	) -> ParseResult<&'a str, Self> {
 		map(
 			tuple((
@@ -288,13 +294,13 @@ pub enum Route {
 	/// Terminates rule processing as a failure.
 	Reject,
 	/// Continues processing with a new rule-set.
-	Forward(Identifier),
+	Forward(WorkflowId),
 }
 
 impl Route {
 	pub fn parse_with_cache<'a>(
 		text: &'a str,
-		names: &RwLock<Dictionary<str>>,
+		names: &RwLock<Dictionary<str, Workflow>>,
 	) -> ParseResult<&'a str, Self> {
 		alt((
 			value(Self::Accept, tag("A")),
@@ -484,21 +490,5 @@ impl IndexMut<Attr> for ItemSet {
 	}
 }
 
-pub fn cached_label<'a>(
-	text: &'a str,
-	names: &RwLock<Dictionary<str>>,
-) -> ParseResult<&'a str, Identifier> {
-	let (rest, name) = alpha1(text)?;
-	if let Some(ident) = names
-		.read()
-		.expect("poisoned name-cache")
-		.lookup_value(name)
-	{
-		return Ok((rest, ident));
-	}
-	let ident = names.write().expect("poisoned name-cache").insert(name);
-	Ok((rest, ident))
-}
-
 /// The numeric type that fits the attribute values.
 type Number = u16;
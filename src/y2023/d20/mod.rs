@@ -31,10 +31,10 @@ use crate::{
 		Identifier,
 	},
 	prelude::*,
+	y2023::shared::label,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 20, |t| t.parse_dyn_puzzle::<Machine>());
+crate::register_solver!(y2023, d20, Machine);
 
 #[derive(Clone, Debug)]
 pub struct Machine {
@@ -65,6 +65,7 @@ impl<'a> Parsed<&'a str> for Machine {
 }
 
 impl Puzzle for Machine {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn prepare_1(&mut self) -> eyre::Result<()> {
 		self.nodes.insert_test_point("rx");
 		for n in 0 .. 1000 {
@@ -138,6 +139,7 @@ impl Puzzle for Machine {
 }
 
 impl Machine {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	pub fn pulse(
 		&mut self,
 		test_point: Option<Identifier>,
@@ -252,6 +254,7 @@ impl Netlist {
 }
 
 impl<'a> Parsed<&'a str> for Netlist {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
 		let mut names = Dictionary::new();
 		let (rest, nodes) = separated_list1(newline, |t| {
@@ -401,7 +404,7 @@ impl Node {
 				}),
 			)),
 			tag(" -> "),
-			separated_list1(tag(", "), map(alpha1, |t| ctx.insert(t))),
+			separated_list1(tag(", "), |t| label(t, ctx)),
 		)(text)?;
 		let name = ctx.insert(name);
 		Ok((rest, Self {
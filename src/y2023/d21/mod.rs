@@ -18,8 +18,7 @@ use crate::{
 	Coord2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 21, |t| t.parse_dyn_puzzle::<Garden>());
+crate::register_solver!(y2023, d21, Garden);
 
 type Ordinate = i32;
 
@@ -74,7 +73,7 @@ impl Puzzle for Garden {
 
 	fn part_2(&mut self) -> eyre::Result<i64> {
 		// const STEP_COUNT: i32 = 26_501_365;
-		todo!()
+		Err(AocError::NotImplemented { part: 2 }.into())
 	}
 }
 
@@ -211,6 +210,7 @@ pub enum Kind {
 }
 
 impl From<char> for Kind {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn from(c: char) -> Self {
 		match c {
 			'.' => Self::Empty,
@@ -5,12 +5,8 @@ use std::{
 
 use nom::{
 	bytes::complete::tag,
-	character::complete::i16 as get_i16,
 	combinator::map,
-	sequence::{
-		separated_pair,
-		tuple,
-	},
+	sequence::separated_pair,
 };
 use tap::Tap;
 
@@ -20,8 +16,7 @@ use crate::{
 	Grid3D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 22, |t| t.parse_dyn_puzzle::<Sandbox>());
+crate::register_solver!(y2023, d22, Sandbox);
 
 type Ordinate = i16;
 
@@ -95,15 +90,9 @@ impl Brick {
 
 impl<'a> Parsed<&'a str> for Brick {
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
-		map(separated_pair(coord3d, tag("~"), coord3d), |(bgn, end)| {
-			Self { id: 0, bgn, end }
-		})(text)
+		map(
+			separated_pair(Coord3D::parse_wyz, tag("~"), Coord3D::parse_wyz),
+			|(bgn, end)| Self { id: 0, bgn, end },
+		)(text)
 	}
 }
-
-fn coord3d<'a>(text: &'a str) -> ParseResult<&'a str, Coord3D<Ordinate>> {
-	map(
-		tuple((get_i16, tag(","), get_i16, tag(","), get_i16)),
-		|(x, _, y, _, z)| Coord3D { x, y, z },
-	)(text)
-}
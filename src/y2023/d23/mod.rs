@@ -24,10 +24,10 @@ use crate::{
 	},
 	prelude::*,
 	Coord2D,
+	Difficulty,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 23, |t| t.parse_dyn_puzzle::<Trails>());
+crate::register_solver!(y2023, d23, Trails);
 
 #[derive(Clone, Debug, Default)]
 pub struct Trails {
@@ -82,6 +82,12 @@ impl<'a> Parsed<&'a str> for Trails {
 }
 
 impl Puzzle for Trails {
+	/// Without the slopes, part 2 has to try every simple path through the
+	/// maze.
+	fn estimated_difficulty() -> Difficulty {
+		Difficulty::Slow
+	}
+
 	fn part_1(&mut self) -> eyre::Result<i64> {
 		let score = Atom::new(0);
 		rayon::scope(|s| Hiker::new(&self, false).search_par(s, &score));
@@ -252,6 +258,7 @@ pub enum Kind {
 }
 
 impl From<char> for Kind {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	fn from(c: char) -> Self {
 		match c {
 			'.' => Self::Path,
@@ -24,9 +24,7 @@ use crate::{
 	Coord3D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2023, 24, |t| t.parse_dyn_puzzle::<Hailstorm>());
+crate::register_solver!(y2023, d24, Hailstorm);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -74,6 +72,7 @@ impl Puzzle for Hailstorm {
 }
 
 impl Hailstorm {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	pub fn intersections_2d(
 		&self,
 		(min, max): (Coord2D<i64>, Coord2D<i64>),
@@ -1,9 +1,5 @@
-use std::{
-	cmp,
-	path::PathBuf,
-};
+#![doc = include_str!("README.md")]
 
-use eyre::Context;
 use nom::{
 	bytes::complete::tag,
 	character::complete::{
@@ -16,97 +12,111 @@ use nom::{
 };
 
 use crate::{
+	graph::Graph,
 	prelude::*,
-	web::Web,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2023, 25, |t| t.parse_dyn_puzzle::<Wiring>());
+crate::register_solver!(y2023, d25, Wiring);
+
+/// How many wires must be cut to split the machine in two.
+const CUTS: i64 = 3;
 
 #[derive(Debug, Default)]
 pub struct Wiring {
-	web: Web,
+	graph: Graph<String>,
 }
 
 impl<'a> Parsed<&'a str> for Wiring {
 	fn parse_wyz(text: &'a str) -> ParseResult<&'a str, Self> {
-		let mut web = Web::new();
 		let (rest, links) = separated_list1(
 			newline,
 			separated_pair(alpha1, tag(": "), separated_list1(space1, alpha1)),
 		)(text)?;
-		for (src, dsts) in links {
-			let src = web.insert(src);
-			for dst in dsts {
-				let dst = web.insert(dst);
-				web.create_link(src, dst);
-			}
-		}
-		Ok((rest, Self { web }))
+		let graph = Graph::from_undirected_edges(links.into_iter().flat_map(
+			|(src, dsts): (&str, Vec<&str>)| {
+				dsts.into_iter()
+					.map(move |dst| (src.to_owned(), dst.to_owned(), ()))
+			},
+		));
+		Ok((rest, Self { graph }))
 	}
 }
 
 impl Puzzle for Wiring {
 	fn after_parse(&mut self) -> eyre::Result<()> {
-		tracing::debug!(ct=%self.web.nodes().count(), "finished parsing");
-		self.web.find_all_routes();
-		let mut path = PathBuf::from(file!());
-		path.pop();
-		path.push("webbing.gv");
-		std::fs::write(
-			&path,
-			self.web
-				.print_graphviz()
-				.context("could not produce graphviz output")?,
-		)
-		.with_context(|| format!("could not create {}", path.display()))?;
+		tracing::debug!(ct=%self.graph.node_count(), "finished parsing");
+		if self.graph.node_count() < 2 {
+			eyre::bail!("cannot split fewer than two components");
+		}
 		Ok(())
 	}
 
+	fn describe_input(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("components", self.graph.node_count().to_string()),
+			("wires", (self.graph.edge_count() / 2).to_string()),
+		]
+	}
+
 	fn part_1(&mut self) -> eyre::Result<i64> {
-		let mut ends = None;
-		for _ in 0 .. 3 {
-			self.web.find_all_routes();
-			let mut links = self.web.bidi_links().collect::<Vec<_>>();
-			links.sort_by_key(|l| cmp::Reverse(l.count()));
-			// Because the routing algorithm biases towards lower-numbered
-			// nodes, it is possible that links become artificially loaded
-			// without actually being a critical path. This selects the
-			// highest-numbered link of the heaviest loadings, under the
-			// assumption that it is unlikely to be a false positive. It works
-			// correctly on the sample data.
-			let Some(heaviest) = links
-				.iter()
-				.filter(|l| l.count() == links[0].count())
-				.last()
-			else {
-				eyre::bail!("could not find a link to cut")
-			};
-			let (one, two) = heaviest.ends();
-			ends = Some((one, two));
-			self.web.remove_link(one, two);
-			self.web.clear_routes();
+		// Every wire carries one unit of flow. Any node in the other half from
+		// the source is separated from it by exactly the three wires we want;
+		// any node in the same half is joined to it by more, unless it has only
+		// three wires of its own. Cutting those splits off a lone node, so the
+		// source must have more wires than that, and cuts which leave a lone
+		// node on either side are passed over.
+		let count = self.graph.node_count();
+		let source = (0 .. count)
+			.find(|&node| self.graph.neighbors(node).count() > CUTS as usize)
+			.unwrap_or_default();
+		for sink in (0 .. count).filter(|&sink| sink != source) {
+			let cut = self.graph.max_flow(source, sink, |_| 1);
+			let left = cut.source_side.len();
+			let right = count - left;
+			if cut.flow == CUTS && left > 1 && right > 1 {
+				tracing::debug!(%left, %right, "found the cut");
+				return Ok((left * right) as i64);
+			}
 		}
+		eyre::bail!("no set of {CUTS} wires splits the machine")
+	}
 
-		let Some((left, right)) = ends
-		else {
-			eyre::bail!("did not find any links to cut");
-		};
-		let (mut ct_left, mut ct_right) = (0, 0);
-		rayon::scope(|s| {
-			s.spawn(|_| ct_left = self.web.count_reachable(left));
-			s.spawn(|_| ct_right = self.web.count_reachable(right));
-		});
-		tracing::info!("DONE");
-		self.web.find_all_routes();
-		let mut path = PathBuf::from(file!());
-		path.pop();
-		path.push("webbing-cut.gv");
-		std::fs::write(&path, self.web.print_graphviz()?)?;
-
-		Ok((ct_left * ct_right) as i64)
+	/// The last day's second star is awarded for finishing every other puzzle.
+	fn has_part_2(&self) -> bool {
+		false
 	}
 }
 
-impl Wiring {
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sample_cut() -> eyre::Result<()> {
+		let (_, mut wiring) =
+			include_str!("sample.txt").parse_wyz::<Wiring>()?;
+		assert_eq!(wiring.part_1()?, 54);
+		Ok(())
+	}
+
+	#[test]
+	fn skips_lone_nodes() -> eyre::Result<()> {
+		// `pin` is listed first and has only three wires, and cutting them
+		// would split it off alone.
+		let text = concat!(
+			"pin: aa ab ac\n",
+			"aa: ab ac ad ae bc\n",
+			"ab: ac ad ae\n",
+			"ac: ad ae\n",
+			"ad: ae ba\n",
+			"ae: bb\n",
+			"ba: bb bc bd be\n",
+			"bb: bc bd be\n",
+			"bc: bd be\n",
+			"bd: be\n",
+		);
+		let (_, mut wiring) = text.parse_wyz::<Wiring>()?;
+		assert_eq!(wiring.part_1()?, 6 * 5);
+		Ok(())
+	}
 }
@@ -9,6 +9,7 @@ pub mod d08;
 pub mod d09;
 pub mod d10;
 pub mod d11;
+pub mod d12;
 pub mod d13;
 pub mod d14;
 pub mod d15;
@@ -22,3 +23,6 @@ pub mod d22;
 pub mod d23;
 pub mod d24;
 pub mod d25;
+pub mod shared;
+
+pub use self::shared::*;
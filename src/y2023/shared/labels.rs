@@ -0,0 +1,43 @@
+//! Interning of the short alphabetic names which label workflows, modules, and
+//! so on.
+
+use std::sync::RwLock;
+
+use nom::character::complete::alpha1;
+
+use crate::{
+	dict::{
+		Dictionary,
+		Identifier,
+	},
+	ParseResult,
+};
+
+/// Parses an alphabetic label and interns it in a name cache.
+pub fn label<'a, N>(
+	text: &'a str,
+	names: &mut Dictionary<str, N>,
+) -> ParseResult<&'a str, Identifier<N>> {
+	let (rest, name) = alpha1(text)?;
+	Ok((rest, names.insert(name)))
+}
+
+/// Parses an alphabetic label and interns it in a shared name cache.
+///
+/// The cache is behind a lock, rather than a `&mut`, so that it can be
+/// captured by several parser closures at once.
+pub fn cached_label<'a, N>(
+	text: &'a str,
+	names: &RwLock<Dictionary<str, N>>,
+) -> ParseResult<&'a str, Identifier<N>> {
+	let (rest, name) = alpha1(text)?;
+	if let Some(ident) = names
+		.read()
+		.expect("poisoned name-cache")
+		.lookup_value(name)
+	{
+		return Ok((rest, ident));
+	}
+	let ident = names.write().expect("poisoned name-cache").insert(name);
+	Ok((rest, ident))
+}
@@ -0,0 +1,11 @@
+//! Helpers shared by more than one of 2023's puzzles.
+//!
+//! Anything here is specific to the year's input formats; it moves into the
+//! support library proper once another year needs it too.
+
+pub mod labels;
+
+pub use self::labels::{
+	cached_label,
+	label,
+};
@@ -18,8 +18,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2024, 1, |t| t.parse_dyn_puzzle::<Idents>());
+crate::register_solver!(y2024, d01, Idents);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Idents {
@@ -12,8 +12,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2024, 2, |t| t.parse_dyn_puzzle::<Reports>());
+crate::register_solver!(y2024, d02, Reports);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Reports {
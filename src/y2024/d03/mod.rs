@@ -18,9 +18,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2024, 3, |t| t.parse_dyn_puzzle::<Instructions>());
+crate::register_solver!(y2024, d03, Instructions);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Instructions {
@@ -30,9 +30,7 @@ use crate::{
 	Coord2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2024, 4, |t| t.parse_dyn_puzzle::<WordSearch>());
+crate::register_solver!(y2024, d04, WordSearch);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct WordSearch {
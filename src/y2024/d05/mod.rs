@@ -21,8 +21,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2024, 5, |t| t.parse_dyn_puzzle::<Printer>());
+crate::register_solver!(y2024, d05, Printer);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Printer {
@@ -1,3 +1,11 @@
+use std::{
+	fmt::{
+		self,
+		Write as _,
+	},
+	mem,
+};
+
 use nom::{
 	self,
 	branch::alt,
@@ -6,6 +14,7 @@ use nom::{
 };
 
 use crate::{
+	cancel,
 	coords::{
 		points::{
 			Direction2D,
@@ -15,18 +24,33 @@ use crate::{
 		Dense2DSpace,
 	},
 	prelude::*,
+	sim::{
+		simulate_until,
+		Recorder,
+	},
 	Coord2D,
+	Difficulty,
 	Grid2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2024, 6, |t| t.parse_dyn_puzzle::<Patrol>());
+crate::register_solver!(y2024, d06, Patrol);
+
+crate::budget!(
+	2024,
+	6,
+	parse_ms = 50,
+	part1_ms = 50,
+	part2_ms = 10_000,
+	peak_mb = 64
+);
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Patrol {
 	grid:      Dense2DSpace<i16, Square>,
 	cursor:    Coord2D<i16>,
 	direction: Direction2D,
+	/// Records the guard's walk, when replaying.
+	recorder:  Recorder,
 }
 
 impl Patrol {
@@ -53,9 +77,79 @@ impl Patrol {
 		self.cursor = next;
 		Ok(())
 	}
+
+	/// Walks the guard until it leaves the map.
+	///
+	/// Each step either visits a square in a direction the guard has not
+	/// walked it before, or finds a loop, so the walk cannot take more steps
+	/// than four for every square.
+	fn walk(&mut self, recorder: &mut Recorder) -> eyre::Result<()> {
+		let squares = self.grid.raw_data().iter().map(Vec::len).sum::<usize>();
+		let mut step = 0;
+		let run = simulate_until(
+			self,
+			|this| {
+				this.step_guard()?;
+				step += 1;
+				recorder.record(format_args!("step {step}"), || {
+					format!("{:#}", this.display())
+				});
+				Ok(())
+			},
+			|this| !this.grid.in_bounds(this.cursor),
+			squares as u64 * 4,
+		)?;
+		if !run.finished {
+			return Err(AocError::Timeout(format!(
+				"the guard walked {} steps without leaving",
+				run.steps
+			))
+			.into());
+		}
+		Ok(())
+	}
+
+	/// Walks the guard until it leaves the map, counting the places where an
+	/// obstruction would have trapped it in a loop instead.
+	fn count_loops(&mut self, recorder: &mut Recorder) -> eyre::Result<i64> {
+		let mut cycles = 0;
+		let mut step = 0;
+		while self.grid.in_bounds(self.cursor) {
+			cancel::check()?;
+			let next = self.cursor + self.direction.unit();
+			let mut snapshot = self.clone();
+			if !snapshot.grid.in_bounds(next) {
+				break;
+			}
+			// A cell can only be obstructed if it has not yet been walked
+			// through in any direction.
+			if !snapshot.grid[next].is_visited() {
+				snapshot.grid[next] = Square::Obstructed;
+				while snapshot.grid.in_bounds(snapshot.cursor) {
+					if snapshot.step_guard().is_err() {
+						tracing::trace!(grid=%snapshot.display(), "found cycle");
+						cycles += 1;
+						break;
+					}
+				}
+			}
+			self.step_guard()?;
+			step += 1;
+			recorder.record(format_args!("step {step}, {cycles} loops"), || {
+				format!("{:#}", self.display())
+			});
+		}
+		Ok(cycles)
+	}
 }
 
 impl Puzzle for Patrol {
+	/// Part 2 tries an obstruction on every square of the guard's route, and
+	/// walks the whole patrol again for each.
+	fn estimated_difficulty() -> Difficulty {
+		Difficulty::Slow
+	}
+
 	fn after_parse(&mut self) -> eyre::Result<()> {
 		self.grid.set_origin(Coord2D::ZERO - self.cursor);
 		self.cursor = Coord2D::ZERO;
@@ -63,20 +157,15 @@ impl Puzzle for Patrol {
 	}
 
 	fn prepare_1(&mut self) -> eyre::Result<()> {
-		let begin = std::time::Instant::now();
-		while self.grid.in_bounds(self.cursor) {
-			if std::time::Instant::now() - begin
-				> std::time::Duration::from_secs(5)
-			{
-				eyre::bail!("probably found a cycle in the guard walk");
-			}
-			self.step_guard()?;
-		}
-		Ok(())
+		// The recorder is held outside `self` while walking, so that each
+		// frame can render `self`.
+		let mut recorder = mem::take(&mut self.recorder);
+		let outcome = self.walk(&mut recorder);
+		self.recorder = recorder;
+		outcome
 	}
 
 	fn part_1(&mut self) -> eyre::Result<i64> {
-		println!("{:#}", self.display());
 		Ok(self
 			.grid
 			.iter()
@@ -92,33 +181,51 @@ impl Puzzle for Patrol {
 			}
 		}
 		self.grid[Coord2D::ZERO] = Square::Guard(Direction2D::North);
+		self.cursor = Coord2D::ZERO;
 		self.direction = Direction2D::North;
 		Ok(())
 	}
 
 	fn part_2(&mut self) -> eyre::Result<i64> {
-		let mut cycles = 0;
-		while self.grid.in_bounds(self.cursor) {
-			let next = self.cursor + self.direction.unit();
-			let mut snapshot = self.clone();
-			if !snapshot.grid.in_bounds(next) {
-				break;
-			}
-			// A cell can only be obstructed if it has not yet been walked
-			// through in any direction.
-			if !snapshot.grid[next].is_visited() {
-				snapshot.grid[next] = Square::Obstructed;
-				while snapshot.grid.in_bounds(snapshot.cursor) {
-					if snapshot.step_guard().is_err() {
-						println!("{:#}", snapshot.display());
-						cycles += 1;
-						break;
-					}
-				}
+		// Every trial obstruction clones the whole patrol, which should not
+		// include the recording.
+		let mut recorder = mem::take(&mut self.recorder);
+		let outcome = self.count_loops(&mut recorder);
+		self.recorder = recorder;
+		outcome
+	}
+
+	fn render_input(&self) -> Option<String> {
+		Some(self.to_string())
+	}
+
+	fn render_state(&self) -> Option<String> {
+		Some(format!("{:#}", self.display()))
+	}
+
+	fn artifacts(&self) -> Vec<Artifact> {
+		vec![Artifact::text("patrol", format!("{:#}", self.display()))]
+	}
+
+	fn recorder(&mut self) -> Option<&mut Recorder> {
+		Some(&mut self.recorder)
+	}
+}
+
+/// Writes the map in the puzzle's input format.
+impl fmt::Display for Patrol {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let Some((min, max)) = self.grid.dimensions()
+		else {
+			return Ok(());
+		};
+		for row in min.y ..= max.y {
+			for col in min.x ..= max.x {
+				fmt.write_char(self.grid[Coord2D::new(col, row)].symbol())?;
 			}
-			self.step_guard()?;
+			writeln!(fmt)?;
 		}
-		Ok(cycles)
+		Ok(())
 	}
 }
 
@@ -175,6 +282,7 @@ impl<'a> Parsed<&'a str> for Patrol {
 			grid: grid.into(),
 			cursor,
 			direction,
+			recorder: Recorder::new(),
 		}))
 	}
 }
@@ -189,6 +297,19 @@ pub enum Square {
 }
 
 impl Square {
+	/// Gets the input symbol for the square.
+	pub fn symbol(self) -> char {
+		match self {
+			Self::Open => '.',
+			Self::Obstructed => '#',
+			Self::Guard(Direction2D::North) => '^',
+			Self::Guard(Direction2D::South) => 'v',
+			Self::Guard(Direction2D::West) => '<',
+			Self::Guard(Direction2D::East) => '>',
+			Self::Visited(_) => 'X',
+		}
+	}
+
 	pub fn visit(&mut self, direction: Direction2D) {
 		if let Self::Visited(dirs) = self {
 			dirs.insert(direction);
@@ -247,3 +368,16 @@ impl<'a> Parsed<&'a str> for Square {
 		))(src)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::snapshot;
+
+	#[test]
+	fn sample_patrol() -> eyre::Result<()> {
+		let (_, mut patrol) =
+			include_str!("sample.txt").parse_wyz::<Patrol>()?;
+		snapshot::assert_phases(2024, 6, &mut patrol)
+	}
+}
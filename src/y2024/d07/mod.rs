@@ -15,9 +15,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver =
-	Solver::new(2024, 7, |t| t.parse_dyn_puzzle::<CalibrationSet>());
+crate::register_solver!(y2024, d07, CalibrationSet);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct CalibrationSet {
@@ -33,8 +33,7 @@ use crate::{
 	Coord2D,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2024, 8, |t| t.parse_dyn_puzzle::<Antennae>());
+crate::register_solver!(y2024, d08, Antennae);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Antennae {
@@ -65,9 +64,7 @@ impl Puzzle for Antennae {
 	}
 
 	fn prepare_2(&mut self) -> eyre::Result<()> {
-		self.mark_antinodes(true)?;
-		println!("{:}", self.display());
-		Ok(())
+		self.mark_antinodes(true)
 	}
 
 	fn part_2(&mut self) -> eyre::Result<i64> {
@@ -77,9 +74,14 @@ impl Puzzle for Antennae {
 			.count()
 			.pipe(|ct| Ok(ct as i64))
 	}
+
+	fn artifacts(&self) -> Vec<Artifact> {
+		vec![Artifact::text("antinodes", self.display().to_string())]
+	}
 }
 
 impl Antennae {
+	#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
 	pub fn mark_antinodes(&mut self, repeat: bool) -> eyre::Result<()> {
 		for (&freq, sites) in self.freqs.iter() {
 			tracing::info!(%freq, "scanning");
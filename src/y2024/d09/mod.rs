@@ -20,8 +20,7 @@ use crate::{
 	prelude::*,
 };
 
-#[linkme::distributed_slice(SOLVERS)]
-static ITEM: Solver = Solver::new(2024, 9, |t| t.parse_dyn_puzzle::<TrashFs>());
+crate::register_solver!(y2024, d09, TrashFs);
 
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct TrashFs {
@@ -0,0 +1,36 @@
+//! Checks every declared solver budget against the real inputs on disk.
+//!
+//! This is its own test binary so that it can install the counting allocator,
+//! and so that no other test is allocating while it measures.
+
+use wyz_aoc::budget::{
+	PeakAlloc,
+	BUDGETS,
+};
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+#[test]
+fn within_budget() {
+	let mut budgets = BUDGETS.to_vec();
+	budgets.sort();
+	let mut failures = Vec::new();
+	for budget in budgets {
+		let name = format!("{}-{:0>2}", budget.year, budget.day);
+		match budget.check() {
+			Ok(None) => eprintln!("{name}: no real input; skipped"),
+			Ok(Some(usage)) => {
+				eprintln!("{name}: {usage}");
+				failures.extend(
+					budget
+						.overruns(&usage)
+						.into_iter()
+						.map(|overrun| format!("{name}: {overrun}")),
+				);
+			},
+			Err(err) => failures.push(format!("{name}: {err:#}")),
+		}
+	}
+	assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,349 @@
+//! Maintenance tasks for the per-day crates under `years/`.
+//!
+//! Days are being moved out of their own crates and into the library, but
+//! until each one is done its crate has to keep building, and the crates have
+//! drifted apart over the years: some read `../input.txt` from next to their
+//! manifest, some depend on the library through a registry version that never
+//! existed, and so on. Rather than fix each one by hand, run
+//!
+//! - `cargo xtask list` to see every per-day crate,
+//! - `cargo xtask check` to `cargo check` each of them, and
+//! - `cargo xtask migrate` to move their inputs into `src/y{year}/d{day}/`,
+//!   where the library reads them, and point their `wyz_aoc` dependency at this
+//!   repository.
+//!
+//! All three take `--year` and `--day` to narrow down which crates they visit,
+//! and `migrate` takes `--dry-run` to print what it would change.
+
+use std::{
+	env,
+	fmt,
+	fs,
+	path::{
+		Component,
+		Path,
+		PathBuf,
+	},
+	process::Command,
+};
+
+use clap::Parser;
+use eyre::WrapErr as _;
+use toml_edit::{
+	DocumentMut,
+	InlineTable,
+	Item,
+	Value,
+};
+
+#[derive(Clone, Debug, Parser)]
+#[command(about = "Maintenance tasks for the per-day crates under `years/`")]
+struct Args {
+	#[command(subcommand)]
+	task: Task,
+	/// Only visit crates for this year.
+	#[arg(short, long, global = true)]
+	year: Option<u16>,
+	/// Only visit crates for this day.
+	#[arg(short, long, global = true)]
+	day:  Option<u8>,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+enum Task {
+	/// Lists every per-day crate.
+	List,
+	/// Runs `cargo check` on every per-day crate, and fails if any fail.
+	Check,
+	/// Moves inputs into the library's puzzle folders, and depends on the
+	/// library by path.
+	Migrate {
+		/// Prints the changes without making them.
+		#[arg(long)]
+		dry_run: bool,
+	},
+}
+
+fn main() -> eyre::Result<()> {
+	color_eyre::install()?;
+	let args = Args::parse();
+	let root = workspace_root();
+	let crates = DayCrate::discover(&root)?
+		.into_iter()
+		.filter(|krate| args.year.is_none_or(|year| krate.year == year))
+		.filter(|krate| args.day.is_none_or(|day| krate.day == day))
+		.collect::<Vec<_>>();
+	if crates.is_empty() {
+		eyre::bail!("no per-day crates matched");
+	}
+	match args.task {
+		Task::List => {
+			for krate in &crates {
+				println!("{krate}: {}", krate.relative(&root).display());
+			}
+			Ok(())
+		},
+		Task::Check => check(&crates),
+		Task::Migrate { dry_run } => {
+			for krate in &crates {
+				krate
+					.migrate(&root, dry_run)
+					.wrap_err_with(|| format!("could not migrate {krate}"))?;
+			}
+			Ok(())
+		},
+	}
+}
+
+/// Finds the repository root, which is the parent of this crate.
+fn workspace_root() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.parent()
+		.expect("the xtask crate lives inside the workspace")
+		.to_owned()
+}
+
+/// Runs `cargo check` on each crate.
+fn check(crates: &[DayCrate]) -> eyre::Result<()> {
+	let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+	let mut failed = Vec::new();
+	for krate in crates {
+		let status = Command::new(&cargo)
+			.args(["check", "--quiet", "--manifest-path"])
+			.arg(krate.dir.join("Cargo.toml"))
+			.status()
+			.wrap_err_with(|| format!("could not run {cargo}"))?;
+		if status.success() {
+			println!("{krate}: ok");
+		}
+		else {
+			println!("{krate}: FAILED");
+			failed.push(krate.to_string());
+		}
+	}
+	if !failed.is_empty() {
+		eyre::bail!("{} crates failed: {}", failed.len(), failed.join(", "));
+	}
+	Ok(())
+}
+
+/// A crate for one day, in `years/{year}/d{day}/` or a language folder
+/// beneath it.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct DayCrate {
+	year: u16,
+	day:  u8,
+	/// The directory holding the crate's manifest.
+	dir:  PathBuf,
+}
+
+impl DayCrate {
+	/// Finds every per-day crate, in order.
+	fn discover(root: &Path) -> eyre::Result<Vec<Self>> {
+		let mut out = Vec::new();
+		for year_dir in read_dirs(&root.join("years"))? {
+			// Skips `template/`, and anything else which is not a year.
+			let Some(year) = file_name(&year_dir).and_then(|n| n.parse().ok())
+			else {
+				continue;
+			};
+			for day_dir in read_dirs(&year_dir)? {
+				let Some(day) = file_name(&day_dir)
+					.and_then(|n| n.strip_prefix('d'))
+					.and_then(|n| n.parse().ok())
+				else {
+					continue;
+				};
+				for dir in [day_dir.clone(), day_dir.join("rust")] {
+					if dir.join("Cargo.toml").is_file() {
+						out.push(Self { year, day, dir });
+					}
+				}
+			}
+		}
+		out.sort();
+		Ok(out)
+	}
+
+	fn relative<'a>(&'a self, root: &Path) -> &'a Path {
+		self.dir.strip_prefix(root).unwrap_or(&self.dir)
+	}
+
+	/// The path, from the repository root, of the day's real input. This is
+	/// where the library's `Solver::input_path` looks for the `input` group.
+	fn asset_path(&self) -> PathBuf {
+		Path::new("src")
+			.join(format!("y{}", self.year))
+			.join(format!("d{:0>2}", self.day))
+			.join("input.txt")
+	}
+
+	fn migrate(&self, root: &Path, dry_run: bool) -> eyre::Result<()> {
+		self.migrate_manifest(root, dry_run)?;
+		let mut sources = Vec::new();
+		rust_files(&self.dir.join("src"), &mut sources)?;
+		for source in sources {
+			self.migrate_source(root, &source, dry_run)?;
+		}
+		Ok(())
+	}
+
+	/// Points an existing `wyz_aoc` dependency at this repository.
+	fn migrate_manifest(&self, root: &Path, dry_run: bool) -> eyre::Result<()> {
+		let path = self.dir.join("Cargo.toml");
+		let text = fs::read_to_string(&path)?;
+		let mut manifest = text.parse::<DocumentMut>()?;
+		let Some(dep) = manifest
+			.get_mut("dependencies")
+			.and_then(Item::as_table_like_mut)
+			.and_then(|deps| deps.get_mut("wyz_aoc"))
+		else {
+			return Ok(());
+		};
+		let to_root = path_between(&self.dir, root);
+		let to_root = to_root.to_string_lossy().replace('\\', "/");
+		let is_current = dep
+			.get("path")
+			.and_then(Item::as_str)
+			.is_some_and(|path| path == to_root);
+		if is_current {
+			return Ok(());
+		}
+		let mut table = InlineTable::new();
+		table.insert("path", Value::from(to_root.as_str()));
+		*dep = Item::Value(Value::InlineTable(table));
+		println!("{self}: wyz_aoc = {{ path = {to_root:?} }}");
+		if !dry_run {
+			fs::write(&path, manifest.to_string())?;
+		}
+		Ok(())
+	}
+
+	/// Rewrites `include_str!("…/input.txt")` to read from the library's
+	/// puzzle folder, and moves the input file there if it exists.
+	fn migrate_source(
+		&self,
+		root: &Path,
+		source: &Path,
+		dry_run: bool,
+	) -> eyre::Result<()> {
+		const OPEN: &str = "include_str!(\"";
+		let text = fs::read_to_string(source)?;
+		let source_dir = source.parent().unwrap_or(root);
+		let asset = self.asset_path();
+		let new_path = path_between(source_dir, root).join(&asset);
+		let new_path = new_path.to_string_lossy().replace('\\', "/");
+
+		let mut out = String::with_capacity(text.len());
+		let mut rest = text.as_str();
+		while let Some(start) = rest.find(OPEN) {
+			let (head, tail) = rest.split_at(start + OPEN.len());
+			out.push_str(head);
+			let Some(end) = tail.find('"')
+			else {
+				rest = tail;
+				break;
+			};
+			let old_path = &tail[.. end];
+			if Path::new(old_path).file_name() == Some("input.txt".as_ref()) {
+				println!(
+					"{self}: {}: {old_path} -> {new_path}",
+					source.strip_prefix(&self.dir).unwrap_or(source).display()
+				);
+				out.push_str(&new_path);
+				self.move_input(&source_dir.join(old_path), root, dry_run)?;
+			}
+			else {
+				out.push_str(old_path);
+			}
+			rest = &tail[end ..];
+		}
+		out.push_str(rest);
+		if out != text && !dry_run {
+			fs::write(source, out)?;
+		}
+		Ok(())
+	}
+
+	fn move_input(
+		&self,
+		old: &Path,
+		root: &Path,
+		dry_run: bool,
+	) -> eyre::Result<()> {
+		let new = root.join(self.asset_path());
+		if !old.is_file() {
+			return Ok(());
+		}
+		if new.exists() {
+			println!(
+				"{self}: {} already exists; leaving {} in place",
+				self.asset_path().display(),
+				old.display()
+			);
+			return Ok(());
+		}
+		println!("{self}: moving {} into assets", old.display());
+		if !dry_run {
+			if let Some(parent) = new.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			fs::rename(old, &new)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for DayCrate {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}-{:0>2}", self.year, self.day)
+	}
+}
+
+/// Lists the subdirectories of a directory, in order.
+fn read_dirs(dir: &Path) -> eyre::Result<Vec<PathBuf>> {
+	let mut out = fs::read_dir(dir)
+		.wrap_err_with(|| format!("could not read {}", dir.display()))?
+		.map(|entry| entry.map(|entry| entry.path()))
+		.collect::<Result<Vec<_>, _>>()?;
+	out.retain(|path| path.is_dir());
+	out.sort();
+	Ok(out)
+}
+
+/// Collects every Rust source file beneath a directory.
+fn rust_files(dir: &Path, out: &mut Vec<PathBuf>) -> eyre::Result<()> {
+	if !dir.is_dir() {
+		return Ok(());
+	}
+	for entry in fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path.is_dir() {
+			rust_files(&path, out)?;
+		}
+		else if path.extension().is_some_and(|ext| ext == "rs") {
+			out.push(path);
+		}
+	}
+	Ok(())
+}
+
+fn file_name(path: &Path) -> Option<&str> {
+	path.file_name()?.to_str()
+}
+
+/// Computes the relative path from a directory up to one of its ancestors.
+fn path_between(from: &Path, ancestor: &Path) -> PathBuf {
+	let depth = from
+		.strip_prefix(ancestor)
+		.map(|rest| {
+			rest.components()
+				.filter(|c| matches!(c, Component::Normal(_)))
+				.count()
+		})
+		.unwrap_or_default();
+	if depth == 0 {
+		return PathBuf::from(".");
+	}
+	(0 .. depth).map(|_| "..").collect()
+}
@@ -0,0 +1 @@
+wyz_aoc::standalone_main!(2015, 6);
@@ -0,0 +1,3 @@
+// The solution lives in the library's `src/y<year>/d<day>/` module; this crate
+// only runs it.
+wyz_aoc::standalone_main!(YEAR, DAY);